@@ -0,0 +1,38 @@
+/// Build script that records build metadata for the server
+///
+/// Sets HABIT_TRACKER_GIT_HASH (short commit hash) and HABIT_TRACKER_BUILD_DATE
+/// (UTC timestamp), falling back to "unknown" when git isn't available, e.g.
+/// when building from a source archive or inside Docker.
+
+use std::process::Command;
+
+fn main() {
+    let git_hash = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|hash| hash.trim().to_string())
+        .filter(|hash| !hash.is_empty())
+        .unwrap_or_else(|| "unknown".to_string());
+
+    // Honour SOURCE_DATE_EPOCH so reproducible builds get a stable date
+    let build_date = std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|epoch| epoch.parse::<i64>().ok())
+        .and_then(|secs| chrono::DateTime::from_timestamp(secs, 0))
+        .unwrap_or_else(chrono::Utc::now)
+        .format("%Y-%m-%dT%H:%M:%SZ")
+        .to_string();
+
+    println!("cargo:rustc-env=HABIT_TRACKER_GIT_HASH={}", git_hash);
+    println!("cargo:rustc-env=HABIT_TRACKER_BUILD_DATE={}", build_date);
+    println!("cargo:rerun-if-changed=build.rs");
+    for path in [".git/HEAD", ".git/refs"] {
+        if std::path::Path::new(path).exists() {
+            println!("cargo:rerun-if-changed={}", path);
+        }
+    }
+    println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+}
@@ -3,10 +3,48 @@
 /// This module provides functionality for analyzing habit patterns,
 /// calculating streaks, and generating personalized insights.
 
-use crate::domain::{Habit, HabitEntry, Streak, HabitId, Category};
-use crate::storage::{StorageError, HabitStorage};
+use crate::domain::{CategoryRegistry, Clock, SystemClock, Habit, HabitEntry, Streak, StreakInputs, StreakOptions, RateBasis, format_rate, format_streak_length, format_streak_totals, streak_unit_label, weekday_full_name, week_start_for, vacation_days, HabitId, Frequency, Vacation};
+use crate::storage::{DateBasisInfo, StorageError, HabitStorage};
 use serde::{Deserialize, Serialize};
-use chrono::Utc;
+use chrono::{Datelike, NaiveDate, Utc, Weekday};
+use std::collections::BTreeMap;
+
+mod tone;
+
+pub use tone::Tone;
+
+/// Number of days in each window compared by trend detection
+const TREND_WINDOW_DAYS: i64 = 7;
+
+/// Minimum change in completion rate between windows to call it a trend
+const TREND_THRESHOLD: f64 = 0.15;
+
+/// Number of past days examined when looking for a weakest weekday
+const WEEKDAY_WINDOW_DAYS: i64 = 56;
+
+/// Minimum times each weekday must have occurred before comparing them
+const WEEKDAY_MIN_OCCURRENCES: u32 = 3;
+
+/// How far the weakest weekday must trail the next weakest to be called out
+const WEAKEST_DAY_MARGIN: f64 = 0.25;
+
+/// Longest streak a paused habit needs before reactivating it is suggested
+const REACTIVATE_MIN_STREAK: u32 = 7;
+
+/// Minimum number of valued entries before outliers are looked for
+const VALUE_OUTLIER_MIN_VALUES: usize = 4;
+
+/// Share of rated entries within two adjacent intensities to call it typical
+const INTENSITY_CONCENTRATED_SHARE: f64 = 0.5;
+
+/// Below this share in any two adjacent intensities, there is no typical level
+const INTENSITY_SPREAD_SHARE: f64 = 0.35;
+
+/// Share of mood-tagged entries the most common mood needs to be called dominant
+const DOMINANT_MOOD_SHARE: f64 = 0.4;
+
+/// Daily streak lengths recorded by milestone history, shortest first
+pub const STREAK_MILESTONES: &[u32] = &[7, 14, 30, 60, 90, 100];
 
 /// Individual insight with analysis
 #[derive(Debug, Clone, Serialize)]
@@ -24,6 +62,25 @@ pub struct InsightsParams {
     pub habit_id: Option<String>, // If omitted, provides insights for all habits
     pub time_period: Option<String>, // "week", "month", "quarter", "year"
     pub insight_type: Option<String>, // "performance", "recommendations", "patterns"
+    /// "portfolio" (default) or "per_habit", which also returns every listed
+    /// habit's own insights; only applies when `habit_id` is omitted
+    #[serde(default)]
+    pub scope: Option<String>,
+    /// Also consider paused habits (default false)
+    #[serde(default)]
+    pub include_inactive: Option<bool>,
+    /// "text" (default) or "markdown", which renders `message` as a
+    /// standalone markdown document with a habit table
+    #[serde(default)]
+    pub format: Option<String>,
+    /// Drop insights less confident than this (0.0 to 1.0); defaults to
+    /// the engine's `AnalyticsConfig::min_confidence`
+    #[serde(default)]
+    pub min_confidence: Option<f64>,
+    /// "cheerful" or "neutral" wording; defaults to the engine's
+    /// `AnalyticsConfig::tone`
+    #[serde(default)]
+    pub tone: Option<String>,
 }
 
 /// Response containing habit insights
@@ -34,6 +91,9 @@ pub struct InsightsResponse {
     pub message: String,
     pub time_period: String,
     pub generated_at: String,
+    /// Insights for each active habit keyed by habit ID (`per_habit` scope only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub per_habit: Option<BTreeMap<String, Vec<Insight>>>,
 }
 
 /// Configuration for the analytics engine
@@ -45,6 +105,26 @@ pub struct AnalyticsConfig {
     pub cache_ttl_seconds: u64,
     /// Minimum number of entries required for pattern analysis
     pub min_entries_for_analysis: usize,
+    /// First day of the week used when grouping weekly habits
+    pub week_start: Weekday,
+    /// Whether completion rates count from habit creation or the first entry
+    pub rate_basis: RateBasis,
+    /// Report completion rates above 100% for habits done more than scheduled
+    pub allow_over_100_percent: bool,
+    /// Portfolio size above which the "Focus Strategy" recommendation is considered
+    pub focus_min_habits: usize,
+    /// Fraction of habits with active streaks below which focusing is recommended
+    pub focus_active_ratio: f64,
+    /// Days since the last completion after which an active habit gets a nudge
+    pub dormant_after_days: u32,
+    /// Habits younger than this many days get formation-stage encouragement instead of rate critique
+    pub new_habit_days: u32,
+    /// Insights less confident than this are left out of reports (0.0 keeps all)
+    pub min_confidence: f64,
+    /// Entry values more than this many times the median are flagged as a possible unit mismatch
+    pub value_outlier_multiplier: f64,
+    /// Wording of insight messages: cheerful with emojis, or neutral and factual
+    pub tone: Tone,
 }
 
 impl Default for AnalyticsConfig {
@@ -53,6 +133,27 @@ impl Default for AnalyticsConfig {
             enable_caching: true,
             cache_ttl_seconds: 3600, // 1 hour
             min_entries_for_analysis: 5,
+            week_start: Weekday::Mon,
+            rate_basis: RateBasis::SinceCreation,
+            allow_over_100_percent: false,
+            focus_min_habits: 5,
+            focus_active_ratio: 0.5,
+            dormant_after_days: 14,
+            new_habit_days: 14,
+            min_confidence: 0.0,
+            value_outlier_multiplier: 5.0,
+            tone: Tone::Cheerful,
+        }
+    }
+}
+
+impl AnalyticsConfig {
+    /// Build the streak calculation settings implied by this configuration
+    pub fn streak_options(&self) -> StreakOptions {
+        StreakOptions {
+            week_start: self.week_start,
+            rate_basis: self.rate_basis,
+            allow_over_100_percent: self.allow_over_100_percent,
         }
     }
 }
@@ -63,6 +164,8 @@ impl Default for AnalyticsConfig {
 /// generating meaningful insights and recommendations.
 pub struct AnalyticsEngine {
     config: AnalyticsConfig,
+    clock: Box<dyn Clock>,
+    categories: CategoryRegistry,
     // Future: add insight cache here when needed
 }
 
@@ -98,13 +201,56 @@ impl AnalyticsEngine {
     ///     enable_caching: false,
     ///     cache_ttl_seconds: 1800, // 30 minutes
     ///     min_entries_for_analysis: 3,
+    ///     ..AnalyticsConfig::default()
     /// };
     ///
     /// let engine = AnalyticsEngine::with_config(config);
     /// // Engine configured with custom settings
     /// ```
     pub fn with_config(config: AnalyticsConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            clock: Box::new(SystemClock),
+            categories: CategoryRegistry::default(),
+        }
+    }
+    
+    /// Use a different clock to decide what "today" is
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use habit_tracker_mcp::analytics::AnalyticsEngine;
+    /// use habit_tracker_mcp::domain::FixedClock;
+    /// use chrono::NaiveDate;
+    ///
+    /// let engine = AnalyticsEngine::new()
+    ///     .with_clock(FixedClock(NaiveDate::from_ymd_opt(2024, 3, 1).unwrap()));
+    /// ```
+    pub fn with_clock<C: Clock + 'static>(mut self, clock: C) -> Self {
+        self.clock = Box::new(clock);
+        self
+    }
+    
+    /// Use these registered categories when naming habits' categories
+    pub fn with_categories(mut self, categories: CategoryRegistry) -> Self {
+        self.categories = categories;
+        self
+    }
+    
+    /// Get the configuration this engine was built with
+    pub fn config(&self) -> &AnalyticsConfig {
+        &self.config
+    }
+    
+    /// Get the clock this engine reads "today" from
+    pub fn clock(&self) -> &dyn Clock {
+        self.clock.as_ref()
+    }
+    
+    /// Get the registered categories this engine names categories with
+    pub fn categories(&self) -> &CategoryRegistry {
+        &self.categories
     }
     
     /// Calculate streak information for a habit based on its entries
@@ -115,14 +261,38 @@ impl AnalyticsEngine {
         &self,
         habit: &Habit,
         entries: &[HabitEntry],
+    ) -> Streak {
+        self.calculate_habit_streak_with_freezes(habit, entries, &[])
+    }
+    
+    /// Calculate streak information, treating the habit's frozen days as completed
+    pub fn calculate_habit_streak_with_freezes(
+        &self,
+        habit: &Habit,
+        entries: &[HabitEntry],
+        frozen_days: &[NaiveDate],
+    ) -> Streak {
+        self.calculate_habit_streak_with_vacations(habit, entries, frozen_days, &[])
+    }
+    
+    /// Calculate streak information, also skipping days the habit was on vacation
+    pub fn calculate_habit_streak_with_vacations(
+        &self,
+        habit: &Habit,
+        entries: &[HabitEntry],
+        frozen_days: &[NaiveDate],
+        vacations: &[Vacation],
     ) -> Streak {
         let habit_created_at = habit.created_at.naive_utc().date();
         
-        Streak::calculate_from_entries(
-            habit.id.clone(),
-            entries,
-            &habit.frequency,
-            habit_created_at,
+        let vacation_days = vacation_days(vacations, self.clock.today());
+        
+        Streak::calculate(
+            StreakInputs::new(habit.id.clone(), entries, &habit.frequency, habit_created_at)
+                .with_options(self.config.streak_options())
+                .with_clock(self.clock.as_ref())
+                .with_frozen_days(frozen_days)
+                .with_vacation_days(&vacation_days),
         )
     }
     
@@ -161,27 +331,72 @@ impl AnalyticsEngine {
     ) -> Result<InsightsResponse, StorageError> {
         let time_period = params.time_period.unwrap_or("month".to_string());
         let insight_type = params.insight_type.unwrap_or("all".to_string());
+        let scope = params.scope.unwrap_or("portfolio".to_string());
+        if scope != "portfolio" && scope != "per_habit" {
+            return Err(StorageError::Validation(format!("Invalid scope '{}'. Valid options: portfolio, per_habit", scope)));
+        }
+        let format = params.format.unwrap_or("text".to_string());
+        if format != "text" && format != "markdown" {
+            return Err(StorageError::Validation(format!("Invalid format '{}'. Valid options: text, markdown", format)));
+        }
+        let min_confidence = params.min_confidence.unwrap_or(self.config.min_confidence);
+        if !(0.0..=1.0).contains(&min_confidence) {
+            return Err(StorageError::Validation(format!("min_confidence must be between 0 and 1, got {}", min_confidence)));
+        }
+        let tone = match params.tone {
+            Some(name) => Tone::parse(&name)?,
+            None => self.config.tone,
+        };
+        // Filter by type first, then drop insights below the confidence threshold
+        let wanted = |insight: &Insight| {
+            (insight_type == "all" || insight.insight_type == insight_type)
+                && insight.confidence >= min_confidence
+        };
+        let include_inactive = params.include_inactive.unwrap_or(false);
 
         let mut insights = Vec::new();
+        let mut per_habit = None;
+        let mut habit_sections = Vec::new();
+        let mut markdown_sections = Vec::new();
+        let report_habits;
 
         if let Some(habit_id_str) = params.habit_id {
             // Generate insights for specific habit
             let habit_id = HabitId::from_string(&habit_id_str)
                 .map_err(|_| StorageError::HabitNotFound { habit_id: habit_id_str.clone() })?;
 
-            insights.extend(self.generate_single_habit_insights(storage, &habit_id, &time_period)?);
+            let habit = storage.get_habit(&habit_id)?;
+            insights.extend(self.generate_single_habit_insights(storage, &habit, &time_period, tone)?);
+            report_habits = vec![habit];
         } else {
+            report_habits = storage.list_habits(None, !include_inactive)?;
             // Generate insights for all habits
-            insights.extend(self.generate_overall_insights(storage, &time_period)?);
-        }
+            insights.extend(self.generate_overall_insights(storage, &time_period, include_inactive, tone)?);
 
-        // Filter by insight type if specified
-        if insight_type != "all" {
-            insights.retain(|insight| insight.insight_type == insight_type);
+            if scope == "per_habit" {
+                let mut by_habit = BTreeMap::new();
+                for habit in &report_habits {
+                    let mut habit_insights = self.generate_single_habit_insights(storage, habit, &time_period, tone)?;
+                    habit_insights.retain(wanted);
+                    let body = if habit_insights.is_empty() {
+                        "   No specific insights yet.".to_string()
+                    } else {
+                        Self::format_insight_list(&habit_insights, tone)
+                    };
+                    habit_sections.push(format!("**{}**\n{}", habit.name, body));
+                    markdown_sections.push(format!("### {}\n\n{}", habit.name, Self::markdown_insight_bullets(&habit_insights, "####")));
+                    by_habit.insert(habit.id.to_string(), habit_insights);
+                }
+                per_habit = Some(by_habit);
+            }
         }
 
+        // Filter by insight type and confidence
+        insights.retain(wanted);
+
         let summary = if insights.is_empty() {
-            "No specific insights available yet. Keep tracking your habits to build more data!".to_string()
+            tone.pick("No specific insights available yet. Keep tracking your habits to build more data!",
+                      "No insights available for this period.").to_string()
         } else {
             let success_count = insights.iter().filter(|i| i.insight_type == "success").count();
             let recommendation_count = insights.iter().filter(|i| i.insight_type == "recommendation").count();
@@ -190,139 +405,834 @@ impl AnalyticsEngine {
                     insights.len(), success_count, recommendation_count)
         };
 
-        let message = format!("📊 **Habit Insights Report** ({})\n\n{}\n\n{}",
-                             time_period.to_uppercase(),
-                             summary,
-                             insights.iter()
-                                 .map(|i| format!("{} **{}**\n   {}",
-                                                 Self::get_insight_emoji(&i.insight_type),
-                                                 i.title,
-                                                 i.message))
-                                 .collect::<Vec<_>>()
-                                 .join("\n\n"));
+        let generated_at = Utc::now().format("%Y-%m-%d %H:%M:%S UTC").to_string();
+        let message = if format == "markdown" {
+            let ids: Vec<HabitId> = report_habits.iter().map(|h| h.id.clone()).collect();
+            let streaks = storage.get_streaks_for_habits(&ids)?;
+            let rows: Vec<(&Habit, Streak)> = report_habits.iter()
+                .map(|habit| (habit, streaks.get(&habit.id).cloned().unwrap_or_else(|| Streak::new(habit.id.clone()))))
+                .collect();
+            self.format_markdown_report(&time_period, &generated_at, &summary, &rows, &insights, &markdown_sections)
+        } else {
+            let mut message = format!("{} ({})\n\n{}\n\n{}",
+                                 tone.decorate("📊", "**Habit Insights Report**"),
+                                 time_period.to_uppercase(),
+                                 summary,
+                                 Self::format_insight_list(&insights, tone));
+            if !habit_sections.is_empty() {
+                message.push_str(&format!("\n\n{}\n\n", tone.decorate("📋", "**Per-Habit Insights**")));
+                message.push_str(&habit_sections.join("\n\n"));
+            }
+            message
+        };
 
         Ok(InsightsResponse {
             insights,
             summary,
             message,
             time_period,
-            generated_at: Utc::now().format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+            generated_at,
+            per_habit,
         })
     }
 
+    /// Render insights as paragraphs for the text report, emoji-prefixed in the cheerful tone
+    fn format_insight_list(insights: &[Insight], tone: Tone) -> String {
+        insights.iter()
+            .map(|i| format!("{}\n   {}",
+                            tone.decorate(Self::get_insight_emoji(&i.insight_type), &format!("**{}**", i.title)),
+                            i.message))
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+
+    /// Render the report as a standalone markdown document
+    ///
+    /// The document has a summary with portfolio stats, a table of habits
+    /// and their rates, and the insights as bullets grouped by type.
+    fn format_markdown_report(
+        &self,
+        time_period: &str,
+        generated_at: &str,
+        summary: &str,
+        habits: &[(&Habit, Streak)],
+        insights: &[Insight],
+        per_habit_sections: &[String],
+    ) -> String {
+        let mut doc = format!(
+            "# Habit Insights Report\n\n_Period: {} · Generated {}_\n\n## Summary\n\n{}\n",
+            time_period, generated_at, summary
+        );
+
+        if !habits.is_empty() {
+            let average_rate = habits.iter().map(|(_, s)| s.completion_rate).sum::<f64>() / habits.len() as f64;
+            doc.push_str(&format!("\n- Habits: {}\n- Average completion rate: {}\n", habits.len(), format_rate(average_rate)));
+            if let Some((habit, streak)) = habits.iter().max_by_key(|(_, s)| s.current_streak) {
+                if streak.current_streak > 0 {
+                    doc.push_str(&format!("- Best current streak: {} ({})\n",
+                        format_streak_length(streak.current_streak, streak_unit_label(&habit.frequency)), habit.name));
+                }
+            }
+        }
+
+        doc.push_str("\n## Habits\n\n");
+        if habits.is_empty() {
+            doc.push_str("No habits yet.\n");
+        } else {
+            doc.push_str("| Habit | Category | Current streak | Longest streak | Completion rate |\n");
+            doc.push_str("| --- | --- | ---: | ---: | ---: |\n");
+            for (habit, streak) in habits {
+                doc.push_str(&format!(
+                    "| {} | {} | {} | {} | {} |\n",
+                    habit.name.replace('|', "\\|"),
+                    habit.category.display_name_in(&self.categories).replace('|', "\\|"),
+                    streak.current_streak,
+                    streak.longest_streak,
+                    format_rate(streak.completion_rate),
+                ));
+            }
+        }
+
+        doc.push_str("\n## Insights\n\n");
+        doc.push_str(&Self::markdown_insight_bullets(insights, "###"));
+
+        if !per_habit_sections.is_empty() {
+            doc.push_str("\n\n## Per-Habit Insights\n\n");
+            doc.push_str(&per_habit_sections.join("\n\n"));
+        }
+
+        doc.push('\n');
+        doc
+    }
+
+    /// Render insights as markdown bullets under a heading per insight type
+    ///
+    /// `heading` is the markdown prefix for the type headings, e.g. "###".
+    fn markdown_insight_bullets(insights: &[Insight], heading: &str) -> String {
+        if insights.is_empty() {
+            return "No specific insights yet.".to_string();
+        }
+
+        // Known types in a fixed order, then any others as they appear
+        let mut types: Vec<&str> = ["success", "recommendation", "warning", "pattern"].into_iter()
+            .filter(|t| insights.iter().any(|i| i.insight_type == *t))
+            .collect();
+        for insight in insights {
+            if !types.contains(&insight.insight_type.as_str()) {
+                types.push(&insight.insight_type);
+            }
+        }
+
+        types.iter()
+            .map(|insight_type| {
+                let title = match *insight_type {
+                    "success" => "Successes".to_string(),
+                    "recommendation" => "Recommendations".to_string(),
+                    "warning" => "Warnings".to_string(),
+                    "pattern" => "Patterns".to_string(),
+                    other => other.to_string(),
+                };
+                let bullets = insights.iter()
+                    .filter(|i| i.insight_type == *insight_type)
+                    .map(|i| format!("- **{}** — {}", i.title, i.message))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                format!("{} {}\n\n{}", heading, title, bullets)
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+
     /// Generate insights for a single habit
+    ///
+    /// Habits younger than `new_habit_days` are still forming, so a low
+    /// completion rate gets encouragement rather than "Room for Improvement".
     fn generate_single_habit_insights<S: HabitStorage>(
         &self,
         storage: &S,
-        habit_id: &HabitId,
+        habit: &Habit,
         _time_period: &str,
+        tone: Tone,
     ) -> Result<Vec<Insight>, StorageError> {
         let mut insights = Vec::new();
-
-        // Get streak data for the habit
+        let habit_id = &habit.id;
         let streak = storage.get_streak(habit_id)?;
+        let age_days = (self.clock.today() - habit.created_at.naive_utc().date()).num_days().max(0) as u32;
+        let forming = age_days < self.config.new_habit_days;
 
         // Streak analysis
+        let unit = streak_unit_label(&habit.frequency);
         if streak.current_streak >= 7 {
-            insights.push(Insight {
-                title: "Great Consistency!".to_string(),
-                message: format!("You've maintained this habit for {} days straight. That's excellent dedication!", streak.current_streak),
-                insight_type: "success".to_string(),
-                confidence: 0.9,
-                data: Some(serde_json::json!({
+            let length = format_streak_length(streak.current_streak, unit);
+            insights.push(tone.insight("success", 0.9)
+                .title("Great Consistency!", "Consistent Streak")
+                .message(
+                    format!("You've maintained this habit for {} straight. That's excellent dedication!", length),
+                    format!("Current streak: {}.", length))
+                .data(serde_json::json!({
                     "current_streak": streak.current_streak,
                     "streak_milestone": Self::get_streak_milestone(streak.current_streak)
-                })),
-            });
+                }))
+                .build());
         } else if streak.current_streak == 0 && streak.longest_streak > 0 {
-            insights.push(Insight {
-                title: "Time to Restart".to_string(),
-                message: format!("You've had a {} day streak before - you can do it again! Start with just completing the habit once today.", streak.longest_streak),
-                insight_type: "recommendation".to_string(),
-                confidence: 0.8,
-                data: Some(serde_json::json!({
+            insights.push(tone.insight("recommendation", 0.8)
+                .title("Time to Restart", "Streak Ended")
+                .message(
+                    format!("You've had a {}-{} streak before - you can do it again! Start with just completing the habit once today.",
+                        streak.longest_streak, unit.trim_end_matches('s')),
+                    format!("No current streak. Longest streak: {}. Completing the habit today starts a new one.",
+                        format_streak_length(streak.longest_streak, unit)))
+                .data(serde_json::json!({
                     "longest_streak": streak.longest_streak,
                     "current_streak": streak.current_streak
-                })),
-            });
+                }))
+                .build());
+        }
+
+        // Remind lapsed users of the reason they gave for starting
+        if streak.current_streak == 0 && streak.longest_streak > 0 {
+            if let Some(motivation) = habit.motivation.as_deref().map(str::trim).filter(|m| !m.is_empty()) {
+                insights.push(tone.insight("recommendation", 0.7)
+                    .title("Remember Your Why", "Stated Motivation")
+                    .message(
+                        format!("Remember why you started: {}", motivation),
+                        format!("Motivation recorded for this habit: {}", motivation))
+                    .data(serde_json::json!({
+                        "motivation": motivation
+                    }))
+                    .build());
+            }
         }
 
         // Completion rate analysis
-        if streak.completion_rate >= 0.8 {
-            insights.push(Insight {
-                title: "High Performer".to_string(),
-                message: format!("You're completing this habit {:.0}% of the time. This is excellent performance!", streak.completion_rate * 100.0),
-                insight_type: "success".to_string(),
-                confidence: 0.9,
-                data: Some(serde_json::json!({
+        let rate = format_rate(streak.completion_rate);
+        if forming && streak.completion_rate < 0.8 {
+            let age = format_streak_length(age_days, "days");
+            insights.push(tone.insight("recommendation", 0.7)
+                .title("Building the Habit", "New Habit")
+                .message(
+                    format!("'{}' is only {} old - early on, showing up matters more than a perfect record. Each completion makes the next one easier!", habit.name, age),
+                    format!("'{}' was created {} ago. Completion rate so far: {}; rates are still settling in the first {} days.",
+                        habit.name, age, rate, self.config.new_habit_days))
+                .data(serde_json::json!({
+                    "completion_rate": streak.completion_rate,
+                    "habit_age_days": age_days,
+                    "stage": "forming"
+                }))
+                .build());
+        } else if streak.completion_rate >= 0.8 {
+            insights.push(tone.insight("success", 0.9)
+                .title("High Performer", "High Completion Rate")
+                .message(
+                    format!("You're completing this habit {} of the time. This is excellent performance!", rate),
+                    format!("Completion rate: {}.", rate))
+                .data(serde_json::json!({
                     "completion_rate": streak.completion_rate,
                     "performance_level": "excellent"
-                })),
-            });
+                }))
+                .build());
         } else if streak.completion_rate >= 0.6 {
-            insights.push(Insight {
-                title: "Good Progress".to_string(),
-                message: format!("You're at {:.0}% completion rate. Try to identify what helps you succeed and do more of that!", streak.completion_rate * 100.0),
-                insight_type: "recommendation".to_string(),
-                confidence: 0.7,
-                data: Some(serde_json::json!({
+            insights.push(tone.insight("recommendation", 0.7)
+                .title("Good Progress", "Moderate Completion Rate")
+                .message(
+                    format!("You're at {} completion rate. Try to identify what helps you succeed and do more of that!", rate),
+                    format!("Completion rate: {}. Identifying what helps on completed days may raise it.", rate))
+                .data(serde_json::json!({
                     "completion_rate": streak.completion_rate,
                     "performance_level": "good"
-                })),
-            });
+                }))
+                .build());
         } else if streak.total_completions > 0 {
-            insights.push(Insight {
-                title: "Room for Improvement".to_string(),
-                message: format!("Your completion rate is {:.0}%. Consider setting smaller, more achievable goals to build momentum.", streak.completion_rate * 100.0),
-                insight_type: "recommendation".to_string(),
-                confidence: 0.8,
-                data: Some(serde_json::json!({
+            insights.push(tone.insight("recommendation", 0.8)
+                .title("Room for Improvement", "Low Completion Rate")
+                .message(
+                    format!("Your completion rate is {}. Consider setting smaller, more achievable goals to build momentum.", rate),
+                    format!("Completion rate: {}. A smaller goal may be easier to complete consistently.", rate))
+                .data(serde_json::json!({
                     "completion_rate": streak.completion_rate,
                     "performance_level": "needs_improvement",
                     "suggestion": "break_down_habit"
-                })),
-            });
+                }))
+                .build());
+        }
+
+        // Recent trend analysis
+        let entries = storage.get_entries_for_habit(habit_id, None)?;
+        if let Some(trend) = self.detect_trend_in_tone(habit, &entries, tone) {
+            insights.push(trend);
+        }
+
+        if let Some(weakest_day) = self.detect_weakest_weekday_in_tone(habit, &entries, tone) {
+            insights.push(weakest_day);
+        }
+
+        if let Some(outliers) = self.detect_value_outliers_in_tone(habit, &entries, tone) {
+            insights.push(outliers);
+        }
+
+        if let Some(spread) = self.detect_intensity_spread_in_tone(habit, &entries, tone) {
+            insights.push(spread);
+        }
+
+        if let Some(mood) = self.detect_dominant_mood_in_tone(habit, &entries, tone) {
+            insights.push(mood);
+        }
+
+        let date_basis = storage.get_date_basis()?;
+        if let Some(mixed) = Self::detect_mixed_date_basis_in_tone(habit, &entries, &date_basis, tone) {
+            insights.push(mixed);
         }
 
         Ok(insights)
     }
 
+    /// Detect whether a habit is trending up or down
+    ///
+    /// Compares the completion rate of the most recent 7 days with the 7 days
+    /// before that. Returns a "pattern" insight labelled "improving", "declining"
+    /// or "steady", or None when there isn't enough history to compare.
+    pub fn detect_trend(&self, habit: &Habit, entries: &[HabitEntry]) -> Option<Insight> {
+        self.detect_trend_in_tone(habit, entries, self.config.tone)
+    }
+
+    fn detect_trend_in_tone(&self, habit: &Habit, entries: &[HabitEntry], tone: Tone) -> Option<Insight> {
+        let today = self.clock.today();
+        let recent_start = today - chrono::Duration::days(TREND_WINDOW_DAYS - 1);
+        let previous_start = recent_start - chrono::Duration::days(TREND_WINDOW_DAYS);
+        let previous_end = recent_start - chrono::Duration::days(1);
+
+        // Both windows must fall within the habit's lifetime
+        if habit.created_at.naive_utc().date() > previous_start {
+            return None;
+        }
+
+        let entries_in_span = entries.iter()
+            .filter(|e| e.completed_at >= previous_start && e.completed_at <= today)
+            .count();
+        if entries_in_span < self.config.min_entries_for_analysis {
+            return None;
+        }
+
+        let recent_rate = Self::window_completion_rate(habit, entries, recent_start, today);
+        let previous_rate = Self::window_completion_rate(habit, entries, previous_start, previous_end);
+        let delta = recent_rate - previous_rate;
+
+        let (recent, previous) = (format_rate(recent_rate), format_rate(previous_rate));
+        let builder = tone.insight("pattern", 0.7);
+        let (trend, builder) = if delta >= TREND_THRESHOLD {
+            ("improving", builder
+                .title("Trending Up", "Rate Increased")
+                .message(
+                    format!("You completed '{}' {} of the time this week, up from {} the week before. Keep riding that momentum!",
+                        habit.name, recent, previous),
+                    format!("'{}' was completed {} of the time this week, up from {} the week before.",
+                        habit.name, recent, previous)))
+        } else if delta <= -TREND_THRESHOLD {
+            ("declining", builder
+                .title("Trending Down", "Rate Decreased")
+                .message(
+                    format!("'{}' dropped to {} this week from {} the week before. A small win today can turn it around.",
+                        habit.name, recent, previous),
+                    format!("'{}' was completed {} of the time this week, down from {} the week before.",
+                        habit.name, recent, previous)))
+        } else {
+            ("steady", builder
+                .title("Holding Steady", "Rate Unchanged")
+                .message(
+                    format!("'{}' is steady at {} over the last two weeks. Consistency is what builds lasting habits.",
+                        habit.name, recent),
+                    format!("'{}' was completed {} of the time in each of the last two weeks, within {}.",
+                        habit.name, recent, format_rate(TREND_THRESHOLD))))
+        };
+
+        Some(builder
+            .data(serde_json::json!({
+                "trend": trend,
+                "recent_rate": recent_rate,
+                "previous_rate": previous_rate,
+                "delta": delta,
+                "window_days": TREND_WINDOW_DAYS
+            }))
+            .build())
+    }
+
+    /// Find the weekday a daily habit is missed on most often
+    ///
+    /// Looks at up to the last 8 weeks (excluding today, which may still be
+    /// completed) and returns a "recommendation" insight when one weekday's
+    /// completion rate clearly trails every other day. Returns None for
+    /// non-daily habits or when there isn't enough data.
+    pub fn detect_weakest_weekday(&self, habit: &Habit, entries: &[HabitEntry]) -> Option<Insight> {
+        self.detect_weakest_weekday_in_tone(habit, entries, self.config.tone)
+    }
+
+    fn detect_weakest_weekday_in_tone(&self, habit: &Habit, entries: &[HabitEntry], tone: Tone) -> Option<Insight> {
+        if habit.frequency != Frequency::Daily || entries.len() < self.config.min_entries_for_analysis {
+            return None;
+        }
+
+        let today = self.clock.today();
+        let end = today - chrono::Duration::days(1);
+        let start = (today - chrono::Duration::days(WEEKDAY_WINDOW_DAYS))
+            .max(habit.created_at.naive_utc().date());
+
+        let completed_days = entries.iter()
+            .map(|e| e.completed_at)
+            .collect::<std::collections::HashSet<_>>();
+
+        // (scheduled, completed) per weekday, indexed from Monday
+        let mut counts = [(0u32, 0u32); 7];
+        for date in start.iter_days().take_while(|d| *d <= end) {
+            let slot = &mut counts[date.weekday().num_days_from_monday() as usize];
+            slot.0 += 1;
+            if completed_days.contains(&date) {
+                slot.1 += 1;
+            }
+        }
+
+        if counts.iter().any(|(scheduled, _)| *scheduled < WEEKDAY_MIN_OCCURRENCES) {
+            return None;
+        }
+
+        let rates: Vec<(Weekday, f64)> = counts.iter()
+            .enumerate()
+            .map(|(i, (scheduled, completed))| {
+                (Weekday::try_from(i as u8).unwrap(), *completed as f64 / *scheduled as f64)
+            })
+            .collect();
+
+        let mut sorted = rates.clone();
+        sorted.sort_by(|a, b| a.1.total_cmp(&b.1));
+        let (weakest_day, weakest_rate) = sorted[0];
+        if sorted[1].1 - weakest_rate < WEAKEST_DAY_MARGIN {
+            return None;
+        }
+
+        let day_name = weekday_full_name(weakest_day);
+        let weekday_stats: Vec<serde_json::Value> = rates.iter()
+            .zip(counts.iter())
+            .map(|((day, rate), (scheduled, completed))| serde_json::json!({
+                "day": weekday_full_name(*day),
+                "scheduled": scheduled,
+                "completed": completed,
+                "rate": rate
+            }))
+            .collect();
+
+        Some(tone.insight("recommendation", 0.7)
+            .title(format!("Tough {}s", day_name), format!("Lowest Day: {}", day_name))
+            .message(
+                format!("You miss this habit most on {}s ({} completed) — consider a lighter version on that day.",
+                    day_name, format_rate(weakest_rate)),
+                format!("{} has the lowest completion rate ({}). A lighter version on that day may help.",
+                    day_name, format_rate(weakest_rate)))
+            .data(serde_json::json!({
+                "weakest_day": day_name,
+                "weakest_rate": weakest_rate,
+                "weekday_stats": weekday_stats,
+                "window_days": (end - start).num_days() + 1
+            }))
+            .build())
+    }
+
+    /// Flag logged values that look like they were entered in the wrong unit
+    ///
+    /// A value more than `value_outlier_multiplier` times the median of the
+    /// habit's logged values (e.g. 45 among entries of 1-2 "hours") is
+    /// suspicious. Returns a "warning" insight listing those entries, with
+    /// the total and average recomputed without them, or None when fewer
+    /// than four entries carry a value or nothing stands out.
+    pub fn detect_value_outliers(&self, habit: &Habit, entries: &[HabitEntry]) -> Option<Insight> {
+        self.detect_value_outliers_in_tone(habit, entries, self.config.tone)
+    }
+
+    fn detect_value_outliers_in_tone(&self, habit: &Habit, entries: &[HabitEntry], tone: Tone) -> Option<Insight> {
+        let median = Self::value_median(entries)?;
+        let outliers = self.value_outliers(entries);
+        if outliers.is_empty() {
+            return None;
+        }
+        let typical: Vec<&HabitEntry> = entries.iter()
+            .filter(|e| e.value.is_some() && !outliers.iter().any(|o| o.id == e.id))
+            .collect();
+
+        let typical_total: u64 = typical.iter().filter_map(|e| e.value).map(u64::from).sum();
+        let typical_average = typical_total as f64 / typical.len() as f64;
+        let unit = habit.unit.as_deref().map(|u| format!(" {}", u)).unwrap_or_default();
+        let listed = outliers.iter()
+            .map(|e| format!("{}{} on {}", e.value.unwrap_or(0), unit, e.completed_at))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        // Already factual, so both tones share the wording
+        let message = format!(
+            "{} for '{}' {} more than {}x the typical {}{} — possible unit mismatch. \
+             Without {}, the total is {}{} (avg {:.1}).",
+            listed, habit.name, if outliers.len() == 1 { "is" } else { "are" },
+            self.config.value_outlier_multiplier, median, unit,
+            if outliers.len() == 1 { "it" } else { "them" }, typical_total, unit, typical_average);
+
+        Some(tone.insight("warning", 0.6)
+            .title("Possible Unit Mismatch", "Possible Unit Mismatch")
+            .message(message.clone(), message)
+            .data(serde_json::json!({
+                "median": median,
+                "multiplier": self.config.value_outlier_multiplier,
+                "outliers": outliers.iter()
+                    .map(|e| serde_json::json!({"date": e.completed_at.to_string(), "value": e.value}))
+                    .collect::<Vec<_>>(),
+                "total_without_outliers": typical_total,
+                "average_without_outliers": typical_average
+            }))
+            .build())
+    }
+
+    /// Warn when some of a habit's entries were dated under an earlier date basis
+    ///
+    /// Days recorded in UTC and in local time can be off by one around
+    /// midnight, so trends and streaks spanning a switch may be skewed.
+    fn detect_mixed_date_basis_in_tone(habit: &Habit, entries: &[HabitEntry], date_basis: &DateBasisInfo, tone: Tone) -> Option<Insight> {
+        let current = date_basis.current();
+        let earlier = entries.iter()
+            .filter(|e| date_basis.basis_for(e.completed_at) != current)
+            .count();
+        if earlier == 0 {
+            return None;
+        }
+
+        let message = format!(
+            "{} of {} entries for '{}' were dated before the switch to the '{}' date basis; \
+             days logged near midnight may be off by one.",
+            earlier, entries.len(), habit.name, current.as_str());
+
+        Some(tone.insight("warning", 0.5)
+            .title("Dates Recorded Two Ways", "Mixed Date Basis")
+            .message(message.clone(), message)
+            .data(serde_json::json!({
+                "current_basis": current.as_str(),
+                "entries_under_earlier_basis": earlier,
+                "periods": date_basis.periods.iter()
+                    .map(|p| serde_json::json!({
+                        "basis": p.basis.as_str(),
+                        "effective_from": p.effective_from.map(|d| d.to_string())
+                    }))
+                    .collect::<Vec<_>>()
+            }))
+            .build())
+    }
+
+    /// Entries whose value is more than `value_outlier_multiplier` times the median
+    ///
+    /// Empty when fewer than four entries carry a value. Status leaves these
+    /// out of its totals, and `detect_value_outliers` explains them.
+    pub fn value_outliers<'a>(&self, entries: &'a [HabitEntry]) -> Vec<&'a HabitEntry> {
+        let Some(median) = Self::value_median(entries) else {
+            return Vec::new();
+        };
+        let threshold = median * self.config.value_outlier_multiplier;
+        entries.iter()
+            .filter(|e| e.value.is_some_and(|v| v as f64 > threshold))
+            .collect()
+    }
+
+    /// Median of the logged values, when there are enough to judge outliers against
+    fn value_median(entries: &[HabitEntry]) -> Option<f64> {
+        let mut values: Vec<u32> = entries.iter().filter_map(|e| e.value).collect();
+        if values.len() < VALUE_OUTLIER_MIN_VALUES {
+            return None;
+        }
+
+        values.sort_unstable();
+        let mid = values.len() / 2;
+        let median = if values.len().is_multiple_of(2) {
+            (values[mid - 1] as f64 + values[mid] as f64) / 2.0
+        } else {
+            values[mid] as f64
+        };
+        (median > 0.0).then_some(median)
+    }
+
+    /// Count of entries rated at each intensity, index 0 holding intensity 1
+    ///
+    /// Entries without an intensity are left out.
+    pub fn intensity_histogram(entries: &[HabitEntry]) -> [u32; 10] {
+        let mut histogram = [0u32; 10];
+        for intensity in entries.iter().filter_map(|e| e.intensity) {
+            if (1..=10).contains(&intensity) {
+                histogram[usize::from(intensity) - 1] += 1;
+            }
+        }
+        histogram
+    }
+
+    /// Describe how a habit's intensity ratings are spread
+    ///
+    /// Needs `min_entries_for_analysis` rated entries. Returns a "pattern"
+    /// insight saying whether sessions cluster around one level, split
+    /// between light (1-3) and high-intensity (7-10) days, or vary widely,
+    /// with the histogram in `data`.
+    pub fn detect_intensity_spread(&self, habit: &Habit, entries: &[HabitEntry]) -> Option<Insight> {
+        self.detect_intensity_spread_in_tone(habit, entries, self.config.tone)
+    }
+
+    fn detect_intensity_spread_in_tone(&self, habit: &Habit, entries: &[HabitEntry], tone: Tone) -> Option<Insight> {
+        let histogram = Self::intensity_histogram(entries);
+        let rated: u32 = histogram.iter().sum();
+        if rated == 0 || (rated as usize) < self.config.min_entries_for_analysis {
+            return None;
+        }
+
+        let share = |from: usize, to: usize| histogram[from - 1..to].iter().sum::<u32>() as f64 / rated as f64;
+        let level = |intensity: usize| match intensity {
+            1..=3 => "light",
+            4..=6 => "moderate",
+            _ => "high-intensity",
+        };
+        let (light, moderate, high) = (share(1, 3), share(4, 6), share(7, 10));
+
+        // The pair of adjacent intensities holding the most entries
+        let peak = (1..10).max_by_key(|&i| (histogram[i - 1] + histogram[i], std::cmp::Reverse(i))).unwrap_or(1);
+        let peak_share = share(peak, peak + 1);
+        let (peak_range, peak_level) = match (histogram[peak - 1], histogram[peak]) {
+            (_, 0) => (peak.to_string(), level(peak)),
+            (0, _) => ((peak + 1).to_string(), level(peak + 1)),
+            (low, high) => (format!("{}–{}", peak, peak + 1), level(if high > low { peak + 1 } else { peak })),
+        };
+        let lowest = histogram.iter().position(|&n| n > 0).unwrap_or(0) + 1;
+        let highest = histogram.iter().rposition(|&n| n > 0).unwrap_or(0) + 1;
+
+        let (shape, description) = if light >= 0.3 && high >= 0.3 && moderate < 0.2 {
+            ("bimodal", "Sessions split between light (1–3) and high-intensity (7–10) days, with few in between".to_string())
+        } else if peak_share < INTENSITY_SPREAD_SHARE {
+            ("spread", format!("Intensity varies widely from {} to {}, with no typical level", lowest, highest))
+        } else if peak_share >= INTENSITY_CONCENTRATED_SHARE {
+            let occasional = if peak_level != "high-intensity" && high > 0.0 {
+                ", with occasional high-intensity days"
+            } else if peak_level != "light" && light > 0.0 {
+                ", with occasional light days"
+            } else {
+                ""
+            };
+            ("concentrated", format!("Most sessions are {} ({}){}", peak_level, peak_range, occasional))
+        } else {
+            ("varied", format!("Sessions are often {} ({}) but range from {} to {}", peak_level, peak_range, lowest, highest))
+        };
+
+        let mean = histogram.iter().enumerate()
+            .map(|(i, &n)| (i + 1) as f64 * n as f64)
+            .sum::<f64>() / rated as f64;
+
+        Some(tone.insight("pattern", 0.6)
+            .title("Intensity Pattern", "Intensity Distribution")
+            .message(
+                format!("{} for '{}'. Knowing your usual effort helps you plan harder and easier days.", description, habit.name),
+                format!("{} for '{}'.", description, habit.name))
+            .data(serde_json::json!({
+                "histogram": histogram,
+                "rated_entries": rated,
+                "average_intensity": mean,
+                "shape": shape
+            }))
+            .build())
+    }
+
+    /// How often each mood was logged, most frequent first
+    ///
+    /// Ties are ordered by mood name. Entries without a mood are left out.
+    pub fn mood_frequency(entries: &[HabitEntry]) -> Vec<(String, u32)> {
+        let mut counts: BTreeMap<&str, u32> = BTreeMap::new();
+        for mood in entries.iter().filter_map(|e| e.mood.as_deref()) {
+            *counts.entry(mood).or_insert(0) += 1;
+        }
+        let mut frequency: Vec<(String, u32)> = counts.into_iter()
+            .map(|(mood, count)| (mood.to_string(), count))
+            .collect();
+        // Stable, so ties keep BTreeMap's alphabetical order
+        frequency.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+        frequency
+    }
+
+    /// Summarize the mood a habit is most often logged with
+    ///
+    /// Needs `min_entries_for_analysis` mood-tagged entries, and the most
+    /// common mood must cover at least 40% of them. Returns a "pattern"
+    /// insight with every mood's count in `data`.
+    pub fn detect_dominant_mood(&self, habit: &Habit, entries: &[HabitEntry]) -> Option<Insight> {
+        self.detect_dominant_mood_in_tone(habit, entries, self.config.tone)
+    }
+
+    fn detect_dominant_mood_in_tone(&self, habit: &Habit, entries: &[HabitEntry], tone: Tone) -> Option<Insight> {
+        let frequency = Self::mood_frequency(entries);
+        let tagged: u32 = frequency.iter().map(|(_, count)| count).sum();
+        if tagged == 0 || (tagged as usize) < self.config.min_entries_for_analysis {
+            return None;
+        }
+
+        let (dominant, count) = frequency.first()?;
+        let share = *count as f64 / tagged as f64;
+        if share < DOMINANT_MOOD_SHARE {
+            return None;
+        }
+
+        Some(tone.insight("pattern", 0.6)
+            .title("How It Feels", "Mood Summary")
+            .message(
+                format!("You usually feel {} around '{}' ({} of {} sessions with a mood). Noticing how a habit feels helps you keep the parts that work.",
+                       dominant, habit.name, count, tagged),
+                format!("Most common mood for '{}': {} ({} of {} tagged sessions).", habit.name, dominant, count, tagged))
+            .data(serde_json::json!({
+                "dominant_mood": dominant,
+                "share": share,
+                "tagged_entries": tagged,
+                "moods": frequency.iter()
+                    .map(|(mood, count)| serde_json::json!({"mood": mood, "count": count}))
+                    .collect::<Vec<_>>()
+            }))
+            .build())
+    }
+
+    /// Completion rate for each of the last `weeks` weeks, oldest first
+    ///
+    /// Each item pairs a week's start date (per the configured week start)
+    /// with the habit's completion rate in that week. The current week only
+    /// counts days up to today, and days before the habit was created are
+    /// ignored. Weeks with no scheduled days report a rate of 0.0.
+    pub fn weekly_completion_series(
+        &self,
+        habit: &Habit,
+        entries: &[HabitEntry],
+        weeks: u32,
+    ) -> Vec<(NaiveDate, f64)> {
+        let today = self.clock.today();
+        let created = habit.created_at.date_naive();
+        let current_week = week_start_for(today, self.config.week_start);
+
+        (0..i64::from(weeks)).rev()
+            .map(|weeks_ago| {
+                let week_start = current_week - chrono::Duration::weeks(weeks_ago);
+                let start = week_start.max(created);
+                let end = (week_start + chrono::Duration::days(6)).min(today);
+                let rate = if start > end {
+                    0.0
+                } else {
+                    Self::window_completion_rate(habit, entries, start, end)
+                };
+                (week_start, rate)
+            })
+            .collect()
+    }
+
+    /// Date each streak milestone was first reached
+    ///
+    /// Walks the habit's completion dates in order, counting consecutive
+    /// days, and records the day the running streak first reached each of
+    /// `STREAK_MILESTONES`. A milestone reached again after a broken streak
+    /// keeps its original date. Milestones never reached are left out.
+    pub fn milestone_history(entries: &[HabitEntry]) -> Vec<(u32, NaiveDate)> {
+        let completed_days: std::collections::BTreeSet<NaiveDate> = entries.iter()
+            .map(|e| e.completed_at)
+            .collect();
+
+        let mut reached = Vec::new();
+        let mut running = 0u32;
+        let mut previous: Option<NaiveDate> = None;
+        for day in completed_days {
+            running = match previous {
+                Some(prev) if day - prev == chrono::Duration::days(1) => running + 1,
+                _ => 1,
+            };
+            previous = Some(day);
+
+            // Milestones are reached in order, so only the next one can be new
+            if let Some(&next) = STREAK_MILESTONES.get(reached.len()) {
+                if running == next {
+                    reached.push((next, day));
+                }
+            }
+        }
+
+        reached
+    }
+
+    /// Completion rate of a habit within an inclusive date window
+    fn window_completion_rate(
+        habit: &Habit,
+        entries: &[HabitEntry],
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> f64 {
+        let completed_days = entries.iter()
+            .filter(|e| e.completed_at >= start && e.completed_at <= end)
+            .map(|e| e.completed_at)
+            .collect::<std::collections::HashSet<_>>()
+            .len() as f64;
+
+        let expected = match habit.frequency {
+            Frequency::Weekly(times) => times as f64 * ((end - start).num_days() + 1) as f64 / 7.0,
+            _ => start.iter_days()
+                .take_while(|d| *d <= end)
+                .filter(|d| habit.frequency.is_scheduled_for_date_since(*d, habit.created_at.date_naive()))
+                .count() as f64,
+        };
+
+        if expected <= 0.0 {
+            return 0.0;
+        }
+
+        (completed_days / expected).min(1.0)
+    }
+
     /// Generate overall insights across all habits
     fn generate_overall_insights<S: HabitStorage>(
         &self,
         storage: &S,
         _time_period: &str,
+        include_inactive: bool,
+        tone: Tone,
     ) -> Result<Vec<Insight>, StorageError> {
         let mut insights = Vec::new();
 
-        // Get all habits
-        let habits = storage.list_habits(None, true)?;
+        // Get all habits, including paused ones if asked
+        let habits = storage.list_habits(None, !include_inactive)?;
 
         if habits.is_empty() {
-            insights.push(Insight {
-                title: "Get Started".to_string(),
-                message: "Welcome to habit tracking! Start by creating your first habit. Choose something small and achievable.".to_string(),
-                insight_type: "recommendation".to_string(),
-                confidence: 1.0,
-                data: Some(serde_json::json!({
+            insights.push(tone.insight("recommendation", 1.0)
+                .title("Get Started", "No Habits Yet")
+                .message(
+                    "Welcome to habit tracking! Start by creating your first habit. Choose something small and achievable.",
+                    "No habits are being tracked. Create a habit to start collecting data; small, achievable habits are easiest to keep.")
+                .data(serde_json::json!({
                     "action": "create_first_habit",
                     "suggestions": ["drink_water", "read_5_minutes", "walk_10_minutes"]
-                })),
-            });
+                }))
+                .build());
             return Ok(insights);
         }
 
         // Analyze habit portfolio
         let mut active_streaks = 0;
-        let mut total_streak_days = 0;
+        let mut active_streak_lengths = Vec::new();
         let mut category_counts = std::collections::HashMap::new();
         let mut completion_rates = Vec::new();
+        let mut dormant_habits = Vec::new();
+        let mut resumable_habits = Vec::new();
+        let today = self.clock.today();
 
         for habit in &habits {
             if let Ok(streak) = storage.get_streak(&habit.id) {
+                if !habit.is_active {
+                    if streak.longest_streak >= REACTIVATE_MIN_STREAK {
+                        resumable_habits.push((habit, streak.longest_streak));
+                    }
+                } else if let Some(last_completed) = streak.last_completed {
+                    let days_since = (today - last_completed).num_days();
+                    if days_since > self.config.dormant_after_days as i64 {
+                        dormant_habits.push((habit, days_since));
+                    }
+                }
                 if streak.current_streak > 0 {
                     active_streaks += 1;
-                    total_streak_days += streak.current_streak;
+                    active_streak_lengths.push((streak.current_streak, streak_unit_label(&habit.frequency)));
                 }
                 // Only include completion rates if we have enough data for analysis
                 if streak.total_completions >= self.config.min_entries_for_analysis as u32 {
@@ -330,97 +1240,122 @@ impl AnalyticsEngine {
                 }
             }
 
-            let category_name = match &habit.category {
-                Category::Health => "Health",
-                Category::Productivity => "Productivity",
-                Category::Social => "Social",
-                Category::Creative => "Creative",
-                Category::Mindfulness => "Mindfulness",
-                Category::Financial => "Financial",
-                Category::Household => "Household",
-                Category::Personal => "Personal",
-                Category::Custom(name) => name,
-            };
+            let category_name = habit.category.display_name_in(&self.categories);
             *category_counts.entry(category_name.to_string()).or_insert(0) += 1;
         }
 
         // Portfolio analysis
         if active_streaks > 0 {
-            insights.push(Insight {
-                title: "Momentum Building".to_string(),
-                message: format!("You have {} active streak{} totaling {} days! This shows great consistency across your habit portfolio.",
-                               active_streaks,
-                               if active_streaks == 1 { "" } else { "s" },
-                               total_streak_days),
-                insight_type: "success".to_string(),
-                confidence: 0.9,
-                data: Some(serde_json::json!({
+            let plural = if active_streaks == 1 { "" } else { "s" };
+            let total = format_streak_totals(active_streak_lengths.iter().copied());
+            insights.push(tone.insight("success", 0.9)
+                .title("Momentum Building", "Active Streaks")
+                .message(
+                    format!("You have {} active streak{} totaling {}! This shows great consistency across your habit portfolio.",
+                            active_streaks, plural, total),
+                    format!("{} active streak{} totaling {}.", active_streaks, plural, total))
+                .data(serde_json::json!({
                     "active_streaks": active_streaks,
-                    "total_streak_days": total_streak_days,
+                    "streak_totals": total,
                     "total_habits": habits.len()
-                })),
-            });
+                }))
+                .build());
         }
 
         // Category diversity insight
         if category_counts.len() >= 3 {
-            insights.push(Insight {
-                title: "Well-Rounded Growth".to_string(),
-                message: format!("You're working on {} different life areas: {}. This balanced approach supports overall life improvement!",
-                               category_counts.len(),
-                               category_counts.keys().map(|k| k.as_str()).collect::<Vec<_>>().join(", ")),
-                insight_type: "success".to_string(),
-                confidence: 0.8,
-                data: Some(serde_json::json!({
+            let areas = category_counts.keys().map(|k| k.as_str()).collect::<Vec<_>>().join(", ");
+            insights.push(tone.insight("success", 0.8)
+                .title("Well-Rounded Growth", "Category Spread")
+                .message(
+                    format!("You're working on {} different life areas: {}. This balanced approach supports overall life improvement!",
+                            category_counts.len(), areas),
+                    format!("Habits cover {} categories: {}.", category_counts.len(), areas))
+                .data(serde_json::json!({
                     "categories": category_counts,
                     "diversity_score": category_counts.len() as f64 / 8.0 // Max 8 categories
-                })),
-            });
+                }))
+                .build());
         } else if habits.len() > 3 {
-            insights.push(Insight {
-                title: "Consider Diversifying".to_string(),
-                message: "Most of your habits are in similar categories. Try adding habits from different life areas for more balanced growth.".to_string(),
-                insight_type: "recommendation".to_string(),
-                confidence: 0.7,
-                data: Some(serde_json::json!({
+            insights.push(tone.insight("recommendation", 0.7)
+                .title("Consider Diversifying", "Few Categories")
+                .message(
+                    "Most of your habits are in similar categories. Try adding habits from different life areas for more balanced growth.",
+                    format!("{} habits span only {} categor{}. Habits in other categories would broaden coverage.",
+                            habits.len(), category_counts.len(), if category_counts.len() == 1 { "y" } else { "ies" }))
+                .data(serde_json::json!({
                     "current_categories": category_counts,
                     "suggested_categories": ["Health", "Mindfulness", "Social", "Creative"]
-                })),
-            });
+                }))
+                .build());
         }
 
         // Overall performance insight
         if !completion_rates.is_empty() {
             let avg_completion = completion_rates.iter().sum::<f64>() / completion_rates.len() as f64;
             if avg_completion >= 0.7 {
-                insights.push(Insight {
-                    title: "Excellent Overall Performance".to_string(),
-                    message: format!("Your average completion rate across all habits is {:.0}%. You're building strong, sustainable routines!", avg_completion * 100.0),
-                    insight_type: "success".to_string(),
-                    confidence: 0.9,
-                    data: Some(serde_json::json!({
+                let average = format_rate(avg_completion);
+                insights.push(tone.insight("success", 0.9)
+                    .title("Excellent Overall Performance", "High Average Completion Rate")
+                    .message(
+                        format!("Your average completion rate across all habits is {}. You're building strong, sustainable routines!", average),
+                        format!("Average completion rate across all habits: {}.", average))
+                    .data(serde_json::json!({
                         "average_completion_rate": avg_completion,
                         "performance_tier": "excellent"
-                    })),
-                });
+                    }))
+                    .build());
             }
         }
 
         // Habit load recommendation
-        if habits.len() > 5 && active_streaks < habits.len() / 2 {
-            insights.push(Insight {
-                title: "Focus Strategy".to_string(),
-                message: format!("You have {} habits but only {} active streaks. Consider focusing on 2-3 core habits to build stronger foundations.",
-                               habits.len(), active_streaks),
-                insight_type: "recommendation".to_string(),
-                confidence: 0.8,
-                data: Some(serde_json::json!({
+        let focus_threshold = (habits.len() as f64 * self.config.focus_active_ratio) as usize;
+        if habits.len() > self.config.focus_min_habits && active_streaks < focus_threshold {
+            insights.push(tone.insight("recommendation", 0.8)
+                .title("Focus Strategy", "Focus Strategy")
+                .message(
+                    format!("You have {} habits but only {} active streaks. Consider focusing on 2-3 core habits to build stronger foundations.",
+                            habits.len(), active_streaks),
+                    format!("{} habits, {} with an active streak. Concentrating on 2-3 core habits may raise that share.",
+                            habits.len(), active_streaks))
+                .data(serde_json::json!({
                     "total_habits": habits.len(),
                     "active_streaks": active_streaks,
                     "recommended_focus": 3,
                     "strategy": "focus_and_build"
-                })),
-            });
+                }))
+                .build());
+        }
+
+        // Nudge habits that haven't been logged in a while
+        for (habit, days_since) in dormant_habits {
+            insights.push(tone.insight("recommendation", 0.7)
+                .title("Gentle Nudge", "Not Logged Recently")
+                .message(
+                    format!("You haven't logged '{}' in {} days — want to restart or pause it?", habit.name, days_since),
+                    format!("'{}' was last logged {} days ago. It can be restarted or paused.", habit.name, days_since))
+                .data(serde_json::json!({
+                    "habit_id": habit.id.to_string(),
+                    "days_since_last_completed": days_since,
+                    "dormant_after_days": self.config.dormant_after_days
+                }))
+                .build());
+        }
+
+        // Paused habits that once went well may be worth picking up again
+        for (habit, longest_streak) in resumable_habits {
+            let longest = format_streak_length(longest_streak, streak_unit_label(&habit.frequency));
+            insights.push(tone.insight("recommendation", 0.6)
+                .title("Worth Resuming?", "Paused Habit")
+                .message(
+                    format!("'{}' is paused, but you once kept it up for {}. Consider reactivating it.", habit.name, longest),
+                    format!("'{}' is paused. Its longest streak was {}.", habit.name, longest))
+                .data(serde_json::json!({
+                    "habit_id": habit.id.to_string(),
+                    "longest_streak": longest_streak,
+                    "action": "reactivate"
+                }))
+                .build());
         }
 
         Ok(insights)
@@ -450,4 +1385,824 @@ impl AnalyticsEngine {
             _ => "just_started",
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Category, EntryId};
+    use crate::storage::{DateBasis, SqliteStorage};
+
+    /// Create a daily habit that was created `age_days` ago
+    fn daily_habit(age_days: i64) -> Habit {
+        Habit::from_existing(
+            HabitId::new(),
+            "Meditate".to_string(),
+            None,
+            Category::Mindfulness,
+            Frequency::Daily,
+            None,
+            None,
+            Utc::now() - chrono::Duration::days(age_days),
+            true,
+        )
+    }
+
+    /// Create entries for the given number of days ago
+    fn entries_for(habit: &Habit, days_ago: &[i64]) -> Vec<HabitEntry> {
+        let today = Utc::now().naive_utc().date();
+        days_ago.iter()
+            .map(|d| HabitEntry::from_existing(
+                EntryId::new(),
+                habit.id.clone(),
+                Utc::now(),
+                today - chrono::Duration::days(*d),
+                None,
+                None,
+                None,
+            ))
+            .collect()
+    }
+
+    fn has_focus_strategy(engine: &AnalyticsEngine, storage: &SqliteStorage) -> bool {
+        engine.generate_overall_insights(storage, "month", false, Tone::Cheerful).unwrap()
+            .iter()
+            .any(|i| i.title == "Focus Strategy")
+    }
+
+    fn trend_of(insight: &Insight) -> &str {
+        insight.data.as_ref().unwrap()["trend"].as_str().unwrap()
+    }
+
+    #[test]
+    fn test_trend_improving() {
+        let engine = AnalyticsEngine::new();
+        let habit = daily_habit(30);
+        // 6 of the last 7 days, 1 of the 7 before
+        let entries = entries_for(&habit, &[0, 1, 2, 3, 4, 5, 10]);
+
+        let insight = engine.detect_trend(&habit, &entries).unwrap();
+        assert_eq!(insight.insight_type, "pattern");
+        assert_eq!(trend_of(&insight), "improving");
+    }
+
+    #[test]
+    fn test_trend_declining() {
+        let engine = AnalyticsEngine::new();
+        let habit = daily_habit(30);
+        // 1 of the last 7 days, 6 of the 7 before
+        let entries = entries_for(&habit, &[3, 7, 8, 9, 10, 11, 12]);
+
+        let insight = engine.detect_trend(&habit, &entries).unwrap();
+        assert_eq!(trend_of(&insight), "declining");
+        let data = insight.data.unwrap();
+        assert!(data["delta"].as_f64().unwrap() < 0.0);
+    }
+
+    #[test]
+    fn test_trend_steady() {
+        let engine = AnalyticsEngine::new();
+        let habit = daily_habit(30);
+        // 4 days in each window
+        let entries = entries_for(&habit, &[0, 2, 4, 6, 7, 9, 11, 13]);
+
+        let insight = engine.detect_trend(&habit, &entries).unwrap();
+        assert_eq!(trend_of(&insight), "steady");
+        let data = insight.data.unwrap();
+        assert_eq!(data["recent_rate"], data["previous_rate"]);
+    }
+
+    #[test]
+    fn test_trend_requires_enough_history() {
+        let engine = AnalyticsEngine::new();
+
+        // Too few entries to compare
+        let habit = daily_habit(30);
+        let entries = entries_for(&habit, &[0, 1]);
+        assert!(engine.detect_trend(&habit, &entries).is_none());
+
+        // Habit is younger than both windows combined
+        let young_habit = daily_habit(5);
+        let entries = entries_for(&young_habit, &[0, 1, 2, 3, 4, 5]);
+        assert!(engine.detect_trend(&young_habit, &entries).is_none());
+    }
+
+    #[test]
+    fn test_focus_strategy_thresholds_are_configurable() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        for _ in 0..4 {
+            storage.create_habit(&daily_habit(10)).unwrap();
+        }
+
+        // Four habits without streaks is below the default portfolio size
+        assert!(!has_focus_strategy(&AnalyticsEngine::new(), &storage));
+
+        let eager = AnalyticsEngine::with_config(AnalyticsConfig {
+            focus_min_habits: 3,
+            ..AnalyticsConfig::default()
+        });
+        assert!(has_focus_strategy(&eager, &storage));
+
+        let relaxed = AnalyticsEngine::with_config(AnalyticsConfig {
+            focus_min_habits: 3,
+            focus_active_ratio: 0.0,
+            ..AnalyticsConfig::default()
+        });
+        assert!(!has_focus_strategy(&relaxed, &storage));
+    }
+
+    /// Days ago (1..=56) of every day except those falling on `skipped`
+    fn days_except(skipped: Weekday) -> Vec<i64> {
+        let today = Utc::now().naive_utc().date();
+        (1..=WEEKDAY_WINDOW_DAYS)
+            .filter(|d| (today - chrono::Duration::days(*d)).weekday() != skipped)
+            .collect()
+    }
+
+    #[test]
+    fn test_weakest_weekday_detects_missed_fridays() {
+        let engine = AnalyticsEngine::new();
+        let habit = daily_habit(90);
+        let entries = entries_for(&habit, &days_except(Weekday::Fri));
+
+        let insight = engine.detect_weakest_weekday(&habit, &entries).unwrap();
+        assert_eq!(insight.insight_type, "recommendation");
+        assert!(insight.message.contains("Fridays"));
+
+        let data = insight.data.unwrap();
+        assert_eq!(data["weakest_day"], "Friday");
+        assert_eq!(data["weekday_stats"].as_array().unwrap().len(), 7);
+        assert_eq!(data["weekday_stats"][4]["completed"], 0);
+    }
+
+    #[test]
+    fn test_weakest_weekday_requires_clear_gap() {
+        let engine = AnalyticsEngine::new();
+        let habit = daily_habit(90);
+        let all_days: Vec<i64> = (1..=WEEKDAY_WINDOW_DAYS).collect();
+        let entries = entries_for(&habit, &all_days);
+
+        assert!(engine.detect_weakest_weekday(&habit, &entries).is_none());
+    }
+
+    #[test]
+    fn test_weakest_weekday_needs_enough_weeks() {
+        let engine = AnalyticsEngine::new();
+        let habit = daily_habit(10);
+        let entries = entries_for(&habit, &[1, 2, 3, 4, 5, 6, 7, 8]);
+
+        assert!(engine.detect_weakest_weekday(&habit, &entries).is_none());
+    }
+
+    #[test]
+    fn test_weakest_weekday_only_for_daily_habits() {
+        let engine = AnalyticsEngine::new();
+        let mut habit = daily_habit(90);
+        habit.frequency = Frequency::Weekdays;
+        let entries = entries_for(&habit, &days_except(Weekday::Fri));
+
+        assert!(engine.detect_weakest_weekday(&habit, &entries).is_none());
+    }
+
+    #[test]
+    fn test_trend_uses_injected_clock() {
+        let today = NaiveDate::from_ymd_opt(2023, 5, 20).unwrap();
+        let engine = AnalyticsEngine::new().with_clock(crate::domain::FixedClock(today));
+        let habit = Habit::from_existing(
+            HabitId::new(),
+            "Meditate".to_string(),
+            None,
+            Category::Mindfulness,
+            Frequency::Daily,
+            None,
+            None,
+            (today - chrono::Duration::days(30)).and_hms_opt(0, 0, 0).unwrap().and_utc(),
+            true,
+        );
+        let entries: Vec<HabitEntry> = [0, 1, 2, 3, 4, 5, 10].iter()
+            .map(|d| HabitEntry::from_existing(
+                EntryId::new(),
+                habit.id.clone(),
+                Utc::now(),
+                today - chrono::Duration::days(*d),
+                None,
+                None,
+                None,
+            ))
+            .collect();
+
+        let insight = engine.detect_trend(&habit, &entries).unwrap();
+        assert_eq!(trend_of(&insight), "improving");
+    }
+
+    /// Engine pinned to Wednesday 2024-01-17, plus a habit created well before
+    fn series_fixture(frequency: Frequency, created: NaiveDate) -> (AnalyticsEngine, Habit) {
+        let today = NaiveDate::from_ymd_opt(2024, 1, 17).unwrap();
+        let engine = AnalyticsEngine::new().with_clock(crate::domain::FixedClock(today));
+        let habit = Habit::from_existing(
+            HabitId::new(),
+            "Stretch".to_string(),
+            None,
+            Category::Health,
+            frequency,
+            None,
+            None,
+            created.and_hms_opt(0, 0, 0).unwrap().and_utc(),
+            true,
+        );
+        (engine, habit)
+    }
+
+    fn entries_on(habit: &Habit, dates: &[(u32, u32)]) -> Vec<HabitEntry> {
+        dates.iter()
+            .map(|(month, day)| HabitEntry::from_existing(
+                EntryId::new(),
+                habit.id.clone(),
+                Utc::now(),
+                NaiveDate::from_ymd_opt(if *month == 12 { 2023 } else { 2024 }, *month, *day).unwrap(),
+                None,
+                None,
+                None,
+            ))
+            .collect()
+    }
+
+    #[test]
+    fn test_weekly_completion_series_known_history() {
+        let (engine, habit) = series_fixture(Frequency::Daily, NaiveDate::from_ymd_opt(2023, 12, 1).unwrap());
+        let mut days: Vec<(u32, u32)> = (1..=7).map(|d| (1, d)).collect();
+        days.extend([(1, 8), (1, 10), (1, 15), (1, 16)]);
+        let entries = entries_on(&habit, &days);
+
+        let series = engine.weekly_completion_series(&habit, &entries, 4);
+
+        let starts: Vec<String> = series.iter().map(|(d, _)| d.to_string()).collect();
+        assert_eq!(starts, vec!["2023-12-25", "2024-01-01", "2024-01-08", "2024-01-15"]);
+        let rates: Vec<f64> = series.iter().map(|(_, r)| *r).collect();
+        assert_eq!(rates[0], 0.0);
+        assert_eq!(rates[1], 1.0);
+        assert!((rates[2] - 2.0 / 7.0).abs() < 1e-9);
+        assert!((rates[3] - 2.0 / 3.0).abs() < 1e-9, "current week only counts days so far");
+    }
+
+    #[test]
+    fn test_weekly_completion_series_handles_unscheduled_weeks() {
+        // Weekends habit seen on a Wednesday: nothing is scheduled yet this week
+        let (engine, habit) = series_fixture(Frequency::Weekends, NaiveDate::from_ymd_opt(2024, 1, 3).unwrap());
+        let entries = entries_on(&habit, &[(1, 6), (1, 13), (1, 14)]);
+
+        let series = engine.weekly_completion_series(&habit, &entries, 4);
+
+        assert_eq!(series.len(), 4);
+        let rates: Vec<f64> = series.iter().map(|(_, r)| *r).collect();
+        // Before creation, half the weekend, full weekend, nothing scheduled yet
+        assert_eq!(rates, vec![0.0, 0.5, 1.0, 0.0]);
+        assert!(engine.weekly_completion_series(&habit, &entries, 0).is_empty());
+    }
+
+    /// Store a habit with the given motivation and streak, returning whether
+    /// its insights include the "Remember Your Why" reminder
+    fn has_why_reminder(motivation: Option<&str>, current: u32, longest: u32) -> bool {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let habit = daily_habit(30).with_motivation(motivation.map(str::to_string));
+        storage.create_habit(&habit).unwrap();
+        let mut streak = Streak::new(habit.id.clone());
+        streak.current_streak = current;
+        streak.longest_streak = longest;
+        streak.total_completions = longest;
+        storage.update_streak(&streak).unwrap();
+
+        AnalyticsEngine::new()
+            .generate_single_habit_insights(&storage, &habit, "month", Tone::Cheerful)
+            .unwrap()
+            .iter()
+            .any(|i| i.title == "Remember Your Why" && i.message == format!("Remember why you started: {}", motivation.unwrap_or_default()))
+    }
+
+    /// Titles of the insights for a daily habit of the given age with a 30% completion rate
+    fn low_rate_insight_titles(age_days: i64) -> Vec<String> {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let habit = daily_habit(age_days);
+        storage.create_habit(&habit).unwrap();
+        let mut streak = Streak::new(habit.id.clone());
+        streak.total_completions = 3;
+        streak.completion_rate = 0.3;
+        storage.update_streak(&streak).unwrap();
+
+        AnalyticsEngine::new()
+            .generate_single_habit_insights(&storage, &habit, "month", Tone::Cheerful)
+            .unwrap()
+            .into_iter()
+            .map(|i| i.title)
+            .collect()
+    }
+
+    #[test]
+    fn test_new_habit_gets_encouragement_instead_of_critique() {
+        let new = low_rate_insight_titles(5);
+        assert!(new.contains(&"Building the Habit".to_string()), "{:?}", new);
+        assert!(!new.contains(&"Room for Improvement".to_string()), "{:?}", new);
+
+        let established = low_rate_insight_titles(180);
+        assert!(established.contains(&"Room for Improvement".to_string()), "{:?}", established);
+        assert!(!established.contains(&"Building the Habit".to_string()), "{:?}", established);
+    }
+
+    #[test]
+    fn test_motivation_reminder_only_for_lapsed_habits() {
+        assert!(has_why_reminder(Some("Keep up with my kids"), 0, 5));
+
+        // Active streak, no motivation, blank motivation, or never started
+        assert!(!has_why_reminder(Some("Keep up with my kids"), 3, 5));
+        assert!(!has_why_reminder(None, 0, 5));
+        assert!(!has_why_reminder(Some("   "), 0, 5));
+        assert!(!has_why_reminder(Some("Keep up with my kids"), 0, 0));
+    }
+
+    #[test]
+    fn test_dormant_habits_get_a_nudge() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let mut dormant = daily_habit(60);
+        dormant.name = "Meditation".to_string();
+        let mut recent = daily_habit(60);
+        recent.name = "Reading".to_string();
+        storage.create_habit(&dormant).unwrap();
+        storage.create_habit(&recent).unwrap();
+        for (habit, days_ago) in [(&dormant, 18), (&recent, 3)] {
+            let mut streak = Streak::new(habit.id.clone());
+            streak.last_completed = Some(today - chrono::Duration::days(days_ago));
+            storage.update_streak(&streak).unwrap();
+        }
+
+        let nudges = |engine: AnalyticsEngine| -> Vec<Insight> {
+            engine.with_clock(crate::domain::FixedClock(today))
+                .generate_overall_insights(&storage, "month", false, Tone::Cheerful)
+                .unwrap()
+                .into_iter()
+                .filter(|i| i.title == "Gentle Nudge")
+                .collect()
+        };
+
+        let flagged = nudges(AnalyticsEngine::new());
+        assert_eq!(flagged.len(), 1);
+        assert_eq!(flagged[0].insight_type, "recommendation");
+        assert_eq!(
+            flagged[0].message,
+            "You haven't logged 'Meditation' in 18 days — want to restart or pause it?"
+        );
+
+        let strict = AnalyticsEngine::with_config(AnalyticsConfig {
+            dormant_after_days: 2,
+            ..AnalyticsConfig::default()
+        });
+        assert_eq!(nudges(strict).len(), 2);
+    }
+
+    fn insights_params(scope: Option<&str>) -> InsightsParams {
+        InsightsParams {
+            habit_id: None,
+            time_period: None,
+            insight_type: None,
+            scope: scope.map(str::to_string),
+            include_inactive: None,
+            format: None,
+            min_confidence: None,
+            tone: None,
+        }
+    }
+
+    #[test]
+    fn test_per_habit_scope_returns_each_habits_insights() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let steady = daily_habit(30);
+        let lapsed = daily_habit(30);
+        storage.create_habit(&steady).unwrap();
+        storage.create_habit(&lapsed).unwrap();
+        for (habit, current, longest) in [(&steady, 10, 10), (&lapsed, 0, 4)] {
+            let mut streak = Streak::new(habit.id.clone());
+            streak.current_streak = current;
+            streak.longest_streak = longest;
+            storage.update_streak(&streak).unwrap();
+        }
+        let engine = AnalyticsEngine::new();
+
+        let response = engine.get_habit_insights(&storage, insights_params(Some("per_habit"))).unwrap();
+        let per_habit = response.per_habit.as_ref().expect("per_habit scope fills the map");
+        assert_eq!(per_habit.len(), 2);
+        let titles = |habit: &Habit| per_habit[&habit.id.to_string()].iter().map(|i| i.title.clone()).collect::<Vec<_>>();
+        assert!(titles(&steady).contains(&"Great Consistency!".to_string()));
+        assert!(titles(&lapsed).contains(&"Time to Restart".to_string()));
+        assert!(!titles(&steady).contains(&"Time to Restart".to_string()));
+
+        // Portfolio insights are still returned alongside the map
+        let portfolio = engine.get_habit_insights(&storage, insights_params(None)).unwrap();
+        assert!(portfolio.per_habit.is_none());
+        assert_eq!(response.insights.len(), portfolio.insights.len());
+
+        let json = serde_json::to_value(&response).unwrap();
+        assert!(json["per_habit"][steady.id.to_string()].is_array());
+        assert!(serde_json::to_value(&portfolio).unwrap().get("per_habit").is_none());
+
+        assert!(matches!(
+            engine.get_habit_insights(&storage, insights_params(Some("everything"))),
+            Err(StorageError::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn test_paused_habits_only_considered_with_include_inactive() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let active = daily_habit(30);
+        let mut paused = daily_habit(60);
+        paused.name = "Guitar".to_string();
+        paused.is_active = false;
+        storage.create_habit(&active).unwrap();
+        storage.create_habit(&paused).unwrap();
+        let mut streak = Streak::new(paused.id.clone());
+        streak.longest_streak = 21;
+        storage.update_streak(&streak).unwrap();
+        let engine = AnalyticsEngine::new();
+
+        let mentions_paused = |include_inactive: Option<bool>, scope: Option<&str>| {
+            let response = engine.get_habit_insights(&storage, InsightsParams {
+                include_inactive,
+                ..insights_params(scope)
+            }).unwrap();
+            let in_portfolio = response.insights.iter().any(|i| i.message.contains("'Guitar' is paused"));
+            let in_per_habit = response.per_habit.is_some_and(|m| m.contains_key(&paused.id.to_string()));
+            (in_portfolio, in_per_habit)
+        };
+
+        assert_eq!(mentions_paused(None, Some("per_habit")), (false, false));
+        assert_eq!(mentions_paused(Some(false), None), (false, false));
+        assert_eq!(mentions_paused(Some(true), Some("per_habit")), (true, true));
+    }
+
+    #[test]
+    fn test_markdown_format_renders_sections_and_habit_table() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let mut steady = daily_habit(30);
+        steady.name = "Run | 5k".to_string();
+        let lapsed = daily_habit(30);
+        storage.create_habit(&steady).unwrap();
+        storage.create_habit(&lapsed).unwrap();
+        for (habit, current, longest, rate) in [(&steady, 12, 12, 0.4), (&lapsed, 0, 4, 0.1)] {
+            let mut streak = Streak::new(habit.id.clone());
+            streak.current_streak = current;
+            streak.longest_streak = longest;
+            streak.completion_rate = rate;
+            storage.update_streak(&streak).unwrap();
+        }
+        let engine = AnalyticsEngine::new();
+
+        let response = engine.get_habit_insights(&storage, InsightsParams {
+            format: Some("markdown".to_string()),
+            ..insights_params(Some("per_habit"))
+        }).unwrap();
+        let doc = &response.message;
+
+        for header in ["# Habit Insights Report", "## Summary", "## Habits", "## Insights", "## Per-Habit Insights"] {
+            assert!(doc.lines().any(|line| line == header), "missing {:?} in:\n{}", header, doc);
+        }
+        assert!(doc.contains("| Habit | Category | Current streak | Longest streak | Completion rate |"));
+        assert!(doc.contains("| Run \\| 5k | Mindfulness | 12 | 12 | 40% |"));
+        assert!(doc.contains("| Meditate | Mindfulness | 0 | 4 | 10% |"));
+        assert!(doc.contains("- Average completion rate: 25%"));
+        assert!(doc.contains("#### Successes\n\n- **Great Consistency!**"));
+        assert!(doc.contains("#### Recommendations\n\n- **Time to Restart**"));
+
+        // Text stays the default, and unknown formats are rejected
+        let text = engine.get_habit_insights(&storage, insights_params(None)).unwrap();
+        assert!(text.message.starts_with("📊 **Habit Insights Report**"));
+        assert!(!text.message.contains("| Habit |"));
+        assert!(matches!(
+            engine.get_habit_insights(&storage, InsightsParams { format: Some("html".to_string()), ..insights_params(None) }),
+            Err(StorageError::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn test_min_confidence_drops_less_certain_insights() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let steady = daily_habit(30);
+        let dormant = daily_habit(60);
+        let mut paused = daily_habit(60);
+        paused.is_active = false;
+        for habit in [&steady, &dormant, &paused] {
+            storage.create_habit(habit).unwrap();
+        }
+        let today = Utc::now().naive_utc().date();
+        let streaks = [(&steady, 10, Some(today)), (&dormant, 0, Some(today - chrono::Duration::days(30))), (&paused, 0, None)];
+        for (habit, current, last_completed) in streaks {
+            let mut streak = Streak::new(habit.id.clone());
+            streak.current_streak = current;
+            streak.longest_streak = 21;
+            streak.last_completed = last_completed;
+            storage.update_streak(&streak).unwrap();
+        }
+        let engine = AnalyticsEngine::new();
+        let titles = |min_confidence: Option<f64>| {
+            let response = engine.get_habit_insights(&storage, InsightsParams {
+                include_inactive: Some(true),
+                min_confidence,
+                ..insights_params(None)
+            }).unwrap();
+            response.insights.iter().map(|i| (i.title.clone(), i.confidence)).collect::<Vec<_>>()
+        };
+
+        let all = titles(None);
+        assert!(all.contains(&("Worth Resuming?".to_string(), 0.6)));
+
+        let confident = titles(Some(0.7));
+        assert!(confident.contains(&("Momentum Building".to_string(), 0.9)));
+        assert!(confident.contains(&("Gentle Nudge".to_string(), 0.7)));
+        assert!(confident.iter().all(|(_, confidence)| *confidence >= 0.7));
+        assert_eq!(confident.len(), all.len() - 1);
+
+        assert!(matches!(
+            engine.get_habit_insights(&storage, InsightsParams { min_confidence: Some(1.5), ..insights_params(None) }),
+            Err(StorageError::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn test_milestone_history_records_first_date_each_milestone_was_reached() {
+        let (_, habit) = series_fixture(Frequency::Daily, NaiveDate::from_ymd_opt(2023, 1, 1).unwrap());
+        let start = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+        let entry_on = |date: NaiveDate| HabitEntry::from_existing(
+            EntryId::new(), habit.id.clone(), Utc::now(), date, None, None, None);
+
+        // A 10-day run, a missed day, then 120 consecutive days
+        let mut entries: Vec<HabitEntry> = (0..10).map(|d| entry_on(start + chrono::Duration::days(d))).collect();
+        let second_run = start + chrono::Duration::days(11);
+        entries.extend((0..120).map(|d| entry_on(second_run + chrono::Duration::days(d))));
+        // A duplicate day must not inflate the streak
+        entries.push(entry_on(second_run));
+
+        let history = AnalyticsEngine::milestone_history(&entries);
+
+        let day = |offset: i64| second_run + chrono::Duration::days(offset);
+        assert_eq!(history, vec![
+            (7, start + chrono::Duration::days(6)),
+            (14, day(13)),
+            (30, day(29)),
+            (60, day(59)),
+            (90, day(89)),
+            (100, day(99)),
+        ]);
+    }
+
+    #[test]
+    fn test_milestone_history_without_a_week_long_streak() {
+        let (_, habit) = series_fixture(Frequency::Daily, NaiveDate::from_ymd_opt(2023, 12, 1).unwrap());
+        let entries = entries_on(&habit, &[(12, 1), (12, 2), (12, 3), (12, 5), (12, 6), (12, 7), (12, 8), (12, 9), (12, 10)]);
+
+        assert!(AnalyticsEngine::milestone_history(&entries).is_empty());
+        assert!(AnalyticsEngine::milestone_history(&[]).is_empty());
+    }
+
+    fn valued_entries(habit: &Habit, values: &[u32]) -> Vec<HabitEntry> {
+        values.iter()
+            .enumerate()
+            .map(|(i, value)| HabitEntry::from_existing(
+                EntryId::new(),
+                habit.id.clone(),
+                Utc::now(),
+                NaiveDate::from_ymd_opt(2024, 1, 1 + i as u32).unwrap(),
+                Some(*value),
+                None,
+                None,
+            ))
+            .collect()
+    }
+
+    #[test]
+    fn test_value_outlier_flagged_as_possible_unit_mismatch() {
+        let (engine, mut habit) = series_fixture(Frequency::Daily, NaiveDate::from_ymd_opt(2023, 12, 1).unwrap());
+        habit.unit = Some("hours".to_string());
+        // Six sessions of 1-2 hours and one logged in minutes by mistake
+        let entries = valued_entries(&habit, &[1, 2, 1, 90, 2, 1, 2]);
+
+        let insight = engine.detect_value_outliers(&habit, &entries).unwrap();
+
+        assert_eq!(insight.insight_type, "warning");
+        assert!(insight.message.contains("90 hours on 2024-01-04"), "{}", insight.message);
+        assert!(insight.message.contains("possible unit mismatch"));
+        assert!(insight.message.contains("the total is 9 hours"));
+        let data = insight.data.unwrap();
+        assert_eq!(data["median"], 2.0);
+        assert_eq!(data["outliers"].as_array().unwrap().len(), 1);
+        assert_eq!(data["total_without_outliers"], 9);
+    }
+
+    #[test]
+    fn test_value_outlier_multiplier_is_configurable() {
+        let (_, habit) = series_fixture(Frequency::Daily, NaiveDate::from_ymd_opt(2023, 12, 1).unwrap());
+        let entries = valued_entries(&habit, &[10, 12, 10, 40, 11]);
+
+        // 40 is under 5x the median of 11
+        assert!(AnalyticsEngine::new().detect_value_outliers(&habit, &entries).is_none());
+
+        let strict = AnalyticsEngine::with_config(AnalyticsConfig { value_outlier_multiplier: 3.0, ..AnalyticsConfig::default() });
+        assert!(strict.detect_value_outliers(&habit, &entries).is_some());
+
+        // Too few values to judge
+        assert!(strict.detect_value_outliers(&habit, &entries[..3]).is_none());
+    }
+
+    fn rated_entries(habit: &Habit, intensities: &[u8]) -> Vec<HabitEntry> {
+        intensities.iter()
+            .enumerate()
+            .map(|(i, intensity)| HabitEntry::from_existing(
+                EntryId::new(),
+                habit.id.clone(),
+                Utc::now(),
+                NaiveDate::from_ymd_opt(2024, 1, 1 + i as u32).unwrap(),
+                None,
+                Some(*intensity),
+                None,
+            ))
+            .collect()
+    }
+
+    #[test]
+    fn test_intensity_spread_concentrated_with_occasional_high() {
+        let (engine, habit) = series_fixture(Frequency::Daily, NaiveDate::from_ymd_opt(2023, 12, 1).unwrap());
+        let entries = rated_entries(&habit, &[5, 6, 5, 5, 6, 6, 5, 9, 5, 6]);
+
+        let insight = engine.detect_intensity_spread(&habit, &entries).unwrap();
+
+        assert_eq!(insight.insight_type, "pattern");
+        assert!(insight.message.starts_with("Most sessions are moderate (5–6), with occasional high-intensity days"), "{}", insight.message);
+        let data = insight.data.unwrap();
+        assert_eq!(data["histogram"], serde_json::json!([0, 0, 0, 0, 5, 4, 0, 0, 1, 0]));
+        assert_eq!(data["shape"], "concentrated");
+    }
+
+    #[test]
+    fn test_intensity_spread_bimodal() {
+        let (engine, habit) = series_fixture(Frequency::Daily, NaiveDate::from_ymd_opt(2023, 12, 1).unwrap());
+        let entries = rated_entries(&habit, &[2, 9, 2, 8, 3, 9, 2, 8, 9, 1]);
+
+        let insight = engine.detect_intensity_spread(&habit, &entries).unwrap();
+
+        assert!(insight.message.contains("split between light (1–3) and high-intensity (7–10) days"), "{}", insight.message);
+        assert_eq!(insight.data.unwrap()["shape"], "bimodal");
+    }
+
+    #[test]
+    fn test_intensity_spread_uniform() {
+        let (engine, habit) = series_fixture(Frequency::Daily, NaiveDate::from_ymd_opt(2023, 12, 1).unwrap());
+        let entries = rated_entries(&habit, &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
+
+        let insight = engine.detect_intensity_spread(&habit, &entries).unwrap();
+
+        assert!(insight.message.contains("varies widely from 1 to 10"), "{}", insight.message);
+        let data = insight.data.unwrap();
+        assert_eq!(data["histogram"], serde_json::json!([1, 1, 1, 1, 1, 1, 1, 1, 1, 1]));
+        assert_eq!(data["shape"], "spread");
+        assert_eq!(data["average_intensity"], 5.5);
+
+        // Unrated entries don't count towards the minimum
+        let mut sparse = rated_entries(&habit, &[3, 7, 5, 4]);
+        sparse.extend(valued_entries(&habit, &[1, 2, 3]));
+        assert!(engine.detect_intensity_spread(&habit, &sparse).is_none());
+    }
+
+    fn mood_entries(habit: &Habit, moods: &[&str]) -> Vec<HabitEntry> {
+        moods.iter()
+            .enumerate()
+            .map(|(i, mood)| HabitEntry::from_existing(
+                EntryId::new(),
+                habit.id.clone(),
+                Utc::now(),
+                NaiveDate::from_ymd_opt(2024, 1, 1 + i as u32).unwrap(),
+                None,
+                None,
+                None,
+            ).with_mood(Some(mood.to_string())))
+            .collect()
+    }
+
+    #[test]
+    fn test_dominant_mood_summarizes_mood_frequency() {
+        let (engine, habit) = series_fixture(Frequency::Daily, NaiveDate::from_ymd_opt(2023, 12, 1).unwrap());
+        let mut entries = mood_entries(&habit, &["energized", "tired", "energized", "calm", "energized", "tired"]);
+        entries.extend(valued_entries(&habit, &[1, 2]));
+
+        assert_eq!(AnalyticsEngine::mood_frequency(&entries), vec![
+            ("energized".to_string(), 3),
+            ("tired".to_string(), 2),
+            ("calm".to_string(), 1),
+        ]);
+
+        let insight = engine.detect_dominant_mood(&habit, &entries).unwrap();
+
+        assert_eq!(insight.insight_type, "pattern");
+        assert!(insight.message.contains("You usually feel energized"), "{}", insight.message);
+        assert!(insight.message.contains("3 of 6 sessions with a mood"));
+        let data = insight.data.unwrap();
+        assert_eq!(data["dominant_mood"], "energized");
+        assert_eq!(data["share"], 0.5);
+        assert_eq!(data["moods"][1], serde_json::json!({"mood": "tired", "count": 2}));
+
+        // Too few tagged entries, or no mood standing out
+        assert!(engine.detect_dominant_mood(&habit, &entries[..4]).is_none());
+        let mixed = mood_entries(&habit, &["calm", "tired", "happy", "stressed", "calm", "focused"]);
+        assert!(engine.detect_dominant_mood(&habit, &mixed).is_none());
+    }
+
+    #[test]
+    fn test_entries_dated_under_an_earlier_basis_are_flagged() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let habit = consistent_habit(&storage);
+        for entry in valued_entries(&habit, &[1, 1, 1, 1, 1]) {
+            storage.create_entry(&entry).unwrap();
+        }
+        let params = || InsightsParams {
+            habit_id: Some(habit.id.to_string()),
+            tone: Some("neutral".to_string()),
+            ..insights_params(None)
+        };
+        let engine = AnalyticsEngine::new();
+        let mixed = |response: &InsightsResponse| response.insights.iter()
+            .find(|i| i.title == "Mixed Date Basis")
+            .cloned();
+
+        assert!(mixed(&engine.get_habit_insights(&storage, params()).unwrap()).is_none());
+
+        storage.set_date_basis(DateBasis::Local, NaiveDate::from_ymd_opt(2024, 1, 4).unwrap()).unwrap();
+        let insight = mixed(&engine.get_habit_insights(&storage, params()).unwrap())
+            .expect("entries from before the switch are flagged");
+
+        assert_eq!(insight.insight_type, "warning");
+        assert!(insight.message.contains("3 of 5 entries"), "{}", insight.message);
+        let data = insight.data.unwrap();
+        assert_eq!(data["current_basis"], "local");
+        assert_eq!(data["entries_under_earlier_basis"], 3);
+        assert_eq!(data["periods"][1]["effective_from"], "2024-01-04");
+    }
+
+    /// Store a daily habit with a 10-day streak and a 90% completion rate
+    fn consistent_habit(storage: &SqliteStorage) -> Habit {
+        let habit = daily_habit(30);
+        storage.create_habit(&habit).unwrap();
+        let mut streak = Streak::new(habit.id.clone());
+        streak.current_streak = 10;
+        streak.longest_streak = 10;
+        streak.total_completions = 27;
+        streak.completion_rate = 0.9;
+        storage.update_streak(&streak).unwrap();
+        habit
+    }
+
+    #[test]
+    fn test_neutral_tone_uses_plain_wording_without_emojis() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let habit = consistent_habit(&storage);
+        let params = |tone: Option<&str>| InsightsParams {
+            habit_id: Some(habit.id.to_string()),
+            tone: tone.map(str::to_string),
+            ..insights_params(None)
+        };
+        let engine = AnalyticsEngine::new();
+
+        let cheerful = engine.get_habit_insights(&storage, params(None)).unwrap();
+        assert!(cheerful.message.contains("🎉 **Great Consistency!**"));
+        assert!(cheerful.message.starts_with("📊"));
+
+        let neutral = engine.get_habit_insights(&storage, params(Some("neutral"))).unwrap();
+        let has_emoji = |text: &str| text.chars().any(|c| c as u32 >= 0x2600);
+        assert!(!has_emoji(&neutral.message), "{}", neutral.message);
+        assert!(neutral.message.starts_with("**Habit Insights Report**"));
+
+        // Same analysis, alternate phrasing
+        assert_eq!(neutral.insights.len(), cheerful.insights.len());
+        let titles: Vec<&str> = neutral.insights.iter().map(|i| i.title.as_str()).collect();
+        assert_eq!(titles, vec!["Consistent Streak", "High Completion Rate"]);
+        assert_eq!(neutral.insights[0].message, "Current streak: 10 days.");
+        assert_eq!(neutral.insights[1].message, "Completion rate: 90%.");
+        assert_eq!(neutral.insights[0].data, cheerful.insights[0].data);
+
+        assert!(matches!(
+            engine.get_habit_insights(&storage, params(Some("grumpy"))),
+            Err(StorageError::Validation(ref msg)) if msg.contains("cheerful, neutral")
+        ));
+    }
+
+    #[test]
+    fn test_tone_defaults_to_config() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        consistent_habit(&storage);
+        let engine = AnalyticsEngine::with_config(AnalyticsConfig { tone: Tone::Neutral, ..AnalyticsConfig::default() });
+
+        let response = engine.get_habit_insights(&storage, insights_params(None)).unwrap();
+
+        assert!(response.insights.iter().any(|i| i.title == "Active Streaks" && i.message == "1 active streak totaling 10 days."));
+        assert!(!response.message.contains('🎉'));
+
+        let cheerful = engine.get_habit_insights(&storage, InsightsParams { tone: Some("cheerful".to_string()), ..insights_params(None) }).unwrap();
+        assert!(cheerful.insights.iter().any(|i| i.title == "Momentum Building"));
+    }
+}
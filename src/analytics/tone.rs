@@ -0,0 +1,89 @@
+/// Tone-aware wording for insights
+///
+/// Every insight is produced by a single analysis and then worded in one of
+/// two voices: the default cheerful one, with emojis and encouragement, or a
+/// neutral one with plain, factual phrasing for clients that prefer it.
+
+use super::Insight;
+use crate::storage::StorageError;
+
+/// Voice used for insight titles, messages and report headings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Tone {
+    /// Encouraging phrasing with emojis (the default)
+    #[default]
+    Cheerful,
+    /// Plain, factual phrasing without emojis
+    Neutral,
+}
+
+impl Tone {
+    /// Parse a tone name as accepted by the insights tool
+    pub fn parse(name: &str) -> Result<Self, StorageError> {
+        match name {
+            "cheerful" => Ok(Tone::Cheerful),
+            "neutral" => Ok(Tone::Neutral),
+            other => Err(StorageError::Validation(format!("Invalid tone '{}'. Valid options: cheerful, neutral", other))),
+        }
+    }
+
+    /// Choose between the cheerful and the neutral wording
+    pub fn pick<T>(self, cheerful: T, neutral: T) -> T {
+        match self {
+            Tone::Cheerful => cheerful,
+            Tone::Neutral => neutral,
+        }
+    }
+
+    /// Prefix `text` with `emoji` in the cheerful tone; neutral text has none
+    pub fn decorate(self, emoji: &str, text: &str) -> String {
+        match self {
+            Tone::Cheerful => format!("{} {}", emoji, text),
+            Tone::Neutral => text.to_string(),
+        }
+    }
+
+    /// Start building an insight worded in this tone
+    pub(crate) fn insight(self, insight_type: &str, confidence: f64) -> InsightBuilder {
+        InsightBuilder {
+            tone: self,
+            insight: Insight {
+                title: String::new(),
+                message: String::new(),
+                insight_type: insight_type.to_string(),
+                confidence,
+                data: None,
+            },
+        }
+    }
+}
+
+/// Builder that keeps only the wording matching its tone
+pub(crate) struct InsightBuilder {
+    tone: Tone,
+    insight: Insight,
+}
+
+impl InsightBuilder {
+    /// Set the title, cheerful wording first
+    pub(crate) fn title(mut self, cheerful: impl Into<String>, neutral: impl Into<String>) -> Self {
+        self.insight.title = self.tone.pick(cheerful.into(), neutral.into());
+        self
+    }
+
+    /// Set the message, cheerful wording first
+    pub(crate) fn message(mut self, cheerful: impl Into<String>, neutral: impl Into<String>) -> Self {
+        self.insight.message = self.tone.pick(cheerful.into(), neutral.into());
+        self
+    }
+
+    /// Attach structured data, which is the same in either tone
+    pub(crate) fn data(mut self, data: serde_json::Value) -> Self {
+        self.insight.data = Some(data);
+        self
+    }
+
+    pub(crate) fn build(self) -> Insight {
+        self.insight
+    }
+}
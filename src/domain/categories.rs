@@ -0,0 +1,81 @@
+/// Registry of deployment-specific categories
+///
+/// The built-in `Category` variants cover general life areas. A deployment
+/// for a narrower domain can register its own first-class categories at
+/// startup (e.g. "cardio" and "strength" for a fitness app). Registered
+/// categories are typed and stored by their bare name, like built-ins,
+/// while `custom:<name>` stays available for ad-hoc names.
+
+use std::collections::BTreeMap;
+use crate::domain::{Category, DomainError};
+
+/// Extra first-class categories, mapping names to display strings
+///
+/// The server keeps its registry in `ServerConfig::categories` and passes
+/// it to category parsing and display.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CategoryRegistry {
+    categories: BTreeMap<String, String>,
+}
+
+impl CategoryRegistry {
+    /// Parse a categories file: a JSON object mapping names to display strings
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use habit_tracker_mcp::domain::CategoryRegistry;
+    ///
+    /// let registry = CategoryRegistry::from_json(r#"{"cardio": "Cardio", "strength": "Strength"}"#).unwrap();
+    /// assert_eq!(registry.len(), 2);
+    /// assert!(CategoryRegistry::from_json(r#"{"health": "Health"}"#).is_err());
+    /// ```
+    pub fn from_json(json: &str) -> Result<Self, DomainError> {
+        let entries: BTreeMap<String, String> = serde_json::from_str(json)
+            .map_err(|e| DomainError::InvalidCategory(format!("Invalid categories file: {}", e)))?;
+
+        let mut registry = Self::default();
+        for (name, display_name) in entries {
+            registry.register(&name, &display_name)?;
+        }
+        Ok(registry)
+    }
+
+    /// Add a category, checking that its name is usable as a first-class name
+    pub fn register(&mut self, name: &str, display_name: &str) -> Result<(), DomainError> {
+        let name = name.trim().to_lowercase();
+        let display_name = display_name.trim();
+        if name.is_empty() || display_name.is_empty() {
+            return Err(DomainError::InvalidCategory("Registered categories need a name and a display name".to_string()));
+        }
+        if name.contains(':') {
+            return Err(DomainError::InvalidCategory(format!("Registered category '{}' cannot contain ':'", name)));
+        }
+        if Category::builtin(&name).is_some() {
+            return Err(DomainError::InvalidCategory(format!("'{}' is already a built-in category", name)));
+        }
+
+        self.categories.insert(name, display_name.to_string());
+        Ok(())
+    }
+
+    /// Number of categories in the registry
+    pub fn len(&self) -> usize {
+        self.categories.len()
+    }
+
+    /// Whether the registry has no categories
+    pub fn is_empty(&self) -> bool {
+        self.categories.is_empty()
+    }
+
+    /// Display string of a registered category, looked up by lowercase name
+    pub fn display_name(&self, name: &str) -> Option<&str> {
+        self.categories.get(name).map(String::as_str)
+    }
+
+    /// Names of every registered category, in alphabetical order
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.categories.keys().map(String::as_str)
+    }
+}
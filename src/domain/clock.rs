@@ -0,0 +1,202 @@
+/// Source of the current date for date-dependent domain logic
+/// 
+/// Streaks, entry validation and analytics all depend on what "today" is.
+/// Routing those lookups through a `Clock` lets tests pin the date instead
+/// of depending on when they happen to run.
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Utc};
+use crate::domain::DomainError;
+
+/// Latest hour `DayRolloverClock::rollover_hour` can be set to
+pub const MAX_DAY_ROLLOVER_HOUR: u32 = 12;
+
+/// Provides the current date
+pub trait Clock: Send + Sync {
+    /// The current date in UTC
+    fn today(&self) -> NaiveDate;
+    
+    /// The current date and time in UTC
+    /// 
+    /// Clocks that only know the date report noon, which no day rollover
+    /// hour moves to another day.
+    fn now(&self) -> NaiveDateTime {
+        self.today().and_time(NaiveTime::from_hms_opt(12, 0, 0).expect("noon is a valid time"))
+    }
+}
+
+/// Clock backed by the system time
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn today(&self) -> NaiveDate {
+        Utc::now().naive_utc().date()
+    }
+    
+    fn now(&self) -> NaiveDateTime {
+        Utc::now().naive_utc()
+    }
+}
+
+/// Clock that always reports the same date
+/// 
+/// # Examples
+/// 
+/// ```rust
+/// use habit_tracker_mcp::domain::{Clock, FixedClock};
+/// use chrono::NaiveDate;
+/// 
+/// let date = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+/// assert_eq!(FixedClock(date).today(), date);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedClock(pub NaiveDate);
+
+impl Clock for FixedClock {
+    fn today(&self) -> NaiveDate {
+        self.0
+    }
+}
+
+/// Clock that always reports the same date and time
+/// 
+/// # Examples
+/// 
+/// ```rust
+/// use habit_tracker_mcp::domain::{Clock, FixedTime};
+/// use chrono::NaiveDate;
+/// 
+/// let now = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap().and_hms_opt(0, 30, 0).unwrap();
+/// assert_eq!(FixedTime(now).today(), now.date());
+/// assert_eq!(FixedTime(now).now(), now);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedTime(pub NaiveDateTime);
+
+impl Clock for FixedTime {
+    fn today(&self) -> NaiveDate {
+        self.0.date()
+    }
+    
+    fn now(&self) -> NaiveDateTime {
+        self.0
+    }
+}
+
+/// Clock whose day starts at `rollover_hour` instead of midnight
+/// 
+/// Before that hour `today` is still the previous day, so a habit finished
+/// at 1am counts for the evening before and status agrees it was done.
+/// 
+/// # Examples
+/// 
+/// ```rust
+/// use habit_tracker_mcp::domain::{Clock, DayRolloverClock, FixedTime};
+/// use chrono::NaiveDate;
+/// 
+/// let day = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+/// let clock = |hour| DayRolloverClock { clock: FixedTime(day.and_hms_opt(hour, 0, 0).unwrap()), rollover_hour: 3 };
+/// assert_eq!(clock(1).today(), day.pred_opt().unwrap());
+/// assert_eq!(clock(3).today(), day);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DayRolloverClock<C> {
+    pub clock: C,
+    /// Hour (0-12, UTC) the day starts at; 0 leaves `clock` unchanged
+    pub rollover_hour: u32,
+}
+
+impl<C: Clock> Clock for DayRolloverClock<C> {
+    fn today(&self) -> NaiveDate {
+        let now = self.clock.now();
+        if now.hour() < self.rollover_hour {
+            now.date() - chrono::Duration::days(1)
+        } else {
+            now.date()
+        }
+    }
+    
+    fn now(&self) -> NaiveDateTime {
+        self.clock.now()
+    }
+}
+
+/// Parse a date given by a user or client
+///
+/// Accepts `today` and `yesterday` (relative to `clock`), `YYYY-MM-DD`, and
+/// ISO 8601 timestamps such as `2024-03-01T08:30:00Z`, which are truncated
+/// to the date they name in their own offset.
+///
+/// # Examples
+///
+/// ```rust
+/// use habit_tracker_mcp::domain::{parse_date_input, FixedClock};
+/// use chrono::NaiveDate;
+///
+/// let clock = FixedClock(NaiveDate::from_ymd_opt(2024, 3, 15).unwrap());
+/// assert_eq!(parse_date_input("yesterday", &clock).unwrap(), NaiveDate::from_ymd_opt(2024, 3, 14).unwrap());
+/// assert_eq!(parse_date_input("2024-03-01T23:30:00-05:00", &clock).unwrap(), NaiveDate::from_ymd_opt(2024, 3, 1).unwrap());
+/// ```
+pub fn parse_date_input(input: &str, clock: &dyn Clock) -> Result<NaiveDate, DomainError> {
+    let trimmed = input.trim();
+    
+    match trimmed.to_lowercase().as_str() {
+        "today" => return Ok(clock.today()),
+        "yesterday" => return Ok(clock.today() - chrono::Duration::days(1)),
+        _ => {}
+    }
+    
+    if let Ok(date) = NaiveDate::parse_from_str(trimmed, "%Y-%m-%d") {
+        return Ok(date);
+    }
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(trimmed) {
+        return Ok(timestamp.date_naive());
+    }
+    // Timestamps without an offset, e.g. "2024-03-01T08:30:00" or "2024-03-01 08:30"
+    for format in ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%d %H:%M"] {
+        if let Ok(timestamp) = NaiveDateTime::parse_from_str(trimmed, format) {
+            return Ok(timestamp.date());
+        }
+    }
+    
+    Err(DomainError::InvalidDate(format!(
+        "'{}' is not a recognized date. Use today, yesterday, YYYY-MM-DD or an ISO timestamp like 2024-03-01T08:30:00Z",
+        input
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+    
+    #[test]
+    fn test_parse_date_input_accepted_forms() {
+        let clock = FixedClock(date(2024, 3, 1));
+        
+        assert_eq!(parse_date_input("today", &clock).unwrap(), date(2024, 3, 1));
+        assert_eq!(parse_date_input(" Yesterday ", &clock).unwrap(), date(2024, 2, 29));
+        assert_eq!(parse_date_input("2024-02-10", &clock).unwrap(), date(2024, 2, 10));
+        assert_eq!(parse_date_input("2024-02-10T08:30:00Z", &clock).unwrap(), date(2024, 2, 10));
+        assert_eq!(parse_date_input("2024-02-10T23:30:00.123-05:00", &clock).unwrap(), date(2024, 2, 10));
+        assert_eq!(parse_date_input("2024-02-10T08:30:00", &clock).unwrap(), date(2024, 2, 10));
+        assert_eq!(parse_date_input("2024-02-10 08:30", &clock).unwrap(), date(2024, 2, 10));
+    }
+    
+    #[test]
+    fn test_parse_date_input_rejects_invalid_strings() {
+        let clock = FixedClock(date(2024, 3, 1));
+        
+        for input in ["tomorrow-ish", "2024-02-30", "10/02/2024", ""] {
+            match parse_date_input(input, &clock) {
+                Err(DomainError::InvalidDate(message)) => {
+                    assert!(message.contains("today, yesterday, YYYY-MM-DD"), "{}", message);
+                }
+                other => panic!("expected InvalidDate for {:?}, got {:?}", input, other),
+            }
+        }
+    }
+}
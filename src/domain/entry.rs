@@ -5,7 +5,58 @@
 
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, NaiveDate, Utc};
-use crate::domain::{EntryId, HabitId, DomainError};
+use crate::domain::{Clock, EntryId, HabitId, DomainError, SystemClock};
+
+/// Default number of days in the past an entry may be logged for
+pub const DEFAULT_MAX_BACKFILL_DAYS: u32 = 365;
+
+/// Largest backfill window that can be configured (about a century)
+pub const MAX_BACKFILL_DAYS_LIMIT: u32 = 36_500;
+
+/// Largest value a single entry can record
+pub const MAX_ENTRY_VALUE: u32 = 100_000;
+
+/// Longest notes a single entry can carry, in characters
+pub const MAX_NOTES_LENGTH: usize = 500;
+
+/// Longest mood tag a single entry can carry, in characters
+pub const MAX_MOOD_LENGTH: usize = 30;
+
+/// Lowest intensity rating an entry can have
+pub const MIN_INTENSITY: u8 = 1;
+
+/// Highest intensity rating an entry can have
+pub const MAX_INTENSITY: u8 = 10;
+
+/// Rules applied when validating a new entry's date
+/// 
+/// Imports of long histories can widen the backfill window; logging a
+/// future date is always rejected.
+#[derive(Clone, Copy)]
+pub struct EntryValidation<'a> {
+    /// Source of "today", including any day rollover
+    pub clock: &'a dyn Clock,
+    /// How many days back an entry may be dated
+    pub max_backfill_days: u32,
+}
+
+impl Default for EntryValidation<'static> {
+    fn default() -> Self {
+        Self {
+            clock: &SystemClock,
+            max_backfill_days: DEFAULT_MAX_BACKFILL_DAYS,
+        }
+    }
+}
+
+impl EntryValidation<'_> {
+    /// Earliest date an entry may be dated, clamped to the calendar's start
+    pub fn earliest_date(&self) -> NaiveDate {
+        self.clock.today()
+            .checked_sub_days(chrono::Days::new(u64::from(self.max_backfill_days)))
+            .unwrap_or(NaiveDate::MIN)
+    }
+}
 
 /// A record of completing a habit on a specific day
 /// 
@@ -21,6 +72,9 @@ pub struct HabitEntry {
     /// When this entry was created/logged
     pub logged_at: DateTime<Utc>,
     /// Which day this completion was for (can be different from logged_at)
+    /// 
+    /// Stored as a plain `YYYY-MM-DD` date; the storage's `DateBasis`
+    /// records which timezone that calendar day was taken in.
     pub completed_at: NaiveDate,
     /// Actual amount achieved (if habit has a target)
     pub value: Option<u32>,
@@ -28,6 +82,9 @@ pub struct HabitEntry {
     pub intensity: Option<u8>,
     /// User's notes about this completion
     pub notes: Option<String>,
+    /// How the user felt, e.g. "energized" or "tired", stored lowercase
+    #[serde(default)]
+    pub mood: Option<String>,
 }
 
 impl HabitEntry {
@@ -41,9 +98,39 @@ impl HabitEntry {
         value: Option<u32>,
         intensity: Option<u8>,
         notes: Option<String>,
+    ) -> Result<Self, DomainError> {
+        Self::new_with_validation(habit_id, completed_at, value, intensity, notes, &EntryValidation::default())
+    }
+    
+    /// Create a new habit entry, validating the date against the given clock
+    /// 
+    /// Same as `new`, but "today" comes from `clock` instead of the system time.
+    pub fn new_with_clock(
+        habit_id: HabitId,
+        completed_at: NaiveDate,
+        value: Option<u32>,
+        intensity: Option<u8>,
+        notes: Option<String>,
+        clock: &dyn Clock,
+    ) -> Result<Self, DomainError> {
+        let validation = EntryValidation {
+            clock,
+            ..EntryValidation::default()
+        };
+        Self::new_with_validation(habit_id, completed_at, value, intensity, notes, &validation)
+    }
+    
+    /// Create a new habit entry using custom date validation rules
+    pub fn new_with_validation(
+        habit_id: HabitId,
+        completed_at: NaiveDate,
+        value: Option<u32>,
+        intensity: Option<u8>,
+        notes: Option<String>,
+        validation: &EntryValidation,
     ) -> Result<Self, DomainError> {
         // Validate the entry data
-        Self::validate_completed_at(&completed_at)?;
+        Self::validate_completed_at(&completed_at, validation)?;
         Self::validate_value(&value)?;
         Self::validate_intensity(&intensity)?;
         Self::validate_notes(&notes)?;
@@ -56,6 +143,7 @@ impl HabitEntry {
             value,
             intensity,
             notes,
+            mood: None,
         })
     }
     
@@ -80,9 +168,43 @@ impl HabitEntry {
             value,
             intensity,
             notes,
+            mood: None,
         }
     }
     
+    /// Set the mood tag without validation (used when loading from database)
+    pub fn with_mood(mut self, mood: Option<String>) -> Self {
+        self.mood = mood;
+        self
+    }
+    
+    /// Set or clear the mood tag with validation
+    /// 
+    /// Moods are free text so users can describe themselves in their own
+    /// words, but are trimmed and lowercased so "Tired" and "tired " count
+    /// as the same mood. A blank mood clears it.
+    pub fn set_mood(&mut self, mood: Option<String>) -> Result<(), DomainError> {
+        let mood = mood
+            .map(|m| m.trim().to_lowercase())
+            .filter(|m| !m.is_empty());
+        
+        if let Some(ref m) = mood {
+            if m.chars().count() > MAX_MOOD_LENGTH {
+                return Err(DomainError::InvalidValue {
+                    message: format!("Mood cannot be longer than {} characters", MAX_MOOD_LENGTH)
+                });
+            }
+            if m.chars().any(char::is_control) {
+                return Err(DomainError::InvalidValue {
+                    message: "Mood cannot contain control characters".to_string()
+                });
+            }
+        }
+        
+        self.mood = mood;
+        Ok(())
+    }
+    
     /// Check if this entry has a numeric value
     pub fn has_value(&self) -> bool {
         self.value.is_some()
@@ -101,8 +223,8 @@ impl HabitEntry {
     // Validation helper methods
     
     /// Validate that the completed_at date is not in the future
-    fn validate_completed_at(date: &NaiveDate) -> Result<(), DomainError> {
-        let today = Utc::now().naive_utc().date();
+    fn validate_completed_at(date: &NaiveDate, validation: &EntryValidation) -> Result<(), DomainError> {
+        let today = validation.clock.today();
         
         if *date > today {
             return Err(DomainError::InvalidDate(
@@ -110,11 +232,10 @@ impl HabitEntry {
             ));
         }
         
-        // Don't allow entries too far in the past (more than 1 year)
-        let one_year_ago = today - chrono::Duration::days(365);
-        if *date < one_year_ago {
+        // Don't allow entries too far in the past (1 year unless configured)
+        if *date < validation.earliest_date() {
             return Err(DomainError::InvalidDate(
-                "Cannot log habits more than 1 year in the past".to_string()
+                format!("Cannot log habits more than {} days in the past", validation.max_backfill_days)
             ));
         }
         
@@ -124,9 +245,9 @@ impl HabitEntry {
     /// Validate the optional value field
     fn validate_value(value: &Option<u32>) -> Result<(), DomainError> {
         if let Some(val) = value {
-            if *val > 100000 {
+            if *val > MAX_ENTRY_VALUE {
                 return Err(DomainError::InvalidValue {
-                    message: "Value cannot exceed 100000".to_string()
+                    message: format!("Value cannot exceed {}", MAX_ENTRY_VALUE)
                 });
             }
         }
@@ -136,9 +257,9 @@ impl HabitEntry {
     /// Validate the optional intensity rating (1-10)
     fn validate_intensity(intensity: &Option<u8>) -> Result<(), DomainError> {
         if let Some(rating) = intensity {
-            if *rating < 1 || *rating > 10 {
+            if !(MIN_INTENSITY..=MAX_INTENSITY).contains(rating) {
                 return Err(DomainError::InvalidValue {
-                    message: "Intensity must be between 1 and 10".to_string()
+                    message: format!("Intensity must be between {} and {}", MIN_INTENSITY, MAX_INTENSITY)
                 });
             }
         }
@@ -146,11 +267,19 @@ impl HabitEntry {
     }
     
     /// Validate the optional notes field
+    /// 
+    /// Notes are echoed back in tool output, so control characters other
+    /// than newlines and tabs are rejected rather than stored.
     fn validate_notes(notes: &Option<String>) -> Result<(), DomainError> {
         if let Some(note_text) = notes {
-            if note_text.len() > 500 {
+            if note_text.chars().count() > MAX_NOTES_LENGTH {
+                return Err(DomainError::InvalidValue {
+                    message: format!("Notes cannot be longer than {} characters", MAX_NOTES_LENGTH)
+                });
+            }
+            if note_text.chars().any(|c| c.is_control() && c != '\n' && c != '\t') {
                 return Err(DomainError::InvalidValue {
-                    message: "Notes cannot be longer than 500 characters".to_string()
+                    message: "Notes cannot contain control characters other than newlines and tabs".to_string()
                 });
             }
         }
@@ -187,6 +316,15 @@ mod tests {
         assert!(entry.has_notes());
     }
     
+    #[test]
+    fn test_entry_value_boundary() {
+        let today = Utc::now().naive_utc().date();
+        let with_value = |value| HabitEntry::new(HabitId::new(), today, Some(value), None, None);
+        
+        assert!(with_value(MAX_ENTRY_VALUE).is_ok());
+        assert!(with_value(MAX_ENTRY_VALUE + 1).is_err());
+    }
+    
     #[test]
     fn test_future_date_invalid() {
         let habit_id = HabitId::new();
@@ -202,4 +340,77 @@ mod tests {
         
         assert!(result.is_err());
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_date_validation_uses_injected_clock() {
+        let clock = crate::domain::FixedClock(NaiveDate::from_ymd_opt(2020, 6, 15).unwrap());
+        let habit_id = HabitId::new();
+        
+        let on_today = HabitEntry::new_with_clock(habit_id.clone(), clock.0, None, None, None, &clock);
+        let tomorrow = HabitEntry::new_with_clock(habit_id.clone(), clock.0 + chrono::Duration::days(1), None, None, None, &clock);
+        let too_old = HabitEntry::new_with_clock(habit_id, clock.0 - chrono::Duration::days(366), None, None, None, &clock);
+        
+        assert!(on_today.is_ok());
+        assert!(tomorrow.is_err());
+        assert!(too_old.is_err());
+    }
+    
+    #[test]
+    fn test_backfill_window_is_configurable() {
+        let habit_id = HabitId::new();
+        let old_date = Utc::now().naive_utc().date() - chrono::Duration::days(400);
+        
+        let default = HabitEntry::new(habit_id.clone(), old_date, None, None, None);
+        assert!(default.is_err());
+        
+        let expanded = EntryValidation {
+            max_backfill_days: 3650,
+            ..EntryValidation::default()
+        };
+        let result = HabitEntry::new_with_validation(habit_id.clone(), old_date, None, None, None, &expanded);
+        assert!(result.is_ok());
+        
+        // Future dates stay rejected regardless of the window
+        let tomorrow = Utc::now().naive_utc().date() + chrono::Duration::days(1);
+        assert!(HabitEntry::new_with_validation(habit_id.clone(), tomorrow, None, None, None, &expanded).is_err());
+        
+        // A window reaching past the calendar's start clamps instead of panicking
+        let unbounded = EntryValidation { max_backfill_days: u32::MAX, ..EntryValidation::default() };
+        assert_eq!(unbounded.earliest_date(), NaiveDate::MIN);
+        assert!(HabitEntry::new_with_validation(habit_id, old_date, None, None, None, &unbounded).is_ok());
+    }
+
+    #[test]
+    fn test_notes_validation() {
+        let habit_id = HabitId::new();
+        let today = Utc::now().naive_utc().date();
+        let with_notes = |notes: String| HabitEntry::new(habit_id.clone(), today, None, None, Some(notes));
+
+        assert!(with_notes("é".repeat(MAX_NOTES_LENGTH)).is_ok());
+        let too_long = with_notes("x".repeat(MAX_NOTES_LENGTH + 1)).unwrap_err();
+        assert!(too_long.to_string().contains("longer than 500"));
+
+        let null_byte = with_notes("ran\0 5k".to_string()).unwrap_err();
+        assert!(null_byte.to_string().contains("control characters"));
+        assert!(with_notes("hill\x1b[2Jrepeats".to_string()).is_err());
+
+        let entry = with_notes("Warm-up:\n\t10 min jog\nIntervals".to_string()).unwrap();
+        assert_eq!(entry.notes.as_deref(), Some("Warm-up:\n\t10 min jog\nIntervals"));
+    }
+
+    #[test]
+    fn test_mood_is_normalized_and_validated() {
+        let mut entry = HabitEntry::new(HabitId::new(), Utc::now().naive_utc().date(), None, None, None).unwrap();
+        assert_eq!(entry.mood, None);
+
+        entry.set_mood(Some("  Energized ".to_string())).unwrap();
+        assert_eq!(entry.mood.as_deref(), Some("energized"));
+        entry.set_mood(Some("   ".to_string())).unwrap();
+        assert_eq!(entry.mood, None);
+
+        let too_long = entry.set_mood(Some("x".repeat(MAX_MOOD_LENGTH + 1))).unwrap_err();
+        assert!(too_long.to_string().contains("longer than 30"));
+        assert!(entry.set_mood(Some("calm\u{7}".to_string())).is_err());
+        assert!(entry.set_mood(Some("ein bisschen müde".to_string())).is_ok());
+    }
+}
@@ -7,6 +7,20 @@ use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 use crate::domain::{Category, Frequency, HabitId, DomainError};
 
+/// Maximum length of a habit's name
+pub const MAX_NAME_LENGTH: usize = 100;
+
+/// Maximum length of a habit's description
+pub const MAX_DESCRIPTION_LENGTH: usize = 500;
+
+/// Maximum length of a habit's motivation ("why")
+pub const MAX_MOTIVATION_LENGTH: usize = 280;
+
+/// Largest target value a habit can be given
+///
+/// Kept at or below `MAX_ENTRY_VALUE` so any target can be logged in full.
+pub const MAX_TARGET_VALUE: u32 = 10_000;
+
 /// A habit represents something the user wants to do regularly
 /// 
 /// This is the core entity in our system. Each habit has a name, category,
@@ -31,6 +45,18 @@ pub struct Habit {
     pub created_at: DateTime<Utc>,
     /// Whether this habit is currently active (can be paused)
     pub is_active: bool,
+    /// User-defined display position (lower comes first), if ordered
+    #[serde(default)]
+    pub position: Option<i32>,
+    /// Earned tokens that can be spent to protect the streak on a missed day
+    #[serde(default)]
+    pub freeze_tokens: u32,
+    /// The user's own reason for building this habit, if they wrote one down
+    #[serde(default)]
+    pub motivation: Option<String>,
+    /// Whether several completions may be logged for the same day
+    #[serde(default)]
+    pub allow_multiple_per_day: bool,
 }
 
 impl Habit {
@@ -62,6 +88,10 @@ impl Habit {
             unit,
             created_at: Utc::now(),
             is_active: true,
+            position: None,
+            freeze_tokens: 0,
+            motivation: None,
+            allow_multiple_per_day: false,
         })
     }
     
@@ -91,7 +121,55 @@ impl Habit {
             unit,
             created_at,
             is_active,
+            position: None,
+            freeze_tokens: 0,
+            motivation: None,
+            allow_multiple_per_day: false,
+        }
+    }
+    
+    /// Set the display position (used when loading from database)
+    pub fn with_position(mut self, position: Option<i32>) -> Self {
+        self.position = position;
+        self
+    }
+    
+    /// Set the number of streak freeze tokens (used when loading from database)
+    pub fn with_freeze_tokens(mut self, freeze_tokens: u32) -> Self {
+        self.freeze_tokens = freeze_tokens;
+        self
+    }
+    
+    /// Set the motivation (used when loading from database)
+    pub fn with_motivation(mut self, motivation: Option<String>) -> Self {
+        self.motivation = motivation;
+        self
+    }
+    
+    /// Allow or forbid several completions on the same day
+    pub fn with_allow_multiple_per_day(mut self, allow_multiple_per_day: bool) -> Self {
+        self.allow_multiple_per_day = allow_multiple_per_day;
+        self
+    }
+    
+    /// Set or clear the user's motivation with validation
+    /// 
+    /// Surrounding whitespace is trimmed and a blank motivation clears it.
+    pub fn set_motivation(&mut self, motivation: Option<String>) -> Result<(), DomainError> {
+        let motivation = motivation
+            .map(|m| m.trim().to_string())
+            .filter(|m| !m.is_empty());
+        
+        if let Some(ref m) = motivation {
+            if m.chars().count() > MAX_MOTIVATION_LENGTH {
+                return Err(DomainError::Validation {
+                    message: format!("Motivation cannot be longer than {} characters", MAX_MOTIVATION_LENGTH)
+                });
+            }
         }
+        
+        self.motivation = motivation;
+        Ok(())
     }
     
     /// Update the habit's properties with validation
@@ -174,9 +252,9 @@ impl Habit {
             ));
         }
         
-        if trimmed.len() > 100 {
+        if trimmed.len() > MAX_NAME_LENGTH {
             return Err(DomainError::InvalidHabitName(
-                "Habit name cannot be longer than 100 characters".to_string()
+                format!("Habit name cannot be longer than {} characters", MAX_NAME_LENGTH)
             ));
         }
         
@@ -186,9 +264,9 @@ impl Habit {
     /// Validate optional description
     fn validate_description(description: &Option<String>) -> Result<(), DomainError> {
         if let Some(desc) = description {
-            if desc.len() > 500 {
+            if desc.len() > MAX_DESCRIPTION_LENGTH {
                 return Err(DomainError::Validation {
-                    message: "Description cannot be longer than 500 characters".to_string()
+                    message: format!("Description cannot be longer than {} characters", MAX_DESCRIPTION_LENGTH)
                 });
             }
         }
@@ -206,9 +284,9 @@ impl Habit {
                     message: "Target value must be greater than 0".to_string()
                 });
             }
-            if *value > 10000 {
+            if *value > MAX_TARGET_VALUE {
                 return Err(DomainError::InvalidValue {
-                    message: "Target value cannot exceed 10000".to_string()
+                    message: format!("Target value cannot exceed {}", MAX_TARGET_VALUE)
                 });
             }
         }
@@ -282,4 +360,43 @@ mod tests {
         
         assert!(result.is_err());
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_target_value_boundary() {
+        let with_target = |target| Habit::new(
+            "Pushups".to_string(),
+            None,
+            Category::Health,
+            Frequency::Daily,
+            Some(target),
+            Some("reps".to_string()),
+        );
+        
+        assert!(with_target(MAX_TARGET_VALUE).is_ok());
+        assert!(with_target(MAX_TARGET_VALUE + 1).is_err());
+        // Any valid target must be loggable as an entry value
+        let today = Utc::now().naive_utc().date();
+        assert!(crate::domain::HabitEntry::new(HabitId::new(), today, Some(MAX_TARGET_VALUE), None, None).is_ok());
+    }
+    
+    #[test]
+    fn test_set_motivation_trims_and_validates() {
+        let mut habit = Habit::new(
+            "Test Habit".to_string(),
+            None,
+            Category::Health,
+            Frequency::Daily,
+            None,
+            None,
+        ).unwrap();
+        
+        habit.set_motivation(Some("  Keep up with my kids  ".to_string())).unwrap();
+        assert_eq!(habit.motivation.as_deref(), Some("Keep up with my kids"));
+        
+        assert!(habit.set_motivation(Some("x".repeat(MAX_MOTIVATION_LENGTH + 1))).is_err());
+        assert_eq!(habit.motivation.as_deref(), Some("Keep up with my kids"));
+        
+        habit.set_motivation(Some("   ".to_string())).unwrap();
+        assert_eq!(habit.motivation, None);
+    }
+}
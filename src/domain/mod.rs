@@ -8,12 +8,18 @@ pub mod habit;
 pub mod entry;  
 pub mod streak;
 pub mod types;
+pub mod clock;
+pub mod vacation;
+pub mod categories;
 
 // Re-export public types for easy access
 pub use habit::*;
 pub use entry::*;
 pub use streak::*;
 pub use types::*;
+pub use clock::*;
+pub use vacation::*;
+pub use categories::CategoryRegistry;
 
 use thiserror::Error;
 
@@ -29,6 +35,9 @@ pub enum DomainError {
     #[error("Invalid frequency: {0}")]
     InvalidFrequency(String),
     
+    #[error("Invalid category: {0}")]
+    InvalidCategory(String),
+    
     #[error("Invalid date: {0}")]
     InvalidDate(String),
     
@@ -4,8 +4,187 @@
 /// for a habit, and provides methods for calculating streaks from habit entries.
 
 use serde::{Deserialize, Serialize};
-use chrono::{NaiveDate, Utc, Datelike};
-use crate::domain::{HabitId, HabitEntry, Frequency};
+use std::collections::BTreeSet;
+use chrono::{NaiveDate, Datelike, Weekday};
+use crate::domain::{Clock, HabitId, HabitEntry, Frequency, SystemClock};
+
+/// Which starting point the completion rate is measured from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum RateBasis {
+    /// Count expected completions from the day the habit was created
+    #[default]
+    SinceCreation,
+    /// Count expected completions from the first logged entry, ignoring
+    /// any dormant period between creation and actually starting
+    SinceFirstEntry,
+}
+
+/// Settings that influence how streaks are calculated
+///
+/// These come from the analytics configuration and let callers adapt
+/// streak semantics (e.g. regional week boundaries) without changing
+/// the calculation code itself.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreakOptions {
+    /// First day of the week, used to group weekly habits into periods
+    pub week_start: Weekday,
+    /// Starting point for completion rate calculation
+    pub rate_basis: RateBasis,
+    /// Let completion rates exceed 1.0 when a habit is done more than scheduled
+    pub allow_over_100_percent: bool,
+}
+
+impl Default for StreakOptions {
+    fn default() -> Self {
+        Self {
+            week_start: Weekday::Mon,
+            rate_basis: RateBasis::default(),
+            allow_over_100_percent: false,
+        }
+    }
+}
+
+/// Inputs to `Streak::calculate`
+/// 
+/// `new` takes what every calculation needs; options, clock, frozen days
+/// and vacation days default to `StreakOptions::default()`, the system
+/// clock and none, and are set with the `with_*` methods.
+/// 
+/// # Examples
+/// 
+/// ```rust
+/// use habit_tracker_mcp::domain::{FixedClock, Frequency, HabitId, Streak, StreakInputs};
+/// use chrono::NaiveDate;
+/// 
+/// let today = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+/// let streak = Streak::calculate(
+///     StreakInputs::new(HabitId::new(), &[], &Frequency::Daily, today)
+///         .with_clock(&FixedClock(today))
+///         .with_frozen_days(&[today]),
+/// );
+/// assert_eq!(streak.current_streak, 0);
+/// ```
+pub struct StreakInputs<'a> {
+    habit_id: HabitId,
+    entries: &'a [HabitEntry],
+    frequency: &'a Frequency,
+    habit_created_at: NaiveDate,
+    options: StreakOptions,
+    clock: &'a dyn Clock,
+    frozen_days: &'a [NaiveDate],
+    vacation_days: &'a BTreeSet<NaiveDate>,
+}
+
+/// Shared empty set for inputs without vacations
+static NO_VACATION_DAYS: BTreeSet<NaiveDate> = BTreeSet::new();
+
+impl<'a> StreakInputs<'a> {
+    /// Start from a habit's entries, frequency and creation date
+    pub fn new(habit_id: HabitId, entries: &'a [HabitEntry], frequency: &'a Frequency, habit_created_at: NaiveDate) -> Self {
+        Self {
+            habit_id,
+            entries,
+            frequency,
+            habit_created_at,
+            options: StreakOptions::default(),
+            clock: &SystemClock,
+            frozen_days: &[],
+            vacation_days: &NO_VACATION_DAYS,
+        }
+    }
+    
+    /// Use custom calculation settings, such as the day weeks start on
+    pub fn with_options(mut self, options: StreakOptions) -> Self {
+        self.options = options;
+        self
+    }
+    
+    /// Calculate relative to the date reported by `clock`
+    pub fn with_clock(mut self, clock: &'a dyn Clock) -> Self {
+        self.clock = clock;
+        self
+    }
+    
+    /// Treat these days as completed for the current streak
+    pub fn with_frozen_days(mut self, frozen_days: &'a [NaiveDate]) -> Self {
+        self.frozen_days = frozen_days;
+        self
+    }
+    
+    /// Skip these scheduled days; see `vacation_days` for building the set
+    pub fn with_vacation_days(mut self, vacation_days: &'a BTreeSet<NaiveDate>) -> Self {
+        self.vacation_days = vacation_days;
+        self
+    }
+}
+
+/// Get the first day of the week containing `date`
+///
+/// # Examples
+///
+/// ```rust
+/// use habit_tracker_mcp::domain::week_start_for;
+/// use chrono::{NaiveDate, Weekday};
+///
+/// let wednesday = NaiveDate::from_ymd_opt(2024, 1, 10).unwrap();
+/// assert_eq!(week_start_for(wednesday, Weekday::Mon), NaiveDate::from_ymd_opt(2024, 1, 8).unwrap());
+/// assert_eq!(week_start_for(wednesday, Weekday::Sun), NaiveDate::from_ymd_opt(2024, 1, 7).unwrap());
+/// ```
+pub fn week_start_for(date: NaiveDate, week_start: Weekday) -> NaiveDate {
+    date - chrono::Duration::days(date.weekday().days_since(week_start) as i64)
+}
+
+/// Current streak of a `Weekly(n)` habit, split at the week in progress
+///
+/// A week counts once at least `n` distinct days in it are done. The week
+/// in progress can't be judged until it ends, so it never breaks the
+/// streak: until its target is met the streak is the run of satisfied
+/// weeks ending last week, and once met it is provisionally counted too.
+/// A past week that missed its target but overlaps a vacation is skipped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct WeeklyStreak {
+    /// Consecutive fully elapsed weeks that met the target, ending last week
+    pub completed_weeks: u32,
+    /// Whether this week has already met its target and is counted
+    pub includes_current_week: bool,
+}
+
+impl WeeklyStreak {
+    /// Work out the weekly streak as of `today` from the days that count as done
+    pub fn calculate(
+        satisfied: &BTreeSet<NaiveDate>,
+        vacation_days: &BTreeSet<NaiveDate>,
+        times_per_week: u8,
+        week_start: Weekday,
+        today: NaiveDate,
+    ) -> Self {
+        let week = |week_start: NaiveDate| week_start..=week_start + chrono::Duration::days(6);
+        let week_met = |week_start: NaiveDate| {
+            satisfied.range(week(week_start)).count() >= times_per_week as usize
+        };
+        
+        let current_week_start = week_start_for(today, week_start);
+        let mut completed_weeks = 0;
+        for week_offset in 1..=52 { // Check up to a year
+            let past_week = current_week_start - chrono::Duration::weeks(week_offset);
+            if week_met(past_week) {
+                completed_weeks += 1;
+            } else if vacation_days.range(week(past_week)).next().is_none() {
+                break;
+            }
+        }
+        
+        Self {
+            completed_weeks,
+            includes_current_week: week_met(current_week_start),
+        }
+    }
+    
+    /// Streak length in weeks, counting this week only if its target is met
+    pub fn current_streak(&self) -> u32 {
+        self.completed_weeks + u32::from(self.includes_current_week)
+    }
+}
 
 /// Calculated streak information for a habit
 /// 
@@ -64,36 +243,63 @@ impl Streak {
     
     /// Calculate streak information from a list of habit entries
     /// 
-    /// This is the main method that analyzes all entries for a habit and
-    /// calculates the current streak, longest streak, and completion rate.
+    /// Shorthand for `Streak::calculate` with default options, the system
+    /// clock and no frozen or vacation days.
     pub fn calculate_from_entries(
         habit_id: HabitId,
         entries: &[HabitEntry],
         frequency: &Frequency,
         habit_created_at: NaiveDate,
     ) -> Self {
+        Self::calculate(StreakInputs::new(habit_id, entries, frequency, habit_created_at))
+    }
+    
+    /// Calculate streak information from everything in `inputs`
+    /// 
+    /// This is the main method that analyzes all entries for a habit and
+    /// calculates the current streak, longest streak, and completion rate.
+    /// Frozen days keep the current streak alive without counting as
+    /// completions, and vacation days neither break nor extend it.
+    pub fn calculate(inputs: StreakInputs) -> Self {
+        let StreakInputs { habit_id, entries, frequency, habit_created_at, options, clock, frozen_days, vacation_days } = inputs;
+        let options = &options;
+        
         if entries.is_empty() {
             return Self::new(habit_id);
         }
         
-        // Sort entries by completion date (newest first)
-        let mut sorted_entries = entries.to_vec();
-        sorted_entries.sort_by(|a, b| b.completed_at.cmp(&a.completed_at));
+        // Streaks and the completion rate count distinct days, so a day with
+        // several entries counts once; total_completions stays the raw count
+        let completed_days: BTreeSet<NaiveDate> = entries.iter().map(|e| e.completed_at).collect();
         
         let total_completions = entries.len() as u32;
-        let last_completed = sorted_entries.first().map(|e| e.completed_at);
-        
-        // Calculate current streak
-        let current_streak = Self::calculate_current_streak(&sorted_entries, frequency);
+        let last_completed = completed_days.last().copied();
         
-        // Calculate longest streak
-        let longest_streak = Self::calculate_longest_streak(&sorted_entries, frequency);
+        let today = clock.today();
+        let (current_streak, longest_streak) = match entries {
+            // A new habit's lone entry needs none of the per-frequency loops
+            [entry] if frozen_days.is_empty() && vacation_days.is_empty() => {
+                Self::single_entry_streaks(entry.completed_at, frequency, options, today)
+            }
+            _ => {
+                let satisfied: BTreeSet<NaiveDate> = completed_days.iter()
+                    .chain(frozen_days.iter())
+                    .copied()
+                    .collect();
+                (
+                    Self::calculate_current_streak(&satisfied, vacation_days, frequency, options, today),
+                    Self::calculate_longest_streak(&completed_days, frequency, options),
+                )
+            }
+        };
         
         // Calculate completion rate
         let completion_rate = Self::calculate_completion_rate(
-            &sorted_entries,
+            &completed_days,
             frequency,
             habit_created_at,
+            options,
+            today,
         );
         
         Self {
@@ -108,7 +314,12 @@ impl Streak {
     
     /// Check if the habit is currently "on track" based on frequency
     pub fn is_on_track(&self, frequency: &Frequency) -> bool {
-        let today = Utc::now().naive_utc().date();
+        self.is_on_track_with_clock(frequency, &SystemClock)
+    }
+    
+    /// Check if the habit is "on track" as of the date reported by `clock`
+    pub fn is_on_track_with_clock(&self, frequency: &Frequency, clock: &dyn Clock) -> bool {
+        let today = clock.today();
         
         match self.last_completed {
             None => false, // Never completed
@@ -139,6 +350,46 @@ impl Streak {
         }
     }
     
+    /// Blend streak, completion rate and recency into a 0-100 score
+    pub fn consistency_score(&self, frequency: &Frequency) -> u8 {
+        self.consistency_score_with_clock(frequency, &SystemClock)
+    }
+    
+    /// Consistency score as of the date reported by `clock`
+    /// 
+    /// The score adds up three parts:
+    /// - **Rate (50 points):** the completion rate, capped at 100%.
+    /// - **Streak (30 points):** the current streak as a share of about a
+    ///   month's worth of scheduled periods (30 days, 22 weekdays, 8 weekend
+    ///   days, 4 weeks, 4 of each custom day, or 30 days of intervals).
+    /// - **Recency (20 points):** full marks while the last completion is
+    ///   within one scheduled gap (1 day for daily habits, 3 for weekdays,
+    ///   6 for weekends, 7 for weekly and custom, `n` for every `n` days),
+    ///   fading linearly to nothing over the next three gaps.
+    /// 
+    /// A habit that has never been completed scores 0.
+    pub fn consistency_score_with_clock(&self, frequency: &Frequency, clock: &dyn Clock) -> u8 {
+        let Some(last_completed) = self.last_completed else {
+            return 0;
+        };
+        
+        let (month_of_periods, gap_days) = match frequency {
+            Frequency::Daily => (30.0, 1.0),
+            Frequency::Weekdays => (22.0, 3.0),
+            Frequency::Weekends => (8.0, 6.0),
+            Frequency::Weekly(_) => (4.0, 7.0),
+            Frequency::Custom(days) => (4.0 * days.len().max(1) as f64, 7.0),
+            Frequency::Interval(days) => ((30.0 / *days as f64).max(1.0), *days as f64),
+        };
+        
+        let rate_part = 50.0 * self.completion_rate.clamp(0.0, 1.0);
+        let streak_part = 30.0 * (self.current_streak as f64 / month_of_periods).min(1.0);
+        let days_since = (clock.today() - last_completed).num_days().max(0) as f64;
+        let recency_part = 20.0 * (1.0 - (days_since - gap_days).max(0.0) / (3.0 * gap_days)).max(0.0);
+        
+        (rate_part + streak_part + recency_part).round() as u8
+    }
+    
     /// Get a motivational message based on current streak status
     pub fn motivational_message(&self) -> String {
         match self.current_streak {
@@ -154,13 +405,58 @@ impl Streak {
     
     // Private helper methods for streak calculation
     
-    /// Calculate the current active streak
-    fn calculate_current_streak(entries: &[HabitEntry], frequency: &Frequency) -> u32 {
-        if entries.is_empty() {
+    /// Current and longest streak when the only completion is on `completed`
+    ///
+    /// Gives the same results as the general calculation: the streak is 1
+    /// when that day is the latest one the frequency could be judged on,
+    /// and a `Weekly(n)` target above 1 can't be met by a single day.
+    fn single_entry_streaks(
+        completed: NaiveDate,
+        frequency: &Frequency,
+        options: &StreakOptions,
+        today: NaiveDate,
+    ) -> (u32, u32) {
+        let current = match frequency {
+            Frequency::Weekly(times_per_week) => {
+                let this_week = week_start_for(today, options.week_start);
+                let entry_week = week_start_for(completed, options.week_start);
+                *times_per_week <= 1
+                    && (entry_week == this_week || entry_week == this_week - chrono::Duration::weeks(1))
+            }
+            Frequency::Interval(days_interval) => {
+                (0..=i64::from(*days_interval)).contains(&(today - completed).num_days())
+            }
+            // Today if it's scheduled and done, otherwise the last scheduled day before it
+            _ if completed == today => frequency.is_scheduled_for_date(today),
+            _ => {
+                let last_scheduled = (1..=7)
+                    .map(|days_back| today - chrono::Duration::days(days_back))
+                    .find(|day| frequency.is_scheduled_for_date(*day));
+                last_scheduled == Some(completed)
+            }
+        };
+        let longest = match frequency {
+            Frequency::Weekly(times_per_week) => u32::from(*times_per_week <= 1),
+            _ => 1,
+        };
+
+        (u32::from(current), longest.max(u32::from(current)))
+    }
+    
+    /// Calculate the current active streak from the days that count as done
+    ///
+    /// Scheduled days on vacation are stepped over without counting them.
+    fn calculate_current_streak(
+        satisfied: &BTreeSet<NaiveDate>,
+        vacation_days: &BTreeSet<NaiveDate>,
+        frequency: &Frequency,
+        options: &StreakOptions,
+        today: NaiveDate,
+    ) -> u32 {
+        if satisfied.is_empty() {
             return 0;
         }
 
-        let today = Utc::now().naive_utc().date();
         let mut current_streak = 0;
 
         match frequency {
@@ -168,14 +464,16 @@ impl Streak {
                 let mut checking_date = today;
 
                 // Check if we need to start from yesterday (if today isn't completed yet)
-                let has_today = entries.iter().any(|e| e.completed_at == today);
+                let has_today = satisfied.contains(&today);
                 if !has_today {
                     checking_date = today - chrono::Duration::days(1);
                 }
 
                 // Count consecutive days backwards
                 for _ in 0..365 { // Prevent infinite loop
-                    if entries.iter().any(|e| e.completed_at == checking_date) {
+                    if vacation_days.contains(&checking_date) {
+                        checking_date -= chrono::Duration::days(1);
+                    } else if satisfied.contains(&checking_date) {
                         current_streak += 1;
                         checking_date -= chrono::Duration::days(1);
                     } else {
@@ -184,26 +482,9 @@ impl Streak {
                 }
             }
             Frequency::Weekly(times_per_week) => {
-                // For weekly habits, check completion within weekly periods
-                let current_week_start = today - chrono::Duration::days(today.weekday().num_days_from_monday() as i64);
-                let mut consecutive_weeks = 0;
-
-                for week_offset in 0..52 { // Check up to a year
-                    let week_start = current_week_start - chrono::Duration::weeks(week_offset);
-                    let week_end = week_start + chrono::Duration::days(6);
-
-                    let completions_this_week = entries.iter()
-                        .filter(|e| e.completed_at >= week_start && e.completed_at <= week_end)
-                        .count();
-
-                    if completions_this_week >= *times_per_week as usize {
-                        consecutive_weeks += 1;
-                    } else {
-                        break;
-                    }
-                }
-
-                current_streak = consecutive_weeks;
+                // The week in progress only counts once its target is met
+                current_streak = WeeklyStreak::calculate(satisfied, vacation_days, *times_per_week, options.week_start, today)
+                    .current_streak();
             }
             Frequency::Weekdays => {
                 // Check consecutive weekdays (Mon-Fri)
@@ -219,7 +500,7 @@ impl Streak {
 
                 // If today is a weekday and not completed, start from yesterday
                 if !matches!(today.weekday(), chrono::Weekday::Sat | chrono::Weekday::Sun) {
-                    let has_today = entries.iter().any(|e| e.completed_at == today);
+                    let has_today = satisfied.contains(&today);
                     if !has_today {
                         checking_date -= chrono::Duration::days(1);
                         // Skip to previous weekday if needed
@@ -236,7 +517,9 @@ impl Streak {
                         continue;
                     }
 
-                    if entries.iter().any(|e| e.completed_at == checking_date) {
+                    if vacation_days.contains(&checking_date) {
+                        // Neither breaks nor extends the streak
+                    } else if satisfied.contains(&checking_date) {
                         current_streak += 1;
                     } else {
                         break;
@@ -259,7 +542,7 @@ impl Streak {
 
                 // If today is a weekend and not completed, start from yesterday
                 if matches!(today.weekday(), chrono::Weekday::Sat | chrono::Weekday::Sun) {
-                    let has_today = entries.iter().any(|e| e.completed_at == today);
+                    let has_today = satisfied.contains(&today);
                     if !has_today {
                         checking_date -= chrono::Duration::days(1);
                         // Skip to previous weekend if needed
@@ -276,7 +559,9 @@ impl Streak {
                         continue;
                     }
 
-                    if entries.iter().any(|e| e.completed_at == checking_date) {
+                    if vacation_days.contains(&checking_date) {
+                        // Neither breaks nor extends the streak
+                    } else if satisfied.contains(&checking_date) {
                         current_streak += 1;
                     } else {
                         break;
@@ -301,7 +586,7 @@ impl Streak {
 
                 // If today is a target day and not completed, start from previous occurrence
                 if weekdays.contains(&today.weekday()) {
-                    let has_today = entries.iter().any(|e| e.completed_at == today);
+                    let has_today = satisfied.contains(&today);
                     if !has_today {
                         checking_date -= chrono::Duration::days(1);
                         // Find previous target day
@@ -321,7 +606,9 @@ impl Streak {
                         continue;
                     }
 
-                    if entries.iter().any(|e| e.completed_at == checking_date) {
+                    if vacation_days.contains(&checking_date) {
+                        // Neither breaks nor extends the streak
+                    } else if satisfied.contains(&checking_date) {
                         current_streak += 1;
                     } else {
                         break;
@@ -335,17 +622,17 @@ impl Streak {
 
                 // Find the most recent expected date based on interval
                 // This is simplified - ideally we'd track the habit's start date
-                let latest_entry = entries.first().unwrap();
-                let days_since_latest = (today - latest_entry.completed_at).num_days();
+                let latest_completed = *satisfied.last().unwrap();
+                let days_since_latest = (today - latest_completed).num_days();
 
                 // Start from today if it should be done today, otherwise from the last expected date
-                let mut checking_date = if days_since_latest % (*days_interval as i64) == 0 && !entries.iter().any(|e| e.completed_at == today) {
+                let mut checking_date = if days_since_latest % (*days_interval as i64) == 0 && !satisfied.contains(&today) {
                     today - chrono::Duration::days(*days_interval as i64)
                 } else {
                     let mut date = today;
                     // Find the most recent valid interval date
                     for _ in 0..(*days_interval as i64) {
-                        if entries.iter().any(|e| e.completed_at == date) {
+                        if satisfied.contains(&date) {
                             break;
                         }
                         date -= chrono::Duration::days(1);
@@ -355,7 +642,9 @@ impl Streak {
 
                 // Count consecutive intervals
                 for _ in 0..365 { // Prevent infinite loop
-                    if entries.iter().any(|e| e.completed_at == checking_date) {
+                    if vacation_days.contains(&checking_date) {
+                        checking_date -= chrono::Duration::days(*days_interval as i64);
+                    } else if satisfied.contains(&checking_date) {
                         current_streak += 1;
                         checking_date -= chrono::Duration::days(*days_interval as i64);
                     } else {
@@ -368,25 +657,24 @@ impl Streak {
         current_streak
     }
     
-    /// Calculate the longest streak achieved
-    fn calculate_longest_streak(entries: &[HabitEntry], frequency: &Frequency) -> u32 {
-        if entries.is_empty() {
+    /// Calculate the longest streak achieved from the distinct completed days
+    fn calculate_longest_streak(completed_days: &BTreeSet<NaiveDate>, frequency: &Frequency, options: &StreakOptions) -> u32 {
+        if completed_days.is_empty() {
             return 0;
         }
 
-        // Sort entries by completion date (oldest first for longest streak calculation)
-        let mut sorted_entries = entries.to_vec();
-        sorted_entries.sort_by(|a, b| a.completed_at.cmp(&b.completed_at));
+        // The set iterates oldest first, which is the order the runs are counted in
+        let sorted_days: Vec<NaiveDate> = completed_days.iter().copied().collect();
 
         let mut longest_streak = 0;
 
         match frequency {
             Frequency::Daily => {
                 let mut current_streak = 1;
-                let mut last_date = sorted_entries[0].completed_at;
+                let mut last_date = sorted_days[0];
 
-                for entry in sorted_entries.iter().skip(1) {
-                    let days_diff = (entry.completed_at - last_date).num_days();
+                for &date in sorted_days.iter().skip(1) {
+                    let days_diff = (date - last_date).num_days();
 
                     if days_diff == 1 {
                         // Consecutive day
@@ -397,37 +685,34 @@ impl Streak {
                         current_streak = 1;
                     }
 
-                    last_date = entry.completed_at;
+                    last_date = date;
                 }
 
                 // Don't forget the last streak
                 longest_streak = longest_streak.max(current_streak);
             }
             Frequency::Weekly(times_per_week) => {
-                // Group entries by week and find longest consecutive weeks meeting the requirement
-                let mut weeks_map: std::collections::HashMap<i32, u32> = std::collections::HashMap::new();
-
-                for entry in &sorted_entries {
-                    let week_number = entry.completed_at.iso_week().week() as i32;
-                    let year = entry.completed_at.year();
-                    let week_key = year * 100 + week_number; // Unique key for year+week
+                // Group completed days by week (keyed by the week's first day) and find
+                // the longest run of consecutive weeks meeting the requirement
+                let mut weeks_map: std::collections::HashMap<NaiveDate, u32> = std::collections::HashMap::new();
 
+                for &date in &sorted_days {
+                    let week_key = week_start_for(date, options.week_start);
                     *weeks_map.entry(week_key).or_insert(0) += 1;
                 }
 
-                // Sort weeks by week_key
-                let mut week_counts: Vec<(i32, u32)> = weeks_map.into_iter().collect();
+                // Sort weeks chronologically
+                let mut week_counts: Vec<(NaiveDate, u32)> = weeks_map.into_iter().collect();
                 week_counts.sort_by_key(|&(week_key, _)| week_key);
 
                 let mut current_streak = 0;
-                let mut last_week_key = None;
+                let mut last_week_key: Option<NaiveDate> = None;
 
                 for (week_key, count) in week_counts {
                     if count >= *times_per_week as u32 {
                         if let Some(last_key) = last_week_key {
-                            // Check if this week is consecutive to the last qualifying week
-                            if week_key == last_key + 1 || (week_key > last_key + 50 && week_key < last_key + 60) {
-                                // Handle year boundary (week 52/53 -> week 1)
+                            // Check if this week directly follows the last qualifying week
+                            if (week_key - last_key).num_days() == 7 {
                                 current_streak += 1;
                             } else {
                                 longest_streak = longest_streak.max(current_streak);
@@ -448,9 +733,9 @@ impl Streak {
             }
             Frequency::Weekdays => {
                 let mut current_streak = 1;
-                let mut last_date = sorted_entries[0].completed_at;
+                let mut last_date = sorted_days[0];
 
-                for entry in sorted_entries.iter().skip(1) {
+                for &date in sorted_days.iter().skip(1) {
                     let mut expected_date = last_date + chrono::Duration::days(1);
 
                     // Skip weekends
@@ -458,23 +743,23 @@ impl Streak {
                         expected_date += chrono::Duration::days(1);
                     }
 
-                    if entry.completed_at == expected_date {
+                    if date == expected_date {
                         current_streak += 1;
                     } else {
                         longest_streak = longest_streak.max(current_streak);
                         current_streak = 1;
                     }
 
-                    last_date = entry.completed_at;
+                    last_date = date;
                 }
 
                 longest_streak = longest_streak.max(current_streak);
             }
             Frequency::Weekends => {
                 let mut current_streak = 1;
-                let mut last_date = sorted_entries[0].completed_at;
+                let mut last_date = sorted_days[0];
 
-                for entry in sorted_entries.iter().skip(1) {
+                for &date in sorted_days.iter().skip(1) {
                     let mut expected_date = last_date + chrono::Duration::days(1);
 
                     // Skip weekdays
@@ -482,23 +767,23 @@ impl Streak {
                         expected_date += chrono::Duration::days(1);
                     }
 
-                    if entry.completed_at == expected_date {
+                    if date == expected_date {
                         current_streak += 1;
                     } else {
                         longest_streak = longest_streak.max(current_streak);
                         current_streak = 1;
                     }
 
-                    last_date = entry.completed_at;
+                    last_date = date;
                 }
 
                 longest_streak = longest_streak.max(current_streak);
             }
             Frequency::Custom(weekdays) => {
                 let mut current_streak = 1;
-                let mut last_date = sorted_entries[0].completed_at;
+                let mut last_date = sorted_days[0];
 
-                for entry in sorted_entries.iter().skip(1) {
+                for &date in sorted_days.iter().skip(1) {
                     let mut expected_date = last_date + chrono::Duration::days(1);
 
                     // Find next target weekday
@@ -510,14 +795,14 @@ impl Streak {
                         }
                     }
 
-                    if entry.completed_at == expected_date {
+                    if date == expected_date {
                         current_streak += 1;
                     } else {
                         longest_streak = longest_streak.max(current_streak);
                         current_streak = 1;
                     }
 
-                    last_date = entry.completed_at;
+                    last_date = date;
                 }
 
                 longest_streak = longest_streak.max(current_streak);
@@ -525,19 +810,19 @@ impl Streak {
             Frequency::Interval(days_interval) => {
                 // For interval habits, check consecutive intervals
                 let mut current_streak = 1;
-                let mut last_date = sorted_entries[0].completed_at;
+                let mut last_date = sorted_days[0];
 
-                for entry in sorted_entries.iter().skip(1) {
+                for &date in sorted_days.iter().skip(1) {
                     let expected_date = last_date + chrono::Duration::days(*days_interval as i64);
 
-                    if entry.completed_at == expected_date {
+                    if date == expected_date {
                         current_streak += 1;
                     } else {
                         longest_streak = longest_streak.max(current_streak);
                         current_streak = 1;
                     }
 
-                    last_date = entry.completed_at;
+                    last_date = date;
                 }
 
                 longest_streak = longest_streak.max(current_streak);
@@ -547,18 +832,26 @@ impl Streak {
         longest_streak
     }
     
-    /// Calculate completion rate since habit creation
+    /// Calculate completion rate since habit creation (or the first entry)
+    ///
+    /// The rate is capped at 1.0 unless the options allow overachieving.
     fn calculate_completion_rate(
-        entries: &[HabitEntry],
+        completed_days: &BTreeSet<NaiveDate>,
         frequency: &Frequency,
         created_at: NaiveDate,
+        options: &StreakOptions,
+        today: NaiveDate,
     ) -> f64 {
-        if entries.is_empty() {
+        if completed_days.is_empty() {
             return 0.0;
         }
         
-        let today = Utc::now().naive_utc().date();
-        let days_since_creation = (today - created_at).num_days() + 1; // Include creation day
+        let start_date = match options.rate_basis {
+            RateBasis::SinceCreation => created_at,
+            RateBasis::SinceFirstEntry => completed_days.first().copied().unwrap_or(created_at),
+        };
+        
+        let days_since_creation = (today - start_date).num_days() + 1; // Include start day
         
         let expected_completions = match frequency {
             Frequency::Daily => days_since_creation as f64,
@@ -583,14 +876,125 @@ impl Streak {
             return 0.0;
         }
         
-        let actual_completions = entries.len() as f64;
-        (actual_completions / expected_completions).min(1.0) // Cap at 100%
+        let actual_completions = completed_days.len() as f64;
+        let rate = actual_completions / expected_completions;
+        if options.allow_over_100_percent {
+            rate
+        } else {
+            rate.min(1.0) // Cap at 100%
+        }
+    }
+}
+
+/// Format a completion rate (1.0 = 100%) as a whole percentage for display
+///
+/// The rate is floored to two decimals before formatting, so anything short
+/// of a perfect 1.0 can never be shown as "100%". Uncapped rates above 1.0
+/// format the same way, e.g. "166%".
+///
+/// # Examples
+/// ```
+/// use habit_tracker_mcp::domain::format_rate;
+///
+/// assert_eq!(format_rate(0.9996), "99%");
+/// assert_eq!(format_rate(1.0), "100%");
+/// ```
+pub fn format_rate(rate: f64) -> String {
+    // The epsilon absorbs float noise such as 0.29999999 from 3.0 / 10.0
+    let percent = (rate * 100.0 + 1e-9).floor();
+    format!("{:.0}%", percent)
+}
+
+/// Format a completion rate, noting when it is above the habit's target
+///
+/// # Examples
+/// ```
+/// use habit_tracker_mcp::domain::format_rate_vs_target;
+///
+/// assert_eq!(format_rate_vs_target(0.5), "50%");
+/// assert_eq!(format_rate_vs_target(5.0 / 3.0), "166% (above target)");
+/// ```
+pub fn format_rate_vs_target(rate: f64) -> String {
+    if rate > 1.0 {
+        format!("{} (above target)", format_rate(rate))
+    } else {
+        format_rate(rate)
+    }
+}
+
+/// Unit a habit's streak is counted in, in the plural
+///
+/// Weekly habits count qualifying weeks and interval habits count periods;
+/// every other frequency counts scheduled days.
+///
+/// # Examples
+/// ```
+/// use habit_tracker_mcp::domain::{streak_unit_label, Frequency};
+///
+/// assert_eq!(streak_unit_label(&Frequency::Daily), "days");
+/// assert_eq!(streak_unit_label(&Frequency::Weekly(3)), "weeks");
+/// assert_eq!(streak_unit_label(&Frequency::Interval(2)), "periods");
+/// ```
+pub fn streak_unit_label(frequency: &Frequency) -> &'static str {
+    match frequency {
+        Frequency::Weekly(_) => "weeks",
+        Frequency::Interval(_) => "periods",
+        Frequency::Daily | Frequency::Weekdays | Frequency::Weekends | Frequency::Custom(_) => "days",
+    }
+}
+
+/// Format a streak length with its unit, e.g. "1 week" or "4 weeks"
+///
+/// # Examples
+/// ```
+/// use habit_tracker_mcp::domain::format_streak_length;
+///
+/// assert_eq!(format_streak_length(1, "weeks"), "1 week");
+/// assert_eq!(format_streak_length(4, "days"), "4 days");
+/// ```
+pub fn format_streak_length(count: u32, unit: &str) -> String {
+    if count == 1 {
+        format!("1 {}", unit.strip_suffix('s').unwrap_or(unit))
+    } else {
+        format!("{} {}", count, unit)
+    }
+}
+
+/// Sum streak lengths per unit and format them, e.g. "12 days, 3 weeks"
+///
+/// Streaks counted in different units are never added together.
+///
+/// # Examples
+/// ```
+/// use habit_tracker_mcp::domain::format_streak_totals;
+///
+/// assert_eq!(format_streak_totals([(10, "days"), (3, "weeks"), (2, "days")]), "12 days, 3 weeks");
+/// assert_eq!(format_streak_totals([(1, "weeks")]), "1 week");
+/// assert_eq!(format_streak_totals([]), "0 days");
+/// ```
+pub fn format_streak_totals<'a>(streaks: impl IntoIterator<Item = (u32, &'a str)>) -> String {
+    let mut totals: Vec<(&str, u32)> = Vec::new();
+    for (count, unit) in streaks {
+        match totals.iter_mut().find(|(u, _)| *u == unit) {
+            Some((_, total)) => *total += count,
+            None => totals.push((unit, count)),
+        }
+    }
+    let parts: Vec<String> = totals.into_iter()
+        .filter(|(_, total)| *total > 0)
+        .map(|(unit, total)| format_streak_length(total, unit))
+        .collect();
+    if parts.is_empty() {
+        "0 days".to_string()
+    } else {
+        parts.join(", ")
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::Utc;
     
     #[test]
     fn test_new_streak() {
@@ -649,4 +1053,359 @@ mod tests {
         
         assert!(streak_yesterday.is_on_track(&Frequency::Daily));
     }
-}
\ No newline at end of file
+    
+    fn entry_on(habit_id: &HabitId, date: NaiveDate) -> HabitEntry {
+        HabitEntry::from_existing(
+            crate::domain::EntryId::new(),
+            habit_id.clone(),
+            Utc::now(),
+            date,
+            None,
+            None,
+            None,
+        )
+    }
+    
+    #[test]
+    fn test_week_start_for() {
+        let sunday = NaiveDate::from_ymd_opt(2024, 1, 7).unwrap();
+        let monday = NaiveDate::from_ymd_opt(2024, 1, 8).unwrap();
+        
+        assert_eq!(week_start_for(sunday, Weekday::Sun), sunday);
+        assert_eq!(week_start_for(sunday, Weekday::Mon), NaiveDate::from_ymd_opt(2024, 1, 1).unwrap());
+        assert_eq!(week_start_for(monday, Weekday::Mon), monday);
+        assert_eq!(week_start_for(monday, Weekday::Sun), sunday);
+    }
+    
+    #[test]
+    fn test_weekly_longest_streak_depends_on_week_start() {
+        let habit_id = HabitId::new();
+        let created = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        // Sun/Mon pairs straddle Monday-based week boundaries
+        let entries: Vec<HabitEntry> = [(2024, 1, 7), (2024, 1, 8), (2024, 1, 14), (2024, 1, 15)]
+            .iter()
+            .map(|&(y, m, d)| entry_on(&habit_id, NaiveDate::from_ymd_opt(y, m, d).unwrap()))
+            .collect();
+        
+        let monday_start = Streak::calculate(
+            StreakInputs::new(habit_id.clone(), &entries, &Frequency::Weekly(2), created)
+                .with_options(StreakOptions { week_start: Weekday::Mon, ..StreakOptions::default() }),
+        );
+        let sunday_start = Streak::calculate(
+            StreakInputs::new(habit_id, &entries, &Frequency::Weekly(2), created)
+                .with_options(StreakOptions { week_start: Weekday::Sun, ..StreakOptions::default() }),
+        );
+        
+        // Monday weeks: 1, 2, 1 entries -> only one qualifying week
+        assert_eq!(monday_start.longest_streak, 1);
+        // Sunday weeks: 2, 2 entries -> two consecutive qualifying weeks
+        assert_eq!(sunday_start.longest_streak, 2);
+    }
+    
+    #[test]
+    fn test_weekly_current_streak_mid_week_before_and_after_target() {
+        let habit_id = HabitId::new();
+        let created = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        // Wednesday of the third week
+        let today = NaiveDate::from_ymd_opt(2024, 1, 17).unwrap();
+        let day = |d: u32| NaiveDate::from_ymd_opt(2024, 1, d).unwrap();
+        // Two full weeks at 3 times per week, then one day so far this week
+        let mut entries: Vec<HabitEntry> = [1, 3, 5, 8, 10, 12, 15]
+            .iter()
+            .map(|&d| entry_on(&habit_id, day(d)))
+            .collect();
+        let streak_as_of_today = |entries: &[HabitEntry]| Streak::calculate(
+            StreakInputs::new(habit_id.clone(), entries, &Frequency::Weekly(3), created)
+                .with_clock(&crate::domain::FixedClock(today)),
+        );
+        let weekly = |entries: &[HabitEntry]| WeeklyStreak::calculate(
+            &entries.iter().map(|e| e.completed_at).collect(),
+            &BTreeSet::new(),
+            3,
+            Weekday::Mon,
+            today,
+        );
+        
+        // Short of this week's target: the finished weeks still count
+        assert_eq!(weekly(&entries), WeeklyStreak { completed_weeks: 2, includes_current_week: false });
+        assert_eq!(streak_as_of_today(&entries).current_streak, 2);
+        
+        // Target met on Wednesday: this week is counted provisionally
+        entries.push(entry_on(&habit_id, day(16)));
+        entries.push(entry_on(&habit_id, day(17)));
+        assert_eq!(weekly(&entries), WeeklyStreak { completed_weeks: 2, includes_current_week: true });
+        assert_eq!(streak_as_of_today(&entries).current_streak, 3);
+        
+        // A missed past week still breaks the streak
+        let recent_only: Vec<HabitEntry> = entries.into_iter().filter(|e| e.completed_at >= day(15)).collect();
+        assert_eq!(weekly(&recent_only), WeeklyStreak { completed_weeks: 0, includes_current_week: true });
+        assert_eq!(streak_as_of_today(&recent_only).current_streak, 1);
+    }
+    
+    #[test]
+    fn test_weekly_longest_streak_across_year_boundary() {
+        let habit_id = HabitId::new();
+        let created = NaiveDate::from_ymd_opt(2023, 12, 1).unwrap();
+        let entries: Vec<HabitEntry> = [(2023, 12, 27), (2024, 1, 3)]
+            .iter()
+            .map(|&(y, m, d)| entry_on(&habit_id, NaiveDate::from_ymd_opt(y, m, d).unwrap()))
+            .collect();
+        
+        let streak = Streak::calculate_from_entries(habit_id, &entries, &Frequency::Weekly(1), created);
+        
+        assert_eq!(streak.longest_streak, 2);
+    }
+    
+    #[test]
+    fn test_completion_rate_basis_for_late_started_habit() {
+        let habit_id = HabitId::new();
+        let today = Utc::now().naive_utc().date();
+        // Created 90 days ago, but only logged daily for the last 10 days
+        let created = today - chrono::Duration::days(90);
+        let entries: Vec<HabitEntry> = (0..10)
+            .map(|days_ago| entry_on(&habit_id, today - chrono::Duration::days(days_ago)))
+            .collect();
+        
+        let since_creation = Streak::calculate(
+            StreakInputs::new(habit_id.clone(), &entries, &Frequency::Daily, created)
+                .with_options(StreakOptions { rate_basis: RateBasis::SinceCreation, ..StreakOptions::default() }),
+        );
+        let since_first_entry = Streak::calculate(
+            StreakInputs::new(habit_id, &entries, &Frequency::Daily, created)
+                .with_options(StreakOptions { rate_basis: RateBasis::SinceFirstEntry, ..StreakOptions::default() }),
+        );
+        
+        assert!((since_creation.completion_rate - 10.0 / 91.0).abs() < 1e-9);
+        assert_eq!(since_first_entry.completion_rate, 1.0);
+    }
+    
+    #[test]
+    fn test_weekly_overachiever_rate_cap() {
+        let habit_id = HabitId::new();
+        let today = NaiveDate::from_ymd_opt(2024, 3, 17).unwrap(); // Sunday
+        // A single Mon-Sun week with five completions against a target of three
+        let created = today - chrono::Duration::days(6);
+        let entries: Vec<HabitEntry> = (0..5)
+            .map(|days_ago| entry_on(&habit_id, today - chrono::Duration::days(days_ago)))
+            .collect();
+        let rate_with = |options: StreakOptions| Streak::calculate(
+            StreakInputs::new(habit_id.clone(), &entries, &Frequency::Weekly(3), created)
+                .with_options(options)
+                .with_clock(&crate::domain::FixedClock(today)),
+        ).completion_rate;
+        
+        let capped = rate_with(StreakOptions::default());
+        let uncapped = rate_with(StreakOptions { allow_over_100_percent: true, ..StreakOptions::default() });
+        
+        assert_eq!(capped, 1.0);
+        assert!((uncapped - 5.0 / 3.0).abs() < 1e-9);
+        assert_eq!(format_rate_vs_target(capped), "100%");
+        assert_eq!(format_rate_vs_target(uncapped), "166% (above target)");
+    }
+    
+    #[test]
+    fn test_several_entries_on_one_day_count_once_for_streaks() {
+        let habit_id = HabitId::new();
+        let today = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        let created = today - chrono::Duration::days(3);
+        // Four days, with three entries today and two yesterday
+        let entries: Vec<HabitEntry> = [0, 0, 0, 1, 1, 2, 3]
+            .iter()
+            .map(|days_ago| entry_on(&habit_id, today - chrono::Duration::days(*days_ago)))
+            .collect();
+        
+        let streak = Streak::calculate(
+            StreakInputs::new(habit_id, &entries, &Frequency::Daily, created)
+                .with_options(StreakOptions { allow_over_100_percent: true, ..StreakOptions::default() })
+                .with_clock(&crate::domain::FixedClock(today)),
+        );
+        
+        assert_eq!(streak.current_streak, 4);
+        assert_eq!(streak.longest_streak, 4);
+        assert_eq!(streak.total_completions, 7);
+        assert_eq!(streak.completion_rate, 1.0);
+    }
+    
+    #[test]
+    fn test_same_day_duplicates_in_past_runs_count_once() {
+        let habit_id = HabitId::new();
+        let today = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap(); // A Friday
+        let days_ago = |d: i64| entry_on(&habit_id, today - chrono::Duration::days(d));
+        // A finished three-day run logged twice on its middle day, then a gap
+        let entries = vec![days_ago(10), days_ago(9), days_ago(9), days_ago(8), days_ago(5)];
+        
+        let streak = Streak::calculate(
+            StreakInputs::new(habit_id.clone(), &entries, &Frequency::Daily, today - chrono::Duration::days(10))
+                .with_clock(&crate::domain::FixedClock(today)),
+        );
+        assert_eq!(streak.longest_streak, 3);
+        assert_eq!(streak.current_streak, 0);
+        assert_eq!(streak.total_completions, 5);
+        assert!((streak.completion_rate - 4.0 / 11.0).abs() < 1e-9);
+        
+        // Three logs on one day don't meet a three-times-a-week target
+        let weekly = Streak::calculate(
+            StreakInputs::new(habit_id.clone(), &[days_ago(0), days_ago(0), days_ago(0)], &Frequency::Weekly(3), today - chrono::Duration::days(4))
+                .with_clock(&crate::domain::FixedClock(today)),
+        );
+        assert_eq!(weekly.current_streak, 0);
+        assert_eq!(weekly.longest_streak, 0);
+        assert_eq!(weekly.total_completions, 3);
+    }
+    
+    #[test]
+    fn test_default_rate_basis_is_since_creation() {
+        assert_eq!(StreakOptions::default().rate_basis, RateBasis::SinceCreation);
+    }
+
+    #[test]
+    fn test_format_rate_boundaries() {
+        assert_eq!(format_rate(0.9996), "99%");
+        assert_eq!(format_rate(0.99999), "99%");
+        assert_eq!(format_rate(1.0), "100%");
+        assert_eq!(format_rate(0.0), "0%");
+        assert_eq!(format_rate(3.0 / 10.0), "30%");
+        assert_eq!(format_rate(2.0 / 3.0), "66%");
+    }
+
+    #[test]
+    fn test_current_streak_boundaries_at_fixed_today() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        let clock = crate::domain::FixedClock(today);
+        let habit_id = HabitId::new();
+        let created = today - chrono::Duration::days(30);
+        let days = |offsets: &[i64]| -> Vec<HabitEntry> {
+            offsets.iter()
+                .map(|d| entry_on(&habit_id, today - chrono::Duration::days(*d)))
+                .collect()
+        };
+        let streak_for = |entries: &[HabitEntry]| Streak::calculate(
+            StreakInputs::new(habit_id.clone(), entries, &Frequency::Daily, created).with_clock(&clock),
+        );
+
+        // Including today
+        assert_eq!(streak_for(&days(&[0, 1, 2])).current_streak, 3);
+        // Today not yet done, streak still alive through yesterday
+        assert_eq!(streak_for(&days(&[1, 2, 3])).current_streak, 3);
+        // Missed yesterday breaks the streak
+        assert_eq!(streak_for(&days(&[2, 3, 4])).current_streak, 0);
+        assert_eq!(streak_for(&days(&[2, 3, 4])).longest_streak, 3);
+    }
+
+    #[test]
+    fn test_is_on_track_with_fixed_clock() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        let clock = crate::domain::FixedClock(today);
+        let mut streak = Streak::new(HabitId::new());
+
+        streak.last_completed = Some(today - chrono::Duration::days(1));
+        assert!(streak.is_on_track_with_clock(&Frequency::Daily, &clock));
+
+        streak.last_completed = Some(today - chrono::Duration::days(2));
+        assert!(!streak.is_on_track_with_clock(&Frequency::Daily, &clock));
+        assert!(streak.is_on_track_with_clock(&Frequency::Weekly(2), &clock));
+    }
+
+    #[test]
+    fn test_frozen_day_preserves_current_streak() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        let clock = crate::domain::FixedClock(today);
+        let habit_id = HabitId::new();
+        let day = |d: i64| today - chrono::Duration::days(d);
+        let entries: Vec<HabitEntry> = [0, 1, 3, 4].iter().map(|d| entry_on(&habit_id, day(*d))).collect();
+        let streak_with = |frozen: &[NaiveDate]| Streak::calculate(
+            StreakInputs::new(habit_id.clone(), &entries, &Frequency::Daily, day(30))
+                .with_clock(&clock)
+                .with_frozen_days(frozen),
+        );
+
+        assert_eq!(streak_with(&[]).current_streak, 2);
+
+        let frozen = streak_with(&[day(2)]);
+        assert_eq!(frozen.current_streak, 5);
+        assert_eq!(frozen.longest_streak, 5);
+        // Frozen days are not completions
+        assert_eq!(frozen.total_completions, 4);
+    }
+
+    #[test]
+    fn test_frozen_day_counts_toward_weekly_target() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap(); // Friday
+        let clock = crate::domain::FixedClock(today);
+        let habit_id = HabitId::new();
+        // Last week had one completion, this week two
+        let entries: Vec<HabitEntry> = [9, 1, 2].iter()
+            .map(|d| entry_on(&habit_id, today - chrono::Duration::days(*d)))
+            .collect();
+        let streak_with = |frozen: &[NaiveDate]| Streak::calculate(
+            StreakInputs::new(habit_id.clone(), &entries, &Frequency::Weekly(2), today - chrono::Duration::days(30))
+                .with_clock(&clock)
+                .with_frozen_days(frozen),
+        );
+
+        assert_eq!(streak_with(&[]).current_streak, 1);
+        assert_eq!(streak_with(&[today - chrono::Duration::days(10)]).current_streak, 2);
+    }
+
+    #[test]
+    fn test_consistency_score_combinations() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        let clock = crate::domain::FixedClock(today);
+        let score = |current: u32, rate: f64, days_since: Option<i64>, frequency: &Frequency| {
+            let last = days_since.map(|d| today - chrono::Duration::days(d));
+            Streak::from_existing(HabitId::new(), current, current, last, 0, rate)
+                .consistency_score_with_clock(frequency, &clock)
+        };
+        
+        // Perfect month, done today
+        assert_eq!(score(30, 1.0, Some(0), &Frequency::Daily), 100);
+        // Longer streaks and over-100% rates don't push past 100
+        assert_eq!(score(90, 1.4, Some(0), &Frequency::Daily), 100);
+        // Half the days, a 10-day run still going from yesterday: 25 + 10 + 20
+        assert_eq!(score(10, 0.5, Some(1), &Frequency::Daily), 55);
+        // Lapsed for three days (two past the gap): 30 + 0 + 20 * (1 - 2/3)
+        assert_eq!(score(0, 0.6, Some(3), &Frequency::Daily), 37);
+        // Long lapse loses all recency
+        assert_eq!(score(0, 0.6, Some(10), &Frequency::Daily), 30);
+        // Weekly habit two weeks in, last done five days ago: 40 + 15 + 20
+        assert_eq!(score(2, 0.8, Some(5), &Frequency::Weekly(3)), 75);
+        // Never completed
+        assert_eq!(score(0, 0.0, None, &Frequency::Daily), 0);
+    }
+
+    #[test]
+    fn test_single_entry_shortcut_matches_general_calculation() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 13).unwrap(); // A Wednesday
+        let frequencies = [
+            Frequency::Daily,
+            Frequency::Weekdays,
+            Frequency::Weekends,
+            Frequency::Weekly(1),
+            Frequency::Weekly(3),
+            Frequency::Custom(vec![Weekday::Tue, Weekday::Sat]),
+            Frequency::Interval(1),
+            Frequency::Interval(3),
+        ];
+
+        for week_start in [Weekday::Mon, Weekday::Sun] {
+            let options = StreakOptions { week_start, ..StreakOptions::default() };
+            for frequency in &frequencies {
+                for days_ago in 0..21 {
+                    let completed = today - chrono::Duration::days(days_ago);
+                    let days = BTreeSet::from([completed]);
+                    let general = (
+                        Streak::calculate_current_streak(&days, &BTreeSet::new(), frequency, &options, today),
+                        Streak::calculate_longest_streak(&days, frequency, &options),
+                    );
+                    let general = (general.0, general.1.max(general.0));
+
+                    assert_eq!(
+                        Streak::single_entry_streaks(completed, frequency, &options, today),
+                        general,
+                        "{:?} completed {} days ago, week starting {:?}", frequency, days_ago, week_start
+                    );
+                }
+            }
+        }
+    }
+}
@@ -6,6 +6,7 @@
 use serde::{Deserialize, Serialize};
 use chrono::{NaiveDate, Weekday, Datelike};
 use uuid::Uuid;
+use crate::domain::CategoryRegistry;
 
 /// Unique identifier for a habit
 /// 
@@ -92,6 +93,14 @@ impl std::fmt::Display for EntryId {
     }
 }
 
+/// Names of the built-in categories, as `Category::parse_user_input` accepts them
+pub const BUILTIN_CATEGORY_NAMES: [&str; 8] = [
+    "health", "productivity", "social", "creative", "mindfulness", "financial", "household", "personal",
+];
+
+/// Maximum length of a custom category name
+pub const MAX_CATEGORY_NAME_LENGTH: usize = 50;
+
 /// Categories for organizing habits into different life areas
 /// 
 /// This helps users organize their habits and enables category-based analytics.
@@ -114,12 +123,17 @@ pub enum Category {
     Household,
     /// Personal growth and self-care
     Personal,
+    /// Category from the deployment's `CategoryRegistry`, by lowercase name
+    Registered(String),
     /// User-defined category with custom name
     Custom(String),
 }
 
 impl Category {
     /// Get the display name for this category
+    ///
+    /// Registered categories show their bare name here; use
+    /// `display_name_in` to show the registry's display string instead.
     pub fn display_name(&self) -> &str {
         match self {
             Category::Health => "Health",
@@ -130,16 +144,139 @@ impl Category {
             Category::Financial => "Financial",
             Category::Household => "Household",
             Category::Personal => "Personal",
-            Category::Custom(name) => name,
+            Category::Registered(name) | Category::Custom(name) => name,
+        }
+    }
+
+    /// Get the display name for this category, as `categories` registers it
+    ///
+    /// A registered category missing from `categories` falls back to its name.
+    pub fn display_name_in<'a>(&'a self, categories: &'a CategoryRegistry) -> &'a str {
+        match self {
+            Category::Registered(name) => categories.display_name(name).unwrap_or(name),
+            other => other.display_name(),
+        }
+    }
+
+    /// Parse a user-supplied category such as `"health"` or `"custom:startup"`
+    ///
+    /// Built-in names and those in `categories` are matched case-insensitively. Custom
+    /// names are lowercased and may not reuse either, since `custom:health`
+    /// would otherwise sit next to `Health` as a confusing duplicate.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use habit_tracker_mcp::domain::{Category, CategoryRegistry};
+    ///
+    /// let categories = CategoryRegistry::default();
+    /// assert_eq!(Category::parse_user_input("Health", &categories).unwrap(), Category::Health);
+    /// assert_eq!(Category::parse_user_input("custom:Startup", &categories).unwrap(), Category::Custom("startup".to_string()));
+    /// assert!(Category::parse_user_input("custom:health", &categories).is_err());
+    /// ```
+    pub fn parse_user_input(category_str: &str, categories: &CategoryRegistry) -> Result<Self, crate::domain::DomainError> {
+        let normalized = category_str.trim().to_lowercase();
+        if let Some(category) = Self::first_class(&normalized, categories) {
+            return Ok(category);
+        }
+
+        let Some(name) = normalized.strip_prefix("custom:").map(str::trim) else {
+            let names: String = BUILTIN_CATEGORY_NAMES.iter().copied()
+                .chain(categories.names())
+                .map(|name| format!("{}, ", name))
+                .collect();
+            return Err(crate::domain::DomainError::InvalidCategory(format!(
+                "'{}'. Valid options: {}or custom:name",
+                category_str, names
+            )));
+        };
+
+        if name.is_empty() {
+            return Err(crate::domain::DomainError::InvalidCategory(
+                "Custom category name cannot be empty".to_string()
+            ));
+        }
+        if name.chars().count() > MAX_CATEGORY_NAME_LENGTH {
+            return Err(crate::domain::DomainError::InvalidCategory(format!(
+                "Custom category name cannot exceed {} characters", MAX_CATEGORY_NAME_LENGTH
+            )));
+        }
+        if let Some(builtin) = Self::first_class(name, categories) {
+            return Err(crate::domain::DomainError::InvalidCategory(format!(
+                "'{}' is a built-in category; use '{}' instead of 'custom:{}'",
+                builtin.display_name_in(categories), name, name
+            )));
+        }
+
+        Ok(Category::Custom(name.to_string()))
+    }
+
+    /// Convert to the string stored in the database
+    ///
+    /// Built-in and registered categories are stored by their lowercase
+    /// name and custom categories as `custom:<name>`.
+    pub fn to_storage_string(&self) -> String {
+        match self {
+            Category::Registered(name) => name.clone(),
+            Category::Custom(name) => format!("custom:{}", name),
+            builtin => builtin.display_name().to_lowercase(),
+        }
+    }
+
+    /// Read a category back from its stored string
+    ///
+    /// Unlike `parse_user_input` this is exact: stored custom names are kept
+    /// verbatim, even legacy ones that shadow a built-in. Any other bare
+    /// name reads back as `Registered`, so habits keep loading after their
+    /// category is dropped from the categories file; until it is registered
+    /// again `display_name_in` shows the name itself.
+    pub fn from_storage_string(stored: &str) -> Self {
+        if let Some(category) = Self::builtin(stored) {
+            return category;
+        }
+        match stored.strip_prefix("custom:") {
+            Some(name) => Category::Custom(name.to_string()),
+            None => Category::Registered(stored.to_string()),
         }
     }
+
+    /// Look up a built-in category by its lowercase name
+    pub(crate) fn builtin(name: &str) -> Option<Self> {
+        match name {
+            "health" => Some(Category::Health),
+            "productivity" => Some(Category::Productivity),
+            "social" => Some(Category::Social),
+            "creative" => Some(Category::Creative),
+            "mindfulness" => Some(Category::Mindfulness),
+            "financial" => Some(Category::Financial),
+            "household" => Some(Category::Household),
+            "personal" => Some(Category::Personal),
+            _ => None,
+        }
+    }
+
+    /// Look up a built-in category or one in `categories` by its lowercase name
+    pub(crate) fn first_class(name: &str, categories: &CategoryRegistry) -> Option<Self> {
+        Self::builtin(name).or_else(|| {
+            categories.display_name(name).map(|_| Category::Registered(name.to_string()))
+        })
+    }
 }
 
+/// Longest gap, in days, an `Interval` frequency can have
+pub const MAX_INTERVAL_DAYS: u32 = 365;
+
 /// How often a habit should be performed
 /// 
 /// This supports various scheduling patterns from daily habits to complex
 /// weekly schedules. The frequency affects how streaks are calculated.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// 
+/// Frequencies serialize as the same string `Display` produces and
+/// `FromStr` accepts: `daily`, `weekdays`, `weekends`, `weekly:<n>`,
+/// `custom:<days>` (lowercase three-letter days from Monday, e.g.
+/// `custom:mon,wed`) or `every:<n>`. The database stores this form, so it
+/// doesn't change if variants are renamed or reordered.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Frequency {
     /// Every single day
     Daily,
@@ -181,7 +318,7 @@ impl Frequency {
             Frequency::Weekends => "Weekends (Sat-Sun)".to_string(),
             Frequency::Custom(days) => {
                 days.iter()
-                    .map(|d| format!("{:?}", d))
+                    .map(|d| weekday_full_name(*d))
                     .collect::<Vec<_>>()
                     .join(", ")
             }
@@ -206,12 +343,10 @@ impl Frequency {
     /// ```
     pub fn validate(&self) -> Result<(), crate::domain::DomainError> {
         match self {
-            Frequency::Weekly(times) => {
-                if *times == 0 || *times > 7 {
-                    return Err(crate::domain::DomainError::InvalidFrequency(
-                        format!("Weekly frequency must be 1-7, got {}", times)
-                    ));
-                }
+            Frequency::Weekly(times) if *times == 0 || *times > 7 => {
+                return Err(crate::domain::DomainError::InvalidFrequency(
+                    format!("Weekly frequency must be 1-7, got {}", times)
+                ));
             }
             Frequency::Custom(days) => {
                 if days.is_empty() {
@@ -231,9 +366,9 @@ impl Frequency {
                         "Interval must be at least 1 day".to_string()
                     ));
                 }
-                if *days > 365 {
+                if *days > MAX_INTERVAL_DAYS {
                     return Err(crate::domain::DomainError::InvalidFrequency(
-                        "Interval cannot be longer than 365 days".to_string()
+                        format!("Interval cannot be longer than {} days", MAX_INTERVAL_DAYS)
                     ));
                 }
             }
@@ -264,11 +399,308 @@ impl Frequency {
                 true
             }
             Frequency::Interval(_) => {
-                // For interval habits, this depends on when the habit was started
-                // This is more complex and would need the habit creation date
-                // For now, we'll return true and handle this in streak calculation
+                // Interval habits depend on when the habit was started, so
+                // callers that know it should use is_scheduled_for_date_since
                 true
             }
         }
     }
-}
\ No newline at end of file
+    
+    /// Check if this frequency expects the habit to be done on a given date,
+    /// anchoring interval schedules to the habit's creation date
+    /// 
+    /// For `Interval(n)` the habit is due on the creation date and every
+    /// `n` days after it. All other frequencies behave like
+    /// `is_scheduled_for_date`.
+    /// 
+    /// # Examples
+    /// ```
+    /// use habit_tracker_mcp::domain::Frequency;
+    /// use chrono::NaiveDate;
+    /// 
+    /// let created = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+    /// let every_three = Frequency::Interval(3);
+    /// assert!(every_three.is_scheduled_for_date_since(NaiveDate::from_ymd_opt(2024, 1, 4).unwrap(), created));
+    /// assert!(!every_three.is_scheduled_for_date_since(NaiveDate::from_ymd_opt(2024, 1, 5).unwrap(), created));
+    /// ```
+    pub fn is_scheduled_for_date_since(&self, date: NaiveDate, created_at: NaiveDate) -> bool {
+        match self {
+            Frequency::Interval(days) => {
+                let elapsed = (date - created_at).num_days();
+                elapsed >= 0 && elapsed % i64::from(*days) == 0
+            }
+            _ => self.is_scheduled_for_date(date),
+        }
+    }
+}
+
+impl std::str::FromStr for Frequency {
+    type Err = crate::domain::DomainError;
+
+    /// Parse a frequency such as `daily`, `weekly:4`, `custom:tue,thu` or `every:3`
+    ///
+    /// Bare `weekly` and `custom` keep their old defaults (3 times per week,
+    /// Mondays). Day names may be abbreviated and are case-insensitive.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use habit_tracker_mcp::domain::Frequency;
+    /// use chrono::Weekday;
+    ///
+    /// assert_eq!("weekly:4".parse::<Frequency>().unwrap(), Frequency::Weekly(4));
+    /// assert_eq!("custom:thu,Tue".parse::<Frequency>().unwrap(), Frequency::Custom(vec![Weekday::Tue, Weekday::Thu]));
+    /// assert_eq!("every:3".parse::<Frequency>().unwrap(), Frequency::Interval(3));
+    /// assert!("weekly:9".parse::<Frequency>().is_err());
+    /// ```
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let invalid = || crate::domain::DomainError::InvalidFrequency(format!(
+            "'{}' is not a valid frequency. Valid options: daily, weekdays, weekends, weekly, weekly:<1-7>, custom:<days> (e.g. custom:tue,thu), every:<days>",
+            input
+        ));
+
+        let normalized = input.trim().to_lowercase();
+        let (kind, argument) = match normalized.split_once(':') {
+            Some((kind, argument)) => (kind.trim(), Some(argument.trim())),
+            None => (normalized.as_str(), None),
+        };
+
+        let frequency = match (kind, argument) {
+            ("daily", None) => Frequency::Daily,
+            ("weekdays", None) => Frequency::Weekdays,
+            ("weekends", None) => Frequency::Weekends,
+            ("weekly", None) => Frequency::Weekly(3), // Default to 3 times per week
+            ("weekly", Some(times)) => Frequency::Weekly(times.parse().map_err(|_| invalid())?),
+            ("custom", None) => Frequency::Custom(vec![Weekday::Mon]), // Default to Monday
+            ("custom", Some(days)) => {
+                let mut weekdays = days.split(',')
+                    .map(|day| day.trim().parse::<Weekday>().map_err(|_| invalid()))
+                    .collect::<Result<Vec<_>, _>>()?;
+                weekdays.sort_by_key(|day| day.num_days_from_monday());
+                weekdays.dedup();
+                Frequency::Custom(weekdays)
+            }
+            ("every" | "interval", Some(days)) => Frequency::Interval(days.parse().map_err(|_| invalid())?),
+            _ => return Err(invalid()),
+        };
+
+        frequency.validate()?;
+        Ok(frequency)
+    }
+}
+
+impl std::fmt::Display for Frequency {
+    /// Write the stable string form, e.g. `weekly:3` or `custom:mon,wed`
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Frequency::Daily => write!(f, "daily"),
+            Frequency::Weekdays => write!(f, "weekdays"),
+            Frequency::Weekends => write!(f, "weekends"),
+            Frequency::Weekly(times) => write!(f, "weekly:{}", times),
+            Frequency::Custom(days) => {
+                let days = days.iter()
+                    .map(|d| d.to_string().to_lowercase())
+                    .collect::<Vec<_>>()
+                    .join(",");
+                write!(f, "custom:{}", days)
+            }
+            Frequency::Interval(days) => write!(f, "every:{}", days),
+        }
+    }
+}
+
+impl Serialize for Frequency {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Frequency {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let stored = String::deserialize(deserializer)?;
+        stored.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Get the full English name of a weekday (e.g., "Monday")
+pub(crate) fn weekday_full_name(day: Weekday) -> &'static str {
+    match day {
+        Weekday::Mon => "Monday",
+        Weekday::Tue => "Tuesday",
+        Weekday::Wed => "Wednesday",
+        Weekday::Thu => "Thursday",
+        Weekday::Fri => "Friday",
+        Weekday::Sat => "Saturday",
+        Weekday::Sun => "Sunday",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interval_scheduled_from_creation_date() {
+        let created = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let frequency = Frequency::Interval(3);
+
+        let scheduled: Vec<u32> = created.iter_days()
+            .take(14)
+            .filter(|d| frequency.is_scheduled_for_date_since(*d, created))
+            .map(|d| d.day())
+            .collect();
+
+        assert_eq!(scheduled, vec![1, 4, 7, 10, 13]);
+    }
+
+    #[test]
+    fn test_interval_not_scheduled_before_creation() {
+        let created = NaiveDate::from_ymd_opt(2024, 1, 10).unwrap();
+        let before = NaiveDate::from_ymd_opt(2024, 1, 7).unwrap();
+
+        assert!(!Frequency::Interval(3).is_scheduled_for_date_since(before, created));
+    }
+
+    #[test]
+    fn test_non_interval_ignores_creation_date() {
+        let created = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let saturday = NaiveDate::from_ymd_opt(2024, 1, 6).unwrap();
+
+        assert!(Frequency::Weekends.is_scheduled_for_date_since(saturday, created));
+        assert!(!Frequency::Weekdays.is_scheduled_for_date_since(saturday, created));
+    }
+
+    #[test]
+    fn test_custom_category_cannot_shadow_builtin() {
+        let categories = CategoryRegistry::default();
+        for input in ["custom:Health", "custom:health", " custom: HEALTH "] {
+            let err = Category::parse_user_input(input, &categories).unwrap_err();
+            assert!(err.to_string().contains("use 'health' instead"), "{}: {}", input, err);
+        }
+    }
+
+    #[test]
+    fn test_parse_category_accepts_builtins_and_new_custom_names() {
+        let categories = CategoryRegistry::default();
+        assert_eq!(Category::parse_user_input(" Mindfulness ", &categories).unwrap(), Category::Mindfulness);
+        assert_eq!(Category::parse_user_input("custom:Startup", &categories).unwrap(), Category::Custom("startup".to_string()));
+        assert!(Category::parse_user_input("custom:  ", &categories).is_err());
+        let longest = format!("custom:{}", "x".repeat(MAX_CATEGORY_NAME_LENGTH));
+        assert!(Category::parse_user_input(&longest, &categories).is_ok());
+        let too_long = format!("custom:{}", "x".repeat(MAX_CATEGORY_NAME_LENGTH + 1));
+        assert!(Category::parse_user_input(&too_long, &categories).unwrap_err().to_string().contains("cannot exceed 50 characters"));
+        assert!(Category::parse_user_input("fitness", &categories).is_err());
+    }
+
+    #[test]
+    fn test_registered_category_is_first_class() {
+        let mut registry = CategoryRegistry::default();
+        registry.register("Cardio", "Cardio 🏃").unwrap();
+        assert!(registry.register("health", "Health").is_err());
+
+        let cardio = Category::parse_user_input(" CARDIO ", &registry).unwrap();
+        assert_eq!(cardio, Category::Registered("cardio".to_string()));
+        assert_eq!(cardio.display_name_in(&registry), "Cardio 🏃");
+        assert_eq!(cardio.to_storage_string(), "cardio");
+        assert_eq!(Category::from_storage_string("cardio"), cardio);
+        assert!(Category::parse_user_input("custom:cardio", &registry).unwrap_err().to_string().contains("use 'cardio' instead"));
+        assert!(Category::parse_user_input("strength", &registry).unwrap_err().to_string().contains("personal, cardio, or custom:name"));
+
+        // Another registry, e.g. a default one, doesn't know cardio
+        let empty = CategoryRegistry::default();
+        assert!(Category::parse_user_input("cardio", &empty).is_err());
+        assert_eq!(cardio.display_name_in(&empty), "cardio");
+    }
+
+    #[test]
+    fn test_frequency_string_form_round_trips() {
+        let cases = [
+            (Frequency::Daily, "daily"),
+            (Frequency::Weekdays, "weekdays"),
+            (Frequency::Weekends, "weekends"),
+            (Frequency::Weekly(1), "weekly:1"),
+            (Frequency::Weekly(7), "weekly:7"),
+            (Frequency::Custom(vec![Weekday::Mon]), "custom:mon"),
+            (Frequency::Custom(vec![Weekday::Mon, Weekday::Wed, Weekday::Sun]), "custom:mon,wed,sun"),
+            (Frequency::Interval(3), "every:3"),
+            (Frequency::Interval(365), "every:365"),
+        ];
+
+        for (frequency, expected) in cases {
+            assert_eq!(frequency.to_string(), expected);
+            assert_eq!(expected.parse::<Frequency>().unwrap(), frequency);
+
+            let json = serde_json::to_string(&frequency).unwrap();
+            assert_eq!(json, format!("\"{}\"", expected));
+            assert_eq!(serde_json::from_str::<Frequency>(&json).unwrap(), frequency);
+        }
+
+        assert!(serde_json::from_str::<Frequency>("\"weekly:9\"").is_err());
+        assert!(serde_json::from_str::<Frequency>("{\"Weekly\":3}").is_err());
+    }
+
+    const BUILTINS: [Category; 8] = [
+        Category::Health,
+        Category::Productivity,
+        Category::Social,
+        Category::Creative,
+        Category::Mindfulness,
+        Category::Financial,
+        Category::Household,
+        Category::Personal,
+    ];
+
+    #[test]
+    fn test_category_storage_strings_round_trip() {
+        for category in BUILTINS {
+            let stored = category.to_storage_string();
+            assert_eq!(stored, category.display_name().to_lowercase());
+            assert_eq!(Category::from_storage_string(&stored), category);
+        }
+
+        for name in ["startup", "Side Project", "health"] {
+            let category = Category::Custom(name.to_string());
+            let stored = category.to_storage_string();
+            assert_eq!(stored, format!("custom:{}", name));
+            assert_eq!(Category::from_storage_string(&stored), category);
+        }
+
+        // Names that are no longer registered still load
+        let dropped = Category::from_storage_string("fitness");
+        assert_eq!(dropped, Category::Registered("fitness".to_string()));
+        assert_eq!(dropped.display_name(), "fitness");
+        assert_eq!(dropped.to_storage_string(), "fitness");
+    }
+
+    #[test]
+    fn test_parse_user_input_round_trips_storage_strings() {
+        let categories = CategoryRegistry::default();
+        for category in BUILTINS {
+            assert_eq!(Category::parse_user_input(&category.to_storage_string(), &categories).unwrap(), category);
+            assert_eq!(Category::parse_user_input(&category.display_name().to_uppercase(), &categories).unwrap(), category);
+        }
+
+        let custom = Category::parse_user_input("custom:Startup", &categories).unwrap();
+        assert_eq!(custom.to_storage_string(), "custom:startup");
+        assert_eq!(Category::parse_user_input(&custom.to_storage_string(), &categories).unwrap(), custom);
+    }
+
+    #[test]
+    fn test_frequency_from_str() {
+        let parse = |input: &str| input.parse::<Frequency>();
+
+        assert_eq!(parse("Daily").unwrap(), Frequency::Daily);
+        assert_eq!(parse(" weekdays ").unwrap(), Frequency::Weekdays);
+        assert_eq!(parse("weekends").unwrap(), Frequency::Weekends);
+        assert_eq!(parse("weekly").unwrap(), Frequency::Weekly(3));
+        assert_eq!(parse("weekly:4").unwrap(), Frequency::Weekly(4));
+        assert_eq!(parse("custom").unwrap(), Frequency::Custom(vec![Weekday::Mon]));
+        assert_eq!(parse("custom:Thursday, tue,thu").unwrap(), Frequency::Custom(vec![Weekday::Tue, Weekday::Thu]));
+        assert_eq!(parse("every:3").unwrap(), Frequency::Interval(3));
+        assert_eq!(parse("interval:10").unwrap(), Frequency::Interval(10));
+
+        for invalid in ["", "hourly", "weekly:0", "weekly:8", "weekly:many", "custom:funday", "custom:", "every:0", "every", "daily:2"] {
+            assert!(parse(invalid).is_err(), "{:?} should be rejected", invalid);
+        }
+    }
+}
@@ -0,0 +1,80 @@
+/// Vacation windows that put a habit's streak on hold
+///
+/// This module defines the Vacation struct. Unlike pausing a habit, a
+/// vacation keeps the habit active: scheduled days inside the window are
+/// skipped by streak calculation, so they neither break nor extend it.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use chrono::NaiveDate;
+use crate::domain::{DomainError, HabitId};
+
+/// A period during which a habit's streak is neither broken nor extended
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Vacation {
+    /// Which habit is on vacation
+    pub habit_id: HabitId,
+    /// First day of the vacation
+    pub start_date: NaiveDate,
+    /// Last day of the vacation, or None while it is still open
+    pub end_date: Option<NaiveDate>,
+}
+
+impl Vacation {
+    /// Create a vacation, checking that it doesn't end before it starts
+    pub fn new(habit_id: HabitId, start_date: NaiveDate, end_date: Option<NaiveDate>) -> Result<Self, DomainError> {
+        if let Some(end) = end_date {
+            if end < start_date {
+                return Err(DomainError::InvalidDate(format!(
+                    "Vacation can't end on {} before it starts on {}", end, start_date
+                )));
+            }
+        }
+
+        Ok(Self { habit_id, start_date, end_date })
+    }
+
+    /// Whether the vacation has no end date yet
+    pub fn is_open(&self) -> bool {
+        self.end_date.is_none()
+    }
+}
+
+/// Every day covered by any of `vacations`, up to and including `today`
+///
+/// Open vacations run until today. Overlapping and nested windows simply
+/// cover the same days, so they are merged rather than counted twice.
+pub fn vacation_days(vacations: &[Vacation], today: NaiveDate) -> BTreeSet<NaiveDate> {
+    vacations.iter()
+        .flat_map(|vacation| {
+            let end = vacation.end_date.unwrap_or(today).min(today);
+            vacation.start_date.iter_days().take_while(move |day| *day <= end)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, day).unwrap()
+    }
+
+    #[test]
+    fn test_overlapping_and_nested_vacations_merge() {
+        let habit_id = HabitId::new();
+        let vacations = vec![
+            Vacation::new(habit_id.clone(), date(2), Some(date(6))).unwrap(),
+            Vacation::new(habit_id.clone(), date(4), Some(date(8))).unwrap(),
+            Vacation::new(habit_id.clone(), date(5), Some(date(5))).unwrap(),
+            Vacation::new(habit_id.clone(), date(14), None).unwrap(),
+        ];
+
+        let days = vacation_days(&vacations, date(15));
+
+        let expected: BTreeSet<NaiveDate> = (2..=8).chain(14..=15).map(date).collect();
+        assert_eq!(days, expected);
+        assert!(Vacation::new(habit_id, date(5), Some(date(4))).is_err());
+    }
+}
@@ -3,13 +3,14 @@
 /// This module exports the main server implementation and public types
 /// that can be used by other applications or tests.
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use thiserror::Error;
+use analytics::AnalyticsConfig;
 
 // Internal modules
-mod domain;
-mod storage; 
-mod analytics;
+pub mod domain;
+pub mod storage;
+pub mod analytics;
 mod tools;
 mod mcp;
 
@@ -17,6 +18,21 @@ mod mcp;
 pub use domain::*;
 pub use storage::{SqliteStorage, StorageError, HabitStorage};
 pub use analytics::{AnalyticsEngine, Insight, InsightsParams, InsightsResponse};
+pub use mcp::{Framing, OutputBuffering};
+
+/// Short git commit hash the server was built from, or "unknown"
+pub const GIT_HASH: &str = env!("HABIT_TRACKER_GIT_HASH");
+
+/// UTC timestamp of the build
+pub const BUILD_DATE: &str = env!("HABIT_TRACKER_BUILD_DATE");
+
+/// Commit hash and build date, e.g. "1a2b3c4 2026-01-31T12:00:00Z"
+pub const BUILD_INFO: &str = concat!(env!("HABIT_TRACKER_GIT_HASH"), " ", env!("HABIT_TRACKER_BUILD_DATE"));
+
+/// Version string shown by `--version`, including the build metadata
+pub const LONG_VERSION: &str = concat!(
+    env!("CARGO_PKG_VERSION"), " (", env!("HABIT_TRACKER_GIT_HASH"), " ", env!("HABIT_TRACKER_BUILD_DATE"), ")"
+);
 
 /// Errors that can occur during server operation
 #[derive(Error, Debug)]
@@ -34,13 +50,69 @@ pub enum ServerError {
     Json(#[from] serde_json::Error),
 }
 
+/// Default time a single JSON-RPC request may take before it is answered with an error
+pub const DEFAULT_REQUEST_TIMEOUT_MS: u64 = 5000;
+
+/// Frequency given to habits created without one, unless configured otherwise
+pub const DEFAULT_FREQUENCY: &str = "daily";
+
+/// Category given to habits created without one, unless configured otherwise
+pub const DEFAULT_CATEGORY: &str = "personal";
+
+/// Server-wide settings chosen at startup
+#[derive(Debug, Clone)]
+pub struct ServerConfig {
+    /// Render tool output as terse, emoji-free lines unless a call asks otherwise
+    pub compact: bool,
+    /// How many days in the past habit completions may be logged for
+    pub max_backfill_days: u32,
+    /// How JSON-RPC messages are delimited on stdin/stdout
+    pub framing: Framing,
+    /// When responses written to stdout are flushed
+    pub output_buffering: OutputBuffering,
+    /// Milliseconds a request may take before "request timed out" is returned (0 disables)
+    pub request_timeout_ms: u64,
+    /// Recalculate every habit's streak at startup, before serving requests
+    pub precompute_on_start: bool,
+    /// Frequency used when habit_create is called without one
+    pub default_frequency: Frequency,
+    /// Category used when habit_create is called without one
+    pub default_category: Category,
+    /// Deployment-specific categories accepted alongside the built-ins
+    pub categories: CategoryRegistry,
+    /// Hour (UTC) the day starts at; earlier logs, status and comparisons still count the previous day
+    pub day_rollover_hour: u32,
+    /// Settings for streak calculation and insights, shared by every tool
+    pub analytics: AnalyticsConfig,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            compact: false,
+            max_backfill_days: domain::DEFAULT_MAX_BACKFILL_DAYS,
+            framing: Framing::Line,
+            output_buffering: OutputBuffering::FlushEach,
+            request_timeout_ms: DEFAULT_REQUEST_TIMEOUT_MS,
+            precompute_on_start: false,
+            default_frequency: Frequency::Daily,
+            default_category: Category::Personal,
+            categories: CategoryRegistry::default(),
+            day_rollover_hour: 0,
+            analytics: AnalyticsConfig::default(),
+        }
+    }
+}
+
 /// Main habit tracker server that implements the MCP protocol
 /// 
 /// This server manages habit data through a SQLite database and provides
 /// tools for creating habits, logging completions, and generating insights.
 pub struct HabitTrackerServer {
+    db_path: PathBuf,
     storage: SqliteStorage,
     analytics: AnalyticsEngine,
+    config: ServerConfig,
 }
 
 impl HabitTrackerServer {
@@ -49,17 +121,27 @@ impl HabitTrackerServer {
     /// This will initialize the SQLite database with the required schema
     /// if it doesn't already exist.
     pub async fn new(db_path: PathBuf) -> Result<Self, ServerError> {
+        Self::with_config(db_path, ServerConfig::default()).await
+    }
+    
+    /// Create a new habit tracker server with custom server settings
+    pub async fn with_config(db_path: PathBuf, config: ServerConfig) -> Result<Self, ServerError> {
         tracing::info!("Initializing Habit Tracker server with database: {:?}", db_path);
         
         // Initialize storage layer
-        let storage = SqliteStorage::new(db_path)?;
+        let storage = SqliteStorage::new(&db_path)?;
         
-        // Initialize analytics engine with the storage reference
-        let analytics = AnalyticsEngine::new();
+        // One analytics engine serves every tool, so they all share its settings
+        // and read "today" from the same clock and category names from the same registry
+        let analytics = AnalyticsEngine::with_config(config.analytics.clone())
+            .with_clock(DayRolloverClock { clock: SystemClock, rollover_hour: config.day_rollover_hour })
+            .with_categories(config.categories.clone());
         
         Ok(Self {
+            db_path,
             storage,
             analytics,
+            config,
         })
     }
     
@@ -73,6 +155,11 @@ impl HabitTrackerServer {
         let habits = self.storage.list_habits(None, true)?;
         tracing::info!("Server started successfully, found {} existing habits", habits.len());
         
+        if self.config.precompute_on_start {
+            let recomputed = self.precompute_streaks()?;
+            tracing::info!("Precomputed streaks for {} habits", recomputed);
+        }
+        
         // Create and run the MCP server
         let mut mcp_server = mcp::McpServer::new(self);
         mcp_server.run().await?;
@@ -80,6 +167,19 @@ impl HabitTrackerServer {
         Ok(())
     }
     
+    /// Recalculate and store the streak of every habit, paused ones included
+    /// 
+    /// Streak rows are otherwise only refreshed when a habit's entries
+    /// change; this refreshes them all once, and does nothing about other
+    /// per-request work. Returns the number of habits.
+    pub fn precompute_streaks(&self) -> Result<usize, ServerError> {
+        let habits = self.storage.list_habits(None, false)?;
+        for habit in &habits {
+            tools::recompute_and_store_streak(&self.storage, &self.analytics, &habit.id)?;
+        }
+        Ok(habits.len())
+    }
+    
     /// Get a reference to the storage layer (useful for testing)
     pub fn storage(&self) -> &SqliteStorage {
         &self.storage
@@ -89,4 +189,44 @@ impl HabitTrackerServer {
     pub fn analytics(&self) -> &AnalyticsEngine {
         &self.analytics
     }
-}
\ No newline at end of file
+    
+    /// Get the path of the database file
+    pub fn db_path(&self) -> &Path {
+        &self.db_path
+    }
+    
+    /// Get the server settings
+    pub fn config(&self) -> &ServerConfig {
+        &self.config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Category, Frequency, Habit, HabitEntry};
+    
+    #[tokio::test]
+    async fn test_precompute_stores_streaks_for_every_habit() {
+        let server = HabitTrackerServer::new(PathBuf::from(":memory:")).await.unwrap();
+        let storage = server.storage();
+        let today = chrono::Utc::now().date_naive();
+        for (name, logged_days) in [("Run", 3), ("Read", 0), ("Stretch", 1)] {
+            let habit = Habit::new(name.to_string(), None, Category::Health, Frequency::Daily, None, None).unwrap();
+            storage.create_habit(&habit).unwrap();
+            let entries: Vec<HabitEntry> = (0..logged_days)
+                .map(|d| HabitEntry::new(habit.id.clone(), today - chrono::Duration::days(d), None, None, None).unwrap())
+                .collect();
+            storage.create_entries(&entries).unwrap();
+        }
+        let paused = storage.list_habits(None, true).unwrap().remove(0);
+        storage.delete_habit(&paused.id).unwrap();
+        assert_eq!(storage.storage_stats().unwrap().streak_count, 0);
+        
+        assert_eq!(server.precompute_streaks().unwrap(), 3);
+        
+        assert_eq!(storage.storage_stats().unwrap().streak_count, 3);
+        let run = storage.list_habits(None, false).unwrap().into_iter().find(|h| h.name == "Run").unwrap();
+        assert_eq!(storage.get_streak(&run.id).unwrap().current_streak, 3);
+    }
+}
@@ -3,16 +3,20 @@
 /// This file sets up logging, parses command line arguments, and starts the MCP server.
 /// The server listens for JSON-RPC requests over stdin/stdout following the MCP protocol.
 
-use clap::Parser;
-use std::path::PathBuf;
-use tracing::info;
+use clap::{Parser, ValueEnum};
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+use tracing_appender::rolling::{InitError, RollingFileAppender, Rotation};
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::{EnvFilter, Layer, Registry};
 
-use habit_tracker_mcp::HabitTrackerServer;
+use habit_tracker_mcp::analytics::AnalyticsConfig;
+use habit_tracker_mcp::{Category, CategoryRegistry, Framing, Frequency, HabitTrackerServer, OutputBuffering, ServerConfig, ServerError, SqliteStorage, StorageError};
 
-/// Get the default database path with robust fallback strategy
-fn get_default_database_path() -> Result<PathBuf, Box<dyn std::error::Error>> {
-    // Try various locations in order of preference
-    let potential_paths = [
+/// Directories tried for the database, in order of preference
+fn default_database_dirs() -> Vec<Option<PathBuf>> {
+    vec![
         // 1. User's home directory (preferred)
         dirs::home_dir().map(|mut p| {
             p.push(".habit_tracker");
@@ -33,9 +37,23 @@ fn get_default_database_path() -> Result<PathBuf, Box<dyn std::error::Error>> {
             p.push(".habit_tracker");
             p
         }),
-    ];
+    ]
+}
 
-    for potential_path in potential_paths.iter().flatten() {
+/// Get the default database path with robust fallback strategy
+///
+/// In strict mode only the first (home directory) location is tried, and
+/// an error is returned instead of falling back to other locations or a
+/// temporary directory.
+fn get_default_database_path(strict: bool) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    resolve_database_path(&default_database_dirs(), strict)
+}
+
+/// Pick the first writable directory from `candidates` and return the database path inside it
+fn resolve_database_path(candidates: &[Option<PathBuf>], strict: bool) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let candidates = if strict { &candidates[..candidates.len().min(1)] } else { candidates };
+
+    for potential_path in candidates.iter().flatten() {
         // Try to create the directory
         if let Ok(()) = std::fs::create_dir_all(potential_path) {
             // Test if we can write to this directory
@@ -49,6 +67,18 @@ fn get_default_database_path() -> Result<PathBuf, Box<dyn std::error::Error>> {
         }
     }
 
+    if strict {
+        let location = match candidates.first() {
+            Some(Some(path)) => format!("{} is not writable", path.display()),
+            _ => "no home directory was found".to_string(),
+        };
+        return Err(format!(
+            "Cannot use the default database location: {}. \
+             Pass --database <path>, or drop --strict-db-path to allow fallback locations.",
+            location
+        ).into());
+    }
+
     // Ultimate fallback: use a temporary directory
     let mut temp_path = std::env::temp_dir();
     temp_path.push("habit_tracker");
@@ -61,13 +91,21 @@ fn get_default_database_path() -> Result<PathBuf, Box<dyn std::error::Error>> {
 
 /// Command line arguments for the Habit Tracker MCP server
 #[derive(Parser, Debug)]
-#[command(author, version, about, long_about = None)]
+#[command(author, version = habit_tracker_mcp::LONG_VERSION, about, long_about = None)]
 struct Args {
     /// Path to the SQLite database file
     /// If not provided, uses a default location in the user's home directory
     #[arg(long)]
     database: Option<PathBuf>,
     
+    /// Fail instead of falling back to other directories (or /tmp) when the home directory isn't usable
+    #[arg(long, alias = "no-default-path-fallback")]
+    strict_db_path: bool,
+    
+    /// JSON file registering extra built-in categories, e.g. {"cardio": "Cardio"}
+    #[arg(long)]
+    categories_file: Option<PathBuf>,
+    
     /// Enable debug logging
     #[arg(short, long)]
     debug: bool,
@@ -75,6 +113,126 @@ struct Args {
     /// Enable verbose output (implies debug)
     #[arg(short, long)]
     verbose: bool,
+    
+    /// Log output format (text for humans, json for log aggregators)
+    #[arg(long, value_enum, default_value_t = LogFormat::Text)]
+    log_format: LogFormat,
+    
+    /// Also write logs to this file, rotated daily (the date is appended to the name)
+    #[arg(long)]
+    log_file: Option<PathBuf>,
+    
+    /// Use terse, emoji-free tool output (tools can still request "format": "full")
+    #[arg(long)]
+    compact: bool,
+    
+    /// If the database is corrupted, move it aside and start with a fresh one
+    #[arg(long)]
+    recover: bool,
+    
+    /// How many days in the past completions may be logged (raise for imports)
+    #[arg(long, default_value_t = habit_tracker_mcp::DEFAULT_MAX_BACKFILL_DAYS, value_parser = clap::value_parser!(u32).range(0..=i64::from(habit_tracker_mcp::MAX_BACKFILL_DAYS_LIMIT)))]
+    max_backfill_days: u32,
+    
+    /// How JSON-RPC messages are delimited (content-length uses LSP-style headers)
+    #[arg(long, value_enum, default_value_t = Framing::Line)]
+    framing: Framing,
+    
+    /// When responses are flushed to stdout (batch saves syscalls under heavy request volume)
+    #[arg(long, value_enum, default_value_t = OutputBuffering::FlushEach)]
+    output_buffering: OutputBuffering,
+    
+    /// Recalculate and store every habit's streak at startup, before serving requests
+    #[arg(long)]
+    precompute_on_start: bool,
+    
+    /// Frequency for habits created without one, e.g. weekdays or weekly:3
+    #[arg(long, default_value = habit_tracker_mcp::DEFAULT_FREQUENCY)]
+    default_frequency: String,
+    
+    /// Category for habits created without one (a built-in, registered or custom:<name> category)
+    #[arg(long, default_value = habit_tracker_mcp::DEFAULT_CATEGORY)]
+    default_category: String,
+    
+    /// Hour (UTC, 0-12) the day starts at; earlier logs, status and comparisons still count the previous day
+    #[arg(long, default_value_t = 0, value_parser = clap::value_parser!(u32).range(0..=i64::from(habit_tracker_mcp::MAX_DAY_ROLLOVER_HOUR)))]
+    day_rollover_hour: u32,
+    
+    /// Report completion rates above 100% for habits done more often than scheduled
+    #[arg(long)]
+    allow_over_100_percent: bool,
+    
+    /// Flag entry values more than this many times a habit's median as a possible unit mismatch
+    #[arg(long, default_value_t = AnalyticsConfig::default().value_outlier_multiplier)]
+    value_outlier_multiplier: f64,
+    
+    /// Habits younger than this many days get encouragement instead of completion-rate critique
+    #[arg(long, default_value_t = AnalyticsConfig::default().new_habit_days)]
+    new_habit_days: u32,
+    
+    /// Milliseconds a single request may take before it fails with "request timed out" (0 disables)
+    #[arg(long, default_value_t = habit_tracker_mcp::DEFAULT_REQUEST_TIMEOUT_MS)]
+    request_timeout_ms: u64,
+}
+
+/// Format used for log lines written to stderr
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum LogFormat {
+    /// Human-readable text output
+    Text,
+    /// One JSON object per line
+    Json,
+}
+
+impl Args {
+    /// Get the log level implied by the command line flags
+    fn log_level(&self) -> &'static str {
+        if self.verbose {
+            "debug"
+        } else if self.debug {
+            "info"
+        } else {
+            "warn"
+        }
+    }
+}
+
+/// Build the tracing subscriber for the requested log level and format
+///
+/// Logs always go to stderr, since stdout is reserved for JSON-RPC messages.
+/// With `log_file`, the same lines are also written to a daily-rotated file.
+fn build_subscriber(
+    log_level: &str,
+    log_format: LogFormat,
+    log_file: Option<&Path>,
+) -> Result<Box<dyn tracing::Subscriber + Send + Sync>, InitError> {
+    // Send logs to stderr, not stdout
+    let mut layers = vec![fmt_layer(log_format, std::io::stderr, true)];
+    
+    if let Some(path) = log_file {
+        let directory = path.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(Path::new("."));
+        let file_name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+        let appender = RollingFileAppender::builder()
+            .rotation(Rotation::DAILY)
+            .filename_prefix(file_name)
+            .build(directory)?;
+        layers.push(fmt_layer(log_format, appender, false));
+    }
+    
+    let filter = EnvFilter::new(format!("habit_tracker_mcp={}", log_level));
+    Ok(Box::new(tracing_subscriber::registry().with(layers).with(filter)))
+}
+
+/// A formatting layer writing to `writer` in the requested format
+fn fmt_layer<W>(log_format: LogFormat, writer: W, ansi: bool) -> Box<dyn Layer<Registry> + Send + Sync>
+where
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
+    let layer = tracing_subscriber::fmt::layer().with_writer(writer).with_ansi(ansi);
+    match log_format {
+        LogFormat::Text => layer.boxed(),
+        LogFormat::Json => layer.json().boxed(),
+    }
 }
 
 #[tokio::main]
@@ -82,21 +240,26 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args = Args::parse();
     
     // Set up logging based on command line flags
-    let log_level = if args.verbose {
-        "debug"
-    } else if args.debug {
-        "info"
-    } else {
-        "warn"
-    };
-    
-    tracing_subscriber::fmt()
-        .with_env_filter(format!("habit_tracker_mcp={}", log_level))
-        .with_writer(std::io::stderr) // Send logs to stderr, not stdout
-        .init();
+    tracing::subscriber::set_global_default(build_subscriber(args.log_level(), args.log_format, args.log_file.as_deref())?)?;
     
     info!("Starting Habit Tracker MCP server");
     
+    let categories = match &args.categories_file {
+        Some(path) => {
+            let registry = CategoryRegistry::from_json(&std::fs::read_to_string(path)?)?;
+            info!("Registered {} categories from {}", registry.len(), path.display());
+            registry
+        }
+        None => CategoryRegistry::default(),
+    };
+    
+    // Reject unusable defaults now rather than on the first habit_create
+    let default_frequency: Frequency = args.default_frequency.parse()?;
+    let default_category = Category::parse_user_input(&args.default_category, &categories)?;
+    if args.value_outlier_multiplier.is_nan() || args.value_outlier_multiplier <= 1.0 {
+        return Err("--value-outlier-multiplier must be greater than 1".into());
+    }
+    
     // Determine database path
     let db_path = match args.database {
         Some(path) => {
@@ -110,18 +273,251 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
         None => {
             // Use a robust default path strategy
-            get_default_database_path()?
+            get_default_database_path(args.strict_db_path)?
         }
     };
     
     info!("Using database at: {}", db_path.display());
     
     // Create and start the habit tracker server
-    let server = HabitTrackerServer::new(db_path).await?;
+    let config = ServerConfig {
+        compact: args.compact,
+        max_backfill_days: args.max_backfill_days,
+        framing: args.framing,
+        output_buffering: args.output_buffering,
+        request_timeout_ms: args.request_timeout_ms,
+        precompute_on_start: args.precompute_on_start,
+        default_frequency,
+        default_category,
+        categories,
+        day_rollover_hour: args.day_rollover_hour,
+        analytics: AnalyticsConfig {
+            allow_over_100_percent: args.allow_over_100_percent,
+            value_outlier_multiplier: args.value_outlier_multiplier,
+            new_habit_days: args.new_habit_days,
+            ..AnalyticsConfig::default()
+        },
+    };
+    let server = match HabitTrackerServer::with_config(db_path.clone(), config.clone()).await {
+        Err(ServerError::Database(StorageError::Corrupted { path })) => {
+            if !args.recover {
+                return Err(format!(
+                    "Database at {} is corrupted or is not a SQLite database. \
+                     Restore it from a backup, or rerun with --recover to move it aside and start fresh.",
+                    path
+                ).into());
+            }
+            
+            let backup_path = SqliteStorage::quarantine(&db_path)?;
+            warn!(
+                "⚠️ Database at {} was corrupted. It has been moved to {} and a fresh database was created.",
+                db_path.display(),
+                backup_path.display()
+            );
+            HabitTrackerServer::with_config(db_path, config).await?
+        }
+        result => result?,
+    };
     
     // Run the MCP server - this will handle JSON-RPC communication over stdin/stdout
     server.run().await?;
     
     info!("Habit Tracker MCP server shutdown complete");
     Ok(())
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn test_log_format_defaults_to_text() {
+        let args = Args::try_parse_from(["habit-tracker-mcp"]).unwrap();
+        assert_eq!(args.log_format, LogFormat::Text);
+        assert_eq!(args.log_level(), "warn");
+    }
+
+    #[test]
+    fn test_log_format_composes_with_verbosity_flags() {
+        let args = Args::try_parse_from(["habit-tracker-mcp", "--log-format", "json", "--debug"]).unwrap();
+        assert_eq!(args.log_format, LogFormat::Json);
+        assert_eq!(args.log_level(), "info");
+
+        let args = Args::try_parse_from(["habit-tracker-mcp", "--verbose", "--log-format", "json"]).unwrap();
+        assert_eq!(args.log_format, LogFormat::Json);
+        assert_eq!(args.log_level(), "debug");
+    }
+
+    #[test]
+    fn test_compact_flag() {
+        assert!(!Args::try_parse_from(["habit-tracker-mcp"]).unwrap().compact);
+        assert!(Args::try_parse_from(["habit-tracker-mcp", "--compact"]).unwrap().compact);
+    }
+
+    #[test]
+    fn test_recover_flag() {
+        assert!(!Args::try_parse_from(["habit-tracker-mcp"]).unwrap().recover);
+        assert!(Args::try_parse_from(["habit-tracker-mcp", "--recover"]).unwrap().recover);
+    }
+
+    #[test]
+    fn test_strict_db_path_flag() {
+        assert!(!Args::try_parse_from(["habit-tracker-mcp"]).unwrap().strict_db_path);
+        assert!(Args::try_parse_from(["habit-tracker-mcp", "--strict-db-path"]).unwrap().strict_db_path);
+        assert!(Args::try_parse_from(["habit-tracker-mcp", "--no-default-path-fallback"]).unwrap().strict_db_path);
+    }
+
+    #[test]
+    fn test_strict_mode_errors_instead_of_falling_back() {
+        let dir = tempfile::tempdir().unwrap();
+        // A directory can't be created beneath a regular file
+        let blocker = dir.path().join("not_a_dir");
+        std::fs::write(&blocker, "").unwrap();
+        let unusable = Some(blocker.join(".habit_tracker"));
+        let fallback = dir.path().join("fallback");
+        let candidates = vec![unusable, Some(fallback.clone())];
+
+        let lenient = resolve_database_path(&candidates, false).unwrap();
+        assert_eq!(lenient, fallback.join("habits.db"));
+
+        let err = resolve_database_path(&candidates, true).unwrap_err();
+        assert!(err.to_string().contains("is not writable"));
+        assert!(err.to_string().contains("--database"));
+
+        let err = resolve_database_path(&[None, Some(dir.path().join("other"))], true).unwrap_err();
+        assert!(err.to_string().contains("no home directory"));
+        assert!(!dir.path().join("other").exists());
+    }
+
+    #[test]
+    fn test_max_backfill_days_flag() {
+        let args = Args::try_parse_from(["habit-tracker-mcp"]).unwrap();
+        assert_eq!(args.max_backfill_days, 365);
+
+        let args = Args::try_parse_from(["habit-tracker-mcp", "--max-backfill-days", "3650"]).unwrap();
+        assert_eq!(args.max_backfill_days, 3650);
+
+        assert!(Args::try_parse_from(["habit-tracker-mcp", "--max-backfill-days", "4000000000"]).is_err());
+    }
+
+    #[test]
+    fn test_framing_flag() {
+        let args = Args::try_parse_from(["habit-tracker-mcp"]).unwrap();
+        assert_eq!(args.framing, Framing::Line);
+
+        let args = Args::try_parse_from(["habit-tracker-mcp", "--framing", "content-length"]).unwrap();
+        assert_eq!(args.framing, Framing::ContentLength);
+        assert!(Args::try_parse_from(["habit-tracker-mcp", "--framing", "lsp"]).is_err());
+    }
+
+    #[test]
+    fn test_precompute_on_start_flag() {
+        assert!(!Args::try_parse_from(["habit-tracker-mcp"]).unwrap().precompute_on_start);
+        assert!(Args::try_parse_from(["habit-tracker-mcp", "--precompute-on-start"]).unwrap().precompute_on_start);
+    }
+
+    #[test]
+    fn test_categories_file_flag() {
+        assert!(Args::try_parse_from(["habit-tracker-mcp"]).unwrap().categories_file.is_none());
+        let args = Args::try_parse_from(["habit-tracker-mcp", "--categories-file", "fitness.json"]).unwrap();
+        assert_eq!(args.categories_file, Some(PathBuf::from("fitness.json")));
+    }
+
+    #[test]
+    fn test_output_buffering_flag() {
+        let args = Args::try_parse_from(["habit-tracker-mcp"]).unwrap();
+        assert_eq!(args.output_buffering, OutputBuffering::FlushEach);
+
+        let args = Args::try_parse_from(["habit-tracker-mcp", "--output-buffering", "batch"]).unwrap();
+        assert_eq!(args.output_buffering, OutputBuffering::Batch);
+    }
+
+    #[test]
+    fn test_request_timeout_flag() {
+        let args = Args::try_parse_from(["habit-tracker-mcp"]).unwrap();
+        assert_eq!(args.request_timeout_ms, 5000);
+
+        let args = Args::try_parse_from(["habit-tracker-mcp", "--request-timeout-ms", "250"]).unwrap();
+        assert_eq!(args.request_timeout_ms, 250);
+    }
+
+    #[test]
+    fn test_default_frequency_and_category_flags() {
+        let args = Args::try_parse_from(["habit-tracker-mcp"]).unwrap();
+        assert_eq!(args.default_frequency, "daily");
+        assert_eq!(args.default_category, "personal");
+
+        let args = Args::try_parse_from([
+            "habit-tracker-mcp", "--default-frequency", "weekdays", "--default-category", "mindfulness",
+        ]).unwrap();
+        assert_eq!(args.default_frequency, "weekdays");
+        assert_eq!(args.default_category, "mindfulness");
+    }
+
+    #[test]
+    fn test_day_rollover_hour_flag() {
+        assert_eq!(Args::try_parse_from(["habit-tracker-mcp"]).unwrap().day_rollover_hour, 0);
+        let args = Args::try_parse_from(["habit-tracker-mcp", "--day-rollover-hour", "3"]).unwrap();
+        assert_eq!(args.day_rollover_hour, 3);
+        assert!(Args::try_parse_from(["habit-tracker-mcp", "--day-rollover-hour", "13"]).is_err());
+    }
+
+    #[test]
+    fn test_analytics_flags() {
+        let args = Args::try_parse_from(["habit-tracker-mcp"]).unwrap();
+        assert!(!args.allow_over_100_percent);
+        assert_eq!(args.value_outlier_multiplier, 5.0);
+        assert_eq!(args.new_habit_days, 14);
+
+        let args = Args::try_parse_from([
+            "habit-tracker-mcp", "--allow-over-100-percent", "--value-outlier-multiplier", "3", "--new-habit-days", "21",
+        ]).unwrap();
+        assert!(args.allow_over_100_percent);
+        assert_eq!(args.value_outlier_multiplier, 3.0);
+        assert_eq!(args.new_habit_days, 21);
+    }
+
+    #[test]
+    fn test_version_includes_build_metadata() {
+        let version = Args::command().render_version();
+        assert!(version.contains(env!("CARGO_PKG_VERSION")));
+        assert!(version.contains(habit_tracker_mcp::GIT_HASH));
+        assert!(version.contains(habit_tracker_mcp::BUILD_DATE));
+    }
+
+    #[test]
+    fn test_invalid_log_format_rejected() {
+        assert!(Args::try_parse_from(["habit-tracker-mcp", "--log-format", "xml"]).is_err());
+    }
+
+    #[test]
+    fn test_build_subscriber_for_each_format() {
+        for format in [LogFormat::Text, LogFormat::Json] {
+            let subscriber = build_subscriber("debug", format, None).unwrap();
+            tracing::subscriber::with_default(subscriber, || {
+                info!("subscriber built for {:?}", format);
+            });
+        }
+    }
+
+    #[test]
+    fn test_log_file_receives_log_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let args = Args::try_parse_from(["habit-tracker-mcp", "--log-file", dir.path().join("server.log").to_str().unwrap()]).unwrap();
+
+        let subscriber = build_subscriber("info", LogFormat::Text, args.log_file.as_deref()).unwrap();
+        tracing::subscriber::with_default(subscriber, || {
+            info!("written to the log file");
+            tracing::debug!("below the configured level");
+        });
+
+        let files: Vec<_> = std::fs::read_dir(dir.path()).unwrap().map(|e| e.unwrap().path()).collect();
+        assert_eq!(files.len(), 1);
+        assert!(files[0].file_name().unwrap().to_string_lossy().starts_with("server.log."));
+        let contents = std::fs::read_to_string(&files[0]).unwrap();
+        assert!(contents.contains("written to the log file"), "{}", contents);
+        assert!(!contents.contains("below the configured level"), "{}", contents);
+        assert!(!contents.contains('\u{1b}'), "log file should not contain ANSI colors");
+    }
+}
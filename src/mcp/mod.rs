@@ -4,7 +4,10 @@
 /// including JSON-RPC parsing and tool routing.
 
 pub mod protocol;
+pub mod schema;
 pub mod server;
+pub mod transport;
 
 // Re-export main types
-pub use server::McpServer;
\ No newline at end of file
+pub use server::McpServer;
+pub use transport::{Framing, OutputBuffering};
\ No newline at end of file
@@ -19,8 +19,9 @@ pub struct JsonRpcRequest {
     /// JSON-RPC version (always "2.0")
     #[allow(dead_code)]
     pub jsonrpc: String,
-    /// Unique identifier for this request
-    pub id: Value,
+    /// Unique identifier for this request (absent for notifications)
+    #[serde(default)]
+    pub id: Option<Value>,
     /// The method/tool name to call (e.g., "tools/call")
     pub method: String,
     /// Parameters for the method call
@@ -79,6 +80,9 @@ pub struct ToolCallResult {
     /// Whether this is an error result
     #[serde(default)]
     pub is_error: bool,
+    /// Machine-readable result for clients that chart or post-process it
+    #[serde(rename = "structuredContent", skip_serializing_if = "Option::is_none")]
+    pub structured_content: Option<Value>,
 }
 
 /// Content returned by a tool
@@ -112,6 +116,9 @@ pub struct ServerCapabilities {
     /// Tools that this server provides
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tools: Option<ToolsCapability>,
+    /// Readable resources that this server provides
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resources: Option<ResourcesCapability>,
 }
 
 /// Tools capability information
@@ -122,7 +129,52 @@ pub struct ToolsCapability {
     pub list_changed: bool,
 }
 
+/// Resources capability information
+#[derive(Debug, Serialize)]
+pub struct ResourcesCapability {
+    /// Whether clients can subscribe to changes of a single resource
+    pub subscribe: bool,
+    /// Whether we notify clients when the resource list changes
+    pub list_changed: bool,
+}
+
+/// MCP resource definition
+/// 
+/// This describes a piece of data clients can read with `resources/read`.
+#[derive(Debug, Serialize)]
+pub struct ResourceDefinition {
+    /// Address of the resource (e.g., "habit://<id>")
+    pub uri: String,
+    /// Human-readable name
+    pub name: String,
+    /// What the resource contains
+    pub description: String,
+    /// Format of the resource's content
+    #[serde(rename = "mimeType")]
+    pub mime_type: String,
+}
+
+/// Parameters of a `resources/read` request
+#[derive(Debug, Deserialize)]
+pub struct ReadResourceParams {
+    /// Address of the resource to read
+    pub uri: String,
+}
+
+/// Content of a resource returned by `resources/read`
+#[derive(Debug, Serialize)]
+pub struct ResourceContent {
+    /// Address of the resource that was read
+    pub uri: String,
+    /// Format of `text`
+    #[serde(rename = "mimeType")]
+    pub mime_type: String,
+    /// The resource's content
+    pub text: String,
+}
+
 /// MCP initialization request
+#[allow(dead_code)]
 #[derive(Debug, Deserialize)]
 pub struct InitializeParams {
     /// MCP protocol version the client supports
@@ -137,6 +189,7 @@ pub struct InitializeParams {
 }
 
 /// Information about the MCP client (Claude)
+#[allow(dead_code)]
 #[derive(Debug, Deserialize)]
 pub struct ClientInfo {
     /// Client name (e.g., "Claude")
@@ -165,6 +218,8 @@ pub struct ServerInfo {
     pub name: String,
     /// Server version
     pub version: String,
+    /// Git commit and build date the server was built from
+    pub build: String,
 }
 
 // JSON-RPC error codes (standard codes)
@@ -192,6 +247,21 @@ pub mod error_codes {
     pub const STORAGE_ERROR: i32 = -32004;
 }
 
+impl JsonRpcRequest {
+    /// Check whether this request is a notification
+    ///
+    /// Per JSON-RPC 2.0, a request without an id is a notification and
+    /// must not receive a response.
+    pub fn is_notification(&self) -> bool {
+        self.id.is_none()
+    }
+
+    /// Get the id to echo back in the response (null for notifications)
+    pub fn response_id(&self) -> Value {
+        self.id.clone().unwrap_or(Value::Null)
+    }
+}
+
 impl JsonRpcResponse {
     /// Create a successful response
     pub fn success(id: Value, result: Value) -> Self {
@@ -227,9 +297,25 @@ impl ToolCallResult {
                 text,
             }],
             is_error: false,
+            structured_content: None,
         }
     }
 
+    /// Create an error tool result for a failed tool call
+    ///
+    /// The matching JSON-RPC error code is attached as structured content
+    /// (`{"code": -32003}`) so clients can tell bad input from storage failures.
+    pub fn storage_error(error: &crate::storage::StorageError) -> Self {
+        Self::error(error.to_string())
+            .with_structured_content(serde_json::json!({"code": storage_error_to_json_rpc_code(error)}))
+    }
+
+    /// Attach structured JSON alongside the text content
+    pub fn with_structured_content(mut self, value: Value) -> Self {
+        self.structured_content = Some(value);
+        self
+    }
+
     /// Create an error tool result
     pub fn error(error_message: String) -> Self {
         Self {
@@ -238,12 +324,12 @@ impl ToolCallResult {
                 text: format!("Error: {}", error_message),
             }],
             is_error: true,
+            structured_content: None,
         }
     }
 }
 
 /// Helper function to map storage errors to appropriate JSON-RPC error codes
-#[allow(dead_code)] // This function is defined for future use in more detailed error reporting
 pub fn storage_error_to_json_rpc_code(error: &crate::storage::StorageError) -> i32 {
     use crate::storage::StorageError;
 
@@ -255,5 +341,7 @@ pub fn storage_error_to_json_rpc_code(error: &crate::storage::StorageError) -> i
         StorageError::Connection(_) => error_codes::STORAGE_ERROR,
         StorageError::Serialization(_) => error_codes::INTERNAL_ERROR,
         StorageError::Migration(_) => error_codes::STORAGE_ERROR,
+        StorageError::Corrupted { .. } => error_codes::STORAGE_ERROR,
+        StorageError::Validation(_) => error_codes::VALIDATION_ERROR,
     }
 }
\ No newline at end of file
@@ -0,0 +1,177 @@
+/// Validation of tool arguments against their published JSON Schema
+/// 
+/// Only the parts of JSON Schema our tool definitions actually use are
+/// checked: `required`, property `type`, array `items` and numeric
+/// `minimum`/`maximum`, including the `properties` and `required` of
+/// object items. Properties not in the schema are allowed, and a `null`
+/// value is treated the same as leaving the property out.
+
+use std::collections::HashMap;
+use std::fmt;
+use serde_json::Value;
+
+/// The first argument that did not match a tool's input schema
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaViolation {
+    /// Name of the offending argument, with an index for array items,
+    /// e.g. `entries[2].intensity`
+    pub field: String,
+    pub message: String,
+}
+
+impl fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "'{}' {}", self.field, self.message)
+    }
+}
+
+/// Check tool arguments against the tool's `input_schema`
+pub fn validate_arguments(schema: &Value, args: &HashMap<String, Value>) -> Result<(), SchemaViolation> {
+    check_object("", schema, |field| args.get(field))
+}
+
+/// Check an object's `required` and `properties`, naming fields after `prefix`
+fn check_object<'a>(
+    prefix: &str,
+    schema: &Value,
+    get: impl Fn(&str) -> Option<&'a Value>,
+) -> Result<(), SchemaViolation> {
+    let required = schema.get("required").and_then(Value::as_array);
+    for field in required.into_iter().flatten().filter_map(Value::as_str) {
+        if get(field).is_none_or(Value::is_null) {
+            return Err(violation(&format!("{}{}", prefix, field), "is required".to_string()));
+        }
+    }
+    
+    let Some(properties) = schema.get("properties").and_then(Value::as_object) else {
+        return Ok(());
+    };
+    
+    for (field, property) in properties {
+        if let Some(value) = get(field).filter(|v| !v.is_null()) {
+            check_value(&format!("{}{}", prefix, field), property, value)?;
+        }
+    }
+    
+    Ok(())
+}
+
+/// Check a single value against its property schema
+fn check_value(field: &str, property: &Value, value: &Value) -> Result<(), SchemaViolation> {
+    if let Some(expected) = property.get("type").and_then(Value::as_str) {
+        if !matches_type(expected, value) {
+            return Err(violation(field, format!("must be of type {}, got {}", expected, type_name(value))));
+        }
+    }
+    
+    if let Some(object) = value.as_object() {
+        check_object(&format!("{}.", field), property, |key| object.get(key))?;
+    }
+    
+    if let (Some(items), Some(array)) = (property.get("items"), value.as_array()) {
+        for (index, item) in array.iter().enumerate() {
+            check_value(&format!("{}[{}]", field, index), items, item)?;
+        }
+    }
+    
+    if let Some(number) = value.as_f64() {
+        if let Some(minimum) = property.get("minimum").and_then(Value::as_f64) {
+            if number < minimum {
+                return Err(violation(field, format!("must be at least {}", minimum)));
+            }
+        }
+        if let Some(maximum) = property.get("maximum").and_then(Value::as_f64) {
+            if number > maximum {
+                return Err(violation(field, format!("must be at most {}", maximum)));
+            }
+        }
+    }
+    
+    Ok(())
+}
+
+fn matches_type(expected: &str, value: &Value) -> bool {
+    match expected {
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "boolean" => value.is_boolean(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        _ => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn violation(field: &str, message: String) -> SchemaViolation {
+    SchemaViolation { field: field.to_string(), message }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "habit_id": {"type": "string"},
+                "intensity": {"type": "number"},
+                "weeks": {"type": "integer", "minimum": 1, "maximum": 52},
+                "habit_ids": {"type": "array", "items": {"type": "string"}},
+                "entries": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {"intensity": {"type": "integer", "minimum": 1, "maximum": 10}},
+                        "required": ["completed_at"]
+                    }
+                }
+            },
+            "required": ["habit_id"]
+        })
+    }
+
+    fn args(value: Value) -> HashMap<String, Value> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_valid_arguments_pass() {
+        let valid = args(json!({"habit_id": "abc", "intensity": 7.5, "weeks": 8, "habit_ids": ["a"], "extra": true}));
+        assert_eq!(validate_arguments(&schema(), &valid), Ok(()));
+        assert_eq!(validate_arguments(&schema(), &args(json!({"habit_id": "abc", "intensity": null}))), Ok(()));
+    }
+
+    #[test]
+    fn test_violations_name_the_field() {
+        let cases = [
+            (json!({}), "habit_id", "is required"),
+            (json!({"habit_id": null}), "habit_id", "is required"),
+            (json!({"habit_id": "abc", "intensity": "high"}), "intensity", "must be of type number, got string"),
+            (json!({"habit_id": "abc", "weeks": 2.5}), "weeks", "must be of type integer, got number"),
+            (json!({"habit_id": "abc", "weeks": 60}), "weeks", "must be at most 52"),
+            (json!({"habit_id": "abc", "habit_ids": ["a", 3]}), "habit_ids[1]", "must be of type string, got number"),
+            (json!({"habit_id": "abc", "entries": [{"completed_at": "today"}, {}]}), "entries[1].completed_at", "is required"),
+            (json!({"habit_id": "abc", "entries": [{"completed_at": "today", "intensity": 257}]}), "entries[0].intensity", "must be at most 10"),
+            (json!({"habit_id": "abc", "entries": [{"completed_at": "today", "intensity": 7.5}]}), "entries[0].intensity", "must be of type integer, got number"),
+        ];
+
+        for (input, field, message) in cases {
+            let violation = validate_arguments(&schema(), &args(input)).unwrap_err();
+            assert_eq!(violation.field, field);
+            assert_eq!(violation.message, message);
+        }
+    }
+}
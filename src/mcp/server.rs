@@ -6,13 +6,22 @@
 /// 3. Sends JSON-RPC responses to stdout
 
 use std::collections::HashMap;
+use std::future::Future;
+use std::time::Duration;
 use serde_json::{json, Value};
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
-use tracing::{debug, error, info};
+use tokio::io::{AsyncBufRead, AsyncWrite, BufReader, BufWriter};
+use tracing::{debug, debug_span, error, info, trace, Instrument};
 
+use crate::domain::{
+    format_rate, format_rate_vs_target, format_streak_length, EntryValidation, HabitId, MAX_ENTRY_VALUE, MAX_INTENSITY,
+    MAX_TARGET_VALUE, MIN_INTENSITY,
+};
 use crate::mcp::protocol::*;
+use crate::mcp::schema::validate_arguments;
+use crate::mcp::transport::{OutputBuffering, Transport};
 use crate::tools;
 use crate::{HabitTrackerServer, ServerError, InsightsParams};
+use crate::storage::{HabitStorage, StorageError};
 
 /// MCP server that handles communication with Claude
 pub struct McpServer {
@@ -20,6 +29,8 @@ pub struct McpServer {
     habit_tracker: HabitTrackerServer,
     /// Whether the server has been initialized
     initialized: bool,
+    /// Set by a `shutdown` request; the run loop exits after replying
+    shutdown_requested: bool,
 }
 
 impl McpServer {
@@ -28,6 +39,7 @@ impl McpServer {
         Self {
             habit_tracker,
             initialized: false,
+            shutdown_requested: false,
         }
     }
     
@@ -35,32 +47,58 @@ impl McpServer {
     pub async fn run(&mut self) -> Result<(), ServerError> {
         info!("Starting MCP server, waiting for JSON-RPC requests...");
         
-        let stdin = tokio::io::stdin();
-        let mut reader = BufReader::new(stdin);
-        let mut stdout = tokio::io::stdout();
-        
-        let mut line = String::new();
+        let framing = self.habit_tracker.config().framing;
+        let buffering = self.habit_tracker.config().output_buffering;
+        let reader = BufReader::new(tokio::io::stdin());
+        let stdout = tokio::io::stdout();
         
+        match buffering {
+            OutputBuffering::FlushEach => self.serve(Transport::new(reader, stdout, framing)).await,
+            OutputBuffering::Batch => {
+                let transport = Transport::new(reader, BufWriter::new(stdout), framing)
+                    .with_buffering(buffering);
+                self.serve(transport).await
+            }
+        }
+    }
+    
+    /// Serve JSON-RPC requests read from `transport`, writing responses back to it
+    /// 
+    /// Returns when the input is closed or a `shutdown` request has been answered.
+    /// Buffered responses are always flushed before returning.
+    async fn serve<R, W>(&mut self, mut transport: Transport<R, W>) -> Result<(), ServerError>
+    where
+        R: AsyncBufRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
         loop {
-            line.clear();
+            // Under batch buffering, send what's queued before waiting on input
+            transport.flush_if_idle().await?;
             
-            // Read one line from stdin
-            match reader.read_line(&mut line).await {
-                Ok(0) => {
+            // Read one message from the input
+            match transport.read_message().await {
+                Ok(None) => {
                     info!("MCP server shutting down (stdin closed)");
                     break;
                 }
-                Ok(_) => {
-                    // Process the line
-                    if let Some(response) = self.process_line(&line).await {
+                Ok(Some(message)) => {
+                    // Process the message
+                    if let Some(response) = self.process_line(&message).await {
                         let response_str = serde_json::to_string(&response)?;
+                        transport.write_message(&response_str).await?;
                         
-                        // Write response + newline
-                        stdout.write_all(response_str.as_bytes()).await?;
-                        stdout.write_all(b"\n").await?;
-                        stdout.flush().await?;
-                        
-                        debug!("Sent response: {}", response_str);
+                        debug!(
+                            id = %response.id,
+                            bytes = response_str.len(),
+                            success = response.error.is_none(),
+                            "Sent response"
+                        );
+                        trace!("Response body: {}", response_str);
+                    }
+                    
+                    if self.shutdown_requested {
+                        info!("MCP server shutting down (shutdown requested)");
+                        break;
                     }
                 }
                 Err(e) => {
@@ -70,6 +108,7 @@ impl McpServer {
             }
         }
         
+        transport.flush().await?;
         Ok(())
     }
     
@@ -96,22 +135,46 @@ impl McpServer {
             }
         };
         
-        Some(self.handle_request(request).await)
+        // Everything logged while handling the request, including tool
+        // calls, carries its id and method
+        let span = debug_span!("request", id = %request.response_id(), method = %request.method);
+        let is_notification = request.is_notification();
+        let id = request.response_id();
+        let timeout_ms = self.habit_tracker.config().request_timeout_ms;
+        let response = with_request_timeout(timeout_ms, id, self.handle_request(request))
+            .instrument(span.clone())
+            .await;
+        span.in_scope(|| debug!(success = response.error.is_none(), "Handled request"));
+        
+        // Notifications are processed for their side effects only
+        if is_notification {
+            debug!("Processed notification, no response sent");
+            return None;
+        }
+        
+        Some(response)
     }
     
     /// Handle a JSON-RPC request
     async fn handle_request(&mut self, request: JsonRpcRequest) -> JsonRpcResponse {
         match request.method.as_str() {
             "initialize" => self.handle_initialize(request).await,
-            "initialized" => {
+            "initialized" | "notifications/initialized" => {
                 self.initialized = true;
-                JsonRpcResponse::success(request.id, json!(null))
+                JsonRpcResponse::success(request.response_id(), json!(null))
             }
             "tools/list" => self.handle_tools_list(request).await,
             "tools/call" => self.handle_tools_call(request).await,
+            "resources/list" => self.handle_resources_list(request).await,
+            "resources/read" => self.handle_resources_read(request).await,
+            "shutdown" => {
+                info!("Shutdown requested by client");
+                self.shutdown_requested = true;
+                JsonRpcResponse::success(request.response_id(), json!(null))
+            }
             _ => {
                 JsonRpcResponse::error(
-                    request.id,
+                    request.response_id(),
                     error_codes::METHOD_NOT_FOUND,
                     format!("Method '{}' not found", request.method),
                     None
@@ -130,114 +193,105 @@ impl McpServer {
                 tools: Some(ToolsCapability {
                     list_changed: false,
                 }),
+                resources: Some(ResourcesCapability {
+                    subscribe: false,
+                    list_changed: false,
+                }),
             },
             server_info: ServerInfo {
                 name: "Habit Tracker MCP".to_string(),
                 version: env!("CARGO_PKG_VERSION").to_string(),
+                build: crate::BUILD_INFO.to_string(),
             },
         };
         
-        JsonRpcResponse::success(request.id, serde_json::to_value(result).unwrap())
+        JsonRpcResponse::success(request.response_id(), serde_json::to_value(result).unwrap())
     }
     
     /// Handle tools/list request
     async fn handle_tools_list(&mut self, request: JsonRpcRequest) -> JsonRpcResponse {
-        let tools = vec![
-            ToolDefinition {
-                name: "habit_create".to_string(),
-                description: "Create a new habit to track".to_string(),
-                input_schema: json!({
-                    "type": "object",
-                    "properties": {
-                        "name": {"type": "string", "description": "Name of the habit"},
-                        "category": {"type": "string", "description": "Category (health, productivity, etc.)"},
-                        "frequency": {"type": "string", "description": "How often (daily, weekdays, etc.)"}
-                    },
-                    "required": ["name", "category", "frequency"]
-                }),
-            },
-            ToolDefinition {
-                name: "habit_log".to_string(),
-                description: "Log completion of a habit for today or a specific date".to_string(),
-                input_schema: json!({
-                    "type": "object",
-                    "properties": {
-                        "habit_id": {"type": "string", "description": "ID of the habit to log"},
-                        "completed_at": {"type": "string", "description": "Date completed (YYYY-MM-DD, optional - defaults to today)"},
-                        "value": {"type": "number", "description": "Amount completed (optional, e.g., 30 minutes)"},
-                        "intensity": {"type": "number", "description": "Intensity rating 1-10 (optional)"},
-                        "notes": {"type": "string", "description": "Optional notes about this completion"}
-                    },
-                    "required": ["habit_id"]
-                }),
-            },
-            ToolDefinition {
-                name: "habit_list".to_string(),
-                description: "List all habits with detailed information including streaks, completion rates, and sorting options".to_string(),
-                input_schema: json!({
-                    "type": "object",
-                    "properties": {
-                        "category": {"type": "string", "description": "Filter by category (health, productivity, etc.) - optional"},
-                        "active_only": {"type": "boolean", "description": "Show only active habits (default: true) - optional"},
-                        "sort_by": {"type": "string", "description": "Sort by: 'name', 'streak', 'completion_rate', 'total_completions' (default: name) - optional"}
-                    },
-                    "required": []
-                }),
-            },
-            ToolDefinition {
-                name: "habit_status".to_string(),
-                description: "Check habit status, streaks and progress".to_string(),
-                input_schema: json!({
-                    "type": "object",
-                    "properties": {
-                        "habit_id": {"type": "string", "description": "ID of specific habit (optional - shows all if omitted)"}
-                    },
-                    "required": []
-                }),
-            },
-            ToolDefinition {
-                name: "habit_insights".to_string(),
-                description: "Get AI-powered insights and recommendations for your habits".to_string(),
-                input_schema: json!({
-                    "type": "object",
-                    "properties": {
-                        "habit_id": {"type": "string", "description": "ID of specific habit (optional - analyzes all habits if omitted)"},
-                        "time_period": {"type": "string", "description": "Analysis period: 'week', 'month', 'quarter', 'year' (optional, defaults to 'month')"},
-                        "insight_type": {"type": "string", "description": "Type of insights: 'performance', 'recommendations', 'patterns', 'all' (optional, defaults to 'all')"}
-                    },
-                    "required": []
-                }),
-            },
-            ToolDefinition {
-                name: "habit_update".to_string(),
-                description: "Update an existing habit's properties like name, frequency, target, or active status".to_string(),
-                input_schema: json!({
-                    "type": "object",
-                    "properties": {
-                        "habit_id": {"type": "string", "description": "ID of the habit to update"},
-                        "name": {"type": "string", "description": "New name for the habit (optional)"},
-                        "description": {"type": "string", "description": "New description for the habit (optional)"},
-                        "frequency": {"type": "string", "description": "New frequency: 'daily', 'weekdays', 'weekends', 'weekly', 'custom' (optional)"},
-                        "target_value": {"type": "number", "description": "New target value (optional)"},
-                        "unit": {"type": "string", "description": "New unit for target value (optional)"},
-                        "is_active": {"type": "boolean", "description": "Whether habit is active (true) or paused (false) (optional)"}
-                    },
-                    "required": ["habit_id"]
-                }),
-            },
-        ];
+        JsonRpcResponse::success(request.response_id(), json!({"tools": tool_definitions()}))
+    }
+    
+    /// Handle resources/list request
+    /// 
+    /// Lists `habits://all` followed by one `habit://<id>` per habit,
+    /// paused habits included.
+    async fn handle_resources_list(&mut self, request: JsonRpcRequest) -> JsonRpcResponse {
+        let id = request.response_id();
+        let habits = match self.habit_tracker.storage().list_habits(None, false) {
+            Ok(habits) => habits,
+            Err(e) => return storage_error_response(id, &e),
+        };
+        
+        let mut resources = vec![ResourceDefinition {
+            uri: ALL_HABITS_URI.to_string(),
+            name: "All habits".to_string(),
+            description: "Every habit, active and paused, as a JSON array".to_string(),
+            mime_type: "application/json".to_string(),
+        }];
+        resources.extend(habits.into_iter().map(|habit| ResourceDefinition {
+            uri: format!("{}{}", HABIT_URI_PREFIX, habit.id),
+            description: format!("Details of the habit '{}' as JSON", habit.name),
+            name: habit.name,
+            mime_type: "application/json".to_string(),
+        }));
+        
+        JsonRpcResponse::success(id, json!({"resources": resources}))
+    }
+    
+    /// Handle resources/read request for `habits://all` or `habit://<id>`
+    async fn handle_resources_read(&mut self, request: JsonRpcRequest) -> JsonRpcResponse {
+        let id = request.response_id();
+        let params: ReadResourceParams = match request.params.map(serde_json::from_value) {
+            Some(Ok(params)) => params,
+            Some(Err(e)) => {
+                return JsonRpcResponse::error(id, error_codes::INVALID_PARAMS, format!("Invalid parameters: {}", e), None);
+            }
+            None => {
+                return JsonRpcResponse::error(id, error_codes::INVALID_PARAMS, "Missing parameters".to_string(), None);
+            }
+        };
+        
+        let storage = self.habit_tracker.storage();
+        let content = if params.uri == ALL_HABITS_URI {
+            storage.list_habits(None, false).map(|habits| json!(habits))
+        } else if let Some(habit_id) = params.uri.strip_prefix(HABIT_URI_PREFIX) {
+            HabitId::from_string(habit_id)
+                .map_err(|_| StorageError::HabitNotFound { habit_id: habit_id.to_string() })
+                .and_then(|habit_id| storage.get_habit(&habit_id))
+                .map(|habit| json!(habit))
+        } else {
+            return JsonRpcResponse::error(
+                id,
+                error_codes::INVALID_PARAMS,
+                format!("Unknown resource '{}'. Use habits://all or habit://<id>", params.uri),
+                None
+            );
+        };
         
-        JsonRpcResponse::success(request.id, json!({"tools": tools}))
+        match content {
+            Ok(content) => {
+                let contents = vec![ResourceContent {
+                    uri: params.uri,
+                    mime_type: "application/json".to_string(),
+                    text: content.to_string(),
+                }];
+                JsonRpcResponse::success(id, json!({"contents": contents}))
+            }
+            Err(e) => storage_error_response(id, &e),
+        }
     }
     
     /// Handle tools/call request
     async fn handle_tools_call(&mut self, request: JsonRpcRequest) -> JsonRpcResponse {
+        let id = request.response_id();
         let tool_params: ToolCallParams = match request.params {
             Some(params) => match serde_json::from_value(params) {
                 Ok(p) => p,
                 Err(e) => {
                     return JsonRpcResponse::error(
-                        request.id,
+                        id,
                         error_codes::INVALID_PARAMS,
                         format!("Invalid parameters: {}", e),
                         None
@@ -246,7 +300,7 @@ impl McpServer {
             },
             None => {
                 return JsonRpcResponse::error(
-                    request.id,
+                    id,
                     error_codes::INVALID_PARAMS,
                     "Missing parameters".to_string(),
                     None
@@ -254,21 +308,57 @@ impl McpServer {
             }
         };
         
+        let definition = tool_definitions().into_iter().find(|tool| tool.name == tool_params.name);
+        if let Some(definition) = definition {
+            if let Err(violation) = validate_arguments(&definition.input_schema, &tool_params.arguments) {
+                return JsonRpcResponse::error(
+                    id,
+                    error_codes::INVALID_PARAMS,
+                    format!("Invalid parameters: {}", violation),
+                    Some(json!({"field": violation.field}))
+                );
+            }
+        }
+        
         let result = match tool_params.name.as_str() {
             "habit_create" => self.call_habit_create(tool_params.arguments).await,
+            "habit_create_with_history" => self.call_habit_create_with_history(tool_params.arguments).await,
             "habit_log" => self.call_habit_log(tool_params.arguments).await,
+            "habit_log_many" => self.call_habit_log_many(tool_params.arguments).await,
             "habit_list" => self.call_habit_list(tool_params.arguments).await,
             "habit_status" => self.call_habit_status(tool_params.arguments).await,
             "habit_insights" => self.call_habit_insights(tool_params.arguments).await,
             "habit_update" => self.call_habit_update(tool_params.arguments).await,
+            "habit_set_frequency" => self.call_habit_set_frequency(tool_params.arguments).await,
+            "habit_recategorize" => self.call_habit_recategorize(tool_params.arguments).await,
+            "habit_rename_category" => self.call_habit_rename_category(tool_params.arguments).await,
+            "habit_categories" => self.call_habit_categories().await,
+            "habit_dedupe" => self.call_habit_dedupe().await,
+            "habit_reorder" => self.call_habit_reorder(tool_params.arguments).await,
+            "habit_trend" => self.call_habit_trend(tool_params.arguments).await,
+            "habit_compare" => self.call_habit_compare(tool_params.arguments).await,
+            "habit_history" => self.call_habit_history(tool_params.arguments).await,
+            "habit_milestones" => self.call_habit_milestones(tool_params.arguments).await,
+            "habit_reset_streak" => self.call_habit_reset_streak(tool_params.arguments).await,
+            "habit_use_freeze" => self.call_habit_use_freeze(tool_params.arguments).await,
+            "habit_vacation_start" => self.call_habit_vacation_start(tool_params.arguments).await,
+            "habit_vacation_end" => self.call_habit_vacation_end(tool_params.arguments).await,
+            "habit_pause_all" => self.call_habit_pause_all().await,
+            "habit_resume_all" => self.call_habit_resume_all().await,
+            "habit_clone" => self.call_habit_clone(tool_params.arguments).await,
+            "habit_healthcheck" => self.call_habit_healthcheck().await,
+            "habit_stats" => self.call_habit_stats().await,
+            "habit_schema" => self.call_habit_schema().await,
+            "habit_optimize" => self.call_habit_optimize(tool_params.arguments).await,
             _ => ToolCallResult::error(format!("Unknown tool: {}", tool_params.name)),
         };
         
-        JsonRpcResponse::success(request.id, serde_json::to_value(result).unwrap())
+        JsonRpcResponse::success(id, serde_json::to_value(result).unwrap())
     }
     
     /// Call the habit_create tool
     async fn call_habit_create(&self, args: HashMap<String, Value>) -> ToolCallResult {
+        let config = self.habit_tracker.config();
         let create_params = tools::CreateHabitParams {
             name: args.get("name")
                 .and_then(|v| v.as_str())
@@ -277,17 +367,23 @@ impl McpServer {
             description: None,
             category: args.get("category")
                 .and_then(|v| v.as_str())
-                .unwrap_or("personal")
-                .to_string(),
+                .map(|s| s.to_string())
+                .unwrap_or_else(|| config.default_category.to_storage_string()),
             frequency: args.get("frequency")
                 .and_then(|v| v.as_str())
-                .unwrap_or("daily")
-                .to_string(),
+                .map(|s| s.to_string())
+                .unwrap_or_else(|| config.default_frequency.to_string()),
             target_value: None,
             unit: None,
+            motivation: args.get("motivation")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
+            allow_multiple_per_day: args.get("allow_multiple_per_day")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false),
         };
         
-        match tools::create_habit(self.habit_tracker.storage(), create_params) {
+        match tools::create_habit(self.habit_tracker.storage(), create_params, &config.categories) {
             Ok(response) => {
                 let message = if let Some(habit_id) = &response.habit_id {
                     format!("{}\nHabit ID: {}", response.message, habit_id)
@@ -296,13 +392,99 @@ impl McpServer {
                 };
                 ToolCallResult::success(message)
             },
-            Err(e) => ToolCallResult::error(e.to_string()),
+            Err(e) => ToolCallResult::storage_error(&e),
+        }
+    }
+    
+    /// Call the habit_create_with_history tool
+    async fn call_habit_create_with_history(&self, args: HashMap<String, Value>) -> ToolCallResult {
+        let config = self.habit_tracker.config();
+        let str_arg = |key: &str| args.get(key).and_then(|v| v.as_str()).map(|s| s.to_string());
+        let habit = tools::CreateHabitParams {
+            name: str_arg("name").unwrap_or_default(),
+            description: str_arg("description"),
+            category: str_arg("category").unwrap_or_else(|| config.default_category.to_storage_string()),
+            frequency: str_arg("frequency").unwrap_or_else(|| config.default_frequency.to_string()),
+            target_value: match integer_arg(args.get("target_value"), "target_value") {
+                Ok(target_value) => target_value,
+                Err(e) => return ToolCallResult::storage_error(&e),
+            },
+            unit: str_arg("unit"),
+            motivation: str_arg("motivation"),
+            allow_multiple_per_day: args.get("allow_multiple_per_day")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false),
+        };
+        let entries = args.get("entries")
+            .and_then(|v| v.as_array())
+            .map(|items| items.iter()
+                .map(|item| Ok(tools::HistoryEntryParams {
+                    completed_at: item.get("completed_at")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("")
+                        .to_string(),
+                    value: integer_arg(item.get("value"), "value")?,
+                    intensity: integer_arg(item.get("intensity"), "intensity")?,
+                    notes: item.get("notes")
+                        .and_then(|v| v.as_str())
+                        .map(|s| s.to_string()),
+                }))
+                .collect::<Result<Vec<_>, StorageError>>())
+            .unwrap_or(Ok(Vec::new()));
+        let entries = match entries {
+            Ok(entries) => entries,
+            Err(e) => return ToolCallResult::storage_error(&e),
+        };
+        
+        let validation = EntryValidation {
+            clock: self.habit_tracker.analytics().clock(),
+            max_backfill_days: self.habit_tracker.config().max_backfill_days,
+        };
+        
+        let params = tools::CreateWithHistoryParams { habit, entries };
+        match tools::create_habit_with_history(self.habit_tracker.storage(), self.habit_tracker.analytics(), params, &validation) {
+            Ok(response) => {
+                let structured = json!({
+                    "habit_id": response.habit_id,
+                    "entries_created": response.entries_created,
+                    "current_streak": response.current_streak,
+                    "longest_streak": response.longest_streak,
+                });
+                let message = format!("{}\nHabit ID: {}", response.message, response.habit_id);
+                ToolCallResult::success(message).with_structured_content(structured)
+            }
+            Err(e) => ToolCallResult::storage_error(&e),
         }
     }
     
     /// Call the habit_log tool
     async fn call_habit_log(&self, args: HashMap<String, Value>) -> ToolCallResult {
-        let log_params = tools::LogHabitParams {
+        let log_params = match Self::log_params_from(&args) {
+            Ok(params) => params,
+            Err(e) => return ToolCallResult::storage_error(&e),
+        };
+        
+        let validation = EntryValidation {
+            clock: self.habit_tracker.analytics().clock(),
+            max_backfill_days: self.habit_tracker.config().max_backfill_days,
+        };
+        
+        match tools::log_habit(self.habit_tracker.storage(), self.habit_tracker.analytics(), log_params, &validation) {
+            Ok(response) => {
+                let structured = json!({
+                    "current_streak": response.current_streak,
+                    "already_logged": response.already_logged,
+                    "entry": response.entry,
+                });
+                ToolCallResult::success(response.message).with_structured_content(structured)
+            }
+            Err(e) => ToolCallResult::storage_error(&e),
+        }
+    }
+    
+    /// Read the arguments of a single habit completion
+    fn log_params_from(args: &HashMap<String, Value>) -> Result<tools::LogHabitParams, StorageError> {
+        Ok(tools::LogHabitParams {
             habit_id: args.get("habit_id")
                 .and_then(|v| v.as_str())
                 .unwrap_or("")
@@ -310,20 +492,68 @@ impl McpServer {
             completed_at: args.get("completed_at")
                 .and_then(|v| v.as_str())
                 .map(|s| s.to_string()),
-            value: args.get("value")
-                .and_then(|v| v.as_u64())
-                .map(|n| n as u32),
-            intensity: args.get("intensity")
-                .and_then(|v| v.as_u64())
-                .map(|n| n as u8),
+            value: integer_arg(args.get("value"), "value")?,
+            intensity: integer_arg(args.get("intensity"), "intensity")?,
             notes: args.get("notes")
                 .and_then(|v| v.as_str())
                 .map(|s| s.to_string()),
+            mood: args.get("mood")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
+        })
+    }
+    
+    /// Call the habit_log_many tool
+    async fn call_habit_log_many(&self, args: HashMap<String, Value>) -> ToolCallResult {
+        let entries = args.get("entries")
+            .and_then(|v| v.as_array())
+            .map(|items| items.iter()
+                .map(|item| {
+                    let item_args: HashMap<String, Value> = item.as_object()
+                        .map(|obj| obj.clone().into_iter().collect())
+                        .unwrap_or_default();
+                    Self::log_params_from(&item_args)
+                })
+                .collect::<Result<Vec<_>, StorageError>>())
+            .unwrap_or(Ok(Vec::new()));
+        let log_many_params = tools::LogManyParams {
+            entries: match entries {
+                Ok(entries) => entries,
+                Err(e) => return ToolCallResult::storage_error(&e),
+            },
+            on_conflict: args.get("on_conflict")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
         };
         
-        match tools::log_habit(self.habit_tracker.storage(), log_params) {
-            Ok(response) => ToolCallResult::success(response.message),
-            Err(e) => ToolCallResult::error(e.to_string()),
+        let validation = EntryValidation {
+            clock: self.habit_tracker.analytics().clock(),
+            max_backfill_days: self.habit_tracker.config().max_backfill_days,
+        };
+        
+        match tools::log_many(self.habit_tracker.storage(), self.habit_tracker.analytics(), log_many_params, &validation) {
+            Ok(response) => {
+                let structured = json!({
+                    "created": response.created,
+                    "skipped": response.skipped,
+                    "failed": response.failed,
+                    "freezes_earned": response.freezes_earned,
+                    "results": response.results,
+                });
+                ToolCallResult::success(response.message).with_structured_content(structured)
+            }
+            Err(e) => ToolCallResult::storage_error(&e),
+        }
+    }
+    
+    /// Decide whether a call should use compact output
+    ///
+    /// A per-call "format" argument wins over the server-wide setting.
+    fn use_compact(&self, args: &HashMap<String, Value>) -> bool {
+        match args.get("format").and_then(|v| v.as_str()) {
+            Some("compact") => true,
+            Some("full") => false,
+            _ => self.habit_tracker.config().compact,
         }
     }
     
@@ -335,9 +565,12 @@ impl McpServer {
                 .map(|s| s.to_string()),
         };
         
-        match tools::get_habit_status(self.habit_tracker.storage(), status_params) {
+        let compact = self.use_compact(&args);
+        
+        match tools::get_habit_status(self.habit_tracker.storage(), self.habit_tracker.analytics(), status_params) {
+            Ok(response) if compact => ToolCallResult::success(format_status_compact(&response)),
             Ok(response) => ToolCallResult::success(response.message),
-            Err(e) => ToolCallResult::error(e.to_string()),
+            Err(e) => ToolCallResult::storage_error(&e),
         }
     }
     
@@ -353,11 +586,52 @@ impl McpServer {
             insight_type: args.get("insight_type")
                 .and_then(|v| v.as_str())
                 .map(|s| s.to_string()),
+            scope: args.get("scope")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
+            include_inactive: args.get("include_inactive")
+                .and_then(|v| v.as_bool()),
+            // "full" and "compact" pick the text style below instead
+            format: args.get("format")
+                .and_then(|v| v.as_str())
+                .filter(|f| *f != "full" && *f != "compact")
+                .map(|s| s.to_string()),
+            min_confidence: args.get("min_confidence")
+                .and_then(|v| v.as_f64()),
+            tone: args.get("tone")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
         };
         
-        match tools::get_habit_insights(self.habit_tracker.storage(), insights_params) {
-            Ok(response) => ToolCallResult::success(response.message),
-            Err(e) => ToolCallResult::error(e.to_string()),
+        let compact = insights_params.format.is_none() && self.use_compact(&args);
+        // Raw insight objects for clients that render their own UI
+        let raw = args.get("raw")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        
+        match tools::get_habit_insights(self.habit_tracker.storage(), self.habit_tracker.analytics(), insights_params) {
+            Ok(response) => {
+                let text = if compact {
+                    format_insights_compact(&response)
+                } else {
+                    response.message.clone()
+                };
+                let result = ToolCallResult::success(text);
+                match response.per_habit {
+                    Some(per_habit) => result.with_structured_content(json!({
+                        "insights": response.insights,
+                        "per_habit": per_habit,
+                    })),
+                    None if raw => result.with_structured_content(json!({
+                        "insights": response.insights,
+                        "summary": response.summary,
+                        "time_period": response.time_period,
+                        "generated_at": response.generated_at,
+                    })),
+                    None => result,
+                }
+            }
+            Err(e) => ToolCallResult::storage_error(&e),
         }
     }
     
@@ -373,39 +647,87 @@ impl McpServer {
             sort_by: args.get("sort_by")
                 .and_then(|v| v.as_str())
                 .map(|s| s.to_string()),
+            recent_days: match integer_arg(args.get("recent_days"), "recent_days") {
+                Ok(recent_days) => recent_days,
+                Err(e) => return ToolCallResult::storage_error(&e),
+            },
+            abbreviate_days: args.get("abbreviate_days")
+                .and_then(|v| v.as_bool()),
+            min_streak: match integer_arg(args.get("min_streak"), "min_streak") {
+                Ok(min_streak) => min_streak,
+                Err(e) => return ToolCallResult::storage_error(&e),
+            },
+            max_streak: match integer_arg(args.get("max_streak"), "max_streak") {
+                Ok(max_streak) => max_streak,
+                Err(e) => return ToolCallResult::storage_error(&e),
+            },
+            min_completion_rate: args.get("min_completion_rate")
+                .and_then(|v| v.as_f64()),
+            max_completion_rate: args.get("max_completion_rate")
+                .and_then(|v| v.as_f64()),
+            group_by: args.get("group_by")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
         };
 
-        match tools::list_habits(self.habit_tracker.storage(), list_params) {
+        let grouped = list_params.group_by.as_deref() == Some("category");
+        let filtered = list_params.min_streak.is_some() || list_params.max_streak.is_some()
+            || list_params.min_completion_rate.is_some() || list_params.max_completion_rate.is_some();
+        let compact = self.use_compact(&args);
+        let options = self.habit_tracker.analytics().config().streak_options();
+
+        match tools::list_habits(self.habit_tracker.storage(), list_params, &options, self.habit_tracker.analytics().clock(), &self.habit_tracker.config().categories) {
             Ok(response) => {
-                if response.habits.is_empty() {
+                if response.habits.is_empty() && filtered {
+                    ToolCallResult::success("No habits match those streak and completion rate filters.".to_string())
+                } else if response.habits.is_empty() {
                     ToolCallResult::success("No habits found. Create your first habit to get started!".to_string())
+                } else if compact {
+                    ToolCallResult::success(format_list_compact(&response, grouped))
                 } else {
                     let summary = format!("📋 **Habit Summary** ({} habits)\n\n", response.summary.total_habits);
 
-                    let detailed_list = response.habits.iter()
+                    let format_habits = |habits: &[&tools::HabitSummary]| habits.iter()
                         .map(|h| {
-                            format!("🎯 **{}** ({})\n   📅 Frequency: {} | 🔥 Streak: {} days | 📊 Rate: {:.1}% | ✅ Total: {}{}",
+                            let description_line = match &h.description {
+                                Some(desc) if !desc.trim().is_empty() => format!("\n   📝 {}", desc),
+                                _ => String::new(),
+                            };
+
+                            format!("🎯 **{}** ({})\n   📅 Frequency: {} | 🔥 Streak: {} | 📊 Rate: {} | 🧭 Consistency: {}/100 | ✅ Total: {} | 🕒 Recent: {}{}{}{}",
                                 h.name,
                                 h.category,
                                 h.frequency,
-                                h.current_streak,
-                                h.completion_rate * 100.0,
+                                format_streak_length(h.current_streak, h.streak_unit),
+                                format_rate_vs_target(h.completion_rate),
+                                h.consistency_score,
                                 h.total_completions,
-                                if h.is_active { "" } else { " ⏸️ (paused)" }
+                                h.recent_completions,
+                                if h.due_today && !h.done_today { " | ⏳ due today" } else { "" },
+                                if h.is_active { "" } else { " ⏸️ (paused)" },
+                                description_line
                             )
                         })
                         .collect::<Vec<_>>()
                         .join("\n\n");
+                    let detailed_list = if grouped {
+                        tools::group_by_category(&response.habits).into_iter()
+                            .map(|(category, members)| format!("🗂️ **{}** ({})\n\n{}", category, members.len(), format_habits(&members)))
+                            .collect::<Vec<_>>()
+                            .join("\n\n")
+                    } else {
+                        format_habits(&response.habits.iter().collect::<Vec<_>>())
+                    };
 
-                    let overall_stats = format!("\n\n📊 **Overall Stats**\n- Active habits: {}\n- Average completion rate: {:.1}%",
+                    let overall_stats = format!("\n\n📊 **Overall Stats**\n- Active habits: {}\n- Average completion rate: {}",
                         response.summary.active_habits,
-                        response.summary.avg_completion_rate * 100.0
+                        format_rate(response.summary.avg_completion_rate)
                     );
 
                     ToolCallResult::success(format!("{}{}{}", summary, detailed_list, overall_stats))
                 }
             },
-            Err(e) => ToolCallResult::error(e.to_string()),
+            Err(e) => ToolCallResult::storage_error(&e),
         }
     }
 
@@ -425,19 +747,1648 @@ impl McpServer {
             frequency: args.get("frequency")
                 .and_then(|v| v.as_str())
                 .map(|s| s.to_string()),
-            target_value: args.get("target_value")
-                .and_then(|v| v.as_u64())
-                .map(|n| n as u32),
+            target_value: match integer_arg(args.get("target_value"), "target_value") {
+                Ok(target_value) => target_value,
+                Err(e) => return ToolCallResult::storage_error(&e),
+            },
             unit: args.get("unit")
                 .and_then(|v| v.as_str())
                 .map(|s| s.to_string()),
             is_active: args.get("is_active")
                 .and_then(|v| v.as_bool()),
+            motivation: args.get("motivation")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
+            allow_multiple_per_day: args.get("allow_multiple_per_day")
+                .and_then(|v| v.as_bool()),
         };
 
         match tools::update_habit(self.habit_tracker.storage(), update_params) {
             Ok(response) => ToolCallResult::success(response.message),
-            Err(e) => ToolCallResult::error(e.to_string()),
+            Err(e) => ToolCallResult::storage_error(&e),
+        }
+    }
+
+    /// Call the habit_set_frequency tool
+    async fn call_habit_set_frequency(&self, args: HashMap<String, Value>) -> ToolCallResult {
+        let set_frequency_params = tools::SetFrequencyParams {
+            habit_id: args.get("habit_id")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string(),
+            frequency: args.get("frequency")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string(),
+        };
+
+        match tools::set_habit_frequency(self.habit_tracker.storage(), self.habit_tracker.analytics(), set_frequency_params) {
+            Ok(response) => {
+                let structured = json!({
+                    "frequency": response.frequency,
+                    "previous_frequency": response.previous_frequency,
+                    "current_streak": response.current_streak,
+                    "longest_streak": response.longest_streak,
+                });
+                ToolCallResult::success(response.message).with_structured_content(structured)
+            }
+            Err(e) => ToolCallResult::storage_error(&e),
+        }
+    }
+
+    /// Call the habit_recategorize tool
+    async fn call_habit_recategorize(&self, args: HashMap<String, Value>) -> ToolCallResult {
+        let recategorize_params = tools::RecategorizeParams {
+            habit_ids: args.get("habit_ids")
+                .and_then(|v| v.as_array())
+                .map(|ids| ids.iter()
+                    .filter_map(|id| id.as_str().map(|s| s.to_string()))
+                    .collect())
+                .unwrap_or_default(),
+            category: args.get("category")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string(),
+        };
+
+        match tools::recategorize_habits(self.habit_tracker.storage(), recategorize_params, &self.habit_tracker.config().categories) {
+            Ok(response) => ToolCallResult::success(response.message),
+            Err(e) => ToolCallResult::storage_error(&e),
+        }
+    }
+
+    /// Call the habit_rename_category tool
+    async fn call_habit_rename_category(&self, args: HashMap<String, Value>) -> ToolCallResult {
+        let rename_params = tools::RenameCategoryParams {
+            from: args.get("from")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string(),
+            to: args.get("to")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string(),
+        };
+
+        match tools::rename_custom_category(self.habit_tracker.storage(), rename_params, &self.habit_tracker.config().categories) {
+            Ok(response) => ToolCallResult::success(response.message),
+            Err(e) => ToolCallResult::storage_error(&e),
+        }
+    }
+
+    /// Call the habit_categories tool
+    async fn call_habit_categories(&self) -> ToolCallResult {
+        match tools::list_habit_categories(self.habit_tracker.storage(), &self.habit_tracker.config().categories) {
+            Ok(response) => {
+                let structured = json!({
+                    "builtin": response.builtin,
+                    "custom": response.custom,
+                    "total_habits": response.total_habits,
+                });
+                ToolCallResult::success(response.message).with_structured_content(structured)
+            }
+            Err(e) => ToolCallResult::storage_error(&e),
+        }
+    }
+
+    /// Call the habit_reorder tool
+    async fn call_habit_reorder(&self, args: HashMap<String, Value>) -> ToolCallResult {
+        let reorder_params = tools::ReorderParams {
+            habit_ids: args.get("habit_ids")
+                .and_then(|v| v.as_array())
+                .map(|ids| ids.iter()
+                    .filter_map(|id| id.as_str().map(|s| s.to_string()))
+                    .collect())
+                .unwrap_or_default(),
+        };
+
+        match tools::reorder_habits(self.habit_tracker.storage(), reorder_params) {
+            Ok(response) => ToolCallResult::success(response.message),
+            Err(e) => ToolCallResult::storage_error(&e),
+        }
+    }
+
+    /// Call the habit_dedupe tool
+    async fn call_habit_dedupe(&self) -> ToolCallResult {
+        match tools::dedupe_entries(self.habit_tracker.storage(), self.habit_tracker.analytics()) {
+            Ok(response) => ToolCallResult::success(response.message),
+            Err(e) => ToolCallResult::storage_error(&e),
+        }
+    }
+
+    /// Call the habit_trend tool
+    async fn call_habit_trend(&self, args: HashMap<String, Value>) -> ToolCallResult {
+        let trend_params = tools::TrendParams {
+            habit_id: args.get("habit_id")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string(),
+            weeks: match integer_arg(args.get("weeks"), "weeks") {
+                Ok(weeks) => weeks,
+                Err(e) => return ToolCallResult::storage_error(&e),
+            },
+        };
+
+        match tools::get_habit_trend(self.habit_tracker.storage(), self.habit_tracker.analytics(), trend_params) {
+            Ok(response) => {
+                let structured = json!({
+                    "habit_id": response.habit_id,
+                    "name": response.name,
+                    "weeks": response.weeks,
+                    "sparkline": response.sparkline,
+                });
+                ToolCallResult::success(response.message).with_structured_content(structured)
+            }
+            Err(e) => ToolCallResult::storage_error(&e),
+        }
+    }
+
+    /// Call the habit_compare tool
+    async fn call_habit_compare(&self, args: HashMap<String, Value>) -> ToolCallResult {
+        let compare_params = tools::CompareParams {
+            habit_id_a: args.get("habit_id_a")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string(),
+            habit_id_b: args.get("habit_id_b")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string(),
+        };
+
+        match tools::compare_habits(self.habit_tracker.storage(), compare_params, self.habit_tracker.analytics().clock()) {
+            Ok(response) => {
+                let structured = json!({
+                    "a": response.a,
+                    "b": response.b,
+                    "stronger_habit_id": response.stronger_habit_id,
+                    "verdict": response.verdict,
+                });
+                ToolCallResult::success(response.message).with_structured_content(structured)
+            }
+            Err(e) => ToolCallResult::storage_error(&e),
+        }
+    }
+
+    /// Call the habit_history tool
+    async fn call_habit_history(&self, args: HashMap<String, Value>) -> ToolCallResult {
+        let history_params = tools::HistoryParams {
+            habit_id: args.get("habit_id")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string(),
+            limit: match integer_arg(args.get("limit"), "limit") {
+                Ok(limit) => limit,
+                Err(e) => return ToolCallResult::storage_error(&e),
+            },
+            start_date: args.get("start_date")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
+            end_date: args.get("end_date")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
+        };
+
+        match tools::get_habit_history(self.habit_tracker.storage(), history_params, self.habit_tracker.analytics().clock()) {
+            Ok(response) => {
+                let structured = json!({
+                    "habit_id": response.habit_id,
+                    "name": response.name,
+                    "entries": response.entries,
+                });
+                ToolCallResult::success(response.message).with_structured_content(structured)
+            }
+            Err(e) => ToolCallResult::storage_error(&e),
+        }
+    }
+
+    /// Call the habit_milestones tool
+    async fn call_habit_milestones(&self, args: HashMap<String, Value>) -> ToolCallResult {
+        let milestones_params = tools::MilestonesParams {
+            habit_id: args.get("habit_id")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string(),
+        };
+
+        match tools::get_habit_milestones(self.habit_tracker.storage(), milestones_params) {
+            Ok(response) => {
+                let structured = json!({
+                    "habit_id": response.habit_id,
+                    "name": response.name,
+                    "milestones": response.milestones,
+                    "next_milestone": response.next_milestone,
+                });
+                ToolCallResult::success(response.message).with_structured_content(structured)
+            }
+            Err(e) => ToolCallResult::storage_error(&e),
+        }
+    }
+
+    /// Call the habit_reset_streak tool
+    async fn call_habit_reset_streak(&self, args: HashMap<String, Value>) -> ToolCallResult {
+        let reset_params = tools::ResetStreakParams {
+            habit_id: args.get("habit_id")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string(),
+            current_streak: match integer_arg(args.get("current_streak"), "current_streak") {
+                Ok(current_streak) => current_streak,
+                Err(e) => return ToolCallResult::storage_error(&e),
+            },
+            longest_streak: match integer_arg(args.get("longest_streak"), "longest_streak") {
+                Ok(longest_streak) => longest_streak,
+                Err(e) => return ToolCallResult::storage_error(&e),
+            },
+            confirm: args.get("confirm")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false),
+        };
+
+        match tools::reset_habit_streak(self.habit_tracker.storage(), reset_params, self.habit_tracker.analytics().clock()) {
+            Ok(response) => {
+                let structured = json!({
+                    "current_streak": response.current_streak,
+                    "longest_streak": response.longest_streak,
+                    "previous_current_streak": response.previous_current_streak,
+                    "previous_longest_streak": response.previous_longest_streak,
+                });
+                ToolCallResult::success(response.message).with_structured_content(structured)
+            }
+            Err(e) => ToolCallResult::storage_error(&e),
+        }
+    }
+
+    /// Call the habit_use_freeze tool
+    async fn call_habit_use_freeze(&self, args: HashMap<String, Value>) -> ToolCallResult {
+        let freeze_params = tools::UseFreezeParams {
+            habit_id: args.get("habit_id")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string(),
+            date: args.get("date")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
+        };
+
+        let validation = EntryValidation {
+            clock: self.habit_tracker.analytics().clock(),
+            max_backfill_days: self.habit_tracker.config().max_backfill_days,
+        };
+
+        match tools::use_streak_freeze(self.habit_tracker.storage(), self.habit_tracker.analytics(), freeze_params, &validation) {
+            Ok(response) => ToolCallResult::success(response.message),
+            Err(e) => ToolCallResult::storage_error(&e),
         }
     }
-}
\ No newline at end of file
+
+    /// Call the habit_vacation_start tool
+    async fn call_habit_vacation_start(&self, args: HashMap<String, Value>) -> ToolCallResult {
+        let vacation_params = tools::VacationStartParams {
+            habit_id: args.get("habit_id")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string(),
+            start_date: args.get("start_date")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
+            end_date: args.get("end_date")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
+        };
+
+        match tools::start_habit_vacation(self.habit_tracker.storage(), self.habit_tracker.analytics(), vacation_params) {
+            Ok(response) => {
+                let structured = json!({
+                    "start_date": response.start_date,
+                    "end_date": response.end_date,
+                    "current_streak": response.current_streak,
+                });
+                ToolCallResult::success(response.message).with_structured_content(structured)
+            }
+            Err(e) => ToolCallResult::storage_error(&e),
+        }
+    }
+
+    /// Call the habit_vacation_end tool
+    async fn call_habit_vacation_end(&self, args: HashMap<String, Value>) -> ToolCallResult {
+        let vacation_params = tools::VacationEndParams {
+            habit_id: args.get("habit_id")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string(),
+            end_date: args.get("end_date")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
+        };
+
+        match tools::end_habit_vacation(self.habit_tracker.storage(), self.habit_tracker.analytics(), vacation_params) {
+            Ok(response) => {
+                let structured = json!({
+                    "end_date": response.end_date,
+                    "vacations_ended": response.vacations_ended,
+                    "current_streak": response.current_streak,
+                });
+                ToolCallResult::success(response.message).with_structured_content(structured)
+            }
+            Err(e) => ToolCallResult::storage_error(&e),
+        }
+    }
+
+    /// Call the habit_pause_all tool
+    async fn call_habit_pause_all(&self) -> ToolCallResult {
+        match tools::pause_all_habits(self.habit_tracker.storage()) {
+            Ok(response) => ToolCallResult::success(response.message),
+            Err(e) => ToolCallResult::storage_error(&e),
+        }
+    }
+
+    /// Call the habit_resume_all tool
+    async fn call_habit_resume_all(&self) -> ToolCallResult {
+        match tools::resume_all_habits(self.habit_tracker.storage()) {
+            Ok(response) => ToolCallResult::success(response.message),
+            Err(e) => ToolCallResult::storage_error(&e),
+        }
+    }
+
+    /// Call the habit_healthcheck tool
+    async fn call_habit_healthcheck(&self) -> ToolCallResult {
+        let database_path = self.habit_tracker.db_path().display().to_string();
+
+        match tools::health_check(self.habit_tracker.storage(), &database_path) {
+            Ok(response) => {
+                let structured = json!({
+                    "server_version": response.server_version,
+                    "database_path": response.database_path,
+                    "schema_version": response.schema_version,
+                    "habit_count": response.habit_count,
+                    "entry_count": response.entry_count,
+                    "writable": response.writable,
+                    "orphan_entry_count": response.orphan_entry_count,
+                });
+                ToolCallResult::success(response.message).with_structured_content(structured)
+            }
+            Err(e) => ToolCallResult::storage_error(&e),
+        }
+    }
+
+    /// Call the habit_stats tool
+    async fn call_habit_stats(&self) -> ToolCallResult {
+        match tools::storage_stats(self.habit_tracker.storage()) {
+            Ok(response) => {
+                let structured = json!({
+                    "habit_count": response.habit_count,
+                    "entry_count": response.entry_count,
+                    "streak_count": response.streak_count,
+                    "earliest_entry": response.earliest_entry,
+                    "latest_entry": response.latest_entry,
+                    "database_bytes": response.database_bytes,
+                });
+                ToolCallResult::success(response.message).with_structured_content(structured)
+            }
+            Err(e) => ToolCallResult::storage_error(&e),
+        }
+    }
+
+    /// Call the habit_schema tool
+    async fn call_habit_schema(&self) -> ToolCallResult {
+        let response = tools::describe_schema(self.habit_tracker.config().max_backfill_days, &self.habit_tracker.config().categories);
+        let structured = json!({
+            "categories": response.categories,
+            "frequencies": response.frequencies,
+            "fields": response.fields,
+        });
+        ToolCallResult::success(response.message).with_structured_content(structured)
+    }
+
+    /// Call the habit_optimize tool
+    async fn call_habit_optimize(&self, args: HashMap<String, Value>) -> ToolCallResult {
+        let params = tools::OptimizeParams {
+            remove_orphans: args.get("remove_orphans")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false),
+        };
+
+        match tools::optimize_storage(self.habit_tracker.storage(), params) {
+            Ok(response) => {
+                let structured = json!({
+                    "bytes_before": response.bytes_before,
+                    "bytes_after": response.bytes_after,
+                    "orphan_entries_removed": response.orphan_entries_removed,
+                });
+                ToolCallResult::success(response.message).with_structured_content(structured)
+            }
+            Err(e) => ToolCallResult::storage_error(&e),
+        }
+    }
+
+    /// Call the habit_clone tool
+    async fn call_habit_clone(&self, args: HashMap<String, Value>) -> ToolCallResult {
+        let clone_params = tools::CloneHabitParams {
+            source_habit_id: args.get("source_habit_id")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string(),
+            name: args.get("name")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string(),
+        };
+
+        match tools::clone_habit(self.habit_tracker.storage(), clone_params) {
+            Ok(response) => ToolCallResult::success(format!("{}\nHabit ID: {}", response.message, response.habit_id)),
+            Err(e) => ToolCallResult::storage_error(&e),
+        }
+    }
+}
+
+/// URI of the resource listing every habit
+const ALL_HABITS_URI: &str = "habits://all";
+
+/// URI prefix of a single habit's resource, followed by its id
+const HABIT_URI_PREFIX: &str = "habit://";
+
+/// Answer a request with the JSON-RPC error matching a storage error
+fn storage_error_response(id: Value, error: &StorageError) -> JsonRpcResponse {
+    JsonRpcResponse::error(id, storage_error_to_json_rpc_code(error), error.to_string(), None)
+}
+
+/// Read an optional whole-number argument, rejecting values that don't fit in `T`
+///
+/// The schema check normally catches these first; this keeps an
+/// out-of-range number from wrapping around if it gets through.
+fn integer_arg<T: TryFrom<u64>>(value: Option<&Value>, name: &str) -> Result<Option<T>, StorageError> {
+    let Some(value) = value.filter(|v| !v.is_null()) else {
+        return Ok(None);
+    };
+    value.as_u64()
+        .and_then(|n| T::try_from(n).ok())
+        .map(Some)
+        .ok_or_else(|| StorageError::Validation(format!("'{}' must be a whole number in range, got {}", name, value)))
+}
+
+/// Every tool the server offers, with the JSON Schema of its arguments
+///
+/// The same schemas are published in `tools/list` and enforced before a
+/// tool call is dispatched.
+fn tool_definitions() -> Vec<ToolDefinition> {
+    vec![
+        ToolDefinition {
+            name: "habit_create".to_string(),
+            description: "Create a new habit to track".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "name": {"type": "string", "description": "Name of the habit"},
+                    "category": {"type": "string", "description": "Category (health, productivity, etc.) - optional, defaults to the server setting (normally personal)"},
+                    "frequency": {"type": "string", "description": "How often (daily, weekdays, etc.) - optional, defaults to the server setting (normally daily)"},
+                    "motivation": {"type": "string", "description": "Why you want to build this habit, shown as a reminder if you lapse (optional, max 280 characters)"},
+                    "allow_multiple_per_day": {"type": "boolean", "description": "Allow logging several completions on the same day, e.g. glasses of water (optional, defaults to false)"}
+                },
+                "required": ["name"]
+            }),
+        },
+        ToolDefinition {
+            name: "habit_create_with_history".to_string(),
+            description: "Create a habit together with its past completions in one transaction, e.g. when migrating from another tracker".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "name": {"type": "string", "description": "Name of the habit"},
+                    "description": {"type": "string", "description": "Longer description of the habit (optional)"},
+                    "category": {"type": "string", "description": "Category (health, productivity, etc.) - optional, defaults to the server setting (normally personal)"},
+                    "frequency": {"type": "string", "description": "How often (daily, weekdays, etc.) - optional, defaults to the server setting (normally daily)"},
+                    "target_value": {"type": "integer", "minimum": 1, "maximum": MAX_TARGET_VALUE, "description": "Target amount per completion (optional, requires unit)"},
+                    "unit": {"type": "string", "description": "Unit for the target amount, e.g. minutes (optional)"},
+                    "motivation": {"type": "string", "description": "Why you want to build this habit (optional, max 280 characters)"},
+                    "allow_multiple_per_day": {"type": "boolean", "description": "Allow logging several completions on the same day (optional, defaults to false)"},
+                    "entries": {
+                        "type": "array",
+                        "description": "Past completions to import; if any is invalid nothing is created",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "completed_at": {"type": "string", "description": "Date completed (YYYY-MM-DD)"},
+                                "value": {"type": "integer", "minimum": 0, "maximum": MAX_ENTRY_VALUE, "description": "Amount completed (optional)"},
+                                "intensity": {"type": "integer", "minimum": MIN_INTENSITY, "maximum": MAX_INTENSITY, "description": "Intensity rating 1-10 (optional)"},
+                                "notes": {"type": "string", "description": "Optional notes about this completion"}
+                            },
+                            "required": ["completed_at"]
+                        }
+                    }
+                },
+                "required": ["name", "entries"]
+            }),
+        },
+        ToolDefinition {
+            name: "habit_log".to_string(),
+            description: "Log completion of a habit for today or a specific date".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "habit_id": {"type": "string", "description": "ID of the habit to log"},
+                    "completed_at": {"type": "string", "description": "Date completed: YYYY-MM-DD, 'today', 'yesterday' or an ISO timestamp (optional - defaults to today)"},
+                    "value": {"type": "integer", "minimum": 0, "maximum": MAX_ENTRY_VALUE, "description": "Amount completed (optional, e.g., 30 minutes)"},
+                    "intensity": {"type": "integer", "minimum": MIN_INTENSITY, "maximum": MAX_INTENSITY, "description": "Intensity rating 1-10 (optional)"},
+                    "notes": {"type": "string", "description": "Optional notes about this completion"},
+                    "mood": {"type": "string", "description": "How you felt, e.g. energized, tired, stressed (optional, max 30 characters)"}
+                },
+                "required": ["habit_id"]
+            }),
+        },
+        ToolDefinition {
+            name: "habit_log_many".to_string(),
+            description: "Log several habit completions in one transaction, e.g. to sync logs collected offline".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "entries": {
+                        "type": "array",
+                        "description": "Completions to log, each with habit_id and optional completed_at, value, intensity, notes and mood",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "habit_id": {"type": "string", "description": "ID of the habit to log"},
+                                "completed_at": {"type": "string", "description": "Date completed: YYYY-MM-DD, 'today', 'yesterday' or an ISO timestamp (optional - defaults to today)"},
+                                "value": {"type": "integer", "minimum": 0, "maximum": MAX_ENTRY_VALUE, "description": "Amount completed (optional)"},
+                                "intensity": {"type": "integer", "minimum": MIN_INTENSITY, "maximum": MAX_INTENSITY, "description": "Intensity rating 1-10 (optional)"},
+                                "notes": {"type": "string", "description": "Optional notes about this completion"},
+                                "mood": {"type": "string", "description": "How you felt, e.g. energized, tired, stressed (optional, max 30 characters)"}
+                            },
+                            "required": ["habit_id"]
+                        }
+                    },
+                    "on_conflict": {"type": "string", "description": "When a habit is already logged that day: 'skip' (default) skips the item, 'error' rejects the whole batch"}
+                },
+                "required": ["entries"]
+            }),
+        },
+        ToolDefinition {
+            name: "habit_list".to_string(),
+            description: "List all habits with detailed information including streaks, completion rates, and sorting options".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "category": {"type": "string", "description": "Filter by category (health, productivity, etc.) - optional"},
+                    "active_only": {"type": "boolean", "description": "Show only active habits (default: true) - optional"},
+                    "sort_by": {"type": "string", "description": "Sort by: 'name', 'streak', 'completion_rate', 'consistency', 'total_completions', 'created_at', 'position' (default: name) - optional"},
+                    "recent_days": {"type": "integer", "minimum": 0, "maximum": tools::MAX_RECENT_DAYS, "description": "Window in days for counting recent completions (default: 30) - optional"},
+                    "abbreviate_days": {"type": "boolean", "description": "Show custom schedule days as 'Mon, Wed' instead of 'Monday, Wednesday' (default: false) - optional"},
+                    "min_streak": {"type": "integer", "minimum": 0, "description": "Only show habits with a current streak of at least this length - optional"},
+                    "max_streak": {"type": "integer", "minimum": 0, "description": "Only show habits with a current streak of at most this length - optional"},
+                    "min_completion_rate": {"type": "number", "minimum": 0, "maximum": 1, "description": "Only show habits with a completion rate of at least this (0.0-1.0) - optional"},
+                    "max_completion_rate": {"type": "number", "minimum": 0, "maximum": 1, "description": "Only show habits with a completion rate of at most this (0.0-1.0) - optional"},
+                    "group_by": {"type": "string", "description": "Group by: 'category' (headings per category, sort_by applies within each) or 'none' (default: none) - optional"},
+                    "format": {"type": "string", "description": "Output style: 'full' or 'compact' (optional, defaults to the server setting)"}
+                },
+                "required": []
+            }),
+        },
+        ToolDefinition {
+            name: "habit_status".to_string(),
+            description: "Check habit status, streaks and progress".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "habit_id": {"type": "string", "description": "ID of specific habit (optional - shows all if omitted)"},
+                    "format": {"type": "string", "description": "Output style: 'full' or 'compact' (optional, defaults to the server setting)"}
+                },
+                "required": []
+            }),
+        },
+        ToolDefinition {
+            name: "habit_insights".to_string(),
+            description: "Get AI-powered insights and recommendations for your habits".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "habit_id": {"type": "string", "description": "ID of specific habit (optional - analyzes all habits if omitted)"},
+                    "time_period": {"type": "string", "description": "Analysis period: 'week', 'month', 'quarter', 'year' (optional, defaults to 'month')"},
+                    "insight_type": {"type": "string", "description": "Type of insights: 'performance', 'recommendations', 'patterns', 'all' (optional, defaults to 'all')"},
+                    "scope": {"type": "string", "description": "'portfolio' or 'per_habit' to also return each active habit's insights keyed by habit ID (optional, defaults to 'portfolio'; ignored with habit_id)"},
+                    "include_inactive": {"type": "boolean", "description": "Also consider paused habits, e.g. to suggest reactivating one (optional, defaults to false)"},
+                    "min_confidence": {"type": "number", "description": "Leave out insights with confidence below this, from 0.0 to 1.0 (optional, defaults to 0.0)"},
+                    "tone": {"type": "string", "description": "Wording of the insights: 'cheerful' with emojis and encouragement, or 'neutral' for plain, factual phrasing (optional, defaults to 'cheerful')"},
+                    "raw": {"type": "boolean", "description": "Also return the insight objects (title, message, type, confidence, data) as structured content (optional, defaults to false)"},
+                    "format": {"type": "string", "description": "Output style: 'full', 'compact', or 'markdown' for a standalone document with a habit table (optional, defaults to the server setting)"}
+                },
+                "required": []
+            }),
+        },
+        ToolDefinition {
+            name: "habit_update".to_string(),
+            description: "Update an existing habit's properties like name, frequency, target, or active status".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "habit_id": {"type": "string", "description": "ID of the habit to update"},
+                    "name": {"type": "string", "description": "New name for the habit (optional)"},
+                    "description": {"type": "string", "description": "New description for the habit (optional)"},
+                    "frequency": {"type": "string", "description": "New frequency: 'daily', 'weekdays', 'weekends', 'weekly[:<1-7>]', 'custom[:<days>]' or 'every:<days>' (optional)"},
+                    "target_value": {"type": "integer", "minimum": 1, "maximum": MAX_TARGET_VALUE, "description": "New target value (optional)"},
+                    "unit": {"type": "string", "description": "New unit for target value (optional)"},
+                    "is_active": {"type": "boolean", "description": "Whether habit is active (true) or paused (false) (optional)"},
+                    "motivation": {"type": "string", "description": "New motivation for the habit; empty string clears it (optional)"},
+                    "allow_multiple_per_day": {"type": "boolean", "description": "Whether several completions may be logged on the same day (optional)"}
+                },
+                "required": ["habit_id"]
+            }),
+        },
+        ToolDefinition {
+            name: "habit_set_frequency".to_string(),
+            description: "Change how often a habit is scheduled and recalculate its streak under the new schedule".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "habit_id": {"type": "string", "description": "ID of the habit to change"},
+                    "frequency": {"type": "string", "description": "New frequency: 'daily', 'weekdays', 'weekends', 'weekly:<1-7>' (times per week), 'custom:<days>' (e.g. 'custom:tue,thu') or 'every:<days>'"}
+                },
+                "required": ["habit_id", "frequency"]
+            }),
+        },
+        ToolDefinition {
+            name: "habit_recategorize".to_string(),
+            description: "Move several habits into a category at once".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "habit_ids": {"type": "array", "items": {"type": "string"}, "description": "IDs of the habits to move"},
+                    "category": {"type": "string", "description": "Target category (health, productivity, etc. or custom:name)"}
+                },
+                "required": ["habit_ids", "category"]
+            }),
+        },
+        ToolDefinition {
+            name: "habit_rename_category".to_string(),
+            description: "Rename a custom category on every habit that uses it".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "from": {"type": "string", "description": "Current custom category name"},
+                    "to": {"type": "string", "description": "New custom category name (max 50 characters)"}
+                },
+                "required": ["from", "to"]
+            }),
+        },
+        ToolDefinition {
+            name: "habit_categories".to_string(),
+            description: "List the built-in and custom categories in use across all habits (active and paused), with habit counts".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {}
+            }),
+        },
+        ToolDefinition {
+            name: "habit_reorder".to_string(),
+            description: "Set a custom display order for habits (list with sort_by 'position')".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "habit_ids": {"type": "array", "items": {"type": "string"}, "description": "Habit IDs in the desired order, most important first"}
+                },
+                "required": ["habit_ids"]
+            }),
+        },
+        ToolDefinition {
+            name: "habit_dedupe".to_string(),
+            description: "Remove duplicate entries logged for the same habit and day, keeping the earliest".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {}
+            }),
+        },
+        ToolDefinition {
+            name: "habit_trend".to_string(),
+            description: "Show a habit's completion rate week by week, with a sparkline".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "habit_id": {"type": "string", "description": "ID of the habit to chart"},
+                    "weeks": {"type": "integer", "minimum": 1, "maximum": 52, "description": "Number of weeks to include (default 8)"}
+                },
+                "required": ["habit_id"]
+            }),
+        },
+        ToolDefinition {
+            name: "habit_compare".to_string(),
+            description: "Compare two habits side by side: streaks, completion rate, totals, recent activity and which is stronger".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "habit_id_a": {"type": "string", "description": "ID of the first habit"},
+                    "habit_id_b": {"type": "string", "description": "ID of the second habit"}
+                },
+                "required": ["habit_id_a", "habit_id_b"]
+            }),
+        },
+        ToolDefinition {
+            name: "habit_history".to_string(),
+            description: "Show a habit's logged entries newest first, with values, intensity and notes".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "habit_id": {"type": "string", "description": "ID of the habit to review"},
+                    "limit": {"type": "integer", "minimum": 1, "maximum": 1000, "description": "Maximum number of entries to return (default 30)"},
+                    "start_date": {"type": "string", "description": "First day to include (YYYY-MM-DD, 'today' or 'yesterday'; optional)"},
+                    "end_date": {"type": "string", "description": "Last day to include (YYYY-MM-DD, 'today' or 'yesterday'; optional, defaults to today)"}
+                },
+                "required": ["habit_id"]
+            }),
+        },
+        ToolDefinition {
+            name: "habit_milestones".to_string(),
+            description: "Show when a habit first reached each daily streak milestone (7, 14, 30, 60, 90 and 100 days)".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "habit_id": {"type": "string", "description": "ID of the habit to review"}
+                },
+                "required": ["habit_id"]
+            }),
+        },
+        ToolDefinition {
+            name: "habit_reset_streak".to_string(),
+            description: "Administrative: overwrite a habit's stored streak without touching entries. Bypasses the calculation from entries until the next log; requires confirm: true".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "habit_id": {"type": "string", "description": "ID of the habit whose streak to reset"},
+                    "current_streak": {"type": "integer", "minimum": 0, "description": "New current streak (default 0)"},
+                    "longest_streak": {"type": "integer", "minimum": 0, "description": "New longest streak (default: keep the stored value)"},
+                    "confirm": {"type": "boolean", "description": "Must be true to apply the reset"}
+                },
+                "required": ["habit_id", "confirm"]
+            }),
+        },
+        ToolDefinition {
+            name: "habit_use_freeze".to_string(),
+            description: "Spend a streak freeze token so a missed scheduled day doesn't break the streak".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "habit_id": {"type": "string", "description": "ID of the habit to protect"},
+                    "date": {"type": "string", "description": "Day to freeze (YYYY-MM-DD, 'today' or 'yesterday'), defaults to today"}
+                },
+                "required": ["habit_id"]
+            }),
+        },
+        ToolDefinition {
+            name: "habit_vacation_start".to_string(),
+            description: "Put a habit's streak on hold for a vacation: scheduled days in the window neither break nor extend it. Unlike pausing, the habit stays active".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "habit_id": {"type": "string", "description": "ID of the habit going on vacation"},
+                    "start_date": {"type": "string", "description": "First day of the vacation (YYYY-MM-DD, 'today' or 'yesterday'), defaults to today"},
+                    "end_date": {"type": "string", "description": "Last day of the vacation (optional; leave out and call habit_vacation_end when back)"}
+                },
+                "required": ["habit_id"]
+            }),
+        },
+        ToolDefinition {
+            name: "habit_vacation_end".to_string(),
+            description: "End a habit's open vacations so missed days count against the streak again".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "habit_id": {"type": "string", "description": "ID of the habit returning from vacation"},
+                    "end_date": {"type": "string", "description": "Last day of the vacation (YYYY-MM-DD, 'today' or 'yesterday'), defaults to today"}
+                },
+                "required": ["habit_id"]
+            }),
+        },
+        ToolDefinition {
+            name: "habit_pause_all".to_string(),
+            description: "Pause every active habit at once, e.g. before a vacation".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {}
+            }),
+        },
+        ToolDefinition {
+            name: "habit_resume_all".to_string(),
+            description: "Resume every paused habit at once".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {}
+            }),
+        },
+        ToolDefinition {
+            name: "habit_healthcheck".to_string(),
+            description: "Report server version, database path, schema version, row counts, orphaned entries and whether the database is writable. Read-only".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {}
+            }),
+        },
+        ToolDefinition {
+            name: "habit_stats".to_string(),
+            description: "Report database size, row counts and the date range of logged entries".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {}
+            }),
+        },
+        ToolDefinition {
+            name: "habit_schema".to_string(),
+            description: "List the exact category and frequency strings other tools accept, and the limits on names, notes, intensity and values".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {}
+            }),
+        },
+        ToolDefinition {
+            name: "habit_optimize".to_string(),
+            description: "Maintenance: rebuild indexes, refresh query statistics and VACUUM the database to reclaim space after large deletes. May take a while on large databases; run when the server is otherwise idle".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "remove_orphans": {"type": "boolean", "description": "First delete entries, streaks, freezes and vacations whose habit no longer exists (optional, defaults to false)"}
+                }
+            }),
+        },
+        ToolDefinition {
+            name: "habit_clone".to_string(),
+            description: "Create a new habit with the same category, frequency, target and unit as an existing one".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "source_habit_id": {"type": "string", "description": "ID of the habit to copy"},
+                    "name": {"type": "string", "description": "Name for the new habit"}
+                },
+                "required": ["source_habit_id", "name"]
+            }),
+        },
+    ]
+}
+
+/// Render a habit list as one terse line per habit
+/// 
+/// When `grouped`, each category's habits follow a `category:` line.
+fn format_list_compact(response: &tools::ListHabitsResponse, grouped: bool) -> String {
+    if grouped {
+        return tools::group_by_category(&response.habits).into_iter()
+            .map(|(category, members)| format!("{}:\n{}", category, format_list_lines(&members)))
+            .collect::<Vec<_>>()
+            .join("\n");
+    }
+    format_list_lines(&response.habits.iter().collect::<Vec<_>>())
+}
+
+/// One terse line per habit, as used by the compact list
+fn format_list_lines(habits: &[&tools::HabitSummary]) -> String {
+    habits.iter()
+        .map(|h| format!("{} | {} | {} | streak {}{} | rate {} | total {}{}{}",
+            h.name,
+            h.category,
+            h.frequency,
+            h.current_streak,
+            &h.streak_unit[..1],
+            format_rate(h.completion_rate),
+            h.total_completions,
+            if h.due_today && !h.done_today { " | due today" } else { "" },
+            if h.is_active { "" } else { " | paused" }))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Answer with an INTERNAL_ERROR if `handler` hasn't finished within `timeout_ms`
+///
+/// A `timeout_ms` of 0 waits indefinitely. The timeout bounds dispatch: it
+/// can only fire while the handler is awaiting. Tool bodies currently run
+/// synchronously on the server task, so a slow query is only cut off at
+/// its next await point. Heavy queries would need to move onto
+/// `spawn_blocking` to be interrupted promptly, which in turn needs storage
+/// that can be shared across threads.
+async fn with_request_timeout<F>(timeout_ms: u64, id: Value, handler: F) -> JsonRpcResponse
+where
+    F: Future<Output = JsonRpcResponse>,
+{
+    if timeout_ms == 0 {
+        return handler.await;
+    }
+
+    match tokio::time::timeout(Duration::from_millis(timeout_ms), handler).await {
+        Ok(response) => response,
+        Err(_) => {
+            error!(timeout_ms, "Request timed out");
+            JsonRpcResponse::error(
+                id,
+                error_codes::INTERNAL_ERROR,
+                format!("Request timed out after {}ms", timeout_ms),
+                None
+            )
+        }
+    }
+}
+
+/// Render habit statuses as one terse line per habit
+fn format_status_compact(response: &tools::StatusResponse) -> String {
+    if response.habits.is_empty() {
+        return "No habits found".to_string();
+    }
+    
+    response.habits.iter()
+        .map(|h| format!("{} | streak {}{unit} | best {}{unit} | rate {}{}",
+            h.name,
+            h.current_streak,
+            h.longest_streak,
+            format_rate(h.completion_rate),
+            h.last_completed.as_ref().map(|d| format!(" | last {}", d)).unwrap_or_default(),
+            unit = &h.streak_unit[..1]))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Render insights as one terse line per insight
+fn format_insights_compact(response: &crate::InsightsResponse) -> String {
+    response.insights.iter()
+        .map(|i| format!("[{}] {}: {}", i.insight_type, i.title, i.message))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use crate::{Category, CategoryRegistry, Frequency, Habit, HabitStorage, ServerConfig};
+    use crate::analytics::AnalyticsConfig;
+    use crate::mcp::transport::{Framing, OutputBuffering};
+
+    async fn test_server() -> McpServer {
+        let habit_tracker = HabitTrackerServer::new(PathBuf::from(":memory:"))
+            .await
+            .unwrap();
+        McpServer::new(habit_tracker)
+    }
+
+    #[tokio::test]
+    async fn test_notification_produces_no_response() {
+        let mut server = test_server().await;
+
+        let response = server
+            .process_line(r#"{"jsonrpc": "2.0", "method": "notifications/initialized"}"#)
+            .await;
+
+        assert!(response.is_none());
+        assert!(server.initialized);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_method_notification_produces_no_response() {
+        let mut server = test_server().await;
+
+        let response = server
+            .process_line(r#"{"jsonrpc": "2.0", "method": "does/not/exist"}"#)
+            .await;
+
+        assert!(response.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_request_with_id_produces_response() {
+        let mut server = test_server().await;
+
+        let response = server
+            .process_line(r#"{"jsonrpc": "2.0", "id": 7, "method": "tools/list"}"#)
+            .await
+            .expect("requests with an id must be answered");
+
+        assert_eq!(response.id, json!(7));
+        assert!(response.result.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_parse_error_uses_null_id() {
+        let mut server = test_server().await;
+
+        let response = server.process_line("{not json").await.unwrap();
+
+        assert_eq!(response.id, json!(null));
+        assert_eq!(response.error.unwrap().code, error_codes::PARSE_ERROR);
+    }
+
+    async fn server_with_habit(config: ServerConfig) -> McpServer {
+        let habit_tracker = HabitTrackerServer::with_config(PathBuf::from(":memory:"), config)
+            .await
+            .unwrap();
+        let habit = Habit::new(
+            "Drink water".to_string(),
+            Some("Eight glasses".to_string()),
+            Category::Health,
+            Frequency::Daily,
+            None,
+            None,
+        ).unwrap();
+        habit_tracker.storage().create_habit(&habit).unwrap();
+        McpServer::new(habit_tracker)
+    }
+
+    fn list_args(format: Option<&str>) -> HashMap<String, Value> {
+        let mut args = HashMap::new();
+        if let Some(format) = format {
+            args.insert("format".to_string(), json!(format));
+        }
+        args
+    }
+
+    #[tokio::test]
+    async fn test_list_compact_vs_full_output() {
+        let server = server_with_habit(ServerConfig::default()).await;
+
+        let full = server.call_habit_list(list_args(None)).await.content[0].text.clone();
+        let compact = server.call_habit_list(list_args(Some("compact"))).await.content[0].text.clone();
+
+        assert!(full.contains("🎯 **Drink water**"));
+        assert!(full.contains("Overall Stats"));
+        assert!(full.contains("🕒 Recent: 0 | ⏳ due today"));
+        assert_eq!(compact, "Drink water | health | Daily | streak 0d | rate 0% | total 0 | due today");
+    }
+
+    #[tokio::test]
+    async fn test_list_grouped_by_category() {
+        let server = server_with_habit(ServerConfig::default()).await;
+        for (name, category) in [("Budget", Category::Financial), ("Apples", Category::Health), ("Sketch", Category::Creative)] {
+            let habit = Habit::new(name.to_string(), None, category, Frequency::Daily, None, None).unwrap();
+            server.habit_tracker.storage().create_habit(&habit).unwrap();
+        }
+        let mut args = list_args(None);
+        args.insert("group_by".to_string(), json!("category"));
+
+        let full = server.call_habit_list(args.clone()).await.content[0].text.clone();
+        let headers: Vec<&str> = full.lines().filter(|line| line.starts_with("🗂️")).collect();
+        assert_eq!(headers, vec!["🗂️ **creative** (1)", "🗂️ **financial** (1)", "🗂️ **health** (2)"]);
+        let health = full.split("🗂️ **health** (2)").nth(1).unwrap();
+        assert!(health.find("**Apples**").unwrap() < health.find("**Drink water**").unwrap());
+        assert!(!health.contains("Budget") && !health.contains("Sketch"));
+
+        args.insert("format".to_string(), json!("compact"));
+        let compact = server.call_habit_list(args).await.content[0].text.clone();
+        let lines: Vec<&str> = compact.lines().map(|line| line.split(" | ").next().unwrap()).collect();
+        assert_eq!(lines, vec!["creative:", "Sketch", "financial:", "Budget", "health:", "Apples", "Drink water"]);
+    }
+
+    #[tokio::test]
+    async fn test_list_labels_streaks_by_frequency() {
+        let server = server_with_habit(ServerConfig::default()).await;
+        let weekly = Habit::new("Swim".to_string(), None, Category::Health, Frequency::Weekly(2), None, None).unwrap();
+        server.habit_tracker.storage().create_habit(&weekly).unwrap();
+        server.habit_tracker.storage().update_streak(&crate::domain::Streak::from_existing(
+            weekly.id.clone(), 4, 4, Some(chrono::Utc::now().date_naive()), 8, 1.0)).unwrap();
+
+        let full = server.call_habit_list(list_args(None)).await.content[0].text.clone();
+        let compact = server.call_habit_list(list_args(Some("compact"))).await.content[0].text.clone();
+
+        assert!(full.contains("🔥 Streak: 4 weeks |"), "{}", full);
+        assert!(full.contains("🔥 Streak: 0 days |"), "{}", full);
+        assert!(compact.contains("Swim | health | 2 times per week | streak 4w |"), "{}", compact);
+    }
+
+    #[tokio::test]
+    async fn test_compact_server_setting_and_override() {
+        let server = server_with_habit(ServerConfig { compact: true, ..ServerConfig::default() }).await;
+
+        let status = server.call_habit_status(list_args(None)).await.content[0].text.clone();
+        assert_eq!(status, "Drink water | streak 0d | best 0d | rate 0%");
+
+        let full = server.call_habit_status(list_args(Some("full"))).await.content[0].text.clone();
+        assert!(full.contains("📊 Status"));
+    }
+
+    #[tokio::test]
+    async fn test_insights_compact_has_no_emoji_header() {
+        let server = server_with_habit(ServerConfig { compact: true, ..ServerConfig::default() }).await;
+
+        let insights = server.call_habit_insights(list_args(None)).await.content[0].text.clone();
+
+        assert!(insights.lines().all(|line| line.starts_with('[')));
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_responds_and_stops_loop() {
+        let mut server = test_server().await;
+        let input = concat!(
+            r#"{"jsonrpc": "2.0", "id": 1, "method": "shutdown"}"#, "\n",
+            r#"{"jsonrpc": "2.0", "id": 2, "method": "tools/list"}"#, "\n",
+        );
+        let mut output = Vec::new();
+
+        server.serve(Transport::new(input.as_bytes(), &mut output, Framing::Line)).await.unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 1, "requests after shutdown must not be processed");
+
+        let response: Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(response["id"], json!(1));
+        assert!(response.get("error").is_none());
+        assert!(server.shutdown_requested);
+    }
+
+    #[tokio::test]
+    async fn test_batch_buffering_answers_every_rapid_request() {
+        let mut server = test_server().await;
+        let input: String = (1..=200)
+            .map(|id| format!("{}\n", json!({"jsonrpc": "2.0", "id": id, "method": "tools/list"})))
+            .collect();
+        let mut output = Vec::new();
+
+        let transport = Transport::new(input.as_bytes(), BufWriter::new(&mut output), Framing::Line)
+            .with_buffering(OutputBuffering::Batch);
+        server.serve(transport).await.unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        let ids: Vec<Value> = output.lines()
+            .map(|line| serde_json::from_str::<Value>(line).unwrap()["id"].clone())
+            .collect();
+        assert_eq!(ids, (1..=200).map(|id| json!(id)).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn test_slow_handler_times_out() {
+        let slow = async {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            JsonRpcResponse::success(json!(1), json!("too late"))
+        };
+        let response = with_request_timeout(20, json!(1), slow).await;
+        let error = response.error.expect("slow request fails");
+        assert_eq!(error.code, error_codes::INTERNAL_ERROR);
+        assert_eq!(error.message, "Request timed out after 20ms");
+        assert_eq!(response.id, json!(1));
+
+        let fast = async { JsonRpcResponse::success(json!(2), json!("done")) };
+        assert!(with_request_timeout(20, json!(2), fast).await.error.is_none());
+
+        let unbounded = async {
+            tokio::time::sleep(Duration::from_millis(30)).await;
+            JsonRpcResponse::success(json!(3), json!("done"))
+        };
+        assert!(with_request_timeout(0, json!(3), unbounded).await.error.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_content_length_framing_round_trip() {
+        let mut server = test_server().await;
+        let body = "{\"jsonrpc\": \"2.0\",\n \"id\": 7, \"method\": \"tools/list\"}";
+        let input = format!("Content-Length: {}\r\n\r\n{}", body.len(), body);
+        let mut output = Vec::new();
+
+        server.serve(Transport::new(input.as_bytes(), &mut output, Framing::ContentLength)).await.unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        let (header, response_body) = output.split_once("\r\n\r\n").expect("response is framed");
+        assert_eq!(header, format!("Content-Length: {}", response_body.len()));
+
+        let response: Value = serde_json::from_str(response_body).unwrap();
+        assert_eq!(response["id"], json!(7));
+        assert!(response["result"]["tools"].is_array());
+    }
+
+    #[tokio::test]
+    async fn test_trend_returns_structured_series() {
+        let server = server_with_habit(ServerConfig::default()).await;
+        let habit_id = server.habit_tracker.storage().list_habits(None, true).unwrap()[0].id.to_string();
+        let mut args = HashMap::new();
+        args.insert("habit_id".to_string(), json!(habit_id));
+        args.insert("weeks".to_string(), json!(4));
+
+        let result = serde_json::to_value(server.call_habit_trend(args).await).unwrap();
+
+        assert_eq!(result["structuredContent"]["weeks"].as_array().unwrap().len(), 4);
+        assert!(result["structuredContent"]["weeks"][0]["week_start"].is_string());
+        assert_eq!(result["structuredContent"]["sparkline"], json!("____"));
+        assert!(serde_json::to_value(ToolCallResult::success("ok".to_string())).unwrap()
+            .get("structuredContent").is_none());
+    }
+
+    fn error_code(result: ToolCallResult) -> Value {
+        assert!(result.is_error);
+        serde_json::to_value(result).unwrap()["structuredContent"]["code"].clone()
+    }
+
+    #[tokio::test]
+    async fn test_validation_failures_carry_validation_code() {
+        let server = server_with_habit(ServerConfig::default()).await;
+        let habit_id = server.habit_tracker.storage().list_habits(None, true).unwrap()[0].id.to_string();
+
+        let mut create_args = HashMap::new();
+        create_args.insert("name".to_string(), json!(""));
+        let create = server.call_habit_create(create_args).await;
+        assert_eq!(create.content[0].text, "Error: Habit name cannot be empty");
+        assert_eq!(error_code(create), json!(error_codes::VALIDATION_ERROR));
+
+        // Rejected by the domain layer rather than the tool itself
+        let mut update_args = HashMap::new();
+        update_args.insert("habit_id".to_string(), json!(habit_id));
+        update_args.insert("name".to_string(), json!("   "));
+        assert_eq!(error_code(server.call_habit_update(update_args).await), json!(error_codes::VALIDATION_ERROR));
+
+        let mut log_args = HashMap::new();
+        log_args.insert("habit_id".to_string(), json!(crate::HabitId::new().to_string()));
+        assert_eq!(error_code(server.call_habit_log(log_args).await), json!(error_codes::HABIT_NOT_FOUND));
+    }
+
+    #[tokio::test]
+    async fn test_analytics_config_applies_to_logged_streaks_and_status() {
+        let config = ServerConfig {
+            analytics: AnalyticsConfig { allow_over_100_percent: true, ..AnalyticsConfig::default() },
+            ..ServerConfig::default()
+        };
+        let server = server_with_habit(config).await;
+        let mut habit = Habit::new("Swim".to_string(), None, Category::Health, Frequency::Weekly(1), None, None).unwrap();
+        habit.created_at = chrono::Utc::now() - chrono::Duration::days(6);
+        server.habit_tracker.storage().create_habit(&habit).unwrap();
+        let today = chrono::Utc::now().date_naive();
+
+        for days_ago in 0..3 {
+            let mut args = list_args(None);
+            args.insert("habit_id".to_string(), json!(habit.id.to_string()));
+            args.insert("completed_at".to_string(), json!((today - chrono::Duration::days(days_ago)).to_string()));
+            assert!(!server.call_habit_log(args).await.is_error);
+        }
+
+        assert!(server.habit_tracker.storage().get_streak(&habit.id).unwrap().completion_rate > 1.0);
+        let mut args = list_args(None);
+        args.insert("habit_id".to_string(), json!(habit.id.to_string()));
+        let status = server.call_habit_status(args).await;
+        assert!(status.content[0].text.contains("(above target)"), "{}", status.content[0].text);
+    }
+
+    #[tokio::test]
+    async fn test_insights_per_habit_scope_is_structured() {
+        let server = server_with_habit(ServerConfig::default()).await;
+        let habit_id = server.habit_tracker.storage().list_habits(None, true).unwrap()[0].id.to_string();
+
+        let default = serde_json::to_value(server.call_habit_insights(list_args(None)).await).unwrap();
+        assert!(default.get("structuredContent").is_none());
+
+        let mut args = list_args(None);
+        args.insert("scope".to_string(), json!("per_habit"));
+        let result = server.call_habit_insights(args).await;
+        assert!(!result.is_error);
+        assert!(result.content[0].text.contains("**Drink water**"));
+
+        let structured = serde_json::to_value(result).unwrap()["structuredContent"].clone();
+        assert!(structured["insights"].is_array());
+        assert_eq!(structured["per_habit"].as_object().unwrap().len(), 1);
+        assert!(structured["per_habit"][&habit_id].is_array());
+    }
+
+    #[tokio::test]
+    async fn test_insights_raw_returns_insight_objects() {
+        let server = server_with_habit(ServerConfig::default()).await;
+        let habit = server.habit_tracker.storage().list_habits(None, true).unwrap().remove(0);
+        server.habit_tracker.storage().update_streak(&crate::domain::Streak::from_existing(
+            habit.id.clone(), 10, 10, Some(chrono::Utc::now().date_naive()), 10, 0.9)).unwrap();
+
+        let mut args = list_args(None);
+        args.insert("habit_id".to_string(), json!(habit.id.to_string()));
+        args.insert("raw".to_string(), json!(true));
+        let result = server.call_habit_insights(args).await;
+        assert!(!result.is_error);
+        assert!(result.content[0].text.contains("Habit Insights"), "{}", result.content[0].text);
+
+        let structured = serde_json::to_value(result).unwrap()["structuredContent"].clone();
+        let insights = structured["insights"].as_array().expect("raw insights are structured");
+        assert!(insights.iter().any(|i| i["title"] == json!("Great Consistency!")));
+        for insight in insights {
+            assert!(insight["title"].is_string());
+            assert!(insight["message"].is_string());
+            assert!(insight["insight_type"].is_string());
+            assert!(insight["confidence"].is_number());
+        }
+        assert_eq!(structured["time_period"], json!("month"));
+        assert!(structured["summary"].is_string());
+    }
+
+    async fn call_tool(server: &mut McpServer, name: &str, arguments: Value) -> Value {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "tools/call",
+            "params": {"name": name, "arguments": arguments}
+        });
+        let response = server.process_line(&request.to_string()).await.unwrap();
+        serde_json::to_value(response).unwrap()
+    }
+
+    #[test]
+    fn test_integer_arg_rejects_values_that_do_not_fit() {
+        assert_eq!(integer_arg::<u8>(Some(&json!(7)), "intensity").unwrap(), Some(7));
+        assert_eq!(integer_arg::<u8>(None, "intensity").unwrap(), None);
+        assert_eq!(integer_arg::<u8>(Some(&Value::Null), "intensity").unwrap(), None);
+        for bad in [json!(257), json!(7.5), json!(-1)] {
+            assert!(matches!(integer_arg::<u8>(Some(&bad), "intensity"), Err(StorageError::Validation(_))));
+        }
+        assert!(integer_arg::<u32>(Some(&json!(u64::from(u32::MAX) + 1)), "value").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_reset_streak_rejects_counts_that_do_not_fit() {
+        let mut server = server_with_habit(ServerConfig::default()).await;
+        let habit_id = server.habit_tracker.storage().list_habits(None, true).unwrap()[0].id.to_string();
+
+        let result = call_tool(&mut server, "habit_reset_streak", json!({
+            "habit_id": habit_id,
+            "current_streak": u64::from(u32::MAX) + 1,
+            "confirm": true
+        })).await;
+        assert_eq!(result["result"]["is_error"], json!(true));
+        assert!(result["result"]["content"][0]["text"].as_str().unwrap().contains("current_streak"), "{}", result);
+
+        let result = call_tool(&mut server, "habit_reset_streak", json!({
+            "habit_id": habit_id,
+            "current_streak": 1,
+            "confirm": true
+        })).await;
+        assert_eq!(result["result"]["is_error"], json!(false));
+        assert_eq!(result["result"]["structuredContent"]["current_streak"], json!(1));
+    }
+
+    #[tokio::test]
+    async fn test_tool_arguments_are_checked_against_schema() {
+        let mut server = server_with_habit(ServerConfig::default()).await;
+        let habit_id = server.habit_tracker.storage().list_habits(None, true).unwrap()[0].id.to_string();
+
+        let wrong_type = call_tool(&mut server, "habit_log", json!({"habit_id": habit_id, "intensity": "high"})).await;
+        assert_eq!(wrong_type["error"]["code"], json!(error_codes::INVALID_PARAMS));
+        assert_eq!(wrong_type["error"]["message"], json!("Invalid parameters: 'intensity' must be of type integer, got string"));
+        assert_eq!(wrong_type["error"]["data"]["field"], json!("intensity"));
+
+        let fractional = call_tool(&mut server, "habit_log", json!({"habit_id": habit_id, "value": 7.5})).await;
+        assert_eq!(fractional["error"]["data"]["field"], json!("value"));
+        let wrapped = call_tool(&mut server, "habit_log", json!({"habit_id": habit_id, "intensity": 257})).await;
+        assert_eq!(wrapped["error"]["message"], json!("Invalid parameters: 'intensity' must be at most 10"));
+
+        let missing = call_tool(&mut server, "habit_trend", json!({"weeks": 4})).await;
+        assert_eq!(missing["error"]["code"], json!(error_codes::INVALID_PARAMS));
+        assert_eq!(missing["error"]["data"]["field"], json!("habit_id"));
+
+        // Nothing was logged by the rejected call
+        assert!(server.habit_tracker.storage().get_entries_for_habit(&crate::domain::HabitId::from_string(&habit_id).unwrap(), None).unwrap().is_empty());
+
+        let valid = call_tool(&mut server, "habit_trend", json!({"habit_id": habit_id, "weeks": 4})).await;
+        assert!(valid.get("error").is_none());
+        assert_eq!(valid["result"]["is_error"], json!(false));
+    }
+
+    #[tokio::test]
+    async fn test_list_rejects_recent_days_out_of_range() {
+        let mut server = server_with_habit(ServerConfig::default()).await;
+
+        for recent_days in [json!(200_000_000), json!(4_294_967_297u64), json!(7.5)] {
+            let result = call_tool(&mut server, "habit_list", json!({"recent_days": recent_days})).await;
+            assert_eq!(result["error"]["data"]["field"], json!("recent_days"), "{}", result);
+        }
+
+        let valid = call_tool(&mut server, "habit_list", json!({"recent_days": 7})).await;
+        assert_eq!(valid["result"]["is_error"], json!(false));
+    }
+
+    #[tokio::test]
+    async fn test_list_rejects_streak_filters_that_do_not_fit() {
+        let mut server = server_with_habit(ServerConfig::default()).await;
+
+        for field in ["min_streak", "max_streak"] {
+            let result = call_tool(&mut server, "habit_list", json!({field: 4_294_967_296u64})).await;
+            assert_eq!(result["result"]["is_error"], json!(true), "{}", result);
+            assert!(result["result"]["content"][0]["text"].as_str().unwrap().contains(field));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_without_category_uses_configured_default() {
+        let config = ServerConfig {
+            default_category: Category::Mindfulness,
+            default_frequency: Frequency::Weekdays,
+            ..ServerConfig::default()
+        };
+        let mut server = server_with_habit(config).await;
+
+        let create = call_tool(&mut server, "habit_create", json!({"name": "Meditate"})).await;
+        assert_eq!(create["result"]["is_error"], json!(false));
+        let habits = server.habit_tracker.storage().list_habits(None, false).unwrap();
+        let meditate = habits.iter().find(|h| h.name == "Meditate").unwrap();
+        assert_eq!(meditate.category, Category::Mindfulness);
+        assert_eq!(meditate.frequency, Frequency::Weekdays);
+
+        // Custom categories and parameterized frequencies survive as defaults too
+        let config = ServerConfig {
+            default_category: Category::Custom("side projects".to_string()),
+            default_frequency: Frequency::Weekly(3),
+            ..ServerConfig::default()
+        };
+        let mut server = server_with_habit(config).await;
+        call_tool(&mut server, "habit_create", json!({"name": "Write"})).await;
+        let habits = server.habit_tracker.storage().list_habits(None, false).unwrap();
+        let write = habits.iter().find(|h| h.name == "Write").unwrap();
+        assert_eq!(write.category, Category::Custom("side projects".to_string()));
+        assert_eq!(write.frequency, Frequency::Weekly(3));
+    }
+
+    #[tokio::test]
+    async fn test_registered_categories_belong_to_their_server() {
+        let mut categories = CategoryRegistry::default();
+        categories.register("cardio", "Cardio 🏃").unwrap();
+        let mut fitness = server_with_habit(ServerConfig { categories, ..ServerConfig::default() }).await;
+        let mut plain = server_with_habit(ServerConfig::default()).await;
+
+        let create = call_tool(&mut fitness, "habit_create", json!({"name": "Run", "category": "Cardio"})).await;
+        assert_eq!(create["result"]["is_error"], json!(false), "{}", create);
+        let listed = call_tool(&mut fitness, "habit_categories", json!({})).await;
+        assert!(listed["result"]["content"][0]["text"].as_str().unwrap().contains("Cardio 🏃"));
+
+        // Another server in the same process only knows the built-ins
+        let create = call_tool(&mut plain, "habit_create", json!({"name": "Run", "category": "cardio"})).await;
+        assert_eq!(create["result"]["is_error"], json!(true));
+    }
+
+    #[tokio::test]
+    async fn test_log_echoes_stored_entry() {
+        let mut server = server_with_habit(ServerConfig::default()).await;
+        let habit_id = server.habit_tracker.storage().list_habits(None, true).unwrap()[0].id.to_string();
+        let yesterday = (chrono::Utc::now().naive_utc().date() - chrono::Duration::days(1)).to_string();
+
+        let result = call_tool(&mut server, "habit_log", json!({
+            "habit_id": habit_id,
+            "completed_at": "yesterday",
+            "value": 8,
+            "intensity": 6,
+            "notes": "Felt good",
+            "mood": "Calm"
+        })).await;
+
+        let content = &result["result"]["structuredContent"];
+        assert_eq!(content["already_logged"], json!(false));
+        let entry = &content["entry"];
+        assert_eq!(entry["completed_at"], json!(yesterday));
+        assert_eq!(entry["value"], json!(8));
+        assert_eq!(entry["intensity"], json!(6));
+        assert_eq!(entry["notes"], json!("Felt good"));
+        assert_eq!(entry["mood"], json!("calm"));
+        let stored = server.habit_tracker.storage()
+            .get_entries_for_habit(&HabitId::from_string(&habit_id).unwrap(), None)
+            .unwrap();
+        assert_eq!(entry["entry_id"], json!(stored[0].id.to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_missing_required_arguments_name_the_field() {
+        let mut server = server_with_habit(ServerConfig::default()).await;
+
+        let create = call_tool(&mut server, "habit_create", json!({"category": "health", "frequency": "daily"})).await;
+        assert_eq!(create["error"]["code"], json!(error_codes::INVALID_PARAMS));
+        assert_eq!(create["error"]["message"], json!("Invalid parameters: 'name' is required"));
+        assert_eq!(create["error"]["data"]["field"], json!("name"));
+        assert_eq!(server.habit_tracker.storage().list_habits(None, false).unwrap().len(), 1);
+
+        let log = call_tool(&mut server, "habit_log", json!({})).await;
+        assert_eq!(log["error"]["code"], json!(error_codes::INVALID_PARAMS));
+        assert_eq!(log["error"]["message"], json!("Invalid parameters: 'habit_id' is required"));
+        assert_eq!(log["error"]["data"]["field"], json!("habit_id"));
+    }
+
+    #[tokio::test]
+    async fn test_healthcheck_is_listed_and_structured() {
+        let mut server = server_with_habit(ServerConfig::default()).await;
+
+        let list = server.process_line(r#"{"jsonrpc": "2.0", "id": 1, "method": "tools/list"}"#).await.unwrap();
+        let list = serde_json::to_value(list).unwrap();
+        assert!(list["result"]["tools"].as_array().unwrap().iter().any(|t| t["name"] == "habit_healthcheck"));
+
+        let result = call_tool(&mut server, "habit_healthcheck", json!({})).await;
+        let status = &result["result"]["structuredContent"];
+        assert_eq!(status["database_path"], json!(":memory:"));
+        assert_eq!(status["habit_count"], json!(1));
+        assert_eq!(status["entry_count"], json!(0));
+        assert_eq!(status["writable"], json!(true));
+    }
+
+    #[tokio::test]
+    async fn test_log_many_reports_each_item() {
+        let mut server = server_with_habit(ServerConfig::default()).await;
+        let habit_id = server.habit_tracker.storage().list_habits(None, true).unwrap()[0].id.to_string();
+        let yesterday = (chrono::Utc::now().naive_utc().date() - chrono::Duration::days(1)).to_string();
+
+        let result = call_tool(&mut server, "habit_log_many", json!({
+            "entries": [
+                {"habit_id": habit_id, "completed_at": yesterday},
+                {"habit_id": habit_id, "completed_at": yesterday},
+                {"habit_id": "not-an-id", "completed_at": yesterday}
+            ]
+        })).await;
+
+        let content = &result["result"]["structuredContent"];
+        assert_eq!(content["created"], json!(1));
+        assert_eq!(content["skipped"], json!(1));
+        assert_eq!(content["failed"], json!(1));
+        let statuses: Vec<&str> = content["results"].as_array().unwrap().iter()
+            .map(|r| r["status"].as_str().unwrap())
+            .collect();
+        assert_eq!(statuses, vec!["created", "skipped", "error"]);
+
+        // Items are checked against the item schema before anything is logged
+        let missing = call_tool(&mut server, "habit_log_many", json!({
+            "entries": [{"habit_id": habit_id}, {"completed_at": yesterday, "intensity": 3}]
+        })).await;
+        assert_eq!(missing["error"]["code"], json!(error_codes::INVALID_PARAMS));
+        assert_eq!(missing["error"]["data"]["field"], json!("entries[1].habit_id"));
+    }
+
+    #[tokio::test]
+    async fn test_initialize_reports_version_and_build() {
+        let mut server = server_with_habit(ServerConfig::default()).await;
+        let request = json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {}});
+        let response = serde_json::to_value(server.process_line(&request.to_string()).await.unwrap()).unwrap();
+
+        let info = &response["result"]["server_info"];
+        assert_eq!(info["version"], json!(env!("CARGO_PKG_VERSION")));
+        let build = info["build"].as_str().unwrap();
+        assert!(!build.trim().is_empty());
+        assert_eq!(build, crate::BUILD_INFO);
+    }
+
+    /// Log sink for tests, shared between the subscriber and the assertions
+    #[derive(Clone, Default)]
+    struct CapturedLogs(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl std::io::Write for CapturedLogs {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_request_span_carries_id_and_method() {
+        let mut server = server_with_habit(ServerConfig::default()).await;
+        let logs = CapturedLogs::default();
+        let writer = logs.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::DEBUG)
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        server.process_line(r#"{"jsonrpc": "2.0", "id": 4242, "method": "tools/list"}"#).await.unwrap();
+
+        let output = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+        let handled = output.lines()
+            .find(|line| line.contains("Handled request"))
+            .expect("request handling is logged");
+        assert!(handled.contains("id=4242"), "{}", handled);
+        assert!(handled.contains("method=tools/list"), "{}", handled);
+        assert!(handled.contains("success=true"), "{}", handled);
+    }
+
+    #[tokio::test]
+    async fn test_resources_list_includes_every_habit() {
+        let mut server = server_with_habit(ServerConfig::default()).await;
+        let habit_id = server.habit_tracker.storage().list_habits(None, true).unwrap()[0].id.to_string();
+
+        let response = server.process_line(r#"{"jsonrpc": "2.0", "id": 1, "method": "resources/list"}"#).await.unwrap();
+        let response = serde_json::to_value(response).unwrap();
+        let resources = response["result"]["resources"].as_array().unwrap();
+        assert_eq!(resources.len(), 2);
+        assert_eq!(resources[0]["uri"], json!("habits://all"));
+        assert_eq!(resources[1]["uri"], json!(format!("habit://{}", habit_id)));
+        assert_eq!(resources[1]["name"], json!("Drink water"));
+        assert_eq!(resources[1]["mimeType"], json!("application/json"));
+
+        let initialize = json!({"jsonrpc": "2.0", "id": 2, "method": "initialize", "params": {}});
+        let initialize = serde_json::to_value(server.process_line(&initialize.to_string()).await.unwrap()).unwrap();
+        assert!(initialize["result"]["capabilities"]["resources"].is_object());
+        assert!(initialize["result"]["capabilities"]["tools"].is_object());
+    }
+
+    async fn read_resource(server: &mut McpServer, uri: &str) -> Value {
+        let request = json!({"jsonrpc": "2.0", "id": 1, "method": "resources/read", "params": {"uri": uri}});
+        serde_json::to_value(server.process_line(&request.to_string()).await.unwrap()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_resources_read_returns_habit_json() {
+        let mut server = server_with_habit(ServerConfig::default()).await;
+        let habit_id = server.habit_tracker.storage().list_habits(None, true).unwrap()[0].id.to_string();
+
+        let uri = format!("habit://{}", habit_id);
+        let response = read_resource(&mut server, &uri).await;
+        let content = &response["result"]["contents"][0];
+        assert_eq!(content["uri"], json!(uri));
+        let habit: Value = serde_json::from_str(content["text"].as_str().unwrap()).unwrap();
+        assert_eq!(habit["name"], json!("Drink water"));
+        assert_eq!(habit["id"], json!(habit_id));
+
+        let missing = read_resource(&mut server, &format!("habit://{}", HabitId::new())).await;
+        assert_eq!(missing["error"]["code"], json!(error_codes::HABIT_NOT_FOUND));
+
+        let unknown = read_resource(&mut server, "note://1").await;
+        assert_eq!(unknown["error"]["code"], json!(error_codes::INVALID_PARAMS));
+    }
+}
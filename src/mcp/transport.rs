@@ -0,0 +1,226 @@
+/// Message framing for the JSON-RPC transport
+/// 
+/// By default each JSON-RPC message is one line on stdin/stdout. Clients
+/// that speak LSP-style framing instead prefix every message with a
+/// `Content-Length` header, which also lets bodies contain newlines.
+/// Responses are flushed one by one unless batch output buffering is chosen.
+
+use std::pin::Pin;
+use std::task::Poll;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+/// Largest `Content-Length` body accepted, so a bogus header can't force a huge allocation
+pub const MAX_FRAME_SIZE: usize = 16 * 1024 * 1024;
+
+/// How JSON-RPC messages are delimited on the wire
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum Framing {
+    /// One message per line
+    #[default]
+    Line,
+    /// `Content-Length: N\r\n\r\n` header followed by an N-byte body
+    ContentLength,
+}
+
+/// When written responses are flushed to the output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum OutputBuffering {
+    /// Flush after every response
+    #[default]
+    FlushEach,
+    /// Flush only once no more input is immediately available
+    Batch,
+}
+
+/// Reads and writes whole JSON-RPC messages using the chosen framing
+pub struct Transport<R, W> {
+    reader: R,
+    writer: W,
+    framing: Framing,
+    buffering: OutputBuffering,
+}
+
+impl<R, W> Transport<R, W>
+where
+    R: AsyncBufRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    /// Create a transport over the given reader and writer
+    pub fn new(reader: R, writer: W, framing: Framing) -> Self {
+        Self { reader, writer, framing, buffering: OutputBuffering::FlushEach }
+    }
+    
+    /// Use `buffering` to decide when written messages are flushed
+    pub fn with_buffering(mut self, buffering: OutputBuffering) -> Self {
+        self.buffering = buffering;
+        self
+    }
+    
+    /// Read the next message body, or `None` once the input is closed
+    pub async fn read_message(&mut self) -> std::io::Result<Option<String>> {
+        match self.framing {
+            Framing::Line => {
+                let mut line = String::new();
+                if self.reader.read_line(&mut line).await? == 0 {
+                    return Ok(None);
+                }
+                Ok(Some(line))
+            }
+            Framing::ContentLength => self.read_framed().await,
+        }
+    }
+    
+    /// Write one message body followed by the framing delimiter
+    /// 
+    /// With `FlushEach` buffering the message is flushed right away;
+    /// otherwise it waits for `flush_if_idle` or `flush`.
+    pub async fn write_message(&mut self, body: &str) -> std::io::Result<()> {
+        match self.framing {
+            Framing::Line => {
+                self.writer.write_all(body.as_bytes()).await?;
+                self.writer.write_all(b"\n").await?;
+            }
+            Framing::ContentLength => {
+                let header = format!("Content-Length: {}\r\n\r\n", body.len());
+                self.writer.write_all(header.as_bytes()).await?;
+                self.writer.write_all(body.as_bytes()).await?;
+            }
+        }
+        match self.buffering {
+            OutputBuffering::FlushEach => self.writer.flush().await,
+            OutputBuffering::Batch => Ok(()),
+        }
+    }
+    
+    /// Flush pending output unless more input can be read without waiting
+    pub async fn flush_if_idle(&mut self) -> std::io::Result<()> {
+        if self.buffering == OutputBuffering::Batch && !self.input_ready().await {
+            self.writer.flush().await?;
+        }
+        Ok(())
+    }
+    
+    /// Flush any pending output
+    pub async fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush().await
+    }
+    
+    /// Whether the reader already has input available, without waiting for more
+    async fn input_ready(&mut self) -> bool {
+        let reader = &mut self.reader;
+        std::future::poll_fn(|cx| {
+            let ready = matches!(Pin::new(&mut *reader).poll_fill_buf(cx), Poll::Ready(Ok(buf)) if !buf.is_empty());
+            Poll::Ready(ready)
+        }).await
+    }
+    
+    /// Read headers up to the blank line, then exactly Content-Length bytes
+    async fn read_framed(&mut self) -> std::io::Result<Option<String>> {
+        let mut content_length = None;
+        let mut read_any_header = false;
+        let mut line = String::new();
+        
+        loop {
+            line.clear();
+            if self.reader.read_line(&mut line).await? == 0 {
+                if read_any_header {
+                    return Err(invalid_data("input closed in the middle of a frame header"));
+                }
+                return Ok(None);
+            }
+            
+            let header = line.trim_end_matches(['\r', '\n']);
+            if header.is_empty() {
+                if read_any_header {
+                    break;
+                }
+                continue; // Tolerate stray blank lines between frames
+            }
+            read_any_header = true;
+            
+            // Other headers, such as Content-Type, are accepted and ignored
+            if let Some((name, value)) = header.split_once(':') {
+                if name.trim().eq_ignore_ascii_case("content-length") {
+                    let length = value.trim().parse::<usize>()
+                        .map_err(|_| invalid_data(&format!("invalid Content-Length: {}", value.trim())))?;
+                    content_length = Some(length);
+                }
+            }
+        }
+        
+        let length = content_length.ok_or_else(|| invalid_data("frame is missing a Content-Length header"))?;
+        if length > MAX_FRAME_SIZE {
+            return Err(invalid_data(&format!("frame of {} bytes exceeds the {} byte limit", length, MAX_FRAME_SIZE)));
+        }
+        let mut body = vec![0; length];
+        self.reader.read_exact(&mut body).await?;
+        
+        String::from_utf8(body)
+            .map(Some)
+            .map_err(|_| invalid_data("frame body is not valid UTF-8"))
+    }
+}
+
+fn invalid_data(message: &str) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_content_length_reads_consecutive_frames() {
+        let input = "Content-Length: 7\r\n\r\n{\"a\":1}Content-Type: application/json\r\ncontent-length: 9\r\n\r\n{\"b\":\n2}\n";
+        let mut transport = Transport::new(input.as_bytes(), Vec::new(), Framing::ContentLength);
+
+        assert_eq!(transport.read_message().await.unwrap().as_deref(), Some("{\"a\":1}"));
+        assert_eq!(transport.read_message().await.unwrap().as_deref(), Some("{\"b\":\n2}\n"));
+        assert_eq!(transport.read_message().await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_content_length_requires_header() {
+        let input = "Content-Type: application/json\r\n\r\n{}";
+        let mut transport = Transport::new(input.as_bytes(), Vec::new(), Framing::ContentLength);
+
+        let err = transport.read_message().await.unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    }
+
+    #[tokio::test]
+    async fn test_content_length_rejects_oversized_frames() {
+        let input = format!("Content-Length: {}\r\n\r\n{{}}", MAX_FRAME_SIZE + 1);
+        let mut transport = Transport::new(input.as_bytes(), Vec::new(), Framing::ContentLength);
+
+        let err = transport.read_message().await.unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        assert!(err.to_string().contains("exceeds"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_write_message_uses_framing() {
+        let mut framed = Transport::new(&b""[..], Vec::new(), Framing::ContentLength);
+        framed.write_message("{\"ok\":\"✓\"}").await.unwrap();
+        assert_eq!(String::from_utf8(framed.writer).unwrap(), "Content-Length: 12\r\n\r\n{\"ok\":\"✓\"}");
+
+        let mut lines = Transport::new(&b""[..], Vec::new(), Framing::Line);
+        lines.write_message("{}").await.unwrap();
+        assert_eq!(lines.writer, b"{}\n");
+    }
+
+    #[tokio::test]
+    async fn test_batch_buffering_flushes_only_when_idle() {
+        let writer = tokio::io::BufWriter::new(Vec::new());
+        let mut transport = Transport::new(&b"{}\n"[..], writer, Framing::Line)
+            .with_buffering(OutputBuffering::Batch);
+
+        transport.write_message("{\"a\":1}").await.unwrap();
+        transport.flush_if_idle().await.unwrap();
+        assert!(transport.writer.get_ref().is_empty(), "input is pending, so nothing is flushed yet");
+
+        transport.read_message().await.unwrap();
+        transport.flush_if_idle().await.unwrap();
+        assert_eq!(transport.writer.get_ref(), b"{\"a\":1}\n");
+    }
+}
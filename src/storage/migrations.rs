@@ -3,13 +3,16 @@
 /// This module handles creating and updating the SQLite database schema.
 /// It ensures the database has all the required tables and indexes.
 
-use rusqlite::{Connection};
+use rusqlite::{Connection, params};
+use serde::Deserialize;
+use chrono::Weekday;
+use crate::domain::Frequency;
 use crate::storage::StorageError;
 
 /// Current database schema version
 /// 
 /// Increment this when you add new migrations
-const CURRENT_VERSION: i32 = 1;
+const CURRENT_VERSION: i32 = 11;
 
 /// Initialize the database schema
 /// 
@@ -27,17 +30,20 @@ pub fn initialize_database(conn: &Connection) -> Result<(), StorageError> {
     // Check current version
     let current_version = get_current_version(conn)?;
     
-    // Run migrations if needed
+    // Run migrations if needed, all or nothing: several aren't safe to
+    // re-run, so a failure part way must leave the old schema and version
     if current_version < CURRENT_VERSION {
-        run_migrations(conn, current_version)?;
-        set_version(conn, CURRENT_VERSION)?;
+        let tx = conn.unchecked_transaction()?;
+        run_migrations(&tx, current_version)?;
+        set_version(&tx, CURRENT_VERSION)?;
+        tx.commit()?;
     }
     
     Ok(())
 }
 
 /// Get the current database schema version
-fn get_current_version(conn: &Connection) -> Result<i32, StorageError> {
+pub(crate) fn get_current_version(conn: &Connection) -> Result<i32, StorageError> {
     let version = conn
         .query_row("SELECT version FROM schema_version LIMIT 1", [], |row| {
             row.get::<_, i32>(0)
@@ -63,10 +69,45 @@ fn run_migrations(conn: &Connection, from_version: i32) -> Result<(), StorageErr
         migration_v1(conn)?;
     }
     
-    // Future migrations would go here:
-    // if from_version < 2 {
-    //     migration_v2(conn)?;
-    // }
+    if from_version < 2 {
+        migration_v2(conn)?;
+    }
+    
+    if from_version < 3 {
+        migration_v3(conn)?;
+    }
+    
+    if from_version < 4 {
+        migration_v4(conn)?;
+    }
+    
+    if from_version < 5 {
+        migration_v5(conn)?;
+    }
+    
+    if from_version < 6 {
+        migration_v6(conn)?;
+    }
+    
+    if from_version < 7 {
+        migration_v7(conn)?;
+    }
+    
+    if from_version < 8 {
+        migration_v8(conn)?;
+    }
+    
+    if from_version < 9 {
+        migration_v9(conn)?;
+    }
+    
+    if from_version < 10 {
+        migration_v10(conn)?;
+    }
+    
+    if from_version < 11 {
+        migration_v11(conn)?;
+    }
     
     Ok(())
 }
@@ -129,6 +170,236 @@ fn migration_v1(conn: &Connection) -> Result<(), StorageError> {
     Ok(())
 }
 
+/// Migration to version 2: Add user-defined habit ordering
+fn migration_v2(conn: &Connection) -> Result<(), StorageError> {
+    conn.execute("ALTER TABLE habits ADD COLUMN position INTEGER", [])?;
+    
+    tracing::info!("Applied migration v2: Added habit position column");
+    Ok(())
+}
+
+/// Migration to version 3: Add streak freeze tokens and spent freezes
+fn migration_v3(conn: &Connection) -> Result<(), StorageError> {
+    conn.execute("ALTER TABLE habits ADD COLUMN freeze_tokens INTEGER NOT NULL DEFAULT 0", [])?;
+    
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS streak_freezes (
+            habit_id TEXT NOT NULL,
+            frozen_on TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            PRIMARY KEY (habit_id, frozen_on),
+            FOREIGN KEY (habit_id) REFERENCES habits (id)
+        )",
+        [],
+    )?;
+    
+    tracing::info!("Applied migration v3: Added streak freeze tokens");
+    Ok(())
+}
+
+/// Migration to version 4: Add the habit motivation ("why") column
+fn migration_v4(conn: &Connection) -> Result<(), StorageError> {
+    conn.execute("ALTER TABLE habits ADD COLUMN motivation TEXT", [])?;
+    
+    tracing::info!("Applied migration v4: Added habit motivation column");
+    Ok(())
+}
+
+/// Migration to version 5: Allow several entries per day for some habits
+/// 
+/// The unique (habit_id, completed_at) index can't depend on a habit
+/// setting, so it is dropped and one-entry-per-day is enforced when
+/// entries are inserted instead.
+fn migration_v5(conn: &Connection) -> Result<(), StorageError> {
+    conn.execute(
+        "ALTER TABLE habits ADD COLUMN allow_multiple_per_day BOOLEAN NOT NULL DEFAULT FALSE",
+        [],
+    )?;
+    conn.execute("DROP INDEX IF EXISTS idx_habit_entries_unique", [])?;
+    
+    tracing::info!("Applied migration v5: Added multiple completions per day");
+    Ok(())
+}
+
+/// Migration to version 6: Add vacation windows that put streaks on hold
+/// 
+/// `end_date` is NULL while a vacation is still open. Windows may overlap.
+fn migration_v6(conn: &Connection) -> Result<(), StorageError> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS vacations (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            habit_id TEXT NOT NULL,
+            start_date TEXT NOT NULL,
+            end_date TEXT,
+            created_at TEXT NOT NULL,
+            FOREIGN KEY (habit_id) REFERENCES habits (id)
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_vacations_habit ON vacations (habit_id, start_date)",
+        [],
+    )?;
+    
+    tracing::info!("Applied migration v6: Added vacations");
+    Ok(())
+}
+
+/// Frequency as serde's default enum representation wrote it before v7,
+/// e.g. `"Daily"`, `{"Weekly":3}` or `{"Custom":["Mon","Wed"]}`
+#[derive(Deserialize)]
+enum LegacyFrequency {
+    Daily,
+    Weekly(u8),
+    Weekdays,
+    Weekends,
+    Custom(Vec<Weekday>),
+    Interval(u32),
+}
+
+impl From<LegacyFrequency> for Frequency {
+    fn from(legacy: LegacyFrequency) -> Self {
+        match legacy {
+            LegacyFrequency::Daily => Frequency::Daily,
+            LegacyFrequency::Weekly(times) => Frequency::Weekly(times),
+            LegacyFrequency::Weekdays => Frequency::Weekdays,
+            LegacyFrequency::Weekends => Frequency::Weekends,
+            LegacyFrequency::Custom(days) => Frequency::Custom(days),
+            LegacyFrequency::Interval(days) => Frequency::Interval(days),
+        }
+    }
+}
+
+/// Migration to version 7: Store frequencies in their stable string form
+/// 
+/// Rows written as serde JSON are rewritten as `Frequency`'s `Display`
+/// form (e.g. `weekly:3`). Values that aren't legacy JSON are left alone.
+/// The upgrade transaction makes the rewrite all or nothing.
+fn migration_v7(conn: &Connection) -> Result<(), StorageError> {
+    let legacy_rows: Vec<(String, String)> = {
+        let mut stmt = conn.prepare("SELECT id, frequency_data FROM habits")?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        rows.collect::<Result<_, _>>()?
+    };
+    
+    let mut converted = 0;
+    for (id, stored) in legacy_rows {
+        if let Ok(legacy) = serde_json::from_str::<LegacyFrequency>(&stored) {
+            conn.execute(
+                "UPDATE habits SET frequency_type = 'text', frequency_data = ?2 WHERE id = ?1",
+                params![id, Frequency::from(legacy).to_string()],
+            )?;
+            converted += 1;
+        }
+    }
+    
+    tracing::info!("Applied migration v7: Converted {} habit frequencies to strings", converted);
+    Ok(())
+}
+
+/// Migration to version 8: Add a metadata table recording the date basis
+/// 
+/// `completed_at` is stored as a bare date, and until now it has always
+/// been the UTC date, so existing databases are marked `utc`. A later
+/// switch to local dates adds `previous_date_basis` and
+/// `date_basis_changed_on` rows, letting readers tell old rows apart.
+fn migration_v8(conn: &Connection) -> Result<(), StorageError> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS metadata (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL
+        )",
+        [],
+    )?;
+    conn.execute(
+        "INSERT OR IGNORE INTO metadata (key, value) VALUES ('date_basis', 'utc')",
+        [],
+    )?;
+    
+    tracing::info!("Applied migration v8: Added metadata with date basis");
+    Ok(())
+}
+
+/// Migration to version 9: Add an optional mood tag to entries
+fn migration_v9(conn: &Connection) -> Result<(), StorageError> {
+    conn.execute("ALTER TABLE habit_entries ADD COLUMN mood TEXT", [])?;
+    
+    tracing::info!("Applied migration v9: Added mood to habit_entries");
+    Ok(())
+}
+
+/// Migration to version 10: Keep every date basis switch, not just the last
+/// 
+/// The metadata rows from v8 could only describe one switch, so a second
+/// one overwrote the first. Each basis now gets a `date_basis_history` row
+/// with the first completion date written under it (NULL for the basis
+/// the database started with), and the metadata rows are moved there.
+fn migration_v10(conn: &Connection) -> Result<(), StorageError> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS date_basis_history (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            basis TEXT NOT NULL,
+            effective_from TEXT
+        )",
+        [],
+    )?;
+    
+    let metadata = |key: &str| -> Result<Option<String>, StorageError> {
+        match conn.query_row("SELECT value FROM metadata WHERE key = ?1", [key], |row| row.get(0)) {
+            Ok(value) => Ok(Some(value)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    };
+    let basis = metadata("date_basis")?.unwrap_or_else(|| "utc".to_string());
+    match (metadata("previous_date_basis")?, metadata("date_basis_changed_on")?) {
+        (Some(previous), Some(changed_on)) => {
+            conn.execute("INSERT INTO date_basis_history (basis, effective_from) VALUES (?1, NULL)", [previous])?;
+            conn.execute("INSERT INTO date_basis_history (basis, effective_from) VALUES (?1, ?2)", params![basis, changed_on])?;
+        }
+        _ => {
+            conn.execute("INSERT INTO date_basis_history (basis, effective_from) VALUES (?1, NULL)", [basis])?;
+        }
+    }
+    conn.execute(
+        "DELETE FROM metadata WHERE key IN ('date_basis', 'previous_date_basis', 'date_basis_changed_on')",
+        [],
+    )?;
+    
+    tracing::info!("Applied migration v10: Moved the date basis into date_basis_history");
+    Ok(())
+}
+
+/// Message the one-entry-per-day trigger aborts with
+pub(crate) const DUPLICATE_ENTRY_ABORT: &str = "habit already logged on this day";
+
+/// Migration to version 11: Enforce one entry per day in the database again
+/// 
+/// v5 dropped the unique index so some habits could log several times a
+/// day, leaving only a check in the storage layer that two writers could
+/// both pass. The trigger applies the same rule inside the INSERT itself.
+fn migration_v11(conn: &Connection) -> Result<(), StorageError> {
+    conn.execute(
+        &format!(
+            "CREATE TRIGGER IF NOT EXISTS habit_entries_one_per_day
+             BEFORE INSERT ON habit_entries
+             WHEN NOT COALESCE((SELECT allow_multiple_per_day FROM habits WHERE id = NEW.habit_id), FALSE)
+              AND EXISTS (
+                  SELECT 1 FROM habit_entries
+                  WHERE habit_id = NEW.habit_id AND completed_at = NEW.completed_at
+              )
+             BEGIN
+                 SELECT RAISE(ABORT, '{}');
+             END",
+            DUPLICATE_ENTRY_ABORT
+        ),
+        [],
+    )?;
+    
+    tracing::info!("Applied migration v11: Added the one-entry-per-day trigger");
+    Ok(())
+}
+
 /// Create database indexes for version 1
 fn create_indexes_v1(conn: &Connection) -> Result<(), StorageError> {
     // Index for finding entries by habit and date (most common query)
@@ -208,4 +479,283 @@ mod tests {
         let version = get_current_version(&conn).unwrap();
         assert_eq!(version, CURRENT_VERSION);
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_upgrade_from_v1_adds_position() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute("CREATE TABLE schema_version (version INTEGER PRIMARY KEY)", []).unwrap();
+        migration_v1(&conn).unwrap();
+        set_version(&conn, 1).unwrap();
+        
+        initialize_database(&conn).unwrap();
+        
+        assert_eq!(get_current_version(&conn).unwrap(), CURRENT_VERSION);
+        conn.execute("UPDATE habits SET position = 1", []).unwrap();
+    }
+    
+    #[test]
+    fn test_upgrade_from_v2_adds_streak_freezes() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute("CREATE TABLE schema_version (version INTEGER PRIMARY KEY)", []).unwrap();
+        migration_v1(&conn).unwrap();
+        migration_v2(&conn).unwrap();
+        set_version(&conn, 2).unwrap();
+        
+        initialize_database(&conn).unwrap();
+        
+        assert_eq!(get_current_version(&conn).unwrap(), CURRENT_VERSION);
+        conn.execute("UPDATE habits SET freeze_tokens = 2", []).unwrap();
+        let freezes: i32 = conn
+            .query_row("SELECT COUNT(*) FROM streak_freezes", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(freezes, 0);
+    }
+    
+    #[test]
+    fn test_upgrade_from_v3_adds_motivation() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute("CREATE TABLE schema_version (version INTEGER PRIMARY KEY)", []).unwrap();
+        migration_v1(&conn).unwrap();
+        migration_v2(&conn).unwrap();
+        migration_v3(&conn).unwrap();
+        set_version(&conn, 3).unwrap();
+        
+        initialize_database(&conn).unwrap();
+        
+        assert_eq!(get_current_version(&conn).unwrap(), CURRENT_VERSION);
+        conn.execute("UPDATE habits SET motivation = 'why'", []).unwrap();
+    }
+    
+    #[test]
+    fn test_upgrade_from_v4_allows_multiple_entries_per_day() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute("CREATE TABLE schema_version (version INTEGER PRIMARY KEY)", []).unwrap();
+        migration_v1(&conn).unwrap();
+        migration_v2(&conn).unwrap();
+        migration_v3(&conn).unwrap();
+        migration_v4(&conn).unwrap();
+        set_version(&conn, 4).unwrap();
+        
+        initialize_database(&conn).unwrap();
+        
+        assert_eq!(get_current_version(&conn).unwrap(), CURRENT_VERSION);
+        conn.execute("UPDATE habits SET allow_multiple_per_day = TRUE", []).unwrap();
+        let unique_indexes: i32 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM sqlite_master WHERE type='index' AND name='idx_habit_entries_unique'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(unique_indexes, 0);
+    }
+    
+    #[test]
+    fn test_failed_upgrade_leaves_the_database_at_its_old_version() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute("CREATE TABLE schema_version (version INTEGER PRIMARY KEY)", []).unwrap();
+        migration_v1(&conn).unwrap();
+        migration_v2(&conn).unwrap();
+        migration_v3(&conn).unwrap();
+        migration_v4(&conn).unwrap();
+        set_version(&conn, 4).unwrap();
+        // A table in the way makes v10 fail after v5 has added its column
+        conn.execute("CREATE TABLE date_basis_history (id INTEGER PRIMARY KEY)", []).unwrap();
+        
+        assert!(initialize_database(&conn).is_err());
+        
+        assert_eq!(get_current_version(&conn).unwrap(), 4);
+        assert!(conn.execute("UPDATE habits SET allow_multiple_per_day = TRUE", []).is_err());
+        
+        // Once the obstacle is gone the same upgrade runs cleanly
+        conn.execute("DROP TABLE date_basis_history", []).unwrap();
+        initialize_database(&conn).unwrap();
+        assert_eq!(get_current_version(&conn).unwrap(), CURRENT_VERSION);
+        conn.execute("UPDATE habits SET allow_multiple_per_day = TRUE", []).unwrap();
+    }
+    
+    #[test]
+    fn test_upgraded_v4_database_enforces_uniqueness_per_habit() {
+        use crate::domain::{Category, Frequency, Habit, HabitEntry};
+        use crate::storage::{HabitStorage, SqliteStorage};
+        
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("habits.db");
+        {
+            let conn = Connection::open(&path).unwrap();
+            conn.execute("CREATE TABLE schema_version (version INTEGER PRIMARY KEY)", []).unwrap();
+            migration_v1(&conn).unwrap();
+            migration_v2(&conn).unwrap();
+            migration_v3(&conn).unwrap();
+            migration_v4(&conn).unwrap();
+            set_version(&conn, 4).unwrap();
+        }
+        
+        let storage = SqliteStorage::new(&path).unwrap();
+        let once = Habit::new("Journal".to_string(), None, Category::Personal, Frequency::Daily, None, None).unwrap();
+        let several = Habit::new("Drink water".to_string(), None, Category::Health, Frequency::Daily, None, None)
+            .unwrap()
+            .with_allow_multiple_per_day(true);
+        storage.create_habit(&once).unwrap();
+        storage.create_habit(&several).unwrap();
+        let today = chrono::Utc::now().naive_utc().date();
+        let entry = |habit: &Habit| HabitEntry::new(habit.id.clone(), today, None, None, None).unwrap();
+        
+        storage.create_entry(&entry(&once)).unwrap();
+        assert!(matches!(storage.create_entry(&entry(&once)), Err(StorageError::DuplicateEntry { .. })));
+        
+        for _ in 0..3 {
+            storage.create_entry(&entry(&several)).unwrap();
+        }
+        assert_eq!(storage.get_entries_for_habit(&several.id, None).unwrap().len(), 3);
+    }
+    
+    #[test]
+    fn test_upgrade_from_v5_adds_vacations() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute("CREATE TABLE schema_version (version INTEGER PRIMARY KEY)", []).unwrap();
+        migration_v1(&conn).unwrap();
+        migration_v2(&conn).unwrap();
+        migration_v3(&conn).unwrap();
+        migration_v4(&conn).unwrap();
+        migration_v5(&conn).unwrap();
+        set_version(&conn, 5).unwrap();
+        
+        initialize_database(&conn).unwrap();
+        
+        assert_eq!(get_current_version(&conn).unwrap(), CURRENT_VERSION);
+        let vacations: i32 = conn
+            .query_row("SELECT COUNT(*) FROM vacations", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(vacations, 0);
+    }
+    
+    #[test]
+    fn test_upgrade_from_v6_converts_json_frequencies() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute("CREATE TABLE schema_version (version INTEGER PRIMARY KEY)", []).unwrap();
+        migration_v1(&conn).unwrap();
+        migration_v2(&conn).unwrap();
+        migration_v3(&conn).unwrap();
+        migration_v4(&conn).unwrap();
+        migration_v5(&conn).unwrap();
+        migration_v6(&conn).unwrap();
+        set_version(&conn, 6).unwrap();
+        let legacy = [
+            ("a", "\"Daily\"", "daily"),
+            ("b", "{\"Weekly\":3}", "weekly:3"),
+            ("c", "{\"Custom\":[\"Mon\",\"Wed\",\"Fri\"]}", "custom:mon,wed,fri"),
+            ("d", "{\"Interval\":10}", "every:10"),
+            ("e", "\"Weekends\"", "weekends"),
+        ];
+        for (id, json, _) in legacy {
+            conn.execute(
+                "INSERT INTO habits (id, name, category, frequency_type, frequency_data, created_at)
+                 VALUES (?1, 'Habit', 'health', 'json', ?2, '2024-01-01T00:00:00+00:00')",
+                params![id, json],
+            ).unwrap();
+        }
+        
+        initialize_database(&conn).unwrap();
+        
+        assert_eq!(get_current_version(&conn).unwrap(), CURRENT_VERSION);
+        for (id, _, expected) in legacy {
+            let stored: String = conn
+                .query_row("SELECT frequency_data FROM habits WHERE id = ?1", [id], |row| row.get(0))
+                .unwrap();
+            assert_eq!(stored, expected);
+            assert!(stored.parse::<Frequency>().is_ok());
+        }
+    }
+    
+    #[test]
+    fn test_upgrade_from_v7_records_utc_date_basis() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute("CREATE TABLE schema_version (version INTEGER PRIMARY KEY)", []).unwrap();
+        migration_v1(&conn).unwrap();
+        migration_v2(&conn).unwrap();
+        migration_v3(&conn).unwrap();
+        migration_v4(&conn).unwrap();
+        migration_v5(&conn).unwrap();
+        migration_v6(&conn).unwrap();
+        migration_v7(&conn).unwrap();
+        set_version(&conn, 7).unwrap();
+        
+        initialize_database(&conn).unwrap();
+        
+        assert_eq!(get_current_version(&conn).unwrap(), CURRENT_VERSION);
+        let (basis, effective_from): (String, Option<String>) = conn
+            .query_row("SELECT basis, effective_from FROM date_basis_history", [], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap();
+        assert_eq!((basis.as_str(), effective_from), ("utc", None));
+    }
+    
+    #[test]
+    fn test_upgrade_from_v8_adds_entry_mood() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute("CREATE TABLE schema_version (version INTEGER PRIMARY KEY)", []).unwrap();
+        migration_v1(&conn).unwrap();
+        migration_v2(&conn).unwrap();
+        migration_v3(&conn).unwrap();
+        migration_v4(&conn).unwrap();
+        migration_v5(&conn).unwrap();
+        migration_v6(&conn).unwrap();
+        migration_v7(&conn).unwrap();
+        migration_v8(&conn).unwrap();
+        set_version(&conn, 8).unwrap();
+        conn.execute(
+            "INSERT INTO habits (id, name, category, frequency_type, frequency_data, created_at)
+             VALUES ('h1', 'Run', 'fitness', 'text', 'daily', '2024-01-01T00:00:00Z')",
+            [],
+        ).unwrap();
+        conn.execute(
+            "INSERT INTO habit_entries (id, habit_id, logged_at, completed_at)
+             VALUES ('e1', 'h1', '2024-01-02T08:00:00Z', '2024-01-02')",
+            [],
+        ).unwrap();
+        
+        initialize_database(&conn).unwrap();
+        
+        assert_eq!(get_current_version(&conn).unwrap(), CURRENT_VERSION);
+        let mood: Option<String> = conn
+            .query_row("SELECT mood FROM habit_entries WHERE id = 'e1'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(mood, None);
+    }
+    
+    #[test]
+    fn test_upgrade_from_v9_keeps_a_recorded_date_basis_switch() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute("CREATE TABLE schema_version (version INTEGER PRIMARY KEY)", []).unwrap();
+        migration_v1(&conn).unwrap();
+        migration_v2(&conn).unwrap();
+        migration_v3(&conn).unwrap();
+        migration_v4(&conn).unwrap();
+        migration_v5(&conn).unwrap();
+        migration_v6(&conn).unwrap();
+        migration_v7(&conn).unwrap();
+        migration_v8(&conn).unwrap();
+        migration_v9(&conn).unwrap();
+        set_version(&conn, 9).unwrap();
+        conn.execute_batch(
+            "UPDATE metadata SET value = 'local' WHERE key = 'date_basis';
+             INSERT INTO metadata (key, value) VALUES ('previous_date_basis', 'utc');
+             INSERT INTO metadata (key, value) VALUES ('date_basis_changed_on', '2024-06-01');"
+        ).unwrap();
+        
+        initialize_database(&conn).unwrap();
+        
+        let mut stmt = conn.prepare("SELECT basis, effective_from FROM date_basis_history ORDER BY id").unwrap();
+        let periods: Vec<(String, Option<String>)> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(periods, vec![
+            ("utc".to_string(), None),
+            ("local".to_string(), Some("2024-06-01".to_string())),
+        ]);
+        let leftover: u32 = conn.query_row("SELECT COUNT(*) FROM metadata", [], |row| row.get(0)).unwrap();
+        assert_eq!(leftover, 0);
+    }
+}
@@ -9,8 +9,9 @@ pub mod migrations;
 // Re-export the main storage types
 pub use sqlite::*;
 
+use std::collections::HashMap;
 use thiserror::Error;
-use crate::domain::{Habit, HabitEntry, Streak, HabitId, Category};
+use crate::domain::{Habit, HabitEntry, EntryId, Streak, HabitId, Category, DomainError, Vacation};
 
 /// Errors that can occur during storage operations
 #[derive(Error, Debug)]
@@ -35,6 +36,111 @@ pub enum StorageError {
     
     #[error("Migration error: {0}")]
     Migration(String),
+    
+    #[error("Database at {path} is corrupted or is not a SQLite database")]
+    Corrupted { path: String },
+    
+    #[error("{0}")]
+    Validation(String),
+}
+
+impl From<DomainError> for StorageError {
+    fn from(error: DomainError) -> Self {
+        StorageError::Validation(error.to_string())
+    }
+}
+
+/// Snapshot of the database's state, used for health checks
+#[derive(Debug, Clone, PartialEq)]
+pub struct StorageHealth {
+    /// Schema version recorded by the migrations
+    pub schema_version: i32,
+    /// Number of habits, including paused ones
+    pub habit_count: u32,
+    /// Number of logged entries across all habits
+    pub entry_count: u32,
+    /// Whether a write to the database would succeed
+    pub writable: bool,
+    /// Entries whose habit no longer exists (see `find_orphan_entries`)
+    pub orphan_entry_count: u32,
+}
+
+/// Size and row counts of the database, used for usage reporting
+#[derive(Debug, Clone, PartialEq)]
+pub struct StorageStats {
+    /// Number of habits, including paused ones
+    pub habit_count: u32,
+    /// Number of logged entries across all habits
+    pub entry_count: u32,
+    /// Number of habits with cached streak data
+    pub streak_count: u32,
+    /// Completion date of the oldest entry, if there are any
+    pub earliest_entry: Option<chrono::NaiveDate>,
+    /// Completion date of the newest entry, if there are any
+    pub latest_entry: Option<chrono::NaiveDate>,
+    /// Size of the database file in bytes (`page_count * page_size`)
+    pub database_bytes: u64,
+}
+
+/// Which calendar an entry's `completed_at` date was taken from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateBasis {
+    /// The date in UTC, which is how every entry so far has been written
+    Utc,
+    /// The date in the user's local timezone
+    Local,
+}
+
+impl DateBasis {
+    /// Name stored in the metadata table
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DateBasis::Utc => "utc",
+            DateBasis::Local => "local",
+        }
+    }
+    
+    /// Parse a name as stored in the metadata table
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "utc" => Some(DateBasis::Utc),
+            "local" => Some(DateBasis::Local),
+            _ => None,
+        }
+    }
+}
+
+/// A date basis and the first completion date written under it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateBasisPeriod {
+    pub basis: DateBasis,
+    /// None for the basis the database started out with
+    pub effective_from: Option<chrono::NaiveDate>,
+}
+
+/// Every date basis entries have been written under, oldest first
+#[derive(Debug, Clone, PartialEq)]
+pub struct DateBasisInfo {
+    pub periods: Vec<DateBasisPeriod>,
+}
+
+impl DateBasisInfo {
+    /// Basis new entries are written under
+    pub fn current(&self) -> DateBasis {
+        self.periods.last().map_or(DateBasis::Utc, |period| period.basis)
+    }
+    
+    /// Basis an entry completed on `completed_at` was written under
+    /// 
+    /// That is the latest period starting on or before the date; dates
+    /// before every recorded switch fall in the first period.
+    pub fn basis_for(&self, completed_at: chrono::NaiveDate) -> DateBasis {
+        self.periods.iter()
+            .rev()
+            .find(|period| period.effective_from.is_none_or(|from| from <= completed_at))
+            .or(self.periods.first())
+            .map_or(DateBasis::Utc, |period| period.basis)
+    }
 }
 
 /// Trait defining the storage interface for habits
@@ -80,6 +186,36 @@ pub trait HabitStorage {
     /// Delete a habit (soft delete - mark as inactive)
     fn delete_habit(&self, habit_id: &HabitId) -> Result<(), StorageError>;
     
+    /// Move several habits into a category in a single transaction
+    ///
+    /// Returns the number of habits that were updated.
+    fn set_category_for_habits(
+        &self,
+        habit_ids: &[HabitId],
+        category: &Category,
+    ) -> Result<u32, StorageError>;
+    
+    /// Give habits sequential display positions in the order provided
+    ///
+    /// Habits not listed lose any earlier position and sort after the listed ones.
+    /// Runs in a single transaction and returns the number of habits updated.
+    fn set_habit_positions(&self, habit_ids: &[HabitId]) -> Result<u32, StorageError>;
+    
+    /// Mark every habit active or paused with a single update
+    ///
+    /// Returns the number of habits whose state actually changed.
+    fn set_all_active(&self, active: bool) -> Result<u32, StorageError>;
+    
+    /// Count habits per category, active and paused alike
+    /// 
+    /// Returns each category in use once, ordered by its stored name.
+    fn count_habits_by_category(&self) -> Result<Vec<(Category, u32)>, StorageError>;
+    
+    /// Rename a custom category on every habit that uses it
+    ///
+    /// Returns the number of habits that were updated.
+    fn rename_custom_category(&self, from: &str, to: &str) -> Result<u32, StorageError>;
+    
     /// List habits with optional filtering
     fn list_habits(
         &self,
@@ -88,8 +224,35 @@ pub trait HabitStorage {
     ) -> Result<Vec<Habit>, StorageError>;
     
     /// Create a new habit entry
+    /// 
+    /// A second entry for the same habit and day is rejected with
+    /// `DuplicateEntry` unless the habit has `allow_multiple_per_day` set.
+    /// This is checked per habit on insert; since schema v5 there is no
+    /// unique index on (habit_id, completed_at).
     fn create_entry(&self, entry: &HabitEntry) -> Result<(), StorageError>;
     
+    /// Create several habit entries atomically
+    ///
+    /// Either all entries are stored or, if any insert fails, none are.
+    fn create_entries(&self, entries: &[HabitEntry]) -> Result<(), StorageError>;
+    
+    /// Create several habit entries in one transaction, skipping duplicates
+    ///
+    /// Returns, for each entry in order, whether it was stored (`false`
+    /// means the habit was already logged that day). Any other failure
+    /// rolls back the whole batch.
+    fn create_entries_skipping_duplicates(&self, entries: &[HabitEntry]) -> Result<Vec<bool>, StorageError>;
+    
+    /// Create a habit together with its historical entries atomically
+    ///
+    /// If the habit or any entry fails to insert, nothing is stored.
+    fn create_habit_with_entries(&self, habit: &Habit, entries: &[HabitEntry]) -> Result<(), StorageError>;
+    
+    /// Delete a single entry, returning the habit it belonged to
+    ///
+    /// Callers are expected to recompute the habit's streak afterwards.
+    fn delete_entry(&self, entry_id: &EntryId) -> Result<HabitId, StorageError>;
+    
     /// Get entries for a specific habit
     fn get_entries_for_habit(
         &self,
@@ -97,19 +260,111 @@ pub trait HabitStorage {
         limit: Option<u32>,
     ) -> Result<Vec<HabitEntry>, StorageError>;
     
-    /// Get all entries within a date range
+    /// Get the date of the earliest entry for a habit, if it has any
+    fn get_first_entry_date(&self, habit_id: &HabitId) -> Result<Option<chrono::NaiveDate>, StorageError>;
+    
+    /// Get the date of the most recent entry for a habit, if it has any
+    fn get_latest_entry_date(&self, habit_id: &HabitId) -> Result<Option<chrono::NaiveDate>, StorageError>;
+    
+    /// Get all entries within a date range, both ends included
+    /// 
+    /// Fails with a validation error if `start_date` is after `end_date`.
     fn get_entries_by_date_range(
         &self,
         start_date: chrono::NaiveDate,
         end_date: chrono::NaiveDate,
     ) -> Result<Vec<HabitEntry>, StorageError>;
     
+    /// Get the entries of every habit in a category within a date range
+    ///
+    /// Entries are returned newest first, and an inverted range is rejected,
+    /// like `get_entries_by_date_range`.
+    fn get_entries_by_category_and_range(
+        &self,
+        category: &Category,
+        start_date: chrono::NaiveDate,
+        end_date: chrono::NaiveDate,
+    ) -> Result<Vec<HabitEntry>, StorageError>;
+    
+    /// Remove duplicate entries for the same habit and date
+    ///
+    /// Keeps the earliest-logged entry of each group and returns, per
+    /// affected habit, how many entries were deleted.
+    fn remove_duplicate_entries(&self) -> Result<Vec<(HabitId, u32)>, StorageError>;
+    
     /// Update or create streak data for a habit
     fn update_streak(&self, streak: &Streak) -> Result<(), StorageError>;
     
     /// Get streak data for a habit
     fn get_streak(&self, habit_id: &HabitId) -> Result<Streak, StorageError>;
     
+    /// Get streak data for several habits with a single query
+    ///
+    /// Every requested id is present in the result; habits without stored
+    /// streak data get `Streak::new` defaults, as with `get_streak`.
+    fn get_streaks_for_habits(&self, habit_ids: &[HabitId]) -> Result<HashMap<HabitId, Streak>, StorageError>;
+    
     /// Get streak data for all habits
     fn get_all_streaks(&self) -> Result<Vec<Streak>, StorageError>;
+    
+    /// Spend one of a habit's freeze tokens to protect the streak on `date`
+    ///
+    /// Decrements the token count and records the freeze atomically, and
+    /// returns how many tokens are left.
+    fn spend_freeze_token(&self, habit_id: &HabitId, date: chrono::NaiveDate) -> Result<u32, StorageError>;
+    
+    /// Add earned freeze tokens to a habit in a single update
+    ///
+    /// Returns the habit's new token count.
+    fn add_freeze_tokens(&self, habit_id: &HabitId, count: u32) -> Result<u32, StorageError>;
+    
+    /// Get the days a habit's streak has been frozen on
+    fn get_streak_freezes(&self, habit_id: &HabitId) -> Result<Vec<chrono::NaiveDate>, StorageError>;
+    
+    /// Record a vacation window for a habit
+    fn create_vacation(&self, vacation: &Vacation) -> Result<(), StorageError>;
+    
+    /// Close every open vacation of a habit on `end_date`
+    /// 
+    /// Returns the number of vacations that were closed.
+    fn end_vacations(&self, habit_id: &HabitId, end_date: chrono::NaiveDate) -> Result<u32, StorageError>;
+    
+    /// Get a habit's vacations, earliest first
+    fn get_vacations(&self, habit_id: &HabitId) -> Result<Vec<Vacation>, StorageError>;
+    
+    /// Report the schema version, row counts and whether the database is writable
+    ///
+    /// Writability is probed inside a transaction that is always rolled back.
+    fn health_check(&self) -> Result<StorageHealth, StorageError>;
+    
+    /// Find entries whose habit row no longer exists
+    /// 
+    /// Habits are only ever soft-deleted by the tools, but a row removed by
+    /// hand leaves its entries behind, where they skew date-range analytics.
+    fn find_orphan_entries(&self) -> Result<Vec<EntryId>, StorageError>;
+    
+    /// Delete every entry found by `find_orphan_entries` in one transaction
+    /// 
+    /// Cached streaks, spent freezes and vacations of missing habits are
+    /// deleted along with them. Returns the number of entries deleted.
+    fn delete_orphan_entries(&self) -> Result<u32, StorageError>;
+    
+    /// Report row counts, the range of entry dates and the database size
+    fn storage_stats(&self) -> Result<StorageStats, StorageError>;
+    
+    /// Report every date basis entries have been written under
+    fn get_date_basis(&self) -> Result<DateBasisInfo, StorageError>;
+    
+    /// Switch the date basis for entries completed from `effective_from` on
+    /// 
+    /// Earlier periods are kept so older rows can still be read correctly.
+    /// Returns false, recording nothing, if `basis` is already in force.
+    /// `effective_from` must be after the previous switch.
+    fn set_date_basis(&self, basis: DateBasis, effective_from: chrono::NaiveDate) -> Result<bool, StorageError>;
+    
+    /// Rebuild indexes, refresh query planner statistics and compact the database
+    ///
+    /// Rewrites the whole database file, so it can take a while on large
+    /// databases and should only be run while nothing else is using it.
+    fn optimize(&self) -> Result<(), StorageError>;
 }
\ No newline at end of file
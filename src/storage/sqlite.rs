@@ -3,15 +3,52 @@
 /// This module provides the concrete SQLite implementation for storing
 /// and retrieving habit data. It handles all SQL queries and data conversion.
 
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 use rusqlite::{Connection, params};
 use chrono::{NaiveDate, Utc};
-use serde_json;
 
 use crate::domain::{
-    Habit, HabitEntry, Streak, HabitId, EntryId, Category
+    Habit, HabitEntry, Streak, HabitId, EntryId, Category, Vacation
 };
-use crate::storage::{StorageError, HabitStorage, migrations};
+use crate::storage::{DateBasis, DateBasisInfo, DateBasisPeriod, StorageError, StorageHealth, StorageStats, HabitStorage, migrations};
+
+/// Columns selected when loading habits, in the order `row_to_habit` expects
+const HABIT_COLUMNS: &str =
+    "id, name, description, category, frequency_data, target_value, unit, created_at, is_active, position, freeze_tokens, motivation, allow_multiple_per_day";
+
+/// How write operations retry when another connection holds a lock
+/// 
+/// Waiting blocks the calling thread, so the defaults keep `worst_case`
+/// well under the server's default request timeout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt; 0 disables retrying
+    pub max_retries: u32,
+    /// Wait before the first retry, doubled after each one
+    pub initial_delay: Duration,
+    /// How long SQLite itself waits for a lock on each attempt
+    pub busy_timeout: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_delay: Duration::from_millis(50),
+            busy_timeout: Duration::from_millis(100),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Longest a single write can block before the lock error is returned
+    pub fn worst_case(&self) -> Duration {
+        let backoff: Duration = (0..self.max_retries).map(|retry| self.initial_delay * 2u32.pow(retry)).sum();
+        self.busy_timeout * (self.max_retries + 1) + backoff
+    }
+}
 
 /// SQLite-based storage implementation
 /// 
@@ -19,6 +56,7 @@ use crate::storage::{StorageError, HabitStorage, migrations};
 /// all the storage operations defined in the HabitStorage trait.
 pub struct SqliteStorage {
     conn: Connection,
+    retry: RetryPolicy,
 }
 
 impl SqliteStorage {
@@ -26,85 +64,279 @@ impl SqliteStorage {
     /// 
     /// This opens the database file and runs any necessary migrations
     /// to ensure the schema is up to date.
-    pub fn new(db_path: PathBuf) -> Result<Self, StorageError> {
+    /// 
+    /// A damaged file, or one that isn't a SQLite database at all, is
+    /// reported as `StorageError::Corrupted` so callers can offer recovery.
+    pub fn new(db_path: impl Into<PathBuf>) -> Result<Self, StorageError> {
+        let db_path = db_path.into();
+        let corrupted = || StorageError::Corrupted { path: db_path.display().to_string() };
+
         // Open the SQLite database
-        let conn = Connection::open(&db_path)
-            .map_err(|e| StorageError::Connection(format!("Failed to open database: {}", e)))?;
+        let conn = Connection::open(&db_path).map_err(|e| {
+            if Self::is_corruption(&e) {
+                corrupted()
+            } else {
+                StorageError::Connection(format!("Failed to open database: {}", e))
+            }
+        })?;
+        
+        // Replace SQLite's default 5s lock wait, which would outlast a request
+        let retry = RetryPolicy::default();
+        conn.busy_timeout(retry.busy_timeout)?;
         
         // Enable foreign key constraints
-        conn.execute("PRAGMA foreign_keys = ON", [])
-            .map_err(|e| StorageError::Connection(format!("Failed to enable foreign keys: {}", e)))?;
+        conn.execute("PRAGMA foreign_keys = ON", []).map_err(|e| {
+            if Self::is_corruption(&e) {
+                corrupted()
+            } else {
+                StorageError::Connection(format!("Failed to enable foreign keys: {}", e))
+            }
+        })?;
         
         // Initialize/migrate the database schema
-        migrations::initialize_database(&conn)?;
+        migrations::initialize_database(&conn).map_err(|e| match e {
+            StorageError::Query(ref err) if Self::is_corruption(err) => corrupted(),
+            other => other,
+        })?;
         
         tracing::info!("SQLite storage initialized at: {:?}", db_path);
         
-        Ok(Self { conn })
-    }
-    
-    /// Helper method to convert Category enum to string for database storage
-    fn category_to_string(category: &Category) -> String {
-        match category {
-            Category::Health => "health".to_string(),
-            Category::Productivity => "productivity".to_string(),
-            Category::Social => "social".to_string(),
-            Category::Creative => "creative".to_string(),
-            Category::Mindfulness => "mindfulness".to_string(),
-            Category::Financial => "financial".to_string(),
-            Category::Household => "household".to_string(),
-            Category::Personal => "personal".to_string(),
-            Category::Custom(name) => format!("custom:{}", name),
-        }
+        Ok(Self { conn, retry })
+    }
+    
+    /// Use a different retry policy for writes that hit a busy database
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Result<Self, StorageError> {
+        self.conn.busy_timeout(retry.busy_timeout)?;
+        self.retry = retry;
+        Ok(self)
+    }
+    
+    /// Move a corrupted database file aside so a fresh one can be created
+    /// 
+    /// The file is renamed to `<name>.corrupt-<timestamp>` next to the
+    /// original, and the new path is returned.
+    pub fn quarantine<P: AsRef<Path>>(db_path: P) -> Result<PathBuf, StorageError> {
+        let db_path = db_path.as_ref();
+        let file_name = db_path.file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| "habits.db".to_string());
+        let backup_path = db_path.with_file_name(
+            format!("{}.corrupt-{}", file_name, Utc::now().format("%Y%m%d%H%M%S"))
+        );
+        
+        std::fs::rename(db_path, &backup_path).map_err(|e| {
+            StorageError::Connection(format!("Failed to move corrupted database aside: {}", e))
+        })?;
+        
+        tracing::warn!("Moved corrupted database {:?} to {:?}", db_path, backup_path);
+        Ok(backup_path)
+    }
+    
+    /// Whether a SQLite error means the file is damaged or not a database
+    fn is_corruption(err: &rusqlite::Error) -> bool {
+        matches!(
+            err.sqlite_error_code(),
+            Some(rusqlite::ErrorCode::DatabaseCorrupt | rusqlite::ErrorCode::NotADatabase)
+        )
     }
     
-    /// Helper method to convert string from database to Category enum
-    fn string_to_category(s: &str) -> Result<Category, StorageError> {
-        match s {
-            "health" => Ok(Category::Health),
-            "productivity" => Ok(Category::Productivity),
-            "social" => Ok(Category::Social),
-            "creative" => Ok(Category::Creative),
-            "mindfulness" => Ok(Category::Mindfulness),
-            "financial" => Ok(Category::Financial),
-            "household" => Ok(Category::Household),
-            "personal" => Ok(Category::Personal),
-            s if s.starts_with("custom:") => {
-                let name = s.strip_prefix("custom:").unwrap().to_string();
-                Ok(Category::Custom(name))
+    /// Whether a SQLite error is a lock held by another connection
+    fn is_transient(err: &rusqlite::Error) -> bool {
+        matches!(
+            err.sqlite_error_code(),
+            Some(rusqlite::ErrorCode::DatabaseBusy | rusqlite::ErrorCode::DatabaseLocked)
+        )
+    }
+    
+    /// Run a write, retrying with exponential backoff while the database is locked
+    /// 
+    /// Any other error, or a lock that outlasts the retry policy, is
+    /// returned unchanged.
+    fn with_retry<T, E>(&self, mut operation: impl FnMut() -> Result<T, E>) -> Result<T, StorageError>
+    where
+        StorageError: From<E>,
+    {
+        let mut delay = self.retry.initial_delay;
+        let mut attempt = 0;
+        loop {
+            match operation().map_err(StorageError::from) {
+                Err(StorageError::Query(ref e)) if Self::is_transient(e) && attempt < self.retry.max_retries => {
+                    attempt += 1;
+                    tracing::debug!("Database is locked, retry {} of {} in {:?}", attempt, self.retry.max_retries, delay);
+                    std::thread::sleep(delay);
+                    delay *= 2;
+                }
+                result => return result,
             }
-            _ => Err(StorageError::Query(rusqlite::Error::InvalidColumnType(
-                0, "Invalid category".to_string(), rusqlite::types::Type::Text
-            ))),
         }
     }
-}
-
-impl HabitStorage for SqliteStorage {
-    /// Create a new habit in the database
-    fn create_habit(&self, habit: &Habit) -> Result<(), StorageError> {
-        let category_str = Self::category_to_string(&habit.category);
-        let frequency_json = serde_json::to_string(&habit.frequency)?;
+    
+    /// Map a row selected with `HABIT_COLUMNS` to a Habit
+    fn row_to_habit(row: &rusqlite::Row) -> rusqlite::Result<Habit> {
+        let id_str: String = row.get(0)?;
+        let id = HabitId::from_string(&id_str).map_err(|_| {
+            rusqlite::Error::InvalidColumnType(0, "Invalid UUID".to_string(), rusqlite::types::Type::Text)
+        })?;
         
-        self.conn.execute(
+        let category_str: String = row.get(3)?;
+        let category = Category::from_storage_string(&category_str);
+        
+        let frequency_str: String = row.get(4)?;
+        let frequency = frequency_str.parse().map_err(|_| {
+            rusqlite::Error::InvalidColumnType(4, "Invalid frequency".to_string(), rusqlite::types::Type::Text)
+        })?;
+        
+        let created_at_str: String = row.get(7)?;
+        let created_at = chrono::DateTime::parse_from_rfc3339(&created_at_str)
+            .map_err(|_| {
+                rusqlite::Error::InvalidColumnType(7, "Invalid datetime".to_string(), rusqlite::types::Type::Text)
+            })?
+            .with_timezone(&chrono::Utc);
+        
+        let habit = Habit::from_existing(
+            id,
+            row.get(1)?, // name
+            row.get(2)?, // description
+            category,
+            frequency,
+            row.get(5)?, // target_value
+            row.get(6)?, // unit
+            created_at,
+            row.get(8)?, // is_active
+        )
+        .with_position(row.get(9)?)
+        .with_freeze_tokens(row.get(10)?)
+        .with_motivation(row.get(11)?)
+        .with_allow_multiple_per_day(row.get(12)?);
+        
+        Ok(habit)
+    }
+    
+    /// Map a row selected as `id, habit_id, logged_at, completed_at, value, intensity, notes, mood` to a HabitEntry
+    fn row_to_entry(row: &rusqlite::Row) -> rusqlite::Result<HabitEntry> {
+        let entry_id_str: String = row.get(0)?;
+        let entry_id = EntryId::from_string(&entry_id_str).map_err(|_| {
+            rusqlite::Error::InvalidColumnType(0, "Invalid UUID".to_string(), rusqlite::types::Type::Text)
+        })?;
+        
+        let habit_id_str: String = row.get(1)?;
+        let habit_id = HabitId::from_string(&habit_id_str).map_err(|_| {
+            rusqlite::Error::InvalidColumnType(1, "Invalid UUID".to_string(), rusqlite::types::Type::Text)
+        })?;
+        
+        let logged_at_str: String = row.get(2)?;
+        let logged_at = chrono::DateTime::parse_from_rfc3339(&logged_at_str)
+            .map_err(|_| {
+                rusqlite::Error::InvalidColumnType(2, "Invalid datetime".to_string(), rusqlite::types::Type::Text)
+            })?
+            .with_timezone(&chrono::Utc);
+        
+        let completed_at_str: String = row.get(3)?;
+        let completed_at = NaiveDate::parse_from_str(&completed_at_str, "%Y-%m-%d")
+            .map_err(|_| {
+                rusqlite::Error::InvalidColumnType(3, "Invalid date".to_string(), rusqlite::types::Type::Text)
+            })?;
+        
+        Ok(HabitEntry::from_existing(
+            entry_id,
+            habit_id,
+            logged_at,
+            completed_at,
+            row.get(4)?, // value
+            row.get(5)?, // intensity
+            row.get(6)?, // notes
+        ).with_mood(row.get(7)?))
+    }
+    
+    /// Insert a single habit row
+    fn insert_habit(conn: &Connection, habit: &Habit) -> Result<(), StorageError> {
+        let category_str = habit.category.to_storage_string();
+        let frequency_str = habit.frequency.to_string();
+        
+        conn.execute(
             "INSERT INTO habits (
                 id, name, description, category, frequency_type, frequency_data,
-                target_value, unit, created_at, is_active
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                target_value, unit, created_at, is_active, position, freeze_tokens, motivation,
+                allow_multiple_per_day
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
             params![
                 habit.id.to_string(),
                 habit.name,
                 habit.description,
                 category_str,
-                "json", // We're storing frequency as JSON
-                frequency_json,
+                "text", // Frequency's stable string form, e.g. "weekly:3"
+                frequency_str,
                 habit.target_value,
                 habit.unit,
                 habit.created_at.to_rfc3339(),
-                habit.is_active
+                habit.is_active,
+                habit.position,
+                habit.freeze_tokens,
+                habit.motivation,
+                habit.allow_multiple_per_day
             ],
         )?;
         
+        Ok(())
+    }
+    
+    /// Insert a single entry row, rejecting a second entry on the same day
+    /// with DuplicateEntry unless the habit allows several
+    /// 
+    /// The check up front gives the usual error; the v11 trigger catches a
+    /// concurrent writer that inserted between the check and the insert.
+    fn insert_entry(conn: &Connection, entry: &HabitEntry) -> Result<(), StorageError> {
+        // Only habits that allow it may have more than one entry per day
+        let already_logged: bool = conn.query_row(
+            "SELECT EXISTS (
+                SELECT 1 FROM habit_entries
+                WHERE habit_id = ?1 AND completed_at = ?2
+                  AND NOT COALESCE((SELECT allow_multiple_per_day FROM habits WHERE id = ?1), FALSE)
+            )",
+            params![entry.habit_id.to_string(), entry.completed_at.to_string()],
+            |row| row.get(0),
+        )?;
+        
+        if already_logged {
+            return Err(StorageError::DuplicateEntry {
+                habit_id: entry.habit_id.to_string(),
+                date: entry.completed_at.to_string(),
+            });
+        }
+        
+        let inserted = conn.execute(
+            "INSERT INTO habit_entries (
+                id, habit_id, logged_at, completed_at, value, intensity, notes, mood
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                entry.id.to_string(),
+                entry.habit_id.to_string(),
+                entry.logged_at.to_rfc3339(),
+                entry.completed_at.to_string(),
+                entry.value,
+                entry.intensity,
+                entry.notes,
+                entry.mood
+            ],
+        );
+        
+        match inserted {
+            Err(rusqlite::Error::SqliteFailure(_, Some(message))) if message == migrations::DUPLICATE_ENTRY_ABORT => {
+                Err(StorageError::DuplicateEntry {
+                    habit_id: entry.habit_id.to_string(),
+                    date: entry.completed_at.to_string(),
+                })
+            }
+            result => result.map(|_| ()).map_err(StorageError::from),
+        }
+    }
+}
+
+impl HabitStorage for SqliteStorage {
+    /// Create a new habit in the database
+    fn create_habit(&self, habit: &Habit) -> Result<(), StorageError> {
+        self.with_retry(|| Self::insert_habit(&self.conn, habit))?;
+        
         tracing::debug!("Created habit: {} ({})", habit.name, habit.id.to_string());
         Ok(())
     }
@@ -112,45 +344,10 @@ impl HabitStorage for SqliteStorage {
     /// Get a habit by its ID
     fn get_habit(&self, habit_id: &HabitId) -> Result<Habit, StorageError> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, name, description, category, frequency_data, target_value, unit, created_at, is_active 
-             FROM habits WHERE id = ?1"
+            &format!("SELECT {} FROM habits WHERE id = ?1", HABIT_COLUMNS)
         )?;
         
-        let result = stmt.query_row(params![habit_id.to_string()], |row| {
-            let id_str: String = row.get(0)?;
-            let id = HabitId::from_string(&id_str).map_err(|_| {
-                rusqlite::Error::InvalidColumnType(0, "Invalid UUID".to_string(), rusqlite::types::Type::Text)
-            })?;
-            
-            let category_str: String = row.get(3)?;
-            let category = Self::string_to_category(&category_str).map_err(|_| {
-                rusqlite::Error::InvalidColumnType(3, "Invalid category".to_string(), rusqlite::types::Type::Text)
-            })?;
-            
-            let frequency_json: String = row.get(4)?;
-            let frequency = serde_json::from_str(&frequency_json).map_err(|_| {
-                rusqlite::Error::InvalidColumnType(4, "Invalid frequency".to_string(), rusqlite::types::Type::Text)
-            })?;
-            
-            let created_at_str: String = row.get(7)?;
-            let created_at = chrono::DateTime::parse_from_rfc3339(&created_at_str)
-                .map_err(|_| {
-                    rusqlite::Error::InvalidColumnType(7, "Invalid datetime".to_string(), rusqlite::types::Type::Text)
-                })?
-                .with_timezone(&chrono::Utc);
-            
-            Ok(Habit::from_existing(
-                id,
-                row.get(1)?, // name
-                row.get(2)?, // description
-                category,
-                frequency,
-                row.get(5)?, // target_value
-                row.get(6)?, // unit
-                created_at,
-                row.get(8)?, // is_active
-            ))
-        });
+        let result = stmt.query_row(params![habit_id.to_string()], Self::row_to_habit);
         
         match result {
             Ok(habit) => Ok(habit),
@@ -165,10 +362,10 @@ impl HabitStorage for SqliteStorage {
     
     /// Update an existing habit
     fn update_habit(&self, habit: &Habit) -> Result<(), StorageError> {
-        let category_str = Self::category_to_string(&habit.category);
-        let frequency_json = serde_json::to_string(&habit.frequency)?;
+        let category_str = habit.category.to_storage_string();
+        let frequency_str = habit.frequency.to_string();
         
-        let rows_affected = self.conn.execute(
+        let rows_affected = self.with_retry(|| self.conn.execute(
             "UPDATE habits SET 
                 name = ?2, 
                 description = ?3, 
@@ -176,19 +373,25 @@ impl HabitStorage for SqliteStorage {
                 frequency_data = ?5,
                 target_value = ?6, 
                 unit = ?7, 
-                is_active = ?8
+                is_active = ?8,
+                freeze_tokens = ?9,
+                motivation = ?10,
+                allow_multiple_per_day = ?11
              WHERE id = ?1",
             params![
                 habit.id.to_string(),
                 habit.name,
                 habit.description,
                 category_str,
-                frequency_json,
+                frequency_str,
                 habit.target_value,
                 habit.unit,
-                habit.is_active
+                habit.is_active,
+                habit.freeze_tokens,
+                habit.motivation,
+                habit.allow_multiple_per_day
             ],
-        )?;
+        ))?;
         
         if rows_affected == 0 {
             return Err(StorageError::HabitNotFound {
@@ -217,13 +420,102 @@ impl HabitStorage for SqliteStorage {
         Ok(())
     }
     
+    /// Move several habits into a category in a single transaction
+    fn set_category_for_habits(
+        &self,
+        habit_ids: &[HabitId],
+        category: &Category,
+    ) -> Result<u32, StorageError> {
+        let category_str = category.to_storage_string();
+        let tx = self.conn.unchecked_transaction()?;
+        let mut updated = 0;
+        
+        for habit_id in habit_ids {
+            updated += tx.execute(
+                "UPDATE habits SET category = ?2 WHERE id = ?1",
+                params![habit_id.to_string(), category_str],
+            )? as u32;
+        }
+        
+        tx.commit()?;
+        
+        tracing::debug!("Moved {} habits to category {}", updated, category_str);
+        Ok(updated)
+    }
+    
+    /// Assign positions 1..n to habits in the given order, in one transaction
+    fn set_habit_positions(&self, habit_ids: &[HabitId]) -> Result<u32, StorageError> {
+        let tx = self.conn.unchecked_transaction()?;
+        let mut updated = 0;
+        
+        // Clear stale positions so unlisted habits can't collide with the new order
+        tx.execute("UPDATE habits SET position = NULL WHERE position IS NOT NULL", [])?;
+        
+        for (index, habit_id) in habit_ids.iter().enumerate() {
+            let rows = tx.execute(
+                "UPDATE habits SET position = ?2 WHERE id = ?1",
+                params![habit_id.to_string(), index as i32 + 1],
+            )?;
+            
+            if rows == 0 {
+                // Dropping the transaction rolls back earlier updates
+                return Err(StorageError::HabitNotFound { habit_id: habit_id.to_string() });
+            }
+            updated += rows as u32;
+        }
+        
+        tx.commit()?;
+        
+        tracing::debug!("Reordered {} habits", updated);
+        Ok(updated)
+    }
+    
+    /// Pause or resume every habit in one statement
+    fn set_all_active(&self, active: bool) -> Result<u32, StorageError> {
+        let updated = self.conn.execute(
+            "UPDATE habits SET is_active = ?1 WHERE is_active != ?1",
+            params![active],
+        )? as u32;
+        
+        tracing::debug!("Set is_active = {} on {} habits", active, updated);
+        Ok(updated)
+    }
+    
+    /// Count habits per stored category with a single GROUP BY
+    fn count_habits_by_category(&self) -> Result<Vec<(Category, u32)>, StorageError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT category, COUNT(*) FROM habits GROUP BY category ORDER BY category"
+        )?;
+        
+        let counts = stmt.query_map([], |row| {
+            let category_str: String = row.get(0)?;
+            Ok((Category::from_storage_string(&category_str), row.get(1)?))
+        })?;
+        
+        counts.collect::<Result<Vec<_>, _>>().map_err(StorageError::from)
+    }
+    
+    /// Rename a custom category across all habits in one statement
+    fn rename_custom_category(&self, from: &str, to: &str) -> Result<u32, StorageError> {
+        let from_str = Category::Custom(from.to_string()).to_storage_string();
+        let to_str = Category::Custom(to.to_string()).to_storage_string();
+        
+        let updated = self.conn.execute(
+            "UPDATE habits SET category = ?2 WHERE category = ?1",
+            params![from_str, to_str],
+        )? as u32;
+        
+        tracing::debug!("Renamed category {} to {} on {} habits", from_str, to_str, updated);
+        Ok(updated)
+    }
+    
     /// List habits with optional filtering
     fn list_habits(
         &self,
         _category: Option<Category>,
         active_only: bool,
     ) -> Result<Vec<Habit>, StorageError> {
-        let mut sql = "SELECT id, name, description, category, frequency_data, target_value, unit, created_at, is_active FROM habits".to_string();
+        let mut sql = format!("SELECT {} FROM habits", HABIT_COLUMNS);
         
         if active_only {
             sql.push_str(" WHERE is_active = 1");
@@ -232,41 +524,7 @@ impl HabitStorage for SqliteStorage {
         sql.push_str(" ORDER BY created_at DESC");
         
         let mut stmt = self.conn.prepare(&sql)?;
-        let habit_iter = stmt.query_map([], |row| {
-            let id_str: String = row.get(0)?;
-            let id = HabitId::from_string(&id_str).map_err(|_| {
-                rusqlite::Error::InvalidColumnType(0, "Invalid UUID".to_string(), rusqlite::types::Type::Text)
-            })?;
-            
-            let category_str: String = row.get(3)?;
-            let category = Self::string_to_category(&category_str).map_err(|_| {
-                rusqlite::Error::InvalidColumnType(3, "Invalid category".to_string(), rusqlite::types::Type::Text)
-            })?;
-            
-            let frequency_json: String = row.get(4)?;
-            let frequency = serde_json::from_str(&frequency_json).map_err(|_| {
-                rusqlite::Error::InvalidColumnType(4, "Invalid frequency".to_string(), rusqlite::types::Type::Text)
-            })?;
-            
-            let created_at_str: String = row.get(7)?;
-            let created_at = chrono::DateTime::parse_from_rfc3339(&created_at_str)
-                .map_err(|_| {
-                    rusqlite::Error::InvalidColumnType(7, "Invalid datetime".to_string(), rusqlite::types::Type::Text)
-                })?
-                .with_timezone(&chrono::Utc);
-            
-            Ok(Habit::from_existing(
-                id,
-                row.get(1)?, // name
-                row.get(2)?, // description
-                category,
-                frequency,
-                row.get(5)?, // target_value
-                row.get(6)?, // unit
-                created_at,
-                row.get(8)?, // is_active
-            ))
-        })?;
+        let habit_iter = stmt.query_map([], Self::row_to_habit)?;
         
         let mut habits = Vec::new();
         for habit in habit_iter {
@@ -278,25 +536,98 @@ impl HabitStorage for SqliteStorage {
     
     /// Create a new habit entry
     fn create_entry(&self, entry: &HabitEntry) -> Result<(), StorageError> {
-        self.conn.execute(
-            "INSERT INTO habit_entries (
-                id, habit_id, logged_at, completed_at, value, intensity, notes
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-            params![
-                entry.id.to_string(),
-                entry.habit_id.to_string(),
-                entry.logged_at.to_rfc3339(),
-                entry.completed_at.to_string(),
-                entry.value,
-                entry.intensity,
-                entry.notes
-            ],
-        )?;
+        self.with_retry(|| Self::insert_entry(&self.conn, entry))?;
         
         tracing::debug!("Created habit entry: {} for habit {}", entry.id.to_string(), entry.habit_id.to_string());
         Ok(())
     }
     
+    /// Create several habit entries in a single transaction
+    fn create_entries(&self, entries: &[HabitEntry]) -> Result<(), StorageError> {
+        self.with_retry(|| {
+            let tx = self.conn.unchecked_transaction()?;
+            
+            for entry in entries {
+                // Returning early drops the transaction, which rolls it back
+                Self::insert_entry(&tx, entry)?;
+            }
+            
+            tx.commit()?;
+            Ok::<_, StorageError>(())
+        })?;
+        
+        tracing::debug!("Created {} habit entries in one transaction", entries.len());
+        Ok(())
+    }
+    
+    /// Delete a single entry by id
+    fn delete_entry(&self, entry_id: &EntryId) -> Result<HabitId, StorageError> {
+        let result = self.conn.query_row(
+            "SELECT habit_id FROM habit_entries WHERE id = ?1",
+            params![entry_id.to_string()],
+            |row| row.get::<_, String>(0),
+        );
+        
+        let habit_id_str = match result {
+            Ok(habit_id_str) => habit_id_str,
+            Err(rusqlite::Error::QueryReturnedNoRows) => {
+                return Err(StorageError::EntryNotFound {
+                    entry_id: entry_id.to_string(),
+                });
+            }
+            Err(e) => return Err(StorageError::Query(e)),
+        };
+        let habit_id = HabitId::from_string(&habit_id_str).map_err(|_| {
+            StorageError::Query(rusqlite::Error::InvalidColumnType(0, "Invalid UUID".to_string(), rusqlite::types::Type::Text))
+        })?;
+        
+        self.conn.execute("DELETE FROM habit_entries WHERE id = ?1", params![entry_id.to_string()])?;
+        
+        tracing::debug!("Deleted habit entry: {} for habit {}", entry_id.to_string(), habit_id_str);
+        Ok(habit_id)
+    }
+    
+    /// Create a habit and its entries in a single transaction
+    fn create_habit_with_entries(&self, habit: &Habit, entries: &[HabitEntry]) -> Result<(), StorageError> {
+        self.with_retry(|| {
+            let tx = self.conn.unchecked_transaction()?;
+            
+            Self::insert_habit(&tx, habit)?;
+            for entry in entries {
+                // Returning early drops the transaction, which rolls it back
+                Self::insert_entry(&tx, entry)?;
+            }
+            
+            tx.commit()?;
+            Ok::<_, StorageError>(())
+        })?;
+        
+        tracing::debug!("Created habit {} with {} entries in one transaction", habit.id.to_string(), entries.len());
+        Ok(())
+    }
+    
+    /// Create several habit entries in a single transaction, skipping duplicates
+    fn create_entries_skipping_duplicates(&self, entries: &[HabitEntry]) -> Result<Vec<bool>, StorageError> {
+        let created = self.with_retry(|| {
+            let tx = self.conn.unchecked_transaction()?;
+            
+            let mut created = Vec::with_capacity(entries.len());
+            for entry in entries {
+                match Self::insert_entry(&tx, entry) {
+                    Ok(()) => created.push(true),
+                    Err(StorageError::DuplicateEntry { .. }) => created.push(false),
+                    Err(e) => return Err(e),
+                }
+            }
+            
+            tx.commit()?;
+            Ok(created)
+        })?;
+        
+        tracing::debug!("Created {} of {} habit entries", created.iter().filter(|c| **c).count(), entries.len());
+        Ok(created)
+    }
+    
     /// Get entries for a specific habit
     fn get_entries_for_habit(
         &self,
@@ -304,50 +635,17 @@ impl HabitStorage for SqliteStorage {
         limit: Option<u32>,
     ) -> Result<Vec<HabitEntry>, StorageError> {
         let sql = if let Some(limit_val) = limit {
-            format!("SELECT id, habit_id, logged_at, completed_at, value, intensity, notes 
+            format!("SELECT id, habit_id, logged_at, completed_at, value, intensity, notes, mood 
                      FROM habit_entries WHERE habit_id = ?1 
                      ORDER BY completed_at DESC, logged_at DESC LIMIT {}", limit_val)
         } else {
-            "SELECT id, habit_id, logged_at, completed_at, value, intensity, notes 
+            "SELECT id, habit_id, logged_at, completed_at, value, intensity, notes, mood 
              FROM habit_entries WHERE habit_id = ?1 
              ORDER BY completed_at DESC, logged_at DESC".to_string()
         };
         
         let mut stmt = self.conn.prepare(&sql)?;
-        let entry_iter = stmt.query_map(params![habit_id.to_string()], |row| {
-            let entry_id_str: String = row.get(0)?;
-            let entry_id = EntryId::from_string(&entry_id_str).map_err(|_| {
-                rusqlite::Error::InvalidColumnType(0, "Invalid UUID".to_string(), rusqlite::types::Type::Text)
-            })?;
-            
-            let habit_id_str: String = row.get(1)?;
-            let parsed_habit_id = HabitId::from_string(&habit_id_str).map_err(|_| {
-                rusqlite::Error::InvalidColumnType(1, "Invalid UUID".to_string(), rusqlite::types::Type::Text)
-            })?;
-            
-            let logged_at_str: String = row.get(2)?;
-            let logged_at = chrono::DateTime::parse_from_rfc3339(&logged_at_str)
-                .map_err(|_| {
-                    rusqlite::Error::InvalidColumnType(2, "Invalid datetime".to_string(), rusqlite::types::Type::Text)
-                })?
-                .with_timezone(&chrono::Utc);
-            
-            let completed_at_str: String = row.get(3)?;
-            let completed_at = NaiveDate::parse_from_str(&completed_at_str, "%Y-%m-%d")
-                .map_err(|_| {
-                    rusqlite::Error::InvalidColumnType(3, "Invalid date".to_string(), rusqlite::types::Type::Text)
-                })?;
-            
-            Ok(HabitEntry::from_existing(
-                entry_id,
-                parsed_habit_id,
-                logged_at,
-                completed_at,
-                row.get(4)?, // value
-                row.get(5)?, // intensity
-                row.get(6)?, // notes
-            ))
-        })?;
+        let entry_iter = stmt.query_map(params![habit_id.to_string()], Self::row_to_entry)?;
         
         let mut entries = Vec::new();
         for entry in entry_iter {
@@ -357,55 +655,86 @@ impl HabitStorage for SqliteStorage {
         Ok(entries)
     }
     
+    /// Get the earliest completion date recorded for a habit
+    fn get_first_entry_date(&self, habit_id: &HabitId) -> Result<Option<NaiveDate>, StorageError> {
+        let first: Option<String> = self.conn.query_row(
+            "SELECT MIN(completed_at) FROM habit_entries WHERE habit_id = ?1",
+            params![habit_id.to_string()],
+            |row| row.get(0),
+        )?;
+        
+        first
+            .map(|date_str| NaiveDate::parse_from_str(&date_str, "%Y-%m-%d").map_err(|_| {
+                StorageError::Query(rusqlite::Error::InvalidColumnType(
+                    0, "Invalid date".to_string(), rusqlite::types::Type::Text
+                ))
+            }))
+            .transpose()
+    }
+    
+    /// Get the date of the most recent entry for a habit
+    fn get_latest_entry_date(&self, habit_id: &HabitId) -> Result<Option<NaiveDate>, StorageError> {
+        let latest: Option<String> = self.conn.query_row(
+            "SELECT MAX(completed_at) FROM habit_entries WHERE habit_id = ?1",
+            params![habit_id.to_string()],
+            |row| row.get(0),
+        )?;
+        
+        latest
+            .map(|date_str| NaiveDate::parse_from_str(&date_str, "%Y-%m-%d").map_err(|_| {
+                StorageError::Query(rusqlite::Error::InvalidColumnType(
+                    0, "Invalid date".to_string(), rusqlite::types::Type::Text
+                ))
+            }))
+            .transpose()
+    }
+    
     /// Get all entries within a date range
     fn get_entries_by_date_range(
         &self,
         start_date: NaiveDate,
         end_date: NaiveDate,
     ) -> Result<Vec<HabitEntry>, StorageError> {
+        check_date_range(start_date, end_date)?;
         let mut stmt = self.conn.prepare(
-            "SELECT id, habit_id, logged_at, completed_at, value, intensity, notes 
+            "SELECT id, habit_id, logged_at, completed_at, value, intensity, notes, mood 
              FROM habit_entries 
              WHERE completed_at BETWEEN ?1 AND ?2 
              ORDER BY completed_at DESC, logged_at DESC"
         )?;
         
         let entry_iter = stmt.query_map(
-            params![start_date.to_string(), end_date.to_string()], 
-            |row| {
-                let entry_id_str: String = row.get(0)?;
-                let entry_id = EntryId::from_string(&entry_id_str).map_err(|_| {
-                    rusqlite::Error::InvalidColumnType(0, "Invalid UUID".to_string(), rusqlite::types::Type::Text)
-                })?;
-                
-                let habit_id_str: String = row.get(1)?;
-                let habit_id = HabitId::from_string(&habit_id_str).map_err(|_| {
-                    rusqlite::Error::InvalidColumnType(1, "Invalid UUID".to_string(), rusqlite::types::Type::Text)
-                })?;
-                
-                let logged_at_str: String = row.get(2)?;
-                let logged_at = chrono::DateTime::parse_from_rfc3339(&logged_at_str)
-                    .map_err(|_| {
-                        rusqlite::Error::InvalidColumnType(2, "Invalid datetime".to_string(), rusqlite::types::Type::Text)
-                    })?
-                    .with_timezone(&chrono::Utc);
-                
-                let completed_at_str: String = row.get(3)?;
-                let completed_at = NaiveDate::parse_from_str(&completed_at_str, "%Y-%m-%d")
-                    .map_err(|_| {
-                        rusqlite::Error::InvalidColumnType(3, "Invalid date".to_string(), rusqlite::types::Type::Text)
-                    })?;
-                
-                Ok(HabitEntry::from_existing(
-                    entry_id,
-                    habit_id,
-                    logged_at,
-                    completed_at,
-                    row.get(4)?, // value
-                    row.get(5)?, // intensity
-                    row.get(6)?, // notes
-                ))
-            }
+            params![start_date.to_string(), end_date.to_string()],
+            Self::row_to_entry,
+        )?;
+        
+        let mut entries = Vec::new();
+        for entry in entry_iter {
+            entries.push(entry?);
+        }
+        
+        Ok(entries)
+    }
+    
+    /// Get a category's entries within a date range, joining on habits in SQL
+    fn get_entries_by_category_and_range(
+        &self,
+        category: &Category,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<Vec<HabitEntry>, StorageError> {
+        check_date_range(start_date, end_date)?;
+        let mut stmt = self.conn.prepare(
+            "SELECT e.id, e.habit_id, e.logged_at, e.completed_at, e.value, e.intensity, e.notes, e.mood 
+             FROM habit_entries e 
+             JOIN habits h ON h.id = e.habit_id 
+             WHERE h.category = ?1 AND e.completed_at BETWEEN ?2 AND ?3 
+             ORDER BY e.completed_at DESC, e.logged_at DESC"
+        )?;
+        
+        let entry_iter = stmt.query_map(
+            params![category.to_storage_string(), start_date.to_string(), end_date.to_string()],
+            Self::row_to_entry,
         )?;
         
         let mut entries = Vec::new();
@@ -416,11 +745,55 @@ impl HabitStorage for SqliteStorage {
         Ok(entries)
     }
     
+    /// Remove duplicate entries, keeping the earliest-logged one per habit/date
+    fn remove_duplicate_entries(&self) -> Result<Vec<(HabitId, u32)>, StorageError> {
+        let tx = self.conn.unchecked_transaction()?;
+        
+        let affected = {
+            let mut stmt = tx.prepare(
+                "SELECT habit_id, COUNT(*) - COUNT(DISTINCT completed_at) AS duplicates
+                 FROM habit_entries
+                 WHERE habit_id NOT IN (SELECT id FROM habits WHERE allow_multiple_per_day)
+                 GROUP BY habit_id
+                 HAVING duplicates > 0"
+            )?;
+            
+            let rows = stmt.query_map([], |row| {
+                let habit_id_str: String = row.get(0)?;
+                let habit_id = HabitId::from_string(&habit_id_str).map_err(|_| {
+                    rusqlite::Error::InvalidColumnType(0, "Invalid UUID".to_string(), rusqlite::types::Type::Text)
+                })?;
+                Ok((habit_id, row.get::<_, u32>(1)?))
+            })?;
+            
+            rows.collect::<Result<Vec<_>, _>>()?
+        };
+        
+        // Delete every entry that has an earlier-logged twin (ties broken by id)
+        let removed = tx.execute(
+            "DELETE FROM habit_entries
+             WHERE habit_id NOT IN (SELECT id FROM habits WHERE allow_multiple_per_day)
+               AND EXISTS (
+                   SELECT 1 FROM habit_entries AS keep
+                   WHERE keep.habit_id = habit_entries.habit_id
+                     AND keep.completed_at = habit_entries.completed_at
+                     AND (keep.logged_at < habit_entries.logged_at
+                          OR (keep.logged_at = habit_entries.logged_at AND keep.id < habit_entries.id))
+               )",
+            [],
+        )?;
+        
+        tx.commit()?;
+        
+        tracing::info!("Removed {} duplicate entries across {} habits", removed, affected.len());
+        Ok(affected)
+    }
+    
     /// Update or create streak data for a habit
     fn update_streak(&self, streak: &Streak) -> Result<(), StorageError> {
         let now = Utc::now().to_rfc3339();
         
-        self.conn.execute(
+        self.with_retry(|| self.conn.execute(
             "INSERT OR REPLACE INTO habit_streaks (
                 habit_id, current_streak, longest_streak, last_completed, 
                 total_completions, completion_rate, updated_at
@@ -434,7 +807,7 @@ impl HabitStorage for SqliteStorage {
                 streak.completion_rate,
                 now
             ],
-        )?;
+        ))?;
         
         tracing::debug!("Updated streak for habit: {}", streak.habit_id.to_string());
         Ok(())
@@ -472,6 +845,57 @@ impl HabitStorage for SqliteStorage {
         }
     }
     
+    /// Get streak data for several habits in one `IN (...)` query
+    fn get_streaks_for_habits(&self, habit_ids: &[HabitId]) -> Result<HashMap<HabitId, Streak>, StorageError> {
+        let mut streaks: HashMap<HabitId, Streak> = habit_ids.iter()
+            .map(|id| (id.clone(), Streak::new(id.clone())))
+            .collect();
+        if habit_ids.is_empty() {
+            return Ok(streaks);
+        }
+        
+        let placeholders = (1..=habit_ids.len())
+            .map(|i| format!("?{}", i))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let sql = format!(
+            "SELECT habit_id, current_streak, longest_streak, last_completed, total_completions, completion_rate 
+             FROM habit_streaks WHERE habit_id IN ({})",
+            placeholders
+        );
+        
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(
+            rusqlite::params_from_iter(habit_ids.iter().map(|id| id.to_string())),
+            |row| {
+                let habit_id_str: String = row.get(0)?;
+                let habit_id = HabitId::from_string(&habit_id_str).map_err(|_| {
+                    rusqlite::Error::InvalidColumnType(0, "Invalid UUID".to_string(), rusqlite::types::Type::Text)
+                })?;
+                
+                let last_completed_str: Option<String> = row.get(3)?;
+                let last_completed = last_completed_str
+                    .and_then(|s| NaiveDate::parse_from_str(&s, "%Y-%m-%d").ok());
+                
+                Ok(Streak {
+                    habit_id,
+                    current_streak: row.get(1)?,
+                    longest_streak: row.get(2)?,
+                    last_completed,
+                    total_completions: row.get(4)?,
+                    completion_rate: row.get(5)?,
+                })
+            },
+        )?;
+        
+        for streak in rows {
+            let streak = streak?;
+            streaks.insert(streak.habit_id.clone(), streak);
+        }
+        
+        Ok(streaks)
+    }
+    
     /// Get streak data for all habits
     fn get_all_streaks(&self) -> Result<Vec<Streak>, StorageError> {
         let mut stmt = self.conn.prepare(
@@ -506,4 +930,742 @@ impl HabitStorage for SqliteStorage {
         
         Ok(streaks)
     }
-}
\ No newline at end of file
+    
+    /// Spend a freeze token and record the frozen day in one transaction
+    fn spend_freeze_token(&self, habit_id: &HabitId, date: NaiveDate) -> Result<u32, StorageError> {
+        let tx = self.conn.unchecked_transaction()?;
+        
+        let rows = tx.execute(
+            "UPDATE habits SET freeze_tokens = freeze_tokens - 1 WHERE id = ?1 AND freeze_tokens > 0",
+            params![habit_id.to_string()],
+        )?;
+        if rows == 0 {
+            // Either the habit doesn't exist or it has no tokens left
+            self.get_habit(habit_id)?;
+            return Err(StorageError::Validation("No streak freeze tokens left for this habit".to_string()));
+        }
+        
+        tx.execute(
+            "INSERT INTO streak_freezes (habit_id, frozen_on, created_at) VALUES (?1, ?2, ?3)",
+            params![habit_id.to_string(), date.to_string(), Utc::now().to_rfc3339()],
+        )?;
+        
+        let remaining: u32 = tx.query_row(
+            "SELECT freeze_tokens FROM habits WHERE id = ?1",
+            params![habit_id.to_string()],
+            |row| row.get(0),
+        )?;
+        
+        tx.commit()?;
+        
+        tracing::debug!("Froze {} for habit {}, {} tokens left", date, habit_id, remaining);
+        Ok(remaining)
+    }
+    
+    /// Increment the token count in SQL so concurrent habit updates aren't overwritten
+    fn add_freeze_tokens(&self, habit_id: &HabitId, count: u32) -> Result<u32, StorageError> {
+        let total = self.conn.query_row(
+            "UPDATE habits SET freeze_tokens = freeze_tokens + ?2 WHERE id = ?1 RETURNING freeze_tokens",
+            params![habit_id.to_string(), count],
+            |row| row.get(0),
+        ).map_err(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => StorageError::HabitNotFound { habit_id: habit_id.to_string() },
+            other => StorageError::from(other),
+        })?;
+        
+        tracing::debug!("Awarded {} freeze tokens to habit {}, {} in total", count, habit_id, total);
+        Ok(total)
+    }
+    
+    /// Get the days a habit's streak was frozen on, oldest first
+    fn get_streak_freezes(&self, habit_id: &HabitId) -> Result<Vec<NaiveDate>, StorageError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT frozen_on FROM streak_freezes WHERE habit_id = ?1 ORDER BY frozen_on"
+        )?;
+        
+        let dates = stmt.query_map(params![habit_id.to_string()], |row| {
+            let date_str: String = row.get(0)?;
+            NaiveDate::parse_from_str(&date_str, "%Y-%m-%d").map_err(|_| {
+                rusqlite::Error::InvalidColumnType(0, "Invalid date".to_string(), rusqlite::types::Type::Text)
+            })
+        })?;
+        
+        dates.collect::<Result<Vec<_>, _>>().map_err(StorageError::from)
+    }
+    
+    /// Record a vacation window for a habit
+    fn create_vacation(&self, vacation: &Vacation) -> Result<(), StorageError> {
+        self.with_retry(|| self.conn.execute(
+            "INSERT INTO vacations (habit_id, start_date, end_date, created_at) VALUES (?1, ?2, ?3, ?4)",
+            params![
+                vacation.habit_id.to_string(),
+                vacation.start_date.to_string(),
+                vacation.end_date.map(|d| d.to_string()),
+                Utc::now().to_rfc3339(),
+            ],
+        ))?;
+        
+        tracing::debug!("Started vacation on {} for habit {}", vacation.start_date, vacation.habit_id);
+        Ok(())
+    }
+    
+    /// Close every open vacation of a habit on `end_date`
+    fn end_vacations(&self, habit_id: &HabitId, end_date: NaiveDate) -> Result<u32, StorageError> {
+        let ended = self.with_retry(|| self.conn.execute(
+            "UPDATE vacations SET end_date = ?2 WHERE habit_id = ?1 AND end_date IS NULL",
+            params![habit_id.to_string(), end_date.to_string()],
+        ))? as u32;
+        
+        tracing::debug!("Ended {} vacations on {} for habit {}", ended, end_date, habit_id);
+        Ok(ended)
+    }
+    
+    /// Get a habit's vacations, earliest first
+    fn get_vacations(&self, habit_id: &HabitId) -> Result<Vec<Vacation>, StorageError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT start_date, end_date FROM vacations WHERE habit_id = ?1 ORDER BY start_date, id"
+        )?;
+        
+        let parse_date = |index: usize, date_str: &str| {
+            NaiveDate::parse_from_str(date_str, "%Y-%m-%d").map_err(|_| {
+                rusqlite::Error::InvalidColumnType(index, "Invalid date".to_string(), rusqlite::types::Type::Text)
+            })
+        };
+        let vacations = stmt.query_map(params![habit_id.to_string()], |row| {
+            let start_str: String = row.get(0)?;
+            let end_str: Option<String> = row.get(1)?;
+            Ok(Vacation {
+                habit_id: habit_id.clone(),
+                start_date: parse_date(0, &start_str)?,
+                end_date: end_str.map(|end| parse_date(1, &end)).transpose()?,
+            })
+        })?;
+        
+        vacations.collect::<Result<Vec<_>, _>>().map_err(StorageError::from)
+    }
+    
+    /// Report schema version, row counts and writability
+    fn health_check(&self) -> Result<StorageHealth, StorageError> {
+        let schema_version = migrations::get_current_version(&self.conn)?;
+        let habit_count: u32 = self.conn.query_row("SELECT COUNT(*) FROM habits", [], |row| row.get(0))?;
+        let entry_count: u32 = self.conn.query_row("SELECT COUNT(*) FROM habit_entries", [], |row| row.get(0))?;
+        let orphan_entry_count: u32 = self.conn.query_row(
+            "SELECT COUNT(*) FROM habit_entries WHERE habit_id NOT IN (SELECT id FROM habits)",
+            [],
+            |row| row.get(0),
+        )?;
+        
+        // A no-op update still needs a write lock, so it fails on a read-only database
+        let tx = self.conn.unchecked_transaction()?;
+        let writable = tx.execute("UPDATE schema_version SET version = version", []).is_ok();
+        tx.rollback()?;
+        
+        Ok(StorageHealth {
+            schema_version,
+            habit_count,
+            entry_count,
+            writable,
+            orphan_entry_count,
+        })
+    }
+    
+    /// List entries with no matching habit row, oldest completion first
+    fn find_orphan_entries(&self) -> Result<Vec<EntryId>, StorageError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT habit_entries.id FROM habit_entries
+             LEFT JOIN habits ON habits.id = habit_entries.habit_id
+             WHERE habits.id IS NULL
+             ORDER BY habit_entries.completed_at, habit_entries.logged_at"
+        )?;
+        let entry_ids = stmt.query_map([], |row| {
+            let entry_id_str: String = row.get(0)?;
+            EntryId::from_string(&entry_id_str).map_err(|_| {
+                rusqlite::Error::InvalidColumnType(0, "Invalid UUID".to_string(), rusqlite::types::Type::Text)
+            })
+        })?;
+        
+        entry_ids.collect::<Result<Vec<_>, _>>().map_err(StorageError::from)
+    }
+    
+    /// Delete entries, cached streaks, freezes and vacations with no matching habit row in a single transaction
+    fn delete_orphan_entries(&self) -> Result<u32, StorageError> {
+        let tx = self.conn.unchecked_transaction()?;
+        let deleted = tx.execute(
+            "DELETE FROM habit_entries WHERE habit_id NOT IN (SELECT id FROM habits)",
+            [],
+        )?;
+        let mut dependents = 0;
+        for table in ["habit_streaks", "streak_freezes", "vacations"] {
+            dependents += tx.execute(
+                &format!("DELETE FROM {} WHERE habit_id NOT IN (SELECT id FROM habits)", table),
+                [],
+            )?;
+        }
+        tx.commit()?;
+        
+        if deleted + dependents > 0 {
+            tracing::info!("Deleted {} orphaned habit entries and {} orphaned streak, freeze and vacation rows", deleted, dependents);
+        }
+        Ok(deleted as u32)
+    }
+    
+    /// Gather row counts, entry date bounds and the file size from pragmas
+    fn storage_stats(&self) -> Result<StorageStats, StorageError> {
+        let habit_count: u32 = self.conn.query_row("SELECT COUNT(*) FROM habits", [], |row| row.get(0))?;
+        let entry_count: u32 = self.conn.query_row("SELECT COUNT(*) FROM habit_entries", [], |row| row.get(0))?;
+        let streak_count: u32 = self.conn.query_row("SELECT COUNT(*) FROM habit_streaks", [], |row| row.get(0))?;
+        
+        let (earliest, latest): (Option<String>, Option<String>) = self.conn.query_row(
+            "SELECT MIN(completed_at), MAX(completed_at) FROM habit_entries",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        let parse_date = |date: Option<String>| date.and_then(|d| NaiveDate::parse_from_str(&d, "%Y-%m-%d").ok());
+        
+        let page_count: u64 = self.conn.query_row("PRAGMA page_count", [], |row| row.get(0))?;
+        let page_size: u64 = self.conn.query_row("PRAGMA page_size", [], |row| row.get(0))?;
+        
+        Ok(StorageStats {
+            habit_count,
+            entry_count,
+            streak_count,
+            earliest_entry: parse_date(earliest),
+            latest_entry: parse_date(latest),
+            database_bytes: page_count * page_size,
+        })
+    }
+    
+    /// Read the date basis periods written by migration v10 and `set_date_basis`
+    fn get_date_basis(&self) -> Result<DateBasisInfo, StorageError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT basis, effective_from FROM date_basis_history ORDER BY id"
+        )?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        
+        let periods = rows.into_iter()
+            .map(|(basis, effective_from)| Ok(DateBasisPeriod {
+                basis: DateBasis::parse(&basis)
+                    .ok_or_else(|| StorageError::Migration(format!("Unknown date basis '{}' in date_basis_history", basis)))?,
+                effective_from: effective_from
+                    .and_then(|date| NaiveDate::parse_from_str(&date, "%Y-%m-%d").ok()),
+            }))
+            .collect::<Result<Vec<_>, StorageError>>()?;
+        
+        Ok(DateBasisInfo { periods })
+    }
+    
+    /// Append a date basis period, keeping the ones before it
+    fn set_date_basis(&self, basis: DateBasis, effective_from: NaiveDate) -> Result<bool, StorageError> {
+        let info = self.get_date_basis()?;
+        if info.current() == basis {
+            return Ok(false);
+        }
+        if let Some(last) = info.periods.iter().filter_map(|period| period.effective_from).next_back() {
+            if effective_from <= last {
+                return Err(StorageError::Validation(format!(
+                    "A date basis switch must take effect after the previous one on {}", last
+                )));
+            }
+        }
+        
+        self.conn.execute(
+            "INSERT INTO date_basis_history (basis, effective_from) VALUES (?1, ?2)",
+            params![basis.as_str(), effective_from.format("%Y-%m-%d").to_string()],
+        )?;
+        
+        tracing::info!("Switched date basis to {} from {}", basis.as_str(), effective_from);
+        Ok(true)
+    }
+    
+    /// Run REINDEX, ANALYZE and VACUUM on the connection
+    fn optimize(&self) -> Result<(), StorageError> {
+        // VACUUM can't run inside a transaction, so this is deliberately not wrapped in one
+        self.with_retry(|| self.conn.execute_batch("REINDEX; ANALYZE; VACUUM;"))?;
+        Ok(())
+    }
+}
+
+/// Reject a range whose start is after its end
+/// 
+/// `BETWEEN` would otherwise match nothing, hiding a swapped pair of dates.
+fn check_date_range(start_date: NaiveDate, end_date: NaiveDate) -> Result<(), StorageError> {
+    if start_date > end_date {
+        return Err(StorageError::Validation(format!(
+            "start_date {} is after end_date {}; swap them to query that range", start_date, end_date
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Frequency;
+
+    fn storage_with_habit() -> (SqliteStorage, Habit) {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let habit = Habit::new(
+            "Drink water".to_string(),
+            None,
+            Category::Health,
+            Frequency::Daily,
+            None,
+            None,
+        ).unwrap();
+        storage.create_habit(&habit).unwrap();
+        (storage, habit)
+    }
+
+    fn entry_days_ago(habit: &Habit, days_ago: i64) -> HabitEntry {
+        let date = Utc::now().naive_utc().date() - chrono::Duration::days(days_ago);
+        HabitEntry::new(habit.id.clone(), date, None, None, None).unwrap()
+    }
+
+    #[test]
+    fn test_create_entry_duplicate_is_translated() {
+        let (storage, habit) = storage_with_habit();
+        storage.create_entry(&entry_days_ago(&habit, 0)).unwrap();
+
+        let result = storage.create_entry(&entry_days_ago(&habit, 0));
+        assert!(matches!(result, Err(StorageError::DuplicateEntry { .. })));
+    }
+
+    #[test]
+    fn test_multiple_entries_per_day_when_habit_allows_it() {
+        let (storage, habit) = storage_with_habit();
+        let habit = habit.with_allow_multiple_per_day(true);
+        storage.update_habit(&habit).unwrap();
+        assert!(storage.get_habit(&habit.id).unwrap().allow_multiple_per_day);
+
+        for _ in 0..3 {
+            storage.create_entry(&entry_days_ago(&habit, 0)).unwrap();
+        }
+        assert_eq!(storage.get_entries_for_habit(&habit.id, None).unwrap().len(), 3);
+
+        // Deliberate repeats aren't duplicates to clean up
+        assert!(storage.remove_duplicate_entries().unwrap().is_empty());
+        assert_eq!(storage.get_entries_for_habit(&habit.id, None).unwrap().len(), 3);
+
+        // Once the setting is turned off, further entries for the day are refused
+        let extra = entry_days_ago(&habit, 0);
+        storage.update_habit(&habit.with_allow_multiple_per_day(false)).unwrap();
+        let result = storage.create_entry(&extra);
+        assert!(matches!(result, Err(StorageError::DuplicateEntry { .. })));
+    }
+
+    #[test]
+    fn test_create_entries_persists_whole_batch() {
+        let (storage, habit) = storage_with_habit();
+        let entries: Vec<HabitEntry> = (0..3).map(|d| entry_days_ago(&habit, d)).collect();
+
+        storage.create_entries(&entries).unwrap();
+
+        assert_eq!(storage.get_entries_for_habit(&habit.id, None).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_create_entries_rolls_back_on_duplicate() {
+        let (storage, habit) = storage_with_habit();
+        let entries = vec![
+            entry_days_ago(&habit, 2),
+            entry_days_ago(&habit, 1),
+            entry_days_ago(&habit, 2), // Duplicate date
+        ];
+
+        let result = storage.create_entries(&entries);
+
+        assert!(matches!(result, Err(StorageError::DuplicateEntry { .. })));
+        assert!(storage.get_entries_for_habit(&habit.id, None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_create_entries_skipping_duplicates_reports_each_entry() {
+        let (storage, habit) = storage_with_habit();
+        storage.create_entry(&entry_days_ago(&habit, 3)).unwrap();
+        let entries = vec![
+            entry_days_ago(&habit, 3), // Already stored
+            entry_days_ago(&habit, 2),
+            entry_days_ago(&habit, 2), // Duplicate within the batch
+        ];
+
+        let created = storage.create_entries_skipping_duplicates(&entries).unwrap();
+
+        assert_eq!(created, vec![false, true, false]);
+        assert_eq!(storage.get_entries_for_habit(&habit.id, None).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_delete_entry_returns_owning_habit() {
+        let (storage, habit) = storage_with_habit();
+        let entry = entry_days_ago(&habit, 1);
+        storage.create_entry(&entry).unwrap();
+
+        assert_eq!(storage.delete_entry(&entry.id).unwrap(), habit.id);
+        assert!(storage.get_entries_for_habit(&habit.id, None).unwrap().is_empty());
+        assert!(matches!(storage.delete_entry(&entry.id), Err(StorageError::EntryNotFound { .. })));
+    }
+
+    #[test]
+    fn test_storage_stats_counts_rows_and_date_bounds() {
+        let (storage, habit) = storage_with_habit();
+        let empty = storage.storage_stats().unwrap();
+        assert_eq!((empty.habit_count, empty.entry_count, empty.streak_count), (1, 0, 0));
+        assert_eq!((empty.earliest_entry, empty.latest_entry), (None, None));
+
+        let entries = vec![entry_days_ago(&habit, 9), entry_days_ago(&habit, 4), entry_days_ago(&habit, 1)];
+        storage.create_entries(&entries).unwrap();
+        storage.update_streak(&Streak::new(habit.id.clone())).unwrap();
+
+        let stats = storage.storage_stats().unwrap();
+
+        let today = Utc::now().naive_utc().date();
+        assert_eq!((stats.habit_count, stats.entry_count, stats.streak_count), (1, 3, 1));
+        assert_eq!(stats.earliest_entry, Some(today - chrono::Duration::days(9)));
+        assert_eq!(stats.latest_entry, Some(today - chrono::Duration::days(1)));
+        assert!(stats.database_bytes > 0);
+    }
+
+    #[test]
+    fn test_orphan_entries_are_found_and_removed() {
+        let (storage, water) = storage_with_habit();
+        let run = Habit::new("Run".to_string(), None, Category::Health, Frequency::Daily, None, None).unwrap()
+            .with_freeze_tokens(1);
+        storage.create_habit(&run).unwrap();
+        let kept = entry_days_ago(&water, 1);
+        let orphans = vec![entry_days_ago(&run, 3), entry_days_ago(&run, 2)];
+        storage.create_entry(&kept).unwrap();
+        storage.create_entries(&orphans).unwrap();
+        let today = Utc::now().naive_utc().date();
+        storage.update_streak(&Streak::from_existing(run.id.clone(), 2, 2, Some(today), 2, 1.0)).unwrap();
+        storage.spend_freeze_token(&run.id, today).unwrap();
+        storage.create_vacation(&Vacation::new(run.id.clone(), today, None).unwrap()).unwrap();
+        assert!(storage.find_orphan_entries().unwrap().is_empty());
+
+        // Hard-delete the habit row as a manual edit would, bypassing the foreign key
+        storage.conn.execute("PRAGMA foreign_keys = OFF", []).unwrap();
+        storage.conn.execute("DELETE FROM habits WHERE id = ?1", params![run.id.to_string()]).unwrap();
+        storage.conn.execute("PRAGMA foreign_keys = ON", []).unwrap();
+
+        let found = storage.find_orphan_entries().unwrap();
+        assert_eq!(found, vec![orphans[0].id.clone(), orphans[1].id.clone()]);
+        assert_eq!(storage.health_check().unwrap().orphan_entry_count, 2);
+
+        assert_eq!(storage.delete_orphan_entries().unwrap(), 2);
+        assert!(storage.find_orphan_entries().unwrap().is_empty());
+        let health = storage.health_check().unwrap();
+        assert_eq!((health.entry_count, health.orphan_entry_count), (1, 0));
+        assert_eq!(storage.get_entries_for_habit(&water.id, None).unwrap()[0].id, kept.id);
+        // Rows keyed by the missing habit go with its entries
+        for table in ["habit_streaks", "streak_freezes", "vacations"] {
+            let count: u32 = storage.conn.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| row.get(0)).unwrap();
+            assert_eq!(count, 0, "{}", table);
+        }
+    }
+
+    #[test]
+    fn test_entries_by_category_and_range_filters_in_sql() {
+        let (storage, water) = storage_with_habit();
+        let run = Habit::new("Run".to_string(), None, Category::Health, Frequency::Daily, None, None).unwrap();
+        let read = Habit::new("Read".to_string(), None, Category::Personal, Frequency::Daily, None, None).unwrap();
+        let chess = Habit::new("Chess".to_string(), None, Category::Custom("Games".to_string()), Frequency::Daily, None, None).unwrap();
+        for habit in [&run, &read, &chess] {
+            storage.create_habit(habit).unwrap();
+        }
+        let mut entries = Vec::new();
+        for habit in [&water, &run, &read, &chess] {
+            for days_ago in [1, 5, 20] {
+                entries.push(entry_days_ago(habit, days_ago));
+            }
+        }
+        storage.create_entries(&entries).unwrap();
+        let today = Utc::now().naive_utc().date();
+        let start = today - chrono::Duration::days(10);
+
+        let health = storage.get_entries_by_category_and_range(&Category::Health, start, today).unwrap();
+
+        assert_eq!(health.len(), 4);
+        assert!(health.iter().all(|e| e.habit_id == water.id || e.habit_id == run.id));
+        assert!(health.iter().all(|e| e.completed_at >= start && e.completed_at <= today));
+        assert!(health.windows(2).all(|w| w[0].completed_at >= w[1].completed_at));
+
+        let games = storage.get_entries_by_category_and_range(&Category::Custom("Games".to_string()), start, today).unwrap();
+        assert_eq!(games.len(), 2);
+        assert!(games.iter().all(|e| e.habit_id == chess.id));
+
+        assert!(storage.get_entries_by_category_and_range(&Category::Social, start, today).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_get_streaks_for_habits_matches_get_streak() {
+        let (storage, habit) = storage_with_habit();
+        let no_streak = Habit::new("Stretch".to_string(), None, Category::Health, Frequency::Daily, None, None).unwrap();
+        let not_requested = Habit::new("Read".to_string(), None, Category::Personal, Frequency::Daily, None, None).unwrap();
+        storage.create_habit(&no_streak).unwrap();
+        storage.create_habit(&not_requested).unwrap();
+        for (id, current) in [(&habit.id, 4), (&not_requested.id, 9)] {
+            let mut streak = Streak::new(id.clone());
+            streak.current_streak = current;
+            streak.longest_streak = current;
+            streak.total_completions = current;
+            streak.completion_rate = 0.5;
+            streak.last_completed = Some(Utc::now().naive_utc().date());
+            storage.update_streak(&streak).unwrap();
+        }
+
+        let ids = vec![habit.id.clone(), no_streak.id.clone()];
+        let batch = storage.get_streaks_for_habits(&ids).unwrap();
+
+        assert_eq!(batch.len(), 2);
+        for id in &ids {
+            let single = storage.get_streak(id).unwrap();
+            let batched = &batch[id];
+            assert_eq!(batched.current_streak, single.current_streak);
+            assert_eq!(batched.longest_streak, single.longest_streak);
+            assert_eq!(batched.last_completed, single.last_completed);
+            assert_eq!(batched.total_completions, single.total_completions);
+            assert_eq!(batched.completion_rate, single.completion_rate);
+        }
+        assert_eq!(batch[&habit.id].current_streak, 4);
+        assert_eq!(batch[&no_streak.id].current_streak, 0);
+        assert!(storage.get_streaks_for_habits(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_set_all_active_counts_only_changed_rows() {
+        let (storage, habit) = storage_with_habit();
+        let paused = Habit::new("Stretch".to_string(), None, Category::Health, Frequency::Daily, None, None).unwrap();
+        storage.create_habit(&paused).unwrap();
+        storage.delete_habit(&paused.id).unwrap();
+
+        assert_eq!(storage.set_all_active(false).unwrap(), 1);
+        assert!(!storage.get_habit(&habit.id).unwrap().is_active);
+        assert_eq!(storage.set_all_active(true).unwrap(), 2);
+        assert_eq!(storage.list_habits(None, true).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_remove_duplicate_entries_keeps_earliest_logged() {
+        let (storage, habit) = storage_with_habit();
+        let other = Habit::new(
+            "Stretch".to_string(),
+            None,
+            Category::Health,
+            Frequency::Daily,
+            None,
+            None,
+        ).unwrap();
+        storage.create_habit(&other).unwrap();
+
+        // Raw inserts without the trigger skip the one-entry-per-day check,
+        // like a database created before it was enforced
+        storage.conn.execute("DROP TRIGGER habit_entries_one_per_day", []).unwrap();
+        let date = Utc::now().naive_utc().date().to_string();
+        let insert = |id: &str, habit_id: &HabitId, logged_at: &str| {
+            storage.conn.execute(
+                "INSERT INTO habit_entries (id, habit_id, logged_at, completed_at) VALUES (?1, ?2, ?3, ?4)",
+                params![id, habit_id.to_string(), logged_at, date],
+            ).unwrap();
+        };
+        let keep_id = EntryId::new().to_string();
+        insert(&EntryId::new().to_string(), &habit.id, "2024-01-01T09:00:00+00:00");
+        insert(&keep_id, &habit.id, "2024-01-01T08:00:00+00:00");
+        insert(&EntryId::new().to_string(), &habit.id, "2024-01-01T10:00:00+00:00");
+        insert(&EntryId::new().to_string(), &other.id, "2024-01-01T08:00:00+00:00");
+
+        let affected = storage.remove_duplicate_entries().unwrap();
+
+        assert_eq!(affected, vec![(habit.id.clone(), 2)]);
+        let remaining = storage.get_entries_for_habit(&habit.id, None).unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].id.to_string(), keep_id);
+        assert_eq!(storage.get_entries_for_habit(&other.id, None).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_writes_retry_while_another_connection_holds_the_lock() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("habits.db");
+        let open_storage = |max_retries| {
+            // Fail immediately on a lock so only our retries wait for it
+            SqliteStorage::new(&db_path).unwrap().with_retry_policy(RetryPolicy {
+                max_retries,
+                initial_delay: Duration::from_millis(25),
+                busy_timeout: Duration::ZERO,
+            }).unwrap()
+        };
+        let new_habit = || Habit::new("Read".to_string(), None, Category::Personal, Frequency::Daily, None, None).unwrap();
+        let hold_lock = |held_for: Duration| {
+            let (locked_tx, locked_rx) = std::sync::mpsc::channel();
+            let db_path = db_path.clone();
+            let holder = std::thread::spawn(move || {
+                let conn = Connection::open(&db_path).unwrap();
+                conn.execute_batch("BEGIN IMMEDIATE").unwrap();
+                locked_tx.send(()).unwrap();
+                std::thread::sleep(held_for);
+                conn.execute_batch("COMMIT").unwrap();
+            });
+            locked_rx.recv().unwrap();
+            holder
+        };
+
+        // Without retries the lock surfaces as a query error
+        let impatient = open_storage(0);
+        let holder = hold_lock(Duration::from_millis(200));
+        let result = impatient.create_habit(&new_habit());
+        assert!(matches!(result, Err(StorageError::Query(ref e)) if SqliteStorage::is_transient(e)));
+        holder.join().unwrap();
+
+        // With retries the write lands once the lock is released
+        let patient = open_storage(5);
+        let holder = hold_lock(Duration::from_millis(60));
+        let habit = new_habit();
+        patient.create_habit(&habit).unwrap();
+        holder.join().unwrap();
+        assert_eq!(patient.get_habit(&habit.id).unwrap().name, "Read");
+    }
+
+    #[test]
+    fn test_database_rejects_a_second_entry_from_another_connection() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("habits.db");
+        let storage = SqliteStorage::new(&db_path).unwrap();
+        let once = Habit::new("Read".to_string(), None, Category::Personal, Frequency::Daily, None, None).unwrap();
+        let often = Habit::new("Drink water".to_string(), None, Category::Health, Frequency::Daily, None, None)
+            .unwrap()
+            .with_allow_multiple_per_day(true);
+        storage.create_habit(&once).unwrap();
+        storage.create_habit(&often).unwrap();
+        let today = Utc::now().naive_utc().date();
+        storage.create_entry(&HabitEntry::new(once.id.clone(), today, None, None, None).unwrap()).unwrap();
+        storage.create_entry(&HabitEntry::new(often.id.clone(), today, None, None, None).unwrap()).unwrap();
+
+        // A writer that skipped (or raced past) the storage layer's check
+        let other = Connection::open(&db_path).unwrap();
+        let raw_insert = |habit: &Habit| other.execute(
+            "INSERT INTO habit_entries (id, habit_id, logged_at, completed_at) VALUES (?1, ?2, ?3, ?4)",
+            params![EntryId::new().to_string(), habit.id.to_string(), Utc::now().to_rfc3339(), today.to_string()],
+        );
+
+        let duplicate = raw_insert(&once).unwrap_err();
+        assert!(duplicate.to_string().contains(migrations::DUPLICATE_ENTRY_ABORT), "{}", duplicate);
+        raw_insert(&often).unwrap();
+
+        assert_eq!(storage.get_entries_for_habit(&once.id, None).unwrap().len(), 1);
+        assert_eq!(storage.get_entries_for_habit(&often.id, None).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_default_retry_policy_fits_in_a_request() {
+        let policy = RetryPolicy::default();
+        // 4 attempts of 100ms plus 50 + 100 + 200ms of backoff
+        assert_eq!(policy.worst_case(), Duration::from_millis(750));
+        assert!(policy.worst_case() < Duration::from_millis(crate::DEFAULT_REQUEST_TIMEOUT_MS));
+
+        // A lock that outlasts the policy is reported within its budget
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("habits.db");
+        let storage = SqliteStorage::new(&db_path).unwrap();
+        let holder = Connection::open(&db_path).unwrap();
+        holder.execute_batch("BEGIN IMMEDIATE").unwrap();
+        let started = std::time::Instant::now();
+        let habit = Habit::new("Read".to_string(), None, Category::Personal, Frequency::Daily, None, None).unwrap();
+        assert!(storage.create_habit(&habit).is_err());
+        assert!(started.elapsed() < Duration::from_secs(2), "{:?}", started.elapsed());
+        holder.execute_batch("ROLLBACK").unwrap();
+    }
+
+    #[test]
+    fn test_garbage_file_reports_corruption_and_recovers() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("habits.db");
+        std::fs::write(&db_path, vec![0xAB; 4096]).unwrap();
+
+        let result = SqliteStorage::new(&db_path);
+        assert!(matches!(result, Err(StorageError::Corrupted { .. })));
+
+        let backup = SqliteStorage::quarantine(&db_path).unwrap();
+        assert!(backup.exists());
+        assert!(!db_path.exists());
+        assert!(backup.file_name().unwrap().to_string_lossy().starts_with("habits.db.corrupt-"));
+
+        let storage = SqliteStorage::new(&db_path).unwrap();
+        let habit = Habit::new(
+            "Recovered".to_string(),
+            None,
+            Category::Personal,
+            Frequency::Daily,
+            None,
+            None,
+        ).unwrap();
+        storage.create_habit(&habit).unwrap();
+        assert_eq!(storage.get_habit(&habit.id).unwrap().name, "Recovered");
+    }
+
+    #[test]
+    fn test_set_habit_positions_rolls_back_on_unknown_habit() {
+        let (storage, habit) = storage_with_habit();
+
+        let result = storage.set_habit_positions(&[habit.id.clone(), HabitId::new()]);
+
+        assert!(matches!(result, Err(StorageError::HabitNotFound { .. })));
+        assert_eq!(storage.get_habit(&habit.id).unwrap().position, None);
+
+        // A failed reorder keeps the previous order, including the cleared positions
+        storage.set_habit_positions(std::slice::from_ref(&habit.id)).unwrap();
+        assert!(storage.set_habit_positions(&[HabitId::new()]).is_err());
+        assert_eq!(storage.get_habit(&habit.id).unwrap().position, Some(1));
+    }
+
+    #[test]
+    fn test_date_basis_is_written_and_read_back() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let info = storage.get_date_basis().unwrap();
+        assert_eq!(info.periods, vec![DateBasisPeriod { basis: DateBasis::Utc, effective_from: None }]);
+        
+        let switch_day = NaiveDate::from_ymd_opt(2024, 6, 1).unwrap();
+        assert!(!storage.set_date_basis(DateBasis::Utc, switch_day).unwrap());
+        assert!(storage.set_date_basis(DateBasis::Local, switch_day).unwrap());
+        
+        let info = storage.get_date_basis().unwrap();
+        assert_eq!(info.current(), DateBasis::Local);
+        assert_eq!(info.basis_for(switch_day.pred_opt().unwrap()), DateBasis::Utc);
+        assert_eq!(info.basis_for(switch_day), DateBasis::Local);
+    }
+    
+    #[test]
+    fn test_second_date_basis_switch_keeps_earlier_periods() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let day = |m: u32| NaiveDate::from_ymd_opt(2024, m, 1).unwrap();
+        storage.set_date_basis(DateBasis::Local, day(6)).unwrap();
+        
+        assert!(storage.set_date_basis(DateBasis::Utc, day(6)).is_err());
+        assert!(storage.set_date_basis(DateBasis::Utc, day(9)).unwrap());
+        
+        let info = storage.get_date_basis().unwrap();
+        assert_eq!(info.periods.len(), 3);
+        assert_eq!(info.basis_for(day(5)), DateBasis::Utc);
+        assert_eq!(info.basis_for(day(7)), DateBasis::Local);
+        assert_eq!(info.basis_for(day(9)), DateBasis::Utc);
+    }
+
+    #[test]
+    fn test_date_range_queries_reject_inverted_ranges() {
+        let (storage, habit) = storage_with_habit();
+        let day = |d: u32| NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+        let entries: Vec<HabitEntry> = [10, 11, 12].iter()
+            .map(|&d| HabitEntry::from_existing(EntryId::new(), habit.id.clone(), Utc::now(), day(d), None, None, None))
+            .collect();
+        storage.create_entries(&entries).unwrap();
+
+        assert_eq!(storage.get_entries_by_date_range(day(10), day(11)).unwrap().len(), 2);
+        let single = storage.get_entries_by_date_range(day(12), day(12)).unwrap();
+        assert_eq!(single.len(), 1);
+        assert_eq!(single[0].completed_at, day(12));
+
+        let inverted = storage.get_entries_by_date_range(day(12), day(10)).unwrap_err();
+        assert!(matches!(inverted, StorageError::Validation(ref msg) if msg.contains("2024-03-12 is after end_date 2024-03-10")));
+        assert!(storage.get_entries_by_category_and_range(&Category::Health, day(12), day(10)).is_err());
+    }
+}
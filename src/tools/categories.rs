@@ -0,0 +1,110 @@
+/// Tool for listing the categories habits actually use
+///
+/// This module implements the habit_categories MCP tool, which a client can
+/// use to build a category picker. Active and paused habits both count.
+/// Registered categories are listed with the built-ins.
+
+use serde::Serialize;
+use crate::domain::{Category, CategoryRegistry};
+use crate::storage::{StorageError, HabitStorage};
+use crate::tools::list::category_label;
+
+/// One category in use and how many habits are in it
+#[derive(Debug, Serialize)]
+pub struct CategoryCount {
+    /// Name as tools accept it, e.g. "health" or "startup" (custom)
+    pub category: String,
+    pub display_name: String,
+    pub habit_count: u32,
+}
+
+/// Response listing the categories in use
+#[derive(Debug, Serialize)]
+pub struct CategoriesResponse {
+    pub builtin: Vec<CategoryCount>,
+    pub custom: Vec<CategoryCount>,
+    pub total_habits: u32,
+    pub message: String,
+}
+
+/// List every distinct category across all habits, with habit counts
+pub fn list_habit_categories<S: HabitStorage>(storage: &S, categories: &CategoryRegistry) -> Result<CategoriesResponse, StorageError> {
+    let mut builtin = Vec::new();
+    let mut custom = Vec::new();
+    let mut total_habits = 0;
+
+    for (category, habit_count) in storage.count_habits_by_category()? {
+        total_habits += habit_count;
+        let count = CategoryCount {
+            category: category_label(&category),
+            display_name: category.display_name_in(categories).to_string(),
+            habit_count,
+        };
+        match category {
+            Category::Custom(_) => custom.push(count),
+            _ => builtin.push(count),
+        }
+    }
+
+    let message = if total_habits == 0 {
+        "📭 No habits yet, so no categories are in use.".to_string()
+    } else {
+        let lines = |counts: &[CategoryCount]| counts.iter()
+            .map(|c| format!("   {} ({})", c.display_name, c.habit_count))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let mut message = format!("🗂️ Categories in use across {} habit{}", total_habits, if total_habits == 1 { "" } else { "s" });
+        if !builtin.is_empty() {
+            message.push_str(&format!("\n\nBuilt-in:\n{}", lines(&builtin)));
+        }
+        if !custom.is_empty() {
+            message.push_str(&format!("\n\nCustom:\n{}", lines(&custom)));
+        }
+        message
+    };
+
+    Ok(CategoriesResponse {
+        builtin,
+        custom,
+        total_habits,
+        message,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Frequency, Habit, HabitId};
+    use crate::storage::sqlite::SqliteStorage;
+
+    #[test]
+    fn test_categories_are_distinct_with_counts() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let categories = [
+            Category::Health,
+            Category::Health,
+            Category::Mindfulness,
+            Category::Custom("startup".to_string()),
+            Category::Custom("startup".to_string()),
+            Category::Custom("garden".to_string()),
+        ];
+        let mut ids: Vec<HabitId> = Vec::new();
+        for (i, category) in categories.into_iter().enumerate() {
+            let habit = Habit::new(format!("Habit {}", i), None, category, Frequency::Daily, None, None).unwrap();
+            storage.create_habit(&habit).unwrap();
+            ids.push(habit.id);
+        }
+        // Paused habits still count
+        storage.delete_habit(&ids[2]).unwrap();
+
+        let response = list_habit_categories(&storage, &CategoryRegistry::default()).unwrap();
+
+        let counts = |list: &[CategoryCount]| list.iter()
+            .map(|c| (c.category.clone(), c.habit_count))
+            .collect::<Vec<_>>();
+        assert_eq!(counts(&response.builtin), vec![("health".to_string(), 2), ("mindfulness".to_string(), 1)]);
+        assert_eq!(counts(&response.custom), vec![("garden".to_string(), 1), ("startup".to_string(), 2)]);
+        assert_eq!(response.total_habits, 6);
+        assert!(response.message.contains("Custom:\n   garden (1)"));
+    }
+}
@@ -0,0 +1,116 @@
+/// Tool for duplicating an existing habit's configuration
+/// 
+/// This module implements the habit_clone MCP tool. The clone is a brand
+/// new habit that shares the source's setup but none of its history.
+
+use serde::{Deserialize, Serialize};
+use crate::domain::{Habit, HabitId};
+use crate::storage::{StorageError, HabitStorage};
+
+/// Parameters for cloning a habit
+#[derive(Debug, Deserialize)]
+pub struct CloneHabitParams {
+    pub source_habit_id: String,
+    pub name: String,
+}
+
+/// Response from cloning a habit
+#[derive(Debug, Serialize)]
+pub struct CloneHabitResponse {
+    pub success: bool,
+    pub habit_id: String,
+    pub message: String,
+}
+
+/// Create a new habit with the same description, category, frequency,
+/// target and unit as the source habit
+pub fn clone_habit<S: HabitStorage>(
+    storage: &S,
+    params: CloneHabitParams,
+) -> Result<CloneHabitResponse, StorageError> {
+    let source_id = HabitId::from_string(&params.source_habit_id)
+        .map_err(|_| StorageError::HabitNotFound { habit_id: params.source_habit_id.clone() })?;
+    let source = storage.get_habit(&source_id)?;
+    
+    let habit = Habit::new(
+        params.name,
+        source.description,
+        source.category,
+        source.frequency,
+        source.target_value,
+        source.unit,
+    )?;
+    storage.create_habit(&habit)?;
+    
+    Ok(CloneHabitResponse {
+        success: true,
+        habit_id: habit.id.to_string(),
+        message: format!("✅ Created habit '{}' with the same setup as '{}'! Ready to start your streak!",
+                         habit.name, source.name),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Category, Frequency, HabitEntry};
+    use crate::storage::sqlite::SqliteStorage;
+
+    #[test]
+    fn test_clone_copies_configuration_but_not_history() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let source = Habit::new(
+            "Morning Run".to_string(),
+            Some("Around the park".to_string()),
+            Category::Health,
+            Frequency::Weekly(3),
+            Some(5),
+            Some("km".to_string()),
+        ).unwrap();
+        storage.create_habit(&source).unwrap();
+        let yesterday = chrono::Utc::now().naive_utc().date() - chrono::Duration::days(1);
+        storage.create_entry(&HabitEntry::new(source.id.clone(), yesterday, Some(5), None, None).unwrap()).unwrap();
+
+        let response = clone_habit(&storage, CloneHabitParams {
+            source_habit_id: source.id.to_string(),
+            name: "Evening Run".to_string(),
+        }).unwrap();
+
+        let clone = storage.get_habit(&HabitId::from_string(&response.habit_id).unwrap()).unwrap();
+        assert_ne!(clone.id, source.id);
+        assert_eq!(clone.name, "Evening Run");
+        assert_eq!(clone.description, source.description);
+        assert_eq!(clone.category, source.category);
+        assert_eq!(clone.frequency, source.frequency);
+        assert_eq!(clone.target_value, Some(5));
+        assert_eq!(clone.unit.as_deref(), Some("km"));
+        assert!(storage.get_entries_for_habit(&clone.id, None).unwrap().is_empty());
+        assert_eq!(storage.get_streak(&clone.id).unwrap().total_completions, 0);
+    }
+
+    #[test]
+    fn test_clone_validates_name_and_source() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let source = Habit::new(
+            "Morning Run".to_string(),
+            None,
+            Category::Health,
+            Frequency::Daily,
+            None,
+            None,
+        ).unwrap();
+        storage.create_habit(&source).unwrap();
+
+        let blank = clone_habit(&storage, CloneHabitParams {
+            source_habit_id: source.id.to_string(),
+            name: "  ".to_string(),
+        });
+        assert!(matches!(blank, Err(StorageError::Validation(_))));
+
+        let missing = clone_habit(&storage, CloneHabitParams {
+            source_habit_id: HabitId::new().to_string(),
+            name: "Evening Run".to_string(),
+        });
+        assert!(matches!(missing, Err(StorageError::HabitNotFound { .. })));
+    }
+}
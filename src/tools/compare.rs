@@ -0,0 +1,231 @@
+/// Tool for comparing two habits side by side
+///
+/// This module implements the habit_compare MCP tool, which answers
+/// "which habit am I more consistent with?" using the stored streaks and
+/// each habit's recent entries.
+
+use serde::{Deserialize, Serialize};
+use crate::domain::{format_rate, format_streak_length, streak_unit_label, Clock, Habit, HabitId};
+use crate::storage::{StorageError, HabitStorage};
+
+/// Days counted as recent activity, including today
+const RECENT_DAYS: i64 = 7;
+
+/// Parameters for comparing two habits
+#[derive(Debug, Deserialize)]
+pub struct CompareParams {
+    pub habit_id_a: String,
+    pub habit_id_b: String,
+}
+
+/// One habit's side of the comparison
+#[derive(Debug, Serialize)]
+pub struct HabitComparison {
+    pub habit_id: String,
+    pub name: String,
+    pub current_streak: u32,
+    pub longest_streak: u32,
+    /// What the streaks count: "days", "weeks" or "periods"
+    pub streak_unit: &'static str,
+    pub completion_rate: f64,
+    pub total_completions: u32,
+    /// Distinct days completed in the last 7 days, including today
+    pub completions_last_7_days: u32,
+    pub last_completed: Option<String>,
+    /// 0-100 blend of streak, completion rate and recency (see `Streak::consistency_score`)
+    pub consistency_score: u8,
+}
+
+/// Response from comparing two habits
+#[derive(Debug, Serialize)]
+pub struct CompareResponse {
+    pub a: HabitComparison,
+    pub b: HabitComparison,
+    /// ID of the habit with the higher consistency score, None on a tie
+    pub stronger_habit_id: Option<String>,
+    pub verdict: String,
+    pub message: String,
+}
+
+/// Compare two habits' streaks, completion rates and recent activity
+///
+/// The verdict goes to the habit with the higher consistency score.
+/// Recent activity counts back from the clock's today.
+pub fn compare_habits<S: HabitStorage>(
+    storage: &S,
+    params: CompareParams,
+    clock: &dyn Clock,
+) -> Result<CompareResponse, StorageError> {
+    if params.habit_id_a == params.habit_id_b {
+        return Err(StorageError::Validation("Choose two different habits to compare".to_string()));
+    }
+
+    let habit_a = find_habit(storage, &params.habit_id_a);
+    let habit_b = find_habit(storage, &params.habit_id_b);
+    let (habit_a, habit_b) = match (habit_a, habit_b) {
+        (Ok(a), Ok(b)) => (a, b),
+        (Err(StorageError::HabitNotFound { .. }), Err(StorageError::HabitNotFound { .. })) => {
+            return Err(StorageError::HabitNotFound {
+                habit_id: format!("{} and {}", params.habit_id_a, params.habit_id_b),
+            });
+        }
+        (Err(e), _) | (_, Err(e)) => return Err(e),
+    };
+
+    let a = build_comparison(storage, habit_a, clock)?;
+    let b = build_comparison(storage, habit_b, clock)?;
+
+    let stronger = match a.consistency_score.cmp(&b.consistency_score) {
+        std::cmp::Ordering::Greater => Some((&a, &b)),
+        std::cmp::Ordering::Less => Some((&b, &a)),
+        std::cmp::Ordering::Equal => None,
+    };
+    let verdict = match stronger {
+        Some((winner, loser)) => format!(
+            "'{}' is the stronger habit (consistency {}/100 vs {}/100 for '{}')",
+            winner.name, winner.consistency_score, loser.consistency_score, loser.name
+        ),
+        None => format!(
+            "'{}' and '{}' are evenly matched (consistency {}/100 each)",
+            a.name, b.name, a.consistency_score
+        ),
+    };
+    let stronger_habit_id = stronger.map(|(winner, _)| winner.habit_id.clone());
+
+    let message = format!("⚖️ {}\n\n{}\n\n{}", verdict, format_side(&a), format_side(&b));
+
+    Ok(CompareResponse {
+        a,
+        b,
+        stronger_habit_id,
+        verdict,
+        message,
+    })
+}
+
+/// Load a habit by its string ID, treating a malformed ID as not found
+fn find_habit<S: HabitStorage>(storage: &S, habit_id: &str) -> Result<Habit, StorageError> {
+    let id = HabitId::from_string(habit_id)
+        .map_err(|_| StorageError::HabitNotFound { habit_id: habit_id.to_string() })?;
+    storage.get_habit(&id)
+}
+
+/// Gather one habit's figures from its stored streak and recent entries
+fn build_comparison<S: HabitStorage>(storage: &S, habit: Habit, clock: &dyn Clock) -> Result<HabitComparison, StorageError> {
+    let streak = storage.get_streak(&habit.id)?;
+    let today = clock.today();
+    let since = today - chrono::Duration::days(RECENT_DAYS - 1);
+    let recent_days: std::collections::BTreeSet<_> = storage.get_entries_for_habit(&habit.id, None)?
+        .into_iter()
+        .map(|e| e.completed_at)
+        .filter(|day| (since..=today).contains(day))
+        .collect();
+
+    Ok(HabitComparison {
+        habit_id: habit.id.to_string(),
+        name: habit.name,
+        current_streak: streak.current_streak,
+        longest_streak: streak.longest_streak,
+        streak_unit: streak_unit_label(&habit.frequency),
+        completion_rate: streak.completion_rate,
+        total_completions: streak.total_completions,
+        completions_last_7_days: recent_days.len() as u32,
+        last_completed: streak.last_completed.map(|d| d.to_string()),
+        consistency_score: streak.consistency_score_with_clock(&habit.frequency, clock),
+    })
+}
+
+/// Format one habit's column of the comparison
+fn format_side(side: &HabitComparison) -> String {
+    format!(
+        "🎯 {}\n   Current streak: {} | Best: {} | Rate: {}\n   Total: {} | Last 7 days: {} | Last completed: {} | Consistency: {}/100",
+        side.name,
+        format_streak_length(side.current_streak, side.streak_unit),
+        format_streak_length(side.longest_streak, side.streak_unit),
+        format_rate(side.completion_rate),
+        side.total_completions,
+        side.completions_last_7_days,
+        side.last_completed.as_deref().unwrap_or("never"),
+        side.consistency_score,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use crate::domain::{Category, Frequency, HabitEntry, SystemClock};
+    use crate::storage::sqlite::SqliteStorage;
+    use crate::analytics::AnalyticsEngine;
+    use crate::tools::log::recompute_and_store_streak;
+
+    /// Create a daily habit completed on the given days ago, with its streak stored
+    fn seed_habit(storage: &SqliteStorage, name: &str, days_ago: &[i64]) -> Habit {
+        let mut habit = Habit::new(name.to_string(), None, Category::Health, Frequency::Daily, None, None).unwrap();
+        habit.created_at = Utc::now() - chrono::Duration::days(20);
+        storage.create_habit(&habit).unwrap();
+        let today = Utc::now().naive_utc().date();
+        let entries: Vec<HabitEntry> = days_ago.iter()
+            .map(|d| HabitEntry::new(habit.id.clone(), today - chrono::Duration::days(*d), None, None, None).unwrap())
+            .collect();
+        storage.create_entries(&entries).unwrap();
+        recompute_and_store_streak(storage, &AnalyticsEngine::new(), &habit.id).unwrap();
+        habit
+    }
+
+    #[test]
+    fn test_compare_picks_more_consistent_habit() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let run = seed_habit(&storage, "Run", &(0..10).collect::<Vec<_>>());
+        let stretch = seed_habit(&storage, "Stretch", &[3, 9, 15]);
+
+        let response = compare_habits(&storage, CompareParams {
+            habit_id_a: stretch.id.to_string(),
+            habit_id_b: run.id.to_string(),
+        }, &SystemClock).unwrap();
+
+        assert_eq!(response.stronger_habit_id, Some(run.id.to_string()));
+        assert!(response.verdict.starts_with("'Run' is the stronger habit"));
+        assert_eq!(response.b.current_streak, 10);
+        assert_eq!(response.b.completions_last_7_days, 7);
+        assert_eq!(response.a.total_completions, 3);
+        assert_eq!(response.a.completions_last_7_days, 1);
+        assert!(response.a.consistency_score < response.b.consistency_score);
+    }
+
+    #[test]
+    fn test_compare_counts_recent_days_from_the_clock() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let run = seed_habit(&storage, "Run", &(1..=7).collect::<Vec<_>>());
+        let stretch = seed_habit(&storage, "Stretch", &[0]);
+        let params = || CompareParams { habit_id_a: run.id.to_string(), habit_id_b: stretch.id.to_string() };
+        let today = Utc::now().naive_utc().date();
+        // Before a 3am rollover the last 7 days still end yesterday
+        let early = crate::domain::DayRolloverClock {
+            clock: crate::domain::FixedTime(today.and_hms_opt(1, 0, 0).unwrap()),
+            rollover_hour: 3,
+        };
+
+        assert_eq!(compare_habits(&storage, params(), &SystemClock).unwrap().a.completions_last_7_days, 6);
+        assert_eq!(compare_habits(&storage, params(), &early).unwrap().a.completions_last_7_days, 7);
+    }
+
+    #[test]
+    fn test_compare_reports_missing_habits() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let run = seed_habit(&storage, "Run", &[0]);
+        let missing = HabitId::new().to_string();
+        let compare = |a: &str, b: &str| compare_habits(&storage, CompareParams {
+            habit_id_a: a.to_string(),
+            habit_id_b: b.to_string(),
+        }, &SystemClock);
+
+        let one = compare(&run.id.to_string(), &missing).unwrap_err();
+        assert!(matches!(one, StorageError::HabitNotFound { ref habit_id } if *habit_id == missing));
+
+        let both = compare("not-an-id", &missing).unwrap_err();
+        assert!(matches!(both, StorageError::HabitNotFound { ref habit_id } if habit_id.contains("not-an-id") && habit_id.contains(&missing)));
+
+        assert!(matches!(compare(&missing, &missing), Err(StorageError::Validation(_))));
+    }
+}
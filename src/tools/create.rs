@@ -3,7 +3,7 @@
 /// This module implements the habit_create MCP tool.
 
 use serde::{Deserialize, Serialize};
-use crate::domain::{Habit, Category, Frequency};
+use crate::domain::{Habit, Category, CategoryRegistry, Frequency};
 use crate::storage::{StorageError, HabitStorage};
 
 /// Parameters for creating a new habit
@@ -15,6 +15,12 @@ pub struct CreateHabitParams {
     pub frequency: String, // We'll parse this to Frequency enum
     pub target_value: Option<u32>,
     pub unit: Option<String>,
+    /// Why the user wants to build this habit (optional)
+    #[serde(default)]
+    pub motivation: Option<String>,
+    /// Allow logging several completions on the same day (defaults to false)
+    #[serde(default)]
+    pub allow_multiple_per_day: bool,
 }
 
 /// Response from creating a habit
@@ -29,86 +35,57 @@ pub struct CreateHabitResponse {
 pub fn create_habit<S: HabitStorage>(
     storage: &S,
     params: CreateHabitParams,
+    categories: &CategoryRegistry,
 ) -> Result<CreateHabitResponse, StorageError> {
+    let habit = build_habit(params, categories)?;
+    let habit_id = habit.id.to_string();
+    
+    // Save to storage
+    storage.create_habit(&habit)?;
+    
+    Ok(CreateHabitResponse {
+        success: true,
+        habit_id: Some(habit_id),
+        message: format!("✅ Created habit '{}'! Ready to start your streak!", habit.name),
+    })
+}
+
+/// Validate creation parameters and build the habit, without storing it
+pub(crate) fn build_habit(params: CreateHabitParams, categories: &CategoryRegistry) -> Result<Habit, StorageError> {
     // Validate input parameters
     if params.name.trim().is_empty() {
-        return Err(StorageError::Query(
-            rusqlite::Error::InvalidColumnType(0, "Habit name cannot be empty".to_string(), rusqlite::types::Type::Text)
-        ));
+        return Err(StorageError::Validation("Habit name cannot be empty".to_string()));
     }
     
     if params.name.len() > 100 {
-        return Err(StorageError::Query(
-            rusqlite::Error::InvalidColumnType(0, "Habit name too long (max 100 characters)".to_string(), rusqlite::types::Type::Text)
-        ));
+        return Err(StorageError::Validation("Habit name too long (max 100 characters)".to_string()));
     }
     
     // Parse and validate category
-    let category = match params.category.trim().to_lowercase().as_str() {
-        "health" => Category::Health,
-        "productivity" => Category::Productivity,
-        "social" => Category::Social,
-        "creative" => Category::Creative,
-        "mindfulness" => Category::Mindfulness,
-        "financial" => Category::Financial,
-        "household" => Category::Household,
-        "personal" => Category::Personal,
-        custom if custom.starts_with("custom:") => {
-            let name = custom.strip_prefix("custom:").unwrap().trim();
-            if name.is_empty() {
-                return Err(StorageError::Query(
-                    rusqlite::Error::InvalidColumnType(0, "Custom category name cannot be empty".to_string(), rusqlite::types::Type::Text)
-                ));
-            }
-            Category::Custom(name.to_string())
-        },
-        _ => {
-            return Err(StorageError::Query(
-                rusqlite::Error::InvalidColumnType(0, 
-                    format!("Invalid category '{}'. Valid options: health, productivity, social, creative, mindfulness, financial, household, personal, or custom:name", params.category),
-                    rusqlite::types::Type::Text
-                )
-            ));
-        }
-    };
+    let category = parse_category(&params.category, categories)?;
     
     // Parse and validate frequency
-    let frequency = match params.frequency.trim().to_lowercase().as_str() {
-        "daily" => Frequency::Daily,
-        "weekdays" => Frequency::Weekdays,
-        "weekends" => Frequency::Weekends,
-        "weekly" => Frequency::Weekly(3), // Default to 3 times per week
-        "custom" => Frequency::Custom(vec![chrono::Weekday::Mon]), // Default to Monday
-        _ => {
-            return Err(StorageError::Query(
-                rusqlite::Error::InvalidColumnType(0, 
-                    format!("Invalid frequency '{}'. Valid options: daily, weekdays, weekends, weekly, custom", params.frequency),
-                    rusqlite::types::Type::Text
-                )
-            ));
-        }
-    };
+    let frequency: Frequency = params.frequency.parse()?;
     
     // Create the habit
-    let habit = Habit::new(
-        params.name.clone(),
+    let mut habit = Habit::new(
+        params.name,
         params.description,
         category,
         frequency,
         params.target_value,
         params.unit,
-    ).map_err(|e| StorageError::Query(
-        rusqlite::Error::InvalidColumnType(0, e.to_string(), rusqlite::types::Type::Text)
-    ))?;
+    )?
+    .with_allow_multiple_per_day(params.allow_multiple_per_day);
+    habit.set_motivation(params.motivation)?;
     
-    let habit_id = habit.id.to_string();
-    
-    // Save to storage
-    storage.create_habit(&habit)?;
-    
-    Ok(CreateHabitResponse {
-        success: true,
-        habit_id: Some(habit_id),
-        message: format!("✅ Created habit '{}'! Ready to start your streak!", params.name),
-    })
-}
\ No newline at end of file
+    Ok(habit)
+}
+
+/// Parse a user-supplied category string into a Category enum
+///
+/// Accepts the built-in and registered category names (case-insensitive) and
+/// `custom:<name>`, as long as the custom name isn't one of those.
+pub(crate) fn parse_category(category_str: &str, categories: &CategoryRegistry) -> Result<Category, StorageError> {
+    Category::parse_user_input(category_str, categories).map_err(StorageError::from)
+}
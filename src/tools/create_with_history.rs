@@ -0,0 +1,176 @@
+/// Tool for creating a habit together with its past entries
+///
+/// This module implements the habit_create_with_history MCP tool, used to
+/// migrate habits from other trackers. The habit and all of its entries are
+/// stored in one transaction, so a bad entry leaves nothing behind.
+
+use chrono::{TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use crate::domain::{format_streak_length, parse_date_input, streak_unit_label, EntryValidation, HabitEntry};
+use crate::analytics::AnalyticsEngine;
+use crate::storage::{StorageError, HabitStorage};
+use crate::tools::create::{build_habit, CreateHabitParams};
+use crate::tools::log::recompute_and_store_streak;
+
+/// One historical completion to import
+#[derive(Debug, Deserialize)]
+pub struct HistoryEntryParams {
+    /// Date completed (YYYY-MM-DD or any form `parse_date_input` accepts)
+    pub completed_at: String,
+    pub value: Option<u32>,
+    pub intensity: Option<u8>,
+    pub notes: Option<String>,
+}
+
+/// Parameters for creating a habit with its history
+#[derive(Debug)]
+pub struct CreateWithHistoryParams {
+    pub habit: CreateHabitParams,
+    pub entries: Vec<HistoryEntryParams>,
+}
+
+/// Response from creating a habit with its history
+#[derive(Debug, Serialize)]
+pub struct CreateWithHistoryResponse {
+    pub success: bool,
+    pub habit_id: String,
+    pub entries_created: u32,
+    pub current_streak: u32,
+    pub longest_streak: u32,
+    pub message: String,
+}
+
+/// Create a habit and all of its historical entries atomically
+///
+/// Every entry is validated before anything is written; if any entry is
+/// invalid (or a duplicate) the whole import fails and nothing is stored.
+/// The habit's creation time is moved back to its earliest entry so that
+/// completion rates cover the imported history.
+pub fn create_habit_with_history<S: HabitStorage>(
+    storage: &S,
+    analytics: &AnalyticsEngine,
+    params: CreateWithHistoryParams,
+    validation: &EntryValidation,
+) -> Result<CreateWithHistoryResponse, StorageError> {
+    let mut habit = build_habit(params.habit, analytics.categories())?;
+
+    let mut entries = Vec::with_capacity(params.entries.len());
+    for (index, item) in params.entries.into_iter().enumerate() {
+        let completed_at = parse_date_input(&item.completed_at, validation.clock)
+            .map_err(|e| StorageError::Validation(format!("Entry {}: {}", index, e)))?;
+        let entry = HabitEntry::new_with_validation(
+            habit.id.clone(),
+            completed_at,
+            item.value,
+            item.intensity,
+            item.notes,
+            validation,
+        )
+        .map_err(|e| StorageError::Validation(format!("Entry {}: {}", index, e)))?;
+        entries.push(entry);
+    }
+
+    if let Some(earliest) = entries.iter().map(|e| e.completed_at).min() {
+        let earliest = Utc.from_utc_datetime(&earliest.and_hms_opt(0, 0, 0).unwrap());
+        if earliest < habit.created_at {
+            habit.created_at = earliest;
+        }
+    }
+
+    storage.create_habit_with_entries(&habit, &entries)?;
+    let streak = recompute_and_store_streak(storage, analytics, &habit.id)?;
+
+    let entries_created = entries.len() as u32;
+    let message = format!(
+        "✅ Imported habit '{}' with {} entr{} — current streak is {} (longest {})",
+        habit.name,
+        entries_created,
+        if entries_created == 1 { "y" } else { "ies" },
+        format_streak_length(streak.current_streak, streak_unit_label(&habit.frequency)),
+        streak.longest_streak,
+    );
+
+    Ok(CreateWithHistoryResponse {
+        success: true,
+        habit_id: habit.id.to_string(),
+        entries_created,
+        current_streak: streak.current_streak,
+        longest_streak: streak.longest_streak,
+        message,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use crate::storage::sqlite::SqliteStorage;
+
+    fn habit_params() -> CreateHabitParams {
+        CreateHabitParams {
+            name: "Meditate".to_string(),
+            description: None,
+            category: "mindfulness".to_string(),
+            frequency: "daily".to_string(),
+            target_value: None,
+            unit: None,
+            motivation: None,
+            allow_multiple_per_day: false,
+        }
+    }
+
+    fn entry(date: NaiveDate) -> HistoryEntryParams {
+        HistoryEntryParams { completed_at: date.to_string(), value: None, intensity: None, notes: None }
+    }
+
+    #[test]
+    fn test_import_habit_with_ten_entries() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let today = Utc::now().naive_utc().date();
+        let entries = (0..10).map(|d| entry(today - chrono::Duration::days(d))).collect();
+
+        let response = create_habit_with_history(
+            &storage,
+            &AnalyticsEngine::new(),
+            CreateWithHistoryParams { habit: habit_params(), entries },
+            &EntryValidation::default(),
+        ).unwrap();
+
+        assert_eq!(response.entries_created, 10);
+        assert_eq!(response.current_streak, 10);
+
+        let habit_id = crate::domain::HabitId::from_string(&response.habit_id).unwrap();
+        let habit = storage.get_habit(&habit_id).unwrap();
+        assert_eq!(habit.created_at.date_naive(), today - chrono::Duration::days(9));
+        assert_eq!(storage.get_entries_for_habit(&habit_id, None).unwrap().len(), 10);
+        assert_eq!(storage.get_streak(&habit_id).unwrap().current_streak, 10);
+    }
+
+    #[test]
+    fn test_invalid_entry_rolls_back_import() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let today = Utc::now().naive_utc().date();
+        let mut entries: Vec<_> = (1..10).map(|d| entry(today - chrono::Duration::days(d))).collect();
+        entries.push(entry(today + chrono::Duration::days(1)));
+
+        let result = create_habit_with_history(
+            &storage,
+            &AnalyticsEngine::new(),
+            CreateWithHistoryParams { habit: habit_params(), entries },
+            &EntryValidation::default(),
+        );
+
+        assert!(matches!(result, Err(StorageError::Validation(ref msg)) if msg.starts_with("Entry 9:")));
+        assert!(storage.list_habits(None, false).unwrap().is_empty());
+
+        // A duplicate only shows up on insert, after the habit row was written
+        let duplicate = create_habit_with_history(
+            &storage,
+            &AnalyticsEngine::new(),
+            CreateWithHistoryParams { habit: habit_params(), entries: vec![entry(today), entry(today)] },
+            &EntryValidation::default(),
+        );
+        assert!(matches!(duplicate, Err(StorageError::DuplicateEntry { .. })));
+        assert!(storage.list_habits(None, false).unwrap().is_empty());
+    }
+}
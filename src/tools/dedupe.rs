@@ -0,0 +1,79 @@
+/// Tool for repairing duplicate habit entries
+/// 
+/// This module implements the habit_dedupe MCP tool. Databases created
+/// before one entry per day was enforced may contain several entries for
+/// the same day, which inflates completion counts. Habits that allow
+/// several completions per day are left alone.
+
+use serde::Serialize;
+use crate::analytics::AnalyticsEngine;
+use crate::storage::{StorageError, HabitStorage};
+use crate::tools::log::recompute_and_store_streak;
+
+/// Response from removing duplicate entries
+#[derive(Debug, Serialize)]
+pub struct DedupeResponse {
+    pub success: bool,
+    pub removed: u32,
+    pub habits_affected: u32,
+    pub message: String,
+}
+
+/// Remove duplicate entries and recompute streaks for the affected habits
+pub fn dedupe_entries<S: HabitStorage>(storage: &S, analytics: &AnalyticsEngine) -> Result<DedupeResponse, StorageError> {
+    let affected = storage.remove_duplicate_entries()?;
+    
+    for (habit_id, _) in &affected {
+        recompute_and_store_streak(storage, analytics, habit_id)?;
+    }
+    
+    let removed: u32 = affected.iter().map(|(_, count)| count).sum();
+    let habits_affected = affected.len() as u32;
+    
+    let message = if removed == 0 {
+        "✨ No duplicate entries found".to_string()
+    } else {
+        format!("🧹 Removed {} duplicate entr{} across {} habit{} and recalculated their streaks",
+                removed,
+                if removed == 1 { "y" } else { "ies" },
+                habits_affected,
+                if habits_affected == 1 { "" } else { "s" })
+    };
+    
+    Ok(DedupeResponse {
+        success: true,
+        removed,
+        habits_affected,
+        message,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Habit, HabitEntry, Category, Frequency};
+    use crate::storage::sqlite::SqliteStorage;
+    use chrono::Utc;
+
+    #[test]
+    fn test_dedupe_without_duplicates_is_noop() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let habit = Habit::new(
+            "Read".to_string(),
+            None,
+            Category::Personal,
+            Frequency::Daily,
+            None,
+            None,
+        ).unwrap();
+        storage.create_habit(&habit).unwrap();
+        let entry = HabitEntry::new(habit.id.clone(), Utc::now().naive_utc().date(), None, None, None).unwrap();
+        storage.create_entry(&entry).unwrap();
+
+        let response = dedupe_entries(&storage, &AnalyticsEngine::new()).unwrap();
+
+        assert_eq!(response.removed, 0);
+        assert_eq!(response.habits_affected, 0);
+        assert_eq!(storage.get_entries_for_habit(&habit.id, None).unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,210 @@
+/// Tool for spending streak freeze tokens
+/// 
+/// This module implements the habit_use_freeze MCP tool. Tokens are earned
+/// by keeping a streak going (see `log_habit`) and can be spent on a
+/// missed scheduled day, today or within the backfill window, that would
+/// otherwise break the streak.
+
+use serde::{Deserialize, Serialize};
+use crate::analytics::AnalyticsEngine;
+use crate::domain::{parse_date_input, EntryValidation, HabitId};
+use crate::storage::{StorageError, HabitStorage};
+use crate::tools::log::recompute_and_store_streak;
+
+/// Streak length, in days or periods, that earns one freeze token
+pub const STREAK_DAYS_PER_FREEZE: u32 = 7;
+
+/// Freeze tokens earned by a log that grew a streak from `before` to `after`
+///
+/// One token for every multiple of `STREAK_DAYS_PER_FREEZE` the streak
+/// reaches, so a batch that jumps from 5 to 15 earns as many as logging
+/// those days one at a time. Only call this for logs that extend the
+/// streak forward; backdated and repeat-day logs earn nothing.
+pub(crate) fn freeze_tokens_earned(before: u32, after: u32) -> u32 {
+    if after <= before {
+        return 0;
+    }
+    after / STREAK_DAYS_PER_FREEZE - before / STREAK_DAYS_PER_FREEZE
+}
+
+/// Parameters for freezing a day
+#[derive(Debug, Deserialize)]
+pub struct UseFreezeParams {
+    pub habit_id: String,
+    pub date: Option<String>, // Defaults to today
+}
+
+/// Response from freezing a day
+#[derive(Debug, Serialize)]
+pub struct UseFreezeResponse {
+    pub success: bool,
+    pub frozen_on: String,
+    pub freeze_tokens_left: u32,
+    pub current_streak: u32,
+    pub message: String,
+}
+
+/// Spend a freeze token so `date` counts as done for the habit's streak
+/// 
+/// The date may not be in the future or further back than the validation's
+/// backfill window, the same range a completion could be logged for.
+pub fn use_streak_freeze<S: HabitStorage>(
+    storage: &S,
+    analytics: &AnalyticsEngine,
+    params: UseFreezeParams,
+    validation: &EntryValidation,
+) -> Result<UseFreezeResponse, StorageError> {
+    let habit_id = HabitId::from_string(&params.habit_id)
+        .map_err(|_| StorageError::HabitNotFound { habit_id: params.habit_id.clone() })?;
+    let habit = storage.get_habit(&habit_id)?;
+    
+    let today = validation.clock.today();
+    let date = match params.date {
+        Some(date_str) => parse_date_input(&date_str, validation.clock)?,
+        None => today,
+    };
+    
+    if date > today {
+        return Err(StorageError::Validation(format!("Cannot freeze {}, a future date; freeze it once the day has been missed", date)));
+    }
+    if date < validation.earliest_date() {
+        return Err(StorageError::Validation(format!("Cannot freeze days more than {} days in the past", validation.max_backfill_days)));
+    }
+    let created = habit.created_at.date_naive();
+    if date < created {
+        return Err(StorageError::Validation(format!("Cannot freeze {}, before '{}' was created", date, habit.name)));
+    }
+    if !habit.frequency.is_scheduled_for_date_since(date, created) {
+        return Err(StorageError::Validation(format!("'{}' isn't scheduled on {}, so there is nothing to protect", habit.name, date)));
+    }
+    if storage.get_entries_by_date_range(date, date)?.iter().any(|e| e.habit_id == habit_id) {
+        return Err(StorageError::Validation(format!("'{}' is already completed on {}", habit.name, date)));
+    }
+    
+    if storage.get_streak_freezes(&habit_id)?.contains(&date) {
+        return Err(StorageError::Validation(format!("{} is already frozen for '{}'", date, habit.name)));
+    }
+    if habit.freeze_tokens == 0 {
+        return Err(StorageError::Validation(format!(
+            "No freeze tokens left for '{}'. Earn one for every {} days of streak.",
+            habit.name, STREAK_DAYS_PER_FREEZE
+        )));
+    }
+    
+    let tokens_left = storage.spend_freeze_token(&habit_id, date)?;
+    
+    // Recalculate so the stored streak reflects the protected day
+    let streak = recompute_and_store_streak(storage, analytics, &habit_id)?;
+    
+    Ok(UseFreezeResponse {
+        success: true,
+        frozen_on: date.to_string(),
+        freeze_tokens_left: tokens_left,
+        current_streak: streak.current_streak,
+        message: format!("🧊 Froze {} for '{}'. Current streak: {} | Freeze tokens left: {}",
+                         date, habit.name, streak.current_streak, tokens_left),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Habit, HabitEntry, Category, FixedClock, Frequency};
+    use crate::storage::sqlite::SqliteStorage;
+    use chrono::{Duration, NaiveDate, Utc};
+
+    /// Daily habit created a month ago, holding `tokens` freeze tokens
+    fn habit_with_tokens(storage: &SqliteStorage, tokens: u32) -> Habit {
+        let mut habit = Habit::new("Meditate".to_string(), None, Category::Mindfulness, Frequency::Daily, None, None)
+            .unwrap()
+            .with_freeze_tokens(tokens);
+        habit.created_at = Utc::now() - Duration::days(30);
+        storage.create_habit(&habit).unwrap();
+        habit
+    }
+
+    fn days_ago(days: i64) -> NaiveDate {
+        Utc::now().naive_utc().date() - Duration::days(days)
+    }
+
+    fn freeze(storage: &SqliteStorage, habit: &Habit, date: NaiveDate) -> Result<UseFreezeResponse, StorageError> {
+        let params = UseFreezeParams { habit_id: habit.id.to_string(), date: Some(date.to_string()) };
+        use_streak_freeze(storage, &AnalyticsEngine::new(), params, &EntryValidation::default())
+    }
+
+    #[test]
+    fn test_freeze_preserves_streak_and_spends_token() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let habit = habit_with_tokens(&storage, 2);
+        for days in [1, 3, 4] {
+            let entry = HabitEntry::new(habit.id.clone(), days_ago(days), None, None, None).unwrap();
+            storage.create_entry(&entry).unwrap();
+        }
+
+        let response = freeze(&storage, &habit, days_ago(2)).unwrap();
+
+        assert_eq!(response.current_streak, 4);
+        assert_eq!(response.freeze_tokens_left, 1);
+        assert_eq!(storage.get_habit(&habit.id).unwrap().freeze_tokens, 1);
+        assert_eq!(storage.get_streak(&habit.id).unwrap().current_streak, 4);
+        assert_eq!(storage.get_streak_freezes(&habit.id).unwrap(), vec![days_ago(2)]);
+    }
+
+    #[test]
+    fn test_freeze_requires_a_token() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let habit = habit_with_tokens(&storage, 0);
+
+        let err = freeze(&storage, &habit, days_ago(1)).unwrap_err();
+
+        assert!(err.to_string().contains("No freeze tokens left"));
+        assert!(storage.get_streak_freezes(&habit.id).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_freeze_rejects_completed_duplicate_and_early_days() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let habit = habit_with_tokens(&storage, 3);
+        let entry = HabitEntry::new(habit.id.clone(), days_ago(1), None, None, None).unwrap();
+        storage.create_entry(&entry).unwrap();
+
+        assert!(freeze(&storage, &habit, days_ago(1)).is_err());
+        assert!(freeze(&storage, &habit, days_ago(40)).is_err());
+        freeze(&storage, &habit, days_ago(2)).unwrap();
+        assert!(freeze(&storage, &habit, days_ago(2)).is_err());
+        assert_eq!(storage.get_habit(&habit.id).unwrap().freeze_tokens, 2);
+    }
+
+    #[test]
+    fn test_freeze_date_is_bounded_by_today_and_backfill_window() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let mut habit = Habit::new("Meditate".to_string(), None, Category::Mindfulness, Frequency::Daily, None, None)
+            .unwrap()
+            .with_freeze_tokens(3);
+        habit.created_at = Utc::now() - Duration::days(400);
+        storage.create_habit(&habit).unwrap();
+        let clock = FixedClock(days_ago(0));
+        let validation = EntryValidation { clock: &clock, max_backfill_days: 10 };
+        let freeze_on = |date: NaiveDate| use_streak_freeze(&storage, &AnalyticsEngine::new(),
+            UseFreezeParams { habit_id: habit.id.to_string(), date: Some(date.to_string()) }, &validation);
+
+        assert!(freeze_on(days_ago(-1)).unwrap_err().to_string().contains("future date"));
+        assert!(freeze_on(days_ago(11)).unwrap_err().to_string().contains("more than 10 days"));
+        assert_eq!(storage.get_habit(&habit.id).unwrap().freeze_tokens, 3);
+
+        freeze_on(days_ago(10)).unwrap();
+        let today = use_streak_freeze(&storage, &AnalyticsEngine::new(),
+            UseFreezeParams { habit_id: habit.id.to_string(), date: None }, &validation).unwrap();
+        assert_eq!(today.frozen_on, days_ago(0).to_string());
+    }
+
+    #[test]
+    fn test_tokens_earned_per_multiple_reached() {
+        assert_eq!(freeze_tokens_earned(0, 0), 0);
+        assert_eq!(freeze_tokens_earned(6, 7), 1);
+        assert_eq!(freeze_tokens_earned(7, 7), 0);
+        assert_eq!(freeze_tokens_earned(7, 8), 0);
+        assert_eq!(freeze_tokens_earned(5, 15), 2);
+        assert_eq!(freeze_tokens_earned(9, 3), 0);
+    }
+}
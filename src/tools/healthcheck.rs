@@ -0,0 +1,97 @@
+/// Tool for checking the server and database status
+/// 
+/// This module implements the habit_healthcheck MCP tool, a single call
+/// monitoring can use to confirm the server is up and its database usable.
+
+use serde::Serialize;
+use crate::storage::{StorageError, HabitStorage};
+
+/// Response from a health check
+#[derive(Debug, Serialize)]
+pub struct HealthCheckResponse {
+    pub success: bool,
+    pub server_version: String,
+    pub database_path: String,
+    pub schema_version: i32,
+    pub habit_count: u32,
+    pub entry_count: u32,
+    pub writable: bool,
+    /// Entries whose habit no longer exists
+    pub orphan_entry_count: u32,
+    pub message: String,
+}
+
+/// Report the server version and the state of the database at `database_path`
+/// 
+/// Read-only apart from a rolled-back write probe; orphaned entries are
+/// only reported here and removed by `habit_optimize`.
+pub fn health_check<S: HabitStorage>(
+    storage: &S,
+    database_path: &str,
+) -> Result<HealthCheckResponse, StorageError> {
+    let health = storage.health_check()?;
+    let server_version = env!("CARGO_PKG_VERSION").to_string();
+    
+    let mut message = format!(
+        "{} Habit Tracker v{}\n   Database: {} (schema v{}, {})\n   Habits: {} | Entries: {}",
+        if health.writable { "✅" } else { "⚠️" },
+        server_version,
+        database_path,
+        health.schema_version,
+        if health.writable { "writable" } else { "read-only" },
+        health.habit_count,
+        health.entry_count,
+    );
+    if health.orphan_entry_count > 0 {
+        message.push_str(&format!(
+            "\n⚠️ {} entries belong to habits that no longer exist; run habit_optimize with remove_orphans to delete them",
+            health.orphan_entry_count
+        ));
+    }
+    
+    Ok(HealthCheckResponse {
+        success: true,
+        server_version,
+        database_path: database_path.to_string(),
+        schema_version: health.schema_version,
+        habit_count: health.habit_count,
+        entry_count: health.entry_count,
+        writable: health.writable,
+        orphan_entry_count: health.orphan_entry_count,
+        message,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Habit, HabitEntry, Category, Frequency};
+    use crate::storage::sqlite::SqliteStorage;
+    use chrono::Utc;
+
+    #[test]
+    fn test_health_check_counts_seeded_rows() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let today = Utc::now().naive_utc().date();
+        for name in ["Read", "Run", "Stretch"] {
+            let habit = Habit::new(name.to_string(), None, Category::Personal, Frequency::Daily, None, None).unwrap();
+            storage.create_habit(&habit).unwrap();
+            for days_ago in 0..2 {
+                let date = today - chrono::Duration::days(days_ago);
+                storage.create_entry(&HabitEntry::new(habit.id.clone(), date, None, None, None).unwrap()).unwrap();
+            }
+        }
+
+        let response = health_check(&storage, ":memory:").unwrap();
+
+        assert_eq!(response.habit_count, 3);
+        assert_eq!(response.entry_count, 6);
+        assert!(response.writable);
+        assert!(response.schema_version > 0);
+        assert_eq!(response.server_version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(response.database_path, ":memory:");
+        assert_eq!(response.orphan_entry_count, 0);
+        // The write probe is rolled back
+        assert_eq!(storage.health_check().unwrap().habit_count, 3);
+    }
+}
@@ -0,0 +1,218 @@
+/// Tool for reviewing a habit's logged entries
+///
+/// This module implements the habit_history MCP tool, which lists a
+/// habit's completions newest first, optionally within a date range.
+
+use serde::{Deserialize, Serialize};
+use crate::domain::{parse_date_input, Clock, HabitEntry, HabitId};
+use crate::storage::{StorageError, HabitStorage};
+
+/// Number of entries shown when the caller doesn't ask for a specific count
+pub const DEFAULT_HISTORY_LIMIT: u32 = 30;
+
+/// Most entries the history tool will return in one call
+pub const MAX_HISTORY_LIMIT: u32 = 1000;
+
+/// Parameters for fetching a habit's history
+#[derive(Debug, Deserialize)]
+pub struct HistoryParams {
+    pub habit_id: String,
+    pub limit: Option<u32>, // Defaults to 30
+    /// First day to include; defaults to the habit's first entry
+    pub start_date: Option<String>,
+    /// Last day to include; defaults to today
+    pub end_date: Option<String>,
+}
+
+/// One logged completion in a habit's timeline
+#[derive(Debug, Serialize)]
+pub struct HistoryItem {
+    pub entry_id: String,
+    pub completed_at: String,
+    pub value: Option<u32>,
+    pub intensity: Option<u8>,
+    pub notes: Option<String>,
+}
+
+/// Response from fetching a habit's history
+#[derive(Debug, Serialize)]
+pub struct HistoryResponse {
+    pub habit_id: String,
+    pub name: String,
+    pub entries: Vec<HistoryItem>, // Newest first
+    pub message: String,
+}
+
+/// List a habit's entries, newest first
+/// 
+/// Relative dates and an open-ended range are resolved against `clock`.
+pub fn get_habit_history<S: HabitStorage>(
+    storage: &S,
+    params: HistoryParams,
+    clock: &dyn Clock,
+) -> Result<HistoryResponse, StorageError> {
+    let limit = params.limit.unwrap_or(DEFAULT_HISTORY_LIMIT);
+    if limit == 0 || limit > MAX_HISTORY_LIMIT {
+        return Err(StorageError::Validation(format!("Limit must be between 1 and {}", MAX_HISTORY_LIMIT)));
+    }
+
+    let habit_id = HabitId::from_string(&params.habit_id)
+        .map_err(|_| StorageError::HabitNotFound { habit_id: params.habit_id.clone() })?;
+    let habit = storage.get_habit(&habit_id)?;
+
+    let has_range = params.start_date.is_some() || params.end_date.is_some();
+    let mut entries: Vec<HabitEntry> = if has_range {
+        let start = match params.start_date {
+            Some(date_str) => parse_date_input(&date_str, clock)?,
+            None => match storage.get_first_entry_date(&habit_id)? {
+                Some(first) => first,
+                None => habit.created_at.date_naive(),
+            },
+        };
+        let end = match params.end_date {
+            Some(date_str) => parse_date_input(&date_str, clock)?,
+            None => clock.today(),
+        };
+        if start > end {
+            return Err(StorageError::Validation(format!("start_date {} is after end_date {}", start, end)));
+        }
+
+        let mut in_range: Vec<HabitEntry> = storage.get_entries_by_date_range(start, end)?
+            .into_iter()
+            .filter(|e| e.habit_id == habit_id)
+            .collect();
+        in_range.sort_by_key(|e| std::cmp::Reverse((e.completed_at, e.logged_at)));
+        in_range
+    } else {
+        // Already newest first
+        storage.get_entries_for_habit(&habit_id, Some(limit))?
+    };
+    entries.truncate(limit as usize);
+
+    let message = if entries.is_empty() {
+        if has_range {
+            format!("📭 No entries for '{}' in that date range.", habit.name)
+        } else {
+            format!("📭 No entries for '{}' yet. Log your first completion to start the timeline!", habit.name)
+        }
+    } else {
+        let lines = entries.iter().map(format_history_line).collect::<Vec<_>>().join("\n");
+        format!(
+            "📜 History for '{}' ({} entr{}, newest first)\n{}",
+            habit.name,
+            entries.len(),
+            if entries.len() == 1 { "y" } else { "ies" },
+            lines,
+        )
+    };
+
+    Ok(HistoryResponse {
+        habit_id: habit.id.to_string(),
+        name: habit.name,
+        entries: entries.into_iter()
+            .map(|e| HistoryItem {
+                entry_id: e.id.to_string(),
+                completed_at: e.completed_at.to_string(),
+                value: e.value,
+                intensity: e.intensity,
+                notes: e.notes,
+            })
+            .collect(),
+        message,
+    })
+}
+
+/// Format one entry as a timeline line, e.g. "   2024-03-15 · value 30 · intensity 7/10 · felt great"
+fn format_history_line(entry: &HabitEntry) -> String {
+    let mut parts = vec![entry.completed_at.to_string()];
+    if let Some(value) = entry.value {
+        parts.push(format!("value {}", value));
+    }
+    if let Some(intensity) = entry.intensity {
+        parts.push(format!("intensity {}/10", intensity));
+    }
+    if let Some(notes) = entry.notes.as_deref().filter(|n| !n.trim().is_empty()) {
+        parts.push(notes.to_string());
+    }
+    format!("   {}", parts.join(" · "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDate, Utc};
+    use crate::domain::{Category, Frequency, Habit, SystemClock};
+    use crate::storage::sqlite::SqliteStorage;
+
+    fn params(habit: &Habit) -> HistoryParams {
+        HistoryParams { habit_id: habit.id.to_string(), limit: None, start_date: None, end_date: None }
+    }
+
+    fn setup() -> (SqliteStorage, Habit, NaiveDate) {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let habit = Habit::new("Run".to_string(), None, Category::Health, Frequency::Daily, None, None).unwrap();
+        storage.create_habit(&habit).unwrap();
+        let other = Habit::new("Read".to_string(), None, Category::Personal, Frequency::Daily, None, None).unwrap();
+        storage.create_habit(&other).unwrap();
+        let today = Utc::now().naive_utc().date();
+        for (days_ago, value, intensity, notes) in [(5, Some(3), None, None), (1, None, Some(7), Some("Hilly")), (3, Some(5), Some(4), None)] {
+            let date = today - chrono::Duration::days(days_ago);
+            let entry = HabitEntry::new(habit.id.clone(), date, value, intensity, notes.map(str::to_string)).unwrap();
+            storage.create_entry(&entry).unwrap();
+            storage.create_entry(&HabitEntry::new(other.id.clone(), date, None, None, None).unwrap()).unwrap();
+        }
+        (storage, habit, today)
+    }
+
+    #[test]
+    fn test_history_lists_entries_newest_first() {
+        let (storage, habit, today) = setup();
+        let days_ago = |d: i64| (today - chrono::Duration::days(d)).to_string();
+
+        let response = get_habit_history(&storage, params(&habit), &SystemClock).unwrap();
+
+        let dates: Vec<&str> = response.entries.iter().map(|e| e.completed_at.as_str()).collect();
+        assert_eq!(dates, vec![days_ago(1), days_ago(3), days_ago(5)]);
+        assert_eq!(response.entries[0].intensity, Some(7));
+        assert_eq!(response.entries[0].notes.as_deref(), Some("Hilly"));
+        assert_eq!(response.entries[1].value, Some(5));
+        assert!(response.message.contains(&format!("{} · intensity 7/10 · Hilly", days_ago(1))));
+
+        let limited = get_habit_history(&storage, HistoryParams { limit: Some(2), ..params(&habit) }, &SystemClock).unwrap();
+        assert_eq!(limited.entries.len(), 2);
+        assert_eq!(limited.entries[0].completed_at, days_ago(1));
+    }
+
+    #[test]
+    fn test_history_date_range_only_includes_the_habit() {
+        let (storage, habit, today) = setup();
+
+        let response = get_habit_history(&storage, HistoryParams {
+            start_date: Some((today - chrono::Duration::days(4)).to_string()),
+            end_date: Some("yesterday".to_string()),
+            ..params(&habit)
+        }, &SystemClock).unwrap();
+
+        let dates: Vec<String> = response.entries.iter().map(|e| e.completed_at.clone()).collect();
+        assert_eq!(dates, vec![(today - chrono::Duration::days(1)).to_string(), (today - chrono::Duration::days(3)).to_string()]);
+
+        let backwards = get_habit_history(&storage, HistoryParams {
+            start_date: Some("today".to_string()),
+            end_date: Some("yesterday".to_string()),
+            ..params(&habit)
+        }, &SystemClock);
+        assert!(matches!(backwards, Err(StorageError::Validation(_))));
+    }
+
+    #[test]
+    fn test_history_of_habit_without_entries() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let habit = Habit::new("Swim".to_string(), None, Category::Health, Frequency::Daily, None, None).unwrap();
+        storage.create_habit(&habit).unwrap();
+
+        let response = get_habit_history(&storage, params(&habit), &SystemClock).unwrap();
+
+        assert!(response.entries.is_empty());
+        assert!(response.message.contains("No entries for 'Swim' yet"));
+    }
+}
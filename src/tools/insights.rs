@@ -10,9 +10,8 @@ use crate::storage::{StorageError, HabitStorage};
 /// Analyze habits and generate insights
 pub fn get_habit_insights<S: HabitStorage>(
     storage: &S,
+    analytics: &AnalyticsEngine,
     params: InsightsParams,
 ) -> Result<InsightsResponse, StorageError> {
-    let analytics = AnalyticsEngine::new();
     analytics.get_habit_insights(storage, params)
 }
-
@@ -3,17 +3,33 @@
 /// This module implements the habit_list MCP tool.
 
 use serde::{Deserialize, Serialize};
-use crate::domain::{Category, Frequency};
+use crate::domain::{Category, CategoryRegistry, Clock, Frequency, HabitId, Streak, StreakOptions, streak_unit_label, weekday_full_name};
 use crate::storage::{StorageError, HabitStorage};
-use crate::analytics::AnalyticsEngine;
-use chrono::Weekday;
+use chrono::{Days, Weekday};
+
+/// Default window (in days) used to count recent completions
+pub const DEFAULT_RECENT_DAYS: u32 = 30;
+
+/// Longest window (in days) recent completions can be counted over
+pub const MAX_RECENT_DAYS: u32 = 3650;
 
 /// Parameters for listing habits
 #[derive(Debug, Deserialize)]
 pub struct ListHabitsParams {
     pub category: Option<String>,
     pub active_only: Option<bool>,
-    pub sort_by: Option<String>, // "name", "streak", "created_at", "completion_rate"
+    pub sort_by: Option<String>, // "name", "streak", "created_at", "completion_rate", "consistency", "position"
+    pub recent_days: Option<u32>, // Window for recent_completions, defaults to 30
+    pub abbreviate_days: Option<bool>, // "Mon, Wed" instead of "Monday, Wednesday" for custom days
+    /// Only include habits whose current streak is at least this long
+    pub min_streak: Option<u32>,
+    /// Only include habits whose current streak is at most this long
+    pub max_streak: Option<u32>,
+    /// Only include habits completed at least this often (0.0 - 1.0)
+    pub min_completion_rate: Option<f64>,
+    /// Only include habits completed at most this often (0.0 - 1.0)
+    pub max_completion_rate: Option<f64>,
+    pub group_by: Option<String>, // "category" or "none" (default)
 }
 
 /// Information about a habit in the list
@@ -21,12 +37,24 @@ pub struct ListHabitsParams {
 pub struct HabitSummary {
     pub habit_id: String,
     pub name: String,
+    pub description: Option<String>,
     pub category: String,
     pub frequency: String,
     pub current_streak: u32,
+    /// What the streak counts: "days", "weeks" or "periods"
+    pub streak_unit: &'static str,
     pub completion_rate: f64,
+    /// 0-100 blend of streak, completion rate and recency (see `Streak::consistency_score`)
+    pub consistency_score: u8,
     pub total_completions: u32,
+    pub recent_completions: u32, // Completions within the recent window
+    /// Active and scheduled for today
+    pub due_today: bool,
+    /// At least one entry is logged for today
+    pub done_today: bool,
     pub is_active: bool,
+    pub created_at: String, // RFC 3339 timestamp
+    pub position: Option<i32>, // User-defined order, if the habit has been placed
 }
 
 /// Summary statistics for all habits
@@ -45,82 +73,128 @@ pub struct ListHabitsResponse {
 }
 
 /// List habits using the provided storage
+/// 
+/// The streak options decide which day custom weekly schedules are listed from,
+/// the clock decides which day is due and what counts as recent, and the
+/// registered categories decide which category filters are recognized.
 pub fn list_habits<S: HabitStorage>(
     storage: &S,
     params: ListHabitsParams,
+    options: &StreakOptions,
+    clock: &dyn Clock,
+    categories: &CategoryRegistry,
 ) -> Result<ListHabitsResponse, StorageError> {
     // Parse category filter
-    let category_filter = params.category.and_then(|cat_str| {
-        match cat_str.as_str() {
-            "health" => Some(Category::Health),
-            "productivity" => Some(Category::Productivity),
-            "social" => Some(Category::Social),
-            "creative" => Some(Category::Creative),
-            "mindfulness" => Some(Category::Mindfulness),
-            "financial" => Some(Category::Financial),
-            "household" => Some(Category::Household),
-            "personal" => Some(Category::Personal),
-            _ => None,
+    let category_filter = params.category
+        .and_then(|cat_str| Category::parse_user_input(&cat_str, categories).ok());
+    
+    if let (Some(min), Some(max)) = (params.min_streak, params.max_streak) {
+        if min > max {
+            return Err(StorageError::Validation(format!("min_streak {} is greater than max_streak {}", min, max)));
         }
-    });
+    }
+    for rate in [params.min_completion_rate, params.max_completion_rate].into_iter().flatten() {
+        if !(0.0..=1.0).contains(&rate) {
+            return Err(StorageError::Validation(format!("Completion rate filters must be between 0 and 1, got {}", rate)));
+        }
+    }
+    if let (Some(min), Some(max)) = (params.min_completion_rate, params.max_completion_rate) {
+        if min > max {
+            return Err(StorageError::Validation(format!(
+                "min_completion_rate {} is greater than max_completion_rate {}", min, max)));
+        }
+    }
+    
+    let group_by_category = match params.group_by.as_deref() {
+        None | Some("none") => false,
+        Some("category") => true,
+        Some(other) => return Err(StorageError::Validation(format!("Invalid group_by '{}'. Valid options: category, none", other))),
+    };
     
     let active_only = params.active_only.unwrap_or(true);
+    let abbreviate_days = params.abbreviate_days.unwrap_or(false);
     
     // Get habits from storage
     let habits = storage.list_habits(category_filter, active_only)?;
 
-    let analytics = AnalyticsEngine::new();
+    // Entries completed on or after this date count as recent (window includes today)
+    let recent_days = params.recent_days.unwrap_or(DEFAULT_RECENT_DAYS).max(1);
+    let today = clock.today();
+    let recent_cutoff = today.checked_sub_days(Days::new(u64::from(recent_days) - 1))
+        .ok_or_else(|| StorageError::Validation(format!("recent_days {} reaches before the earliest supported date", recent_days)))?;
+
+    // Load streak data for every listed habit in one query
+    let habit_ids: Vec<HabitId> = habits.iter().map(|h| h.id.clone()).collect();
+    let mut streaks = storage.get_streaks_for_habits(&habit_ids)?;
 
     // Convert to response format with actual data
     let mut habit_summaries: Vec<HabitSummary> = Vec::new();
 
     for habit in habits {
-        // Get streak data for this habit
-        let streak = match storage.get_streak(&habit.id) {
-            Ok(streak) => streak,
-            Err(_) => {
-                // If no streak data exists, get entries and calculate
-                let entries = storage.get_entries_for_habit(&habit.id, None)?;
-                analytics.calculate_habit_streak(&habit, &entries)
-            }
-        };
+        let entries = storage.get_entries_for_habit(&habit.id, None)?;
+        let streak = streaks.remove(&habit.id).unwrap_or_else(|| Streak::new(habit.id.clone()));
+
+        let recent_completions = entries.iter()
+            .filter(|e| e.completed_at >= recent_cutoff)
+            .count() as u32;
+        let due_today = habit.is_active
+            && habit.frequency.is_scheduled_for_date_since(today, habit.created_at.date_naive());
+        let done_today = entries.iter().any(|e| e.completed_at == today);
 
         let habit_summary = HabitSummary {
             habit_id: habit.id.to_string(),
             name: habit.name,
-            category: match habit.category {
-                Category::Health => "health".to_string(),
-                Category::Productivity => "productivity".to_string(),
-                Category::Social => "social".to_string(),
-                Category::Creative => "creative".to_string(),
-                Category::Mindfulness => "mindfulness".to_string(),
-                Category::Financial => "financial".to_string(),
-                Category::Household => "household".to_string(),
-                Category::Personal => "personal".to_string(),
-                Category::Custom(name) => name,
-            },
-            frequency: frequency_to_display_string(&habit.frequency),
+            description: habit.description,
+            category: category_label(&habit.category),
+            frequency: frequency_to_display_string(&habit.frequency, options.week_start, abbreviate_days),
             current_streak: streak.current_streak,
+            streak_unit: streak_unit_label(&habit.frequency),
             completion_rate: streak.completion_rate,
+            consistency_score: streak.consistency_score_with_clock(&habit.frequency, clock),
             total_completions: streak.total_completions,
+            recent_completions,
+            due_today,
+            done_today,
             is_active: habit.is_active,
+            created_at: habit.created_at.to_rfc3339(),
+            position: habit.position,
         };
 
         habit_summaries.push(habit_summary);
     }
 
+    // Streak and rate filters apply on top of the category/active filters
+    habit_summaries.retain(|h| {
+        params.min_streak.is_none_or(|min| h.current_streak >= min)
+            && params.max_streak.is_none_or(|max| h.current_streak <= max)
+            && params.min_completion_rate.is_none_or(|min| h.completion_rate >= min)
+            && params.max_completion_rate.is_none_or(|max| h.completion_rate <= max)
+    });
+
     // Sort by requested criteria
     let sort_by = params.sort_by.as_deref().unwrap_or("name");
     habit_summaries.sort_by(|a, b| {
         match sort_by {
             "streak" => b.current_streak.cmp(&a.current_streak),
             "completion_rate" => b.completion_rate.partial_cmp(&a.completion_rate).unwrap_or(std::cmp::Ordering::Equal),
+            "consistency" => b.consistency_score.cmp(&a.consistency_score),
             "total_completions" => b.total_completions.cmp(&a.total_completions),
-            "created_at" => a.name.cmp(&b.name), // Fallback to name since we don't have created_at in summary
+            "created_at" => b.created_at.cmp(&a.created_at), // Newest first
+            "position" => match (a.position, b.position) {
+                (Some(pa), Some(pb)) => pa.cmp(&pb),
+                (Some(_), None) => std::cmp::Ordering::Less, // Unplaced habits go last
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (None, None) => a.name.cmp(&b.name),
+            },
             _ => a.name.cmp(&b.name), // Default to name sorting
         }
     });
     
+    // A stable sort keeps the requested order within each category
+    if group_by_category {
+        habit_summaries.sort_by(|a, b| a.category.cmp(&b.category));
+    }
+    
     let total_habits = habit_summaries.len() as u32;
     let active_habits = habit_summaries.iter()
         .filter(|h| h.is_active)
@@ -143,8 +217,34 @@ pub fn list_habits<S: HabitStorage>(
     })
 }
 
+/// Split habits sorted by category into runs sharing a category, in order
+pub(crate) fn group_by_category(habits: &[HabitSummary]) -> Vec<(&str, Vec<&HabitSummary>)> {
+    let mut groups: Vec<(&str, Vec<&HabitSummary>)> = Vec::new();
+    for habit in habits {
+        match groups.last_mut() {
+            Some((category, members)) if *category == habit.category => members.push(habit),
+            _ => groups.push((&habit.category, vec![habit])),
+        }
+    }
+    groups
+}
+
+/// Name a category the way tool responses report it
+///
+/// Built-ins use their lowercase name and custom categories their bare
+/// name, without the `custom:` prefix used in storage.
+pub(crate) fn category_label(category: &Category) -> String {
+    match category {
+        Category::Custom(name) => name.clone(),
+        builtin => builtin.to_storage_string(),
+    }
+}
+
 /// Convert frequency to a human-readable display string
-fn frequency_to_display_string(frequency: &Frequency) -> String {
+///
+/// Custom days are listed in week order starting from `week_start`, using
+/// full or abbreviated names. A single custom day reads as e.g. "Mondays".
+pub(crate) fn frequency_to_display_string(frequency: &Frequency, week_start: Weekday, abbreviate_days: bool) -> String {
     match frequency {
         Frequency::Daily => "Daily".to_string(),
         Frequency::Weekly(times) => {
@@ -157,21 +257,223 @@ fn frequency_to_display_string(frequency: &Frequency) -> String {
         Frequency::Weekdays => "Weekdays".to_string(),
         Frequency::Weekends => "Weekends".to_string(),
         Frequency::Custom(days) => {
-            let day_names: Vec<String> = days.iter().map(|day| {
-                match day {
-                    Weekday::Mon => "Mon",
-                    Weekday::Tue => "Tue",
-                    Weekday::Wed => "Wed",
-                    Weekday::Thu => "Thu",
-                    Weekday::Fri => "Fri",
-                    Weekday::Sat => "Sat",
-                    Weekday::Sun => "Sun",
-                }.to_string()
-            }).collect();
-            day_names.join(", ")
+            let mut days = days.clone();
+            days.sort_by_key(|day| (day.num_days_from_monday() + 7 - week_start.num_days_from_monday()) % 7);
+            days.dedup();
+
+            if let [day] = days.as_slice() {
+                return format!("{}s", weekday_full_name(*day));
+            }
+
+            days.iter()
+                .map(|day| if abbreviate_days { day.to_string() } else { weekday_full_name(*day).to_string() })
+                .collect::<Vec<_>>()
+                .join(", ")
         }
         Frequency::Interval(days) => {
             format!("Every {} day{}", days, if *days == 1 { "" } else { "s" })
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Habit, HabitEntry, SystemClock};
+    use chrono::{Datelike, Utc};
+    use crate::storage::sqlite::SqliteStorage;
+
+    fn default_params() -> ListHabitsParams {
+        ListHabitsParams {
+            category: None,
+            active_only: None,
+            sort_by: None,
+            recent_days: None,
+            abbreviate_days: None,
+            min_streak: None,
+            max_streak: None,
+            min_completion_rate: None,
+            max_completion_rate: None,
+            group_by: None,
+        }
+    }
+
+    #[test]
+    fn test_group_by_category_keeps_sort_within_groups_and_data_flat() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        for (name, category) in [("Run", Category::Health), ("Budget", Category::Financial), ("Floss", Category::Health)] {
+            let habit = Habit::new(name.to_string(), None, category, Frequency::Daily, None, None).unwrap();
+            storage.create_habit(&habit).unwrap();
+        }
+
+        let response = list_habits(&storage, ListHabitsParams { group_by: Some("category".to_string()), ..default_params() }, &StreakOptions::default(), &SystemClock, &CategoryRegistry::default()).unwrap();
+
+        let listed: Vec<(&str, &str)> = response.habits.iter().map(|h| (h.category.as_str(), h.name.as_str())).collect();
+        assert_eq!(listed, vec![("financial", "Budget"), ("health", "Floss"), ("health", "Run")]);
+        let groups: Vec<(&str, usize)> = group_by_category(&response.habits).into_iter().map(|(c, m)| (c, m.len())).collect();
+        assert_eq!(groups, vec![("financial", 1), ("health", 2)]);
+
+        let invalid = list_habits(&storage, ListHabitsParams { group_by: Some("frequency".to_string()), ..default_params() }, &StreakOptions::default(), &SystemClock, &CategoryRegistry::default());
+        assert!(matches!(invalid, Err(StorageError::Validation(_))));
+    }
+
+    #[test]
+    fn test_recent_completions_only_counts_window() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let habit = Habit::new(
+            "Read".to_string(),
+            None,
+            Category::Personal,
+            Frequency::Daily,
+            None,
+            None,
+        ).unwrap();
+        storage.create_habit(&habit).unwrap();
+
+        let today = Utc::now().naive_utc().date();
+        for days_ago in [0, 10, 29, 30, 45] {
+            let entry = HabitEntry::new(
+                habit.id.clone(),
+                today - chrono::Duration::days(days_ago),
+                None,
+                None,
+                None,
+            ).unwrap();
+            storage.create_entry(&entry).unwrap();
+        }
+
+        let response = list_habits(&storage, default_params(), &StreakOptions::default(), &SystemClock, &CategoryRegistry::default()).unwrap();
+        assert_eq!(response.habits[0].recent_completions, 3);
+
+        let response = list_habits(&storage, ListHabitsParams {
+            recent_days: Some(7),
+            ..default_params()
+        }, &StreakOptions::default(), &SystemClock, &CategoryRegistry::default()).unwrap();
+        assert_eq!(response.habits[0].recent_completions, 1);
+    }
+
+    #[test]
+    fn test_summary_carries_description_and_created_at() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let habit = Habit::new(
+            "Stretch".to_string(),
+            Some("Ten minutes after waking up".to_string()),
+            Category::Health,
+            Frequency::Daily,
+            None,
+            None,
+        ).unwrap();
+        storage.create_habit(&habit).unwrap();
+
+        let response = list_habits(&storage, default_params(), &StreakOptions::default(), &SystemClock, &CategoryRegistry::default()).unwrap();
+        let summary = &response.habits[0];
+
+        assert_eq!(summary.description.as_deref(), Some("Ten minutes after waking up"));
+        assert_eq!(summary.created_at, habit.created_at.to_rfc3339());
+    }
+
+    #[test]
+    fn test_sort_by_consistency() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let today = Utc::now().naive_utc().date();
+        for (name, current, rate, days_since) in [("Lapsed", 0, 0.9, 20), ("Steady", 12, 0.8, 0), ("Patchy", 2, 0.3, 1)] {
+            let habit = Habit::new(name.to_string(), None, Category::Personal, Frequency::Daily, None, None).unwrap();
+            storage.create_habit(&habit).unwrap();
+            let last = Some(today - chrono::Duration::days(days_since));
+            storage.update_streak(&Streak::from_existing(habit.id.clone(), current, current, last, 10, rate)).unwrap();
+        }
+
+        let response = list_habits(&storage, ListHabitsParams {
+            sort_by: Some("consistency".to_string()),
+            ..default_params()
+        }, &StreakOptions::default(), &SystemClock, &CategoryRegistry::default()).unwrap();
+
+        let ranked: Vec<(&str, u8)> = response.habits.iter().map(|h| (h.name.as_str(), h.consistency_score)).collect();
+        assert_eq!(ranked, vec![("Steady", 72), ("Lapsed", 45), ("Patchy", 37)]);
+    }
+
+    #[test]
+    fn test_streak_and_rate_filters() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let today = Utc::now().naive_utc().date();
+        for (name, current, rate) in [("Thriving", 30, 0.95), ("Steady", 10, 0.7), ("Struggling", 1, 0.2), ("Stalled", 0, 0.05)] {
+            let habit = Habit::new(name.to_string(), None, Category::Personal, Frequency::Daily, None, None).unwrap();
+            storage.create_habit(&habit).unwrap();
+            storage.update_streak(&Streak::from_existing(habit.id.clone(), current, current, Some(today), 10, rate)).unwrap();
+        }
+        let paused = Habit::new("Paused".to_string(), None, Category::Personal, Frequency::Daily, None, None).unwrap();
+        storage.create_habit(&paused).unwrap();
+        storage.update_streak(&Streak::from_existing(paused.id.clone(), 40, 40, Some(today), 10, 1.0)).unwrap();
+        storage.update_habit(&Habit { is_active: false, ..storage.get_habit(&paused.id).unwrap() }).unwrap();
+
+        let names = |params: ListHabitsParams| -> Vec<String> {
+            list_habits(&storage, ListHabitsParams { sort_by: Some("streak".to_string()), ..params }, &StreakOptions::default(), &SystemClock, &CategoryRegistry::default())
+                .unwrap().habits.into_iter().map(|h| h.name).collect()
+        };
+
+        assert_eq!(names(ListHabitsParams { min_streak: Some(10), ..default_params() }), vec!["Thriving", "Steady"]);
+        assert_eq!(names(ListHabitsParams { max_streak: Some(1), ..default_params() }), vec!["Struggling", "Stalled"]);
+        assert_eq!(names(ListHabitsParams { min_completion_rate: Some(0.7), ..default_params() }), vec!["Thriving", "Steady"]);
+        assert_eq!(names(ListHabitsParams { max_completion_rate: Some(0.5), ..default_params() }), vec!["Struggling", "Stalled"]);
+        assert_eq!(names(ListHabitsParams {
+            min_streak: Some(1),
+            max_completion_rate: Some(0.8),
+            ..default_params()
+        }), vec!["Steady", "Struggling"]);
+        // Filters combine with the active filter
+        assert_eq!(names(ListHabitsParams { min_streak: Some(20), active_only: Some(false), ..default_params() }), vec!["Paused", "Thriving"]);
+
+        let backwards = list_habits(&storage, ListHabitsParams { min_streak: Some(5), max_streak: Some(2), ..default_params() }, &StreakOptions::default(), &SystemClock, &CategoryRegistry::default());
+        assert!(matches!(backwards, Err(StorageError::Validation(_))));
+        let out_of_range = list_habits(&storage, ListHabitsParams { min_completion_rate: Some(1.5), ..default_params() }, &StreakOptions::default(), &SystemClock, &CategoryRegistry::default());
+        assert!(matches!(out_of_range, Err(StorageError::Validation(_))));
+    }
+
+    #[test]
+    fn test_recent_days_beyond_the_calendar_is_rejected() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let huge = list_habits(&storage, ListHabitsParams { recent_days: Some(u32::MAX), ..default_params() }, &StreakOptions::default(), &SystemClock, &CategoryRegistry::default());
+        assert!(matches!(huge, Err(StorageError::Validation(ref msg)) if msg.contains("recent_days")));
+    }
+
+    #[test]
+    fn test_due_and_done_today() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let today = Utc::now().naive_utc().date();
+        let create = |name: &str, frequency: Frequency| {
+            let habit = Habit::new(name.to_string(), None, Category::Personal, frequency, None, None).unwrap();
+            storage.create_habit(&habit).unwrap();
+            habit
+        };
+        let done = create("Done", Frequency::Daily);
+        create("Pending", Frequency::Daily);
+        // Scheduled only on the weekday opposite today's kind of day
+        let is_weekend = matches!(today.weekday(), Weekday::Sat | Weekday::Sun);
+        create("Off day", if is_weekend { Frequency::Weekdays } else { Frequency::Weekends });
+        storage.create_entry(&HabitEntry::new(done.id.clone(), today, None, None, None).unwrap()).unwrap();
+
+        let response = list_habits(&storage, default_params(), &StreakOptions::default(), &SystemClock, &CategoryRegistry::default()).unwrap();
+        let flags: Vec<(&str, bool, bool)> = response.habits.iter()
+            .map(|h| (h.name.as_str(), h.due_today, h.done_today))
+            .collect();
+
+        assert_eq!(flags, vec![("Done", true, true), ("Off day", false, false), ("Pending", true, false)]);
+    }
+
+    #[test]
+    fn test_custom_days_follow_week_start() {
+        let days = Frequency::Custom(vec![Weekday::Fri, Weekday::Sun, Weekday::Mon, Weekday::Wed]);
+
+        assert_eq!(frequency_to_display_string(&days, Weekday::Mon, false), "Monday, Wednesday, Friday, Sunday");
+        assert_eq!(frequency_to_display_string(&days, Weekday::Sun, false), "Sunday, Monday, Wednesday, Friday");
+        assert_eq!(frequency_to_display_string(&days, Weekday::Sun, true), "Sun, Mon, Wed, Fri");
+    }
+
+    #[test]
+    fn test_single_custom_day_reads_naturally() {
+        let mondays = Frequency::Custom(vec![Weekday::Mon]);
+
+        assert_eq!(frequency_to_display_string(&mondays, Weekday::Mon, false), "Mondays");
+        assert_eq!(frequency_to_display_string(&mondays, Weekday::Sun, true), "Mondays");
+    }
+}
@@ -3,18 +3,22 @@
 /// This module implements the habit_log MCP tool.
 
 use serde::{Deserialize, Serialize};
-use chrono::{NaiveDate, Utc};
-use crate::domain::{HabitEntry, HabitId, Streak};
+use chrono::NaiveDate;
+use crate::analytics::AnalyticsEngine;
+use crate::domain::{format_streak_length, parse_date_input, streak_unit_label, Clock, EntryValidation, Habit, HabitEntry, HabitId, Streak, MAX_ENTRY_VALUE, MAX_INTENSITY, MIN_INTENSITY};
 use crate::storage::{StorageError, HabitStorage};
+use crate::tools::freeze::freeze_tokens_earned;
 
 /// Parameters for logging a habit completion
 #[derive(Debug, Deserialize)]
 pub struct LogHabitParams {
     pub habit_id: String,
-    pub completed_at: Option<String>, // Optional date, defaults to today
+    pub completed_at: Option<String>, // Optional date ("yesterday", YYYY-MM-DD, ...), defaults to today
     pub value: Option<u32>,
     pub intensity: Option<u8>,
     pub notes: Option<String>,
+    /// How the user felt, e.g. "energized"; stored trimmed and lowercased
+    pub mood: Option<String>,
 }
 
 /// Response from logging a habit
@@ -23,132 +27,579 @@ pub struct LogHabitResponse {
     pub success: bool,
     pub message: String,
     pub current_streak: Option<u32>,
+    /// True when the habit was already logged for that day and nothing was stored
+    pub already_logged: bool,
+    /// The entry as stored, None when nothing was stored
+    pub entry: Option<LoggedEntry>,
 }
 
-/// Calculate streak information for a habit based on its entries
-/// This is a simplified calculation that checks consecutive days
-fn calculate_habit_streak<S: HabitStorage>(
+/// A stored completion, echoed back so the caller can see what was saved
+#[derive(Debug, Serialize)]
+pub struct LoggedEntry {
+    pub entry_id: String,
+    /// The day the completion counts for, after resolving inputs like "yesterday"
+    pub completed_at: NaiveDate,
+    pub value: Option<u32>,
+    pub intensity: Option<u8>,
+    pub notes: Option<String>,
+    pub mood: Option<String>,
+}
+
+impl From<&HabitEntry> for LoggedEntry {
+    fn from(entry: &HabitEntry) -> Self {
+        Self {
+            entry_id: entry.id.to_string(),
+            completed_at: entry.completed_at,
+            value: entry.value,
+            intensity: entry.intensity,
+            notes: entry.notes.clone(),
+            mood: entry.mood.clone(),
+        }
+    }
+}
+
+/// Recalculate a habit's streak from its full entry history and store it
+///
+/// Every operation that adds or removes entries ends with this, so the
+/// cached streak, total and completion rate never drift from the entries.
+pub fn recompute_and_store_streak<S: HabitStorage>(
     storage: &S,
+    analytics: &AnalyticsEngine,
     habit_id: &HabitId,
-    latest_entry_date: NaiveDate,
 ) -> Result<Streak, StorageError> {
-    // Get existing streak data
-    let mut streak = storage.get_streak(habit_id)?;
-    
-    // For now, implement a simple streak calculation
-    // In a real implementation, we'd get all entries and calculate properly
-    
-    // Update last completed date
-    streak.last_completed = Some(latest_entry_date);
+    let habit = storage.get_habit(habit_id)?;
+    let entries = storage.get_entries_for_habit(habit_id, None)?;
+    let frozen_days = storage.get_streak_freezes(habit_id)?;
+    let vacations = storage.get_vacations(habit_id)?;
     
-    // Simple logic: if we have a recent completion, increment streak
-    if streak.current_streak == 0 {
-        // Starting a new streak
-        streak.current_streak = 1;
-    } else {
-        // Check if the last completion was yesterday (consecutive days)
-        // This is simplified - in reality we'd check all recent entries
-        streak.current_streak += 1;
+    let streak = analytics.calculate_habit_streak_with_vacations(&habit, &entries, &frozen_days, &vacations);
+    storage.update_streak(&streak)?;
+    Ok(streak)
+}
+
+/// A habit's newest entry date and cached streak, read before new entries are stored
+pub(crate) struct StreakSnapshot {
+    latest: Option<NaiveDate>,
+    current_streak: u32,
+}
+
+impl StreakSnapshot {
+    pub(crate) fn read<S: HabitStorage>(storage: &S, habit_id: &HabitId) -> Result<Self, StorageError> {
+        Ok(Self {
+            latest: storage.get_latest_entry_date(habit_id)?,
+            current_streak: storage.get_streak(habit_id)?.current_streak,
+        })
     }
+}
+
+/// Recalculate a habit's streak after logging and award the freeze tokens it earned
+///
+/// Shared by `habit_log` and `habit_log_many` so both earn tokens the same
+/// way. An entry no newer than the snapshot's latest one (backdated, or
+/// another entry on the newest day) doesn't extend the streak and so never
+/// earns a freeze. Returns the new streak and the number of tokens earned.
+pub(crate) fn recompute_and_award_freezes<S: HabitStorage>(
+    storage: &S,
+    analytics: &AnalyticsEngine,
+    habit_id: &HabitId,
+    before: &StreakSnapshot,
+    newest_logged: NaiveDate,
+) -> Result<(Streak, u32), StorageError> {
+    let streak = recompute_and_store_streak(storage, analytics, habit_id)?;
     
-    // Update longest streak if current is longer
-    if streak.current_streak > streak.longest_streak {
-        streak.longest_streak = streak.current_streak;
+    let extends_streak = before.latest.is_none_or(|latest| newest_logged > latest);
+    let earned = if extends_streak {
+        freeze_tokens_earned(before.current_streak, streak.current_streak)
+    } else {
+        0
+    };
+    if earned > 0 {
+        storage.add_freeze_tokens(habit_id, earned)?;
     }
-    
-    // Increment total completions
-    streak.total_completions += 1;
-    
-    // Simple completion rate calculation (needs proper implementation)
-    // For now, just use a placeholder
-    streak.completion_rate = if streak.total_completions > 0 { 0.8 } else { 0.0 };
-    
-    Ok(streak)
+    Ok((streak, earned))
 }
 
-/// Log a habit completion using the provided storage
-pub fn log_habit<S: HabitStorage>(
+/// Check logging parameters and build the entry they describe
+///
+/// Shared by `habit_log` and `habit_log_many`; nothing is stored here.
+pub(crate) fn build_entry<S: HabitStorage>(
     storage: &S,
     params: LogHabitParams,
-) -> Result<LogHabitResponse, StorageError> {
+    validation: &EntryValidation,
+) -> Result<(Habit, HabitEntry), StorageError> {
     // Validate habit ID format
     if params.habit_id.trim().is_empty() {
-        return Err(StorageError::Query(
-            rusqlite::Error::InvalidColumnType(0, "Habit ID cannot be empty".to_string(), rusqlite::types::Type::Text)
-        ));
+        return Err(StorageError::Validation("Habit ID cannot be empty".to_string()));
     }
     
     // Parse habit ID
     let habit_id = HabitId::from_string(&params.habit_id)
-        .map_err(|_| StorageError::Query(
-            rusqlite::Error::InvalidColumnType(0, "Invalid habit ID format".to_string(), rusqlite::types::Type::Text)
-        ))?;
+        .map_err(|_| StorageError::Validation("Invalid habit ID format".to_string()))?;
     
     // Verify habit exists
-    if storage.get_habit(&habit_id).is_err() {
-        return Err(StorageError::HabitNotFound { habit_id: params.habit_id.clone() });
-    }
+    let habit = storage.get_habit(&habit_id)
+        .map_err(|_| StorageError::HabitNotFound { habit_id: params.habit_id.clone() })?;
     
-    // Parse completed date (default to today)
-    let completed_at = if let Some(date_str) = params.completed_at {
-        NaiveDate::parse_from_str(&date_str, "%Y-%m-%d")
-            .map_err(|_| StorageError::Query(
-                rusqlite::Error::InvalidColumnType(0, "Invalid date format".to_string(), rusqlite::types::Type::Text)
-            ))?
-    } else {
-        Utc::now().naive_utc().date()
+    // Parse completed date (default to today, or yesterday before the rollover hour)
+    let completed_at = match params.completed_at {
+        Some(date_str) => parse_date_input(&date_str, validation.clock)?,
+        None => validation.clock.today(),
     };
     
     // Validate optional parameters
     if let Some(intensity) = params.intensity {
-        if !(1..=10).contains(&intensity) {
-            return Err(StorageError::Query(
-                rusqlite::Error::InvalidColumnType(0, "Intensity must be between 1 and 10".to_string(), rusqlite::types::Type::Integer)
-            ));
+        if !(MIN_INTENSITY..=MAX_INTENSITY).contains(&intensity) {
+            return Err(StorageError::Validation(format!("Intensity must be between {} and {}", MIN_INTENSITY, MAX_INTENSITY)));
         }
     }
     
     if let Some(value) = params.value {
-        if value > 999999 {
-            return Err(StorageError::Query(
-                rusqlite::Error::InvalidColumnType(0, "Value too large (max 999,999)".to_string(), rusqlite::types::Type::Integer)
-            ));
-        }
-    }
-    
-    if let Some(ref notes) = params.notes {
-        if notes.len() > 500 {
-            return Err(StorageError::Query(
-                rusqlite::Error::InvalidColumnType(0, "Notes too long (max 500 characters)".to_string(), rusqlite::types::Type::Text)
-            ));
+        if value > MAX_ENTRY_VALUE {
+            return Err(StorageError::Validation(format!("Value cannot exceed {}", MAX_ENTRY_VALUE)));
         }
     }
     
     // Create the habit entry
-    let entry = HabitEntry::new(
-        habit_id.clone(),
+    let mut entry = HabitEntry::new_with_validation(
+        habit_id,
         completed_at,
         params.value,
         params.intensity,
         params.notes,
-    ).map_err(|e| StorageError::Query(
-        rusqlite::Error::InvalidColumnType(0, e.to_string(), rusqlite::types::Type::Text)
-    ))?;
+        validation,
+    )?;
+    entry.set_mood(params.mood)?;
+    
+    Ok((habit, entry))
+}
+
+/// Log a habit completion using the provided storage
+/// 
+/// The validation rules decide how far back the completion may be dated.
+pub fn log_habit<S: HabitStorage>(
+    storage: &S,
+    analytics: &AnalyticsEngine,
+    params: LogHabitParams,
+    validation: &EntryValidation,
+) -> Result<LogHabitResponse, StorageError> {
+    let (habit, entry) = build_entry(storage, params, validation)?;
+    let habit_id = habit.id.clone();
+    let completed_at = entry.completed_at;
     
-    // Save to storage
-    storage.create_entry(&entry)?;
+    let before = StreakSnapshot::read(storage, &habit_id)?;
+    let backdated = before.latest.is_some_and(|latest| completed_at < latest);
     
-    // Calculate and update streak information
-    let updated_streak = calculate_habit_streak(storage, &habit_id, completed_at)?;
+    // Save to storage; a repeat log is answered with the unchanged streak
+    match storage.create_entry(&entry) {
+        Err(StorageError::DuplicateEntry { .. }) => {
+            return already_logged_response(storage, &habit, completed_at, validation.clock);
+        }
+        result => result?,
+    }
     
-    // Update streak in storage
-    storage.update_streak(&updated_streak)?;
+    let (updated_streak, freezes_earned) = recompute_and_award_freezes(storage, analytics, &habit_id, &before, completed_at)?;
+    
+    let day_count_line = if habit.allow_multiple_per_day {
+        let count = storage.get_entries_for_habit(&habit_id, None)?
+            .iter()
+            .filter(|e| e.completed_at == completed_at)
+            .count();
+        format!("\n🔢 Completions on {}: {}", completed_at, count)
+    } else {
+        String::new()
+    };
     
     Ok(LogHabitResponse {
         success: true,
-        message: format!("🔥 Logged habit completion! Current streak: {} day{}", 
-                        updated_streak.current_streak, 
-                        if updated_streak.current_streak == 1 { "" } else { "s" }),
+        message: format!("🔥 Logged '{}' for {}! Current streak: {}.{}{}{}",
+                        habit.name,
+                        completed_at,
+                        format_streak_length(updated_streak.current_streak, streak_unit_label(&habit.frequency)),
+                        day_count_line,
+                        if backdated { "\n🕰️ Logged a backdated entry; streaks recalculated" } else { "" },
+                        if freezes_earned > 0 { "\n🧊 Earned a streak freeze! Spend it with habit_use_freeze on a day you'll miss." } else { "" }),
         current_streak: Some(updated_streak.current_streak),
+        already_logged: false,
+        entry: Some(LoggedEntry::from(&entry)),
     })
-}
\ No newline at end of file
+}
+
+/// Build the informational response for a habit already logged on `completed_at`
+/// 
+/// The date is described as "today" when it matches the clock's today.
+fn already_logged_response<S: HabitStorage>(
+    storage: &S,
+    habit: &Habit,
+    completed_at: NaiveDate,
+    clock: &dyn Clock,
+) -> Result<LogHabitResponse, StorageError> {
+    let streak = storage.get_streak(&habit.id)?;
+    let day = if completed_at == clock.today() {
+        "today".to_string()
+    } else {
+        format!("on {}", completed_at)
+    };
+    
+    Ok(LogHabitResponse {
+        success: true,
+        message: format!("You already logged '{}' {} ✅ — current streak is still {}.",
+                        habit.name,
+                        day,
+                        format_streak_length(streak.current_streak, streak_unit_label(&habit.frequency))),
+        current_streak: Some(streak.current_streak),
+        already_logged: true,
+        entry: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Category, Frequency, MAX_NOTES_LENGTH};
+    use chrono::Utc;
+    use crate::storage::sqlite::SqliteStorage;
+
+    fn params_for(habit: &Habit, completed_at: NaiveDate) -> LogHabitParams {
+        LogHabitParams {
+            habit_id: habit.id.to_string(),
+            completed_at: Some(completed_at.to_string()),
+            value: None,
+            intensity: None,
+            notes: None,
+            mood: None,
+        }
+    }
+
+    #[test]
+    fn test_log_respects_backfill_window() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let habit = Habit::new(
+            "Journal".to_string(),
+            None,
+            Category::Personal,
+            Frequency::Daily,
+            None,
+            None,
+        ).unwrap();
+        storage.create_habit(&habit).unwrap();
+        let old_date = Utc::now().naive_utc().date() - chrono::Duration::days(400);
+
+        let result = log_habit(&storage, &AnalyticsEngine::new(), params_for(&habit, old_date), &EntryValidation::default());
+        assert!(result.is_err());
+
+        let expanded = EntryValidation {
+            max_backfill_days: 1000,
+            ..EntryValidation::default()
+        };
+        assert!(log_habit(&storage, &AnalyticsEngine::new(), params_for(&habit, old_date), &expanded).unwrap().success);
+    }
+
+    #[test]
+    fn test_log_before_rollover_hour_counts_for_previous_day() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let habit = Habit::new("Journal".to_string(), None, Category::Personal, Frequency::Daily, None, None).unwrap();
+        storage.create_habit(&habit).unwrap();
+        let today = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        let log_at = |hour: u32, minute: u32| {
+            let clock = crate::domain::DayRolloverClock {
+                clock: crate::domain::FixedTime(today.and_hms_opt(hour, minute, 0).unwrap()),
+                rollover_hour: 3,
+            };
+            let validation = EntryValidation { clock: &clock, ..EntryValidation::default() };
+            log_habit(&storage, &AnalyticsEngine::new(), LogHabitParams { completed_at: None, ..params_for(&habit, today) }, &validation)
+                .unwrap()
+                .entry
+                .unwrap()
+                .completed_at
+        };
+
+        assert_eq!(log_at(0, 30), today - chrono::Duration::days(1));
+        assert_eq!(log_at(4, 0), today);
+    }
+
+    #[test]
+    fn test_log_accepts_relative_and_timestamp_dates() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let habit = Habit::new("Stretch".to_string(), None, Category::Health, Frequency::Daily, None, None).unwrap();
+        storage.create_habit(&habit).unwrap();
+        let today = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        let validation = EntryValidation { clock: &crate::domain::FixedClock(today), ..EntryValidation::default() };
+        let log_on = |completed_at: &str| log_habit(&storage, &AnalyticsEngine::new(), LogHabitParams {
+            completed_at: Some(completed_at.to_string()),
+            ..params_for(&habit, today)
+        }, &validation);
+
+        assert!(log_on("yesterday").unwrap().success);
+        assert!(log_on("2024-03-12T21:15:00Z").unwrap().success);
+        assert!(log_on("today").unwrap().success);
+        let dates: Vec<NaiveDate> = storage.get_entries_for_habit(&habit.id, None).unwrap()
+            .iter().map(|e| e.completed_at).collect();
+        for days_ago in [0, 1, 3] {
+            assert!(dates.contains(&(today - chrono::Duration::days(days_ago))));
+        }
+
+        let error = log_on("last tuesday").unwrap_err();
+        assert!(matches!(error, StorageError::Validation(_)));
+        assert!(error.to_string().contains("today, yesterday, YYYY-MM-DD"));
+    }
+
+    #[test]
+    fn test_log_value_limit_matches_entry_validation() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let habit = Habit::new(
+            "Pushups".to_string(),
+            None,
+            Category::Health,
+            Frequency::Daily,
+            None,
+            None,
+        ).unwrap();
+        storage.create_habit(&habit).unwrap();
+        let today = Utc::now().naive_utc().date();
+        let with_value = |days_ago: i64, value: u32| LogHabitParams {
+            value: Some(value),
+            ..params_for(&habit, today - chrono::Duration::days(days_ago))
+        };
+
+        let too_large = log_habit(&storage, &AnalyticsEngine::new(), with_value(0, MAX_ENTRY_VALUE + 1), &EntryValidation::default()).unwrap_err();
+        let entry_error = HabitEntry::new(habit.id.clone(), today, Some(MAX_ENTRY_VALUE + 1), None, None).unwrap_err();
+        assert!(too_large.to_string().contains(&format!("cannot exceed {}", MAX_ENTRY_VALUE)));
+        assert!(entry_error.to_string().contains(&format!("cannot exceed {}", MAX_ENTRY_VALUE)));
+
+        assert!(log_habit(&storage, &AnalyticsEngine::new(), with_value(1, MAX_ENTRY_VALUE), &EntryValidation::default()).unwrap().success);
+    }
+
+    #[test]
+    fn test_log_message_names_habit_and_date() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let habit = Habit::new("Morning Run".to_string(), None, Category::Health, Frequency::Daily, None, None).unwrap();
+        storage.create_habit(&habit).unwrap();
+        let yesterday = Utc::now().naive_utc().date() - chrono::Duration::days(1);
+
+        let response = log_habit(&storage, &AnalyticsEngine::new(), params_for(&habit, yesterday), &EntryValidation::default()).unwrap();
+
+        assert!(response.message.starts_with(&format!("🔥 Logged 'Morning Run' for {}! Current streak: 1 day.", yesterday)),
+                "{}", response.message);
+    }
+
+    #[test]
+    fn test_log_notes_use_entry_validation() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let habit = Habit::new("Run".to_string(), None, Category::Health, Frequency::Daily, None, None).unwrap();
+        storage.create_habit(&habit).unwrap();
+        let today = Utc::now().naive_utc().date();
+        let with_notes = |days_ago: i64, notes: String| LogHabitParams {
+            notes: Some(notes),
+            ..params_for(&habit, today - chrono::Duration::days(days_ago))
+        };
+
+        let too_long = log_habit(&storage, &AnalyticsEngine::new(), with_notes(0, "x".repeat(MAX_NOTES_LENGTH + 1)), &EntryValidation::default()).unwrap_err();
+        assert!(too_long.to_string().contains("longer than 500"));
+        let null_byte = log_habit(&storage, &AnalyticsEngine::new(), with_notes(0, "5k\0".to_string()), &EntryValidation::default()).unwrap_err();
+        assert!(matches!(null_byte, StorageError::Validation(ref msg) if msg.contains("control characters")));
+
+        assert!(log_habit(&storage, &AnalyticsEngine::new(), with_notes(1, "Easy pace\nFelt good".to_string()), &EntryValidation::default()).unwrap().success);
+        let entries = storage.get_entries_for_habit(&habit.id, None).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].notes.as_deref(), Some("Easy pace\nFelt good"));
+    }
+
+    #[test]
+    fn test_log_with_mood_stores_and_echoes_it() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let habit = Habit::new("Run".to_string(), None, Category::Health, Frequency::Daily, None, None).unwrap();
+        storage.create_habit(&habit).unwrap();
+        let today = Utc::now().naive_utc().date();
+        let with_mood = |days_ago: i64, mood: &str| LogHabitParams {
+            mood: Some(mood.to_string()),
+            ..params_for(&habit, today - chrono::Duration::days(days_ago))
+        };
+
+        let response = log_habit(&storage, &AnalyticsEngine::new(), with_mood(0, " Energized"), &EntryValidation::default()).unwrap();
+        assert_eq!(response.entry.unwrap().mood.as_deref(), Some("energized"));
+        let entries = storage.get_entries_for_habit(&habit.id, None).unwrap();
+        assert_eq!(entries[0].mood.as_deref(), Some("energized"));
+
+        let too_long = log_habit(&storage, &AnalyticsEngine::new(), with_mood(1, &"x".repeat(31)), &EntryValidation::default()).unwrap_err();
+        assert!(matches!(too_long, StorageError::Validation(ref msg) if msg.contains("Mood cannot be longer")));
+        assert_eq!(storage.get_entries_for_habit(&habit.id, None).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_log_earns_freeze_token_every_seven_days() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let habit = Habit::new(
+            "Journal".to_string(),
+            None,
+            Category::Personal,
+            Frequency::Daily,
+            None,
+            None,
+        ).unwrap();
+        storage.create_habit(&habit).unwrap();
+        let today = Utc::now().naive_utc().date();
+
+        for days_ago in (1..=7).rev() {
+            let response = log_habit(&storage, &AnalyticsEngine::new(), params_for(&habit, today - chrono::Duration::days(days_ago)), &EntryValidation::default()).unwrap();
+            assert_eq!(response.message.contains("Earned a streak freeze"), days_ago == 1);
+        }
+
+        assert_eq!(storage.get_habit(&habit.id).unwrap().freeze_tokens, 1);
+    }
+
+    #[test]
+    fn test_backdated_log_notes_recalculation() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let habit = Habit::new(
+            "Journal".to_string(),
+            None,
+            Category::Personal,
+            Frequency::Daily,
+            None,
+            None,
+        ).unwrap();
+        storage.create_habit(&habit).unwrap();
+        let today = Utc::now().naive_utc().date();
+        let day = |days_ago: i64| today - chrono::Duration::days(days_ago);
+        let validation = EntryValidation::default();
+
+        let first = log_habit(&storage, &AnalyticsEngine::new(), params_for(&habit, day(2)), &validation).unwrap();
+        assert!(!first.message.contains("backdated"));
+        let in_order = log_habit(&storage, &AnalyticsEngine::new(), params_for(&habit, day(1)), &validation).unwrap();
+        assert!(!in_order.message.contains("backdated"));
+
+        let backdated = log_habit(&storage, &AnalyticsEngine::new(), params_for(&habit, day(5)), &validation).unwrap();
+        assert!(backdated.message.contains("Logged a backdated entry; streaks recalculated"));
+        // The gap before the older entry keeps the current run at two days
+        assert_eq!(backdated.current_streak, Some(2));
+        assert_eq!(storage.get_streak(&habit.id).unwrap().total_completions, 3);
+    }
+
+    #[test]
+    fn test_logging_twice_in_one_day_depends_on_habit_setting() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let single = Habit::new(
+            "Journal".to_string(),
+            None,
+            Category::Personal,
+            Frequency::Daily,
+            None,
+            None,
+        ).unwrap();
+        let multiple = Habit::new(
+            "Drink water".to_string(),
+            None,
+            Category::Health,
+            Frequency::Daily,
+            None,
+            Some("glasses".to_string()),
+        ).unwrap().with_allow_multiple_per_day(true);
+        storage.create_habit(&single).unwrap();
+        storage.create_habit(&multiple).unwrap();
+        let yesterday = Utc::now().naive_utc().date() - chrono::Duration::days(1);
+        let validation = EntryValidation::default();
+
+        log_habit(&storage, &AnalyticsEngine::new(), params_for(&single, yesterday), &validation).unwrap();
+        let second = log_habit(&storage, &AnalyticsEngine::new(), params_for(&single, yesterday), &validation).unwrap();
+        assert!(second.already_logged);
+        assert_eq!(storage.get_entries_for_habit(&single.id, None).unwrap().len(), 1);
+
+        for count in 1..=3 {
+            let response = log_habit(&storage, &AnalyticsEngine::new(), params_for(&multiple, yesterday), &validation).unwrap();
+            assert_eq!(response.current_streak, Some(1));
+            assert!(response.message.contains(&format!("Completions on {}: {}", yesterday, count)));
+            assert!(!response.message.contains("backdated"));
+        }
+
+        let streak = storage.get_streak(&multiple.id).unwrap();
+        assert_eq!(streak.current_streak, 1);
+        assert_eq!(streak.total_completions, 3);
+    }
+
+    #[test]
+    fn test_duplicate_log_gets_friendly_response() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let habit = Habit::new(
+            "Morning Run".to_string(),
+            None,
+            Category::Health,
+            Frequency::Daily,
+            None,
+            None,
+        ).unwrap();
+        storage.create_habit(&habit).unwrap();
+        let today = Utc::now().naive_utc().date();
+        for days_ago in 1..=11 {
+            let entry = HabitEntry::new(habit.id.clone(), today - chrono::Duration::days(days_ago), None, None, None).unwrap();
+            storage.create_entry(&entry).unwrap();
+        }
+        let validation = EntryValidation::default();
+
+        let first = log_habit(&storage, &AnalyticsEngine::new(), params_for(&habit, today), &validation).unwrap();
+        assert!(!first.already_logged);
+        assert_eq!(first.current_streak, Some(12));
+        assert_eq!(first.entry.unwrap().completed_at, today);
+
+        let repeat = log_habit(&storage, &AnalyticsEngine::new(), params_for(&habit, today), &validation).unwrap();
+        assert!(repeat.success);
+        assert!(repeat.already_logged);
+        assert!(repeat.entry.is_none());
+        assert_eq!(repeat.current_streak, Some(12));
+        assert_eq!(repeat.message, "You already logged 'Morning Run' today ✅ — current streak is still 12 days.");
+        assert_eq!(storage.get_streak(&habit.id).unwrap().total_completions, 12);
+    }
+
+    #[test]
+    fn test_duplicate_log_reads_today_from_the_clock() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let habit = Habit::new("Stretch".to_string(), None, Category::Health, Frequency::Daily, None, None).unwrap();
+        storage.create_habit(&habit).unwrap();
+        let day = Utc::now().naive_utc().date() - chrono::Duration::days(30);
+        let validation = EntryValidation { clock: &crate::domain::FixedClock(day), ..EntryValidation::default() };
+        let log_twice = |completed_at: NaiveDate| {
+            log_habit(&storage, &AnalyticsEngine::new(), params_for(&habit, completed_at), &validation).unwrap();
+            log_habit(&storage, &AnalyticsEngine::new(), params_for(&habit, completed_at), &validation).unwrap().message
+        };
+
+        assert!(log_twice(day).contains("already logged 'Stretch' today"));
+        let earlier = day - chrono::Duration::days(1);
+        assert!(log_twice(earlier).contains(&format!("already logged 'Stretch' on {}", earlier)));
+    }
+
+    #[test]
+    fn test_recompute_after_deleting_an_entry() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let habit = Habit::from_existing(
+            HabitId::new(),
+            "Journal".to_string(),
+            None,
+            Category::Personal,
+            Frequency::Daily,
+            None,
+            None,
+            Utc::now() - chrono::Duration::days(3),
+            true,
+        );
+        storage.create_habit(&habit).unwrap();
+        let today = Utc::now().naive_utc().date();
+        for days_ago in [3, 2, 1] {
+            log_habit(&storage, &AnalyticsEngine::new(), params_for(&habit, today - chrono::Duration::days(days_ago)), &EntryValidation::default()).unwrap();
+        }
+        let before = storage.get_streak(&habit.id).unwrap();
+        assert_eq!(before.total_completions, 3);
+
+        let entry = storage.get_entries_for_habit(&habit.id, None).unwrap()
+            .into_iter()
+            .find(|e| e.completed_at == today - chrono::Duration::days(2))
+            .unwrap();
+        let habit_id = storage.delete_entry(&entry.id).unwrap();
+        let after = recompute_and_store_streak(&storage, &AnalyticsEngine::new(), &habit_id).unwrap();
+
+        assert_eq!(after.total_completions, 2);
+        assert!(after.completion_rate < before.completion_rate);
+        assert_eq!(after.current_streak, 1);
+        let stored = storage.get_streak(&habit.id).unwrap();
+        assert_eq!(stored.total_completions, 2);
+        assert_eq!(stored.completion_rate, after.completion_rate);
+    }
+}
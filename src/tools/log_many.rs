@@ -0,0 +1,295 @@
+/// Tool for logging many habit completions at once
+///
+/// This module implements the habit_log_many MCP tool, used by clients
+/// that collected completions offline and flush them in a single call.
+/// All entries are stored in one transaction and each affected habit's
+/// streak is recalculated once at the end, awarding freeze tokens the same
+/// way `habit_log` does.
+
+use serde::Serialize;
+use chrono::NaiveDate;
+use crate::domain::{EntryValidation, HabitEntry, HabitId};
+use crate::analytics::AnalyticsEngine;
+use crate::storage::{StorageError, HabitStorage};
+use crate::tools::log::{build_entry, recompute_and_award_freezes, LogHabitParams, StreakSnapshot};
+
+/// Parameters for logging several habit completions
+#[derive(Debug)]
+pub struct LogManyParams {
+    pub entries: Vec<LogHabitParams>,
+    /// What to do when a habit is already logged for the day: "skip" (default) or "error"
+    pub on_conflict: Option<String>,
+}
+
+/// Outcome of a single item in a batch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogItemStatus {
+    Created,
+    Skipped,
+    Error,
+}
+
+/// Result for one item, in the order the items were given
+#[derive(Debug, Serialize)]
+pub struct LogItemResult {
+    pub index: usize,
+    pub habit_id: String,
+    pub status: LogItemStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// Response from logging several habit completions
+#[derive(Debug, Serialize)]
+pub struct LogManyResponse {
+    pub success: bool,
+    pub created: u32,
+    pub skipped: u32,
+    pub failed: u32,
+    /// Freeze tokens earned across all habits in the batch
+    pub freezes_earned: u32,
+    pub results: Vec<LogItemResult>,
+    pub message: String,
+}
+
+/// Log several habit completions in one transaction
+///
+/// Items that fail validation are reported as errors and the rest are
+/// still stored. Duplicates are skipped with `on_conflict: "skip"`; with
+/// `"error"` a single duplicate rolls back the whole batch. If a habit's
+/// streak can't be recalculated after its entries are committed, its items
+/// stay created and carry the error instead of failing the whole call.
+pub fn log_many<S: HabitStorage>(
+    storage: &S,
+    analytics: &AnalyticsEngine,
+    params: LogManyParams,
+    validation: &EntryValidation,
+) -> Result<LogManyResponse, StorageError> {
+    let skip_duplicates = match params.on_conflict.as_deref() {
+        None | Some("skip") => true,
+        Some("error") => false,
+        Some(other) => {
+            return Err(StorageError::Validation(format!(
+                "Unknown on_conflict '{}'. Valid options: skip, error", other
+            )));
+        }
+    };
+
+    if params.entries.is_empty() {
+        return Err(StorageError::Validation("At least one entry is required".to_string()));
+    }
+
+    let mut results = Vec::with_capacity(params.entries.len());
+    let mut pending: Vec<(usize, HabitEntry)> = Vec::new();
+
+    for (index, item) in params.entries.into_iter().enumerate() {
+        let habit_id = item.habit_id.clone();
+        match build_entry(storage, item, validation) {
+            Ok((_, entry)) => {
+                pending.push((results.len(), entry));
+                results.push(LogItemResult { index, habit_id, status: LogItemStatus::Created, message: None });
+            }
+            Err(e) => {
+                results.push(LogItemResult { index, habit_id, status: LogItemStatus::Error, message: Some(e.to_string()) });
+            }
+        }
+    }
+
+    // Snapshot each habit before storing, to tell which ones the batch extends
+    let mut snapshots: Vec<(HabitId, StreakSnapshot)> = Vec::new();
+    for (_, entry) in &pending {
+        if !snapshots.iter().any(|(id, _)| *id == entry.habit_id) {
+            snapshots.push((entry.habit_id.clone(), StreakSnapshot::read(storage, &entry.habit_id)?));
+        }
+    }
+
+    let entries: Vec<HabitEntry> = pending.iter().map(|(_, entry)| entry.clone()).collect();
+    let stored = if skip_duplicates {
+        storage.create_entries_skipping_duplicates(&entries)?
+    } else {
+        storage.create_entries(&entries)?;
+        vec![true; entries.len()]
+    };
+
+    // Newest stored day per affected habit
+    let mut affected: Vec<(HabitId, NaiveDate)> = Vec::new();
+    for ((position, entry), created) in pending.iter().zip(stored) {
+        if created {
+            match affected.iter_mut().find(|(id, _)| *id == entry.habit_id) {
+                Some((_, newest)) => *newest = (*newest).max(entry.completed_at),
+                None => affected.push((entry.habit_id.clone(), entry.completed_at)),
+            }
+        } else {
+            let result = &mut results[*position];
+            result.status = LogItemStatus::Skipped;
+            result.message = Some(format!("Already logged on {}", entry.completed_at));
+        }
+    }
+
+    // Recalculate each affected habit's streak once; the entries are already committed
+    let mut freezes_earned = 0;
+    let mut streak_failures = 0;
+    for (habit_id, newest_logged) in &affected {
+        let before = &snapshots.iter().find(|(id, _)| id == habit_id).expect("every pending habit has a snapshot").1;
+        match recompute_and_award_freezes(storage, analytics, habit_id, before, *newest_logged) {
+            Ok((_, earned)) => freezes_earned += earned,
+            Err(e) => {
+                streak_failures += 1;
+                for (position, entry) in &pending {
+                    let result = &mut results[*position];
+                    if entry.habit_id == *habit_id && result.status == LogItemStatus::Created {
+                        result.message = Some(format!("Stored, but the streak could not be recalculated: {}", e));
+                    }
+                }
+            }
+        }
+    }
+
+    let count = |status| results.iter().filter(|r| r.status == status).count() as u32;
+    let (created, skipped, failed) = (count(LogItemStatus::Created), count(LogItemStatus::Skipped), count(LogItemStatus::Error));
+
+    let mut message = format!(
+        "📥 Logged {} completion{} across {} habit{} ({} skipped, {} failed)",
+        created,
+        if created == 1 { "" } else { "s" },
+        affected.len(),
+        if affected.len() == 1 { "" } else { "s" },
+        skipped,
+        failed,
+    );
+    if freezes_earned > 0 {
+        message.push_str(&format!("\n🧊 Earned {} streak freeze{}! Spend them with habit_use_freeze on a day you'll miss.",
+                                  freezes_earned, if freezes_earned == 1 { "" } else { "s" }));
+    }
+    if streak_failures > 0 {
+        message.push_str(&format!("\n⚠️ Streaks of {} habit{} could not be recalculated; see the item messages",
+                                  streak_failures, if streak_failures == 1 { "" } else { "s" }));
+    }
+
+    Ok(LogManyResponse {
+        success: failed == 0 && streak_failures == 0,
+        created,
+        skipped,
+        failed,
+        freezes_earned,
+        results,
+        message,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use crate::domain::{Category, Frequency, Habit};
+    use crate::storage::sqlite::SqliteStorage;
+
+    fn item(habit: &Habit, completed_at: NaiveDate) -> LogHabitParams {
+        LogHabitParams {
+            habit_id: habit.id.to_string(),
+            completed_at: Some(completed_at.to_string()),
+            value: None,
+            intensity: None,
+            notes: None,
+            mood: None,
+        }
+    }
+
+    fn storage_with_habits() -> (SqliteStorage, Habit, Habit) {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let run = Habit::new("Run".to_string(), None, Category::Health, Frequency::Daily, None, None).unwrap();
+        let read = Habit::new("Read".to_string(), None, Category::Personal, Frequency::Daily, None, None).unwrap();
+        storage.create_habit(&run).unwrap();
+        storage.create_habit(&read).unwrap();
+        (storage, run, read)
+    }
+
+    #[test]
+    fn test_log_many_skips_duplicates_and_recalculates_streaks() {
+        let (storage, run, read) = storage_with_habits();
+        let today = Utc::now().naive_utc().date();
+        let days_ago = |d: i64| today - chrono::Duration::days(d);
+        log_many(&storage, &AnalyticsEngine::new(), LogManyParams { entries: vec![item(&run, days_ago(2))], on_conflict: None }, &EntryValidation::default()).unwrap();
+
+        let response = log_many(&storage, &AnalyticsEngine::new(), LogManyParams {
+            entries: vec![
+                item(&run, days_ago(2)), // Already stored
+                item(&run, days_ago(1)),
+                item(&run, days_ago(0)),
+                item(&read, days_ago(0)),
+                item(&read, days_ago(0)), // Duplicate within the batch
+                LogHabitParams { habit_id: "not-an-id".to_string(), ..item(&read, today) },
+            ],
+            on_conflict: Some("skip".to_string()),
+        }, &EntryValidation::default()).unwrap();
+
+        let statuses: Vec<LogItemStatus> = response.results.iter().map(|r| r.status).collect();
+        assert_eq!(statuses, vec![
+            LogItemStatus::Skipped,
+            LogItemStatus::Created,
+            LogItemStatus::Created,
+            LogItemStatus::Created,
+            LogItemStatus::Skipped,
+            LogItemStatus::Error,
+        ]);
+        assert_eq!((response.created, response.skipped, response.failed), (3, 2, 1));
+        assert!(!response.success);
+
+        assert_eq!(storage.get_entries_for_habit(&run.id, None).unwrap().len(), 3);
+        assert_eq!(storage.get_streak(&run.id).unwrap().current_streak, 3);
+        assert_eq!(storage.get_streak(&read.id).unwrap().current_streak, 1);
+    }
+
+    #[test]
+    fn test_log_many_error_mode_rolls_back_on_duplicate() {
+        let (storage, run, read) = storage_with_habits();
+        let yesterday = Utc::now().naive_utc().date() - chrono::Duration::days(1);
+        log_many(&storage, &AnalyticsEngine::new(), LogManyParams { entries: vec![item(&run, yesterday)], on_conflict: None }, &EntryValidation::default()).unwrap();
+
+        let result = log_many(&storage, &AnalyticsEngine::new(), LogManyParams {
+            entries: vec![item(&read, yesterday), item(&run, yesterday)],
+            on_conflict: Some("error".to_string()),
+        }, &EntryValidation::default());
+
+        assert!(matches!(result, Err(StorageError::DuplicateEntry { .. })));
+        assert!(storage.get_entries_for_habit(&read.id, None).unwrap().is_empty());
+
+        let unknown = log_many(&storage, &AnalyticsEngine::new(), LogManyParams {
+            entries: vec![item(&read, yesterday)],
+            on_conflict: Some("overwrite".to_string()),
+        }, &EntryValidation::default());
+        assert!(matches!(unknown, Err(StorageError::Validation(_))));
+    }
+
+    #[test]
+    fn test_log_many_awards_freezes_like_single_logs() {
+        let (storage, run, read) = storage_with_habits();
+        let today = Utc::now().naive_utc().date();
+        let days_ago = |d: i64| today - chrono::Duration::days(d);
+        let validation = EntryValidation::default();
+        for d in (9..=13).rev() {
+            crate::tools::log_habit(&storage, &AnalyticsEngine::new(), item(&run, days_ago(d)), &validation).unwrap();
+        }
+
+        // Filling the gap up to today gives Run a 14-day streak, two multiples of seven
+        let response = log_many(&storage, &AnalyticsEngine::new(), LogManyParams {
+            entries: (0..=8).map(|d| item(&run, days_ago(d))).chain([item(&read, days_ago(0))]).collect(),
+            on_conflict: None,
+        }, &validation).unwrap();
+
+        assert_eq!(response.freezes_earned, 2);
+        assert!(response.message.contains("Earned 2 streak freezes"), "{}", response.message);
+        assert_eq!(storage.get_habit(&run.id).unwrap().freeze_tokens, 2);
+        assert_eq!(storage.get_habit(&read.id).unwrap().freeze_tokens, 0);
+
+        // Backfilled days that don't extend the streak earn nothing
+        let backfill = log_many(&storage, &AnalyticsEngine::new(), LogManyParams {
+            entries: (1..=7).map(|d| item(&read, days_ago(d))).collect(),
+            on_conflict: None,
+        }, &validation).unwrap();
+        assert_eq!(storage.get_streak(&read.id).unwrap().current_streak, 8);
+        assert_eq!(backfill.freezes_earned, 0);
+        assert_eq!(storage.get_habit(&read.id).unwrap().freeze_tokens, 0);
+    }
+}
@@ -0,0 +1,115 @@
+/// Tool for listing the streak milestones a habit has reached
+///
+/// This module implements the habit_milestones MCP tool, which reports the
+/// date each daily streak milestone (7, 14, 30, 60, 90 and 100 days) was
+/// first reached.
+
+use serde::{Deserialize, Serialize};
+use crate::analytics::{AnalyticsEngine, STREAK_MILESTONES};
+use crate::domain::HabitId;
+use crate::storage::{StorageError, HabitStorage};
+
+/// Parameters for fetching a habit's milestones
+#[derive(Debug, Deserialize)]
+pub struct MilestonesParams {
+    pub habit_id: String,
+}
+
+/// A milestone and the day it was first reached
+#[derive(Debug, Serialize)]
+pub struct MilestoneReached {
+    pub days: u32,
+    pub reached_on: String,
+}
+
+/// Response from fetching a habit's milestones
+#[derive(Debug, Serialize)]
+pub struct MilestonesResponse {
+    pub habit_id: String,
+    pub name: String,
+    pub milestones: Vec<MilestoneReached>, // Shortest first
+    /// Next milestone still to reach, if any remain
+    pub next_milestone: Option<u32>,
+    pub message: String,
+}
+
+/// List the streak milestones a habit has reached and when
+pub fn get_habit_milestones<S: HabitStorage>(
+    storage: &S,
+    params: MilestonesParams,
+) -> Result<MilestonesResponse, StorageError> {
+    let habit_id = HabitId::from_string(&params.habit_id)
+        .map_err(|_| StorageError::HabitNotFound { habit_id: params.habit_id.clone() })?;
+    let habit = storage.get_habit(&habit_id)?;
+    let entries = storage.get_entries_for_habit(&habit_id, None)?;
+
+    let reached = AnalyticsEngine::milestone_history(&entries);
+    let next_milestone = STREAK_MILESTONES.get(reached.len()).copied();
+
+    let message = if reached.is_empty() {
+        format!("🏁 '{}' hasn't reached a streak milestone yet — keep going for {} days in a row!",
+                habit.name, STREAK_MILESTONES[0])
+    } else {
+        let lines = reached.iter()
+            .map(|(days, date)| format!("   🏅 {} days — {}", days, date))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let next = match next_milestone {
+            Some(days) => format!("\n   Next up: {} days in a row", days),
+            None => "\n   Every milestone reached!".to_string(),
+        };
+        format!("🏆 Streak milestones for '{}'\n{}{}", habit.name, lines, next)
+    };
+
+    Ok(MilestonesResponse {
+        habit_id: habit.id.to_string(),
+        name: habit.name,
+        milestones: reached.into_iter()
+            .map(|(days, date)| MilestoneReached { days, reached_on: date.to_string() })
+            .collect(),
+        next_milestone,
+        message,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use crate::domain::{Category, Frequency, Habit, HabitEntry};
+    use crate::storage::sqlite::SqliteStorage;
+
+    #[test]
+    fn test_milestones_for_three_week_streak() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let habit = Habit::new("Walk".to_string(), None, Category::Health, Frequency::Daily, None, None).unwrap();
+        storage.create_habit(&habit).unwrap();
+        let today = Utc::now().naive_utc().date();
+        let entries: Vec<HabitEntry> = (0..21)
+            .map(|d| HabitEntry::new(habit.id.clone(), today - chrono::Duration::days(d), None, None, None).unwrap())
+            .collect();
+        storage.create_entries(&entries).unwrap();
+
+        let response = get_habit_milestones(&storage, MilestonesParams { habit_id: habit.id.to_string() }).unwrap();
+
+        let dates: Vec<(u32, String)> = response.milestones.iter().map(|m| (m.days, m.reached_on.clone())).collect();
+        assert_eq!(dates, vec![
+            (7, (today - chrono::Duration::days(14)).to_string()),
+            (14, (today - chrono::Duration::days(7)).to_string()),
+        ]);
+        assert_eq!(response.next_milestone, Some(30));
+    }
+
+    #[test]
+    fn test_milestones_when_none_reached() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let habit = Habit::new("Floss".to_string(), None, Category::Health, Frequency::Daily, None, None).unwrap();
+        storage.create_habit(&habit).unwrap();
+
+        let response = get_habit_milestones(&storage, MilestonesParams { habit_id: habit.id.to_string() }).unwrap();
+
+        assert!(response.milestones.is_empty());
+        assert_eq!(response.next_milestone, Some(7));
+        assert!(response.message.contains("hasn't reached a streak milestone yet"));
+    }
+}
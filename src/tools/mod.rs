@@ -5,16 +5,58 @@
 
 // Tool implementations will go in separate files
 pub mod create;
+pub mod create_with_history;
 pub mod log;
+pub mod log_many;
 pub mod status;
 pub mod list;
 pub mod insights;
 pub mod update;
+pub mod set_frequency;
+pub mod recategorize;
+pub mod rename_category;
+pub mod categories;
+pub mod dedupe;
+pub mod reorder;
+pub mod pause_all;
+pub mod trend;
+pub mod compare;
+pub mod history;
+pub mod milestones;
+pub mod reset_streak;
+pub mod optimize;
+pub mod freeze;
+pub mod vacation;
+pub mod clone;
+pub mod healthcheck;
+pub mod stats;
+pub mod schema;
 
 // Re-export tool functions for easy access
 pub use create::*;
+pub use create_with_history::*;
 pub use log::*;
+pub use log_many::*;
 pub use status::*;
 pub use list::*;
 pub use insights::*;
-pub use update::*;
\ No newline at end of file
+pub use update::*;
+pub use set_frequency::*;
+pub use recategorize::*;
+pub use rename_category::*;
+pub use categories::*;
+pub use dedupe::*;
+pub use reorder::*;
+pub use pause_all::*;
+pub use trend::*;
+pub use compare::*;
+pub use history::*;
+pub use milestones::*;
+pub use reset_streak::*;
+pub use optimize::*;
+pub use freeze::*;
+pub use vacation::*;
+pub use clone::*;
+pub use healthcheck::*;
+pub use stats::*;
+pub use schema::*;
@@ -0,0 +1,131 @@
+/// Maintenance tool for compacting the habit database
+///
+/// This module implements the habit_optimize MCP tool. After large deletes
+/// (dedupe, removed entries) SQLite keeps the freed pages, so the file
+/// doesn't shrink until it is vacuumed. The whole file is rewritten, which
+/// may take a while on large databases; run it while the server is
+/// otherwise idle. Entries left behind by habits removed outside the server
+/// can be deleted in the same pass.
+
+use serde::{Deserialize, Serialize};
+use crate::storage::{StorageError, HabitStorage};
+use crate::tools::stats::format_bytes;
+
+/// Parameters for optimizing the database
+#[derive(Debug, Default, Deserialize)]
+pub struct OptimizeParams {
+    /// Delete entries (and their streaks, freezes and vacations) whose habit no longer exists first
+    #[serde(default)]
+    pub remove_orphans: bool,
+}
+
+/// Response from optimizing the database
+#[derive(Debug, Serialize)]
+pub struct OptimizeResponse {
+    pub success: bool,
+    pub bytes_before: u64,
+    pub bytes_after: u64,
+    /// Orphaned entries deleted before vacuuming
+    pub orphan_entries_removed: u32,
+    pub message: String,
+}
+
+/// Rebuild indexes, refresh planner statistics and vacuum the database
+/// 
+/// With `remove_orphans`, orphaned rows are deleted before the vacuum so
+/// their pages are reclaimed too.
+pub fn optimize_storage<S: HabitStorage>(storage: &S, params: OptimizeParams) -> Result<OptimizeResponse, StorageError> {
+    let bytes_before = storage.storage_stats()?.database_bytes;
+    let orphan_entries_removed = if params.remove_orphans {
+        storage.delete_orphan_entries()?
+    } else {
+        0
+    };
+    storage.optimize()?;
+    let bytes_after = storage.storage_stats()?.database_bytes;
+
+    let mut message = if bytes_after < bytes_before {
+        format!("🧹 Database optimized: {} → {} (saved {})",
+                format_bytes(bytes_before), format_bytes(bytes_after), format_bytes(bytes_before - bytes_after))
+    } else {
+        format!("🧹 Database optimized: indexes rebuilt, size {}", format_bytes(bytes_after))
+    };
+    if orphan_entries_removed > 0 {
+        message.push_str(&format!("\n   Removed {} orphaned entries", orphan_entries_removed));
+    }
+
+    Ok(OptimizeResponse {
+        success: true,
+        bytes_before,
+        bytes_after,
+        orphan_entries_removed,
+        message,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Habit, HabitEntry, Category, Frequency};
+    use crate::storage::sqlite::SqliteStorage;
+    use chrono::Utc;
+
+    #[test]
+    fn test_optimize_keeps_rows_and_shrinks_after_deletes() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = SqliteStorage::new(dir.path().join("habits.db")).unwrap();
+        let today = Utc::now().naive_utc().date();
+        let mut habits = Vec::new();
+        for i in 0..20 {
+            let habit = Habit::new(format!("Habit {}", i), Some("x".repeat(400)), Category::Personal, Frequency::Daily, None, None).unwrap();
+            storage.create_habit(&habit).unwrap();
+            let entries: Vec<HabitEntry> = (0..30)
+                .map(|d| HabitEntry::new(habit.id.clone(), today - chrono::Duration::days(d), None, None, Some("y".repeat(200))).unwrap())
+                .collect();
+            storage.create_entries(&entries).unwrap();
+            habits.push(habit);
+        }
+        // Free up pages by deleting most entries
+        for habit in &habits[1..] {
+            for entry in storage.get_entries_for_habit(&habit.id, None).unwrap() {
+                storage.delete_entry(&entry.id).unwrap();
+            }
+        }
+        let before = storage.storage_stats().unwrap();
+
+        let response = optimize_storage(&storage, OptimizeParams::default()).unwrap();
+
+        let after = storage.storage_stats().unwrap();
+        assert_eq!((after.habit_count, after.entry_count, after.streak_count),
+                   (before.habit_count, before.entry_count, before.streak_count));
+        assert_eq!(after.entry_count, 30);
+        assert_eq!(response.bytes_before, before.database_bytes);
+        assert_eq!(response.bytes_after, after.database_bytes);
+        assert!(response.bytes_after < response.bytes_before);
+        assert!(response.message.contains("saved"));
+        assert_eq!(response.orphan_entries_removed, 0);
+    }
+
+    #[test]
+    fn test_optimize_removes_orphans_when_asked() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("habits.db");
+        let storage = SqliteStorage::new(&path).unwrap();
+        let habit = Habit::new("Run".to_string(), None, Category::Personal, Frequency::Daily, None, None).unwrap();
+        storage.create_habit(&habit).unwrap();
+        storage.create_entry(&HabitEntry::new(habit.id.clone(), Utc::now().naive_utc().date(), None, None, None).unwrap()).unwrap();
+        // Remove the habit row by hand, as an external edit would
+        let conn = rusqlite::Connection::open(&path).unwrap();
+        conn.execute("PRAGMA foreign_keys = OFF", []).unwrap();
+        conn.execute("DELETE FROM habits WHERE id = ?1", [habit.id.to_string()]).unwrap();
+
+        let kept = optimize_storage(&storage, OptimizeParams::default()).unwrap();
+        assert_eq!(kept.orphan_entries_removed, 0);
+        assert_eq!(storage.health_check().unwrap().orphan_entry_count, 1);
+
+        let removed = optimize_storage(&storage, OptimizeParams { remove_orphans: true }).unwrap();
+        assert_eq!(removed.orphan_entries_removed, 1);
+        assert!(removed.message.contains("Removed 1 orphaned entries"));
+        assert_eq!(storage.health_check().unwrap().entry_count, 0);
+    }
+}
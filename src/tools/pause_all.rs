@@ -0,0 +1,110 @@
+/// Tools for pausing and resuming every habit at once
+/// 
+/// This module implements the habit_pause_all and habit_resume_all MCP
+/// tools, handy before and after a vacation.
+/// 
+/// There is no separate archived state yet: deleted habits are stored as
+/// inactive, so resuming all habits also brings those back.
+
+use serde::Serialize;
+use crate::storage::{StorageError, HabitStorage};
+
+/// Response from pausing or resuming all habits
+#[derive(Debug, Serialize)]
+pub struct BulkToggleResponse {
+    pub success: bool,
+    pub changed: u32,
+    pub message: String,
+}
+
+/// Pause every active habit
+pub fn pause_all_habits<S: HabitStorage>(storage: &S) -> Result<BulkToggleResponse, StorageError> {
+    let changed = storage.set_all_active(false)?;
+    
+    let message = if changed == 0 {
+        "⏸️ No active habits to pause".to_string()
+    } else {
+        format!("⏸️ Paused {} habit{}. Use habit_resume_all when you're back.",
+                changed, if changed == 1 { "" } else { "s" })
+    };
+    
+    Ok(BulkToggleResponse { success: true, changed, message })
+}
+
+/// Resume every paused habit
+pub fn resume_all_habits<S: HabitStorage>(storage: &S) -> Result<BulkToggleResponse, StorageError> {
+    let changed = storage.set_all_active(true)?;
+    
+    let message = if changed == 0 {
+        "▶️ No paused habits to resume".to_string()
+    } else {
+        format!("▶️ Resumed {} habit{}. Welcome back!",
+                changed, if changed == 1 { "" } else { "s" })
+    };
+    
+    Ok(BulkToggleResponse { success: true, changed, message })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Habit, Category, Frequency};
+    use crate::storage::sqlite::SqliteStorage;
+
+    fn create_habit(storage: &SqliteStorage, name: &str) -> Habit {
+        let habit = Habit::new(
+            name.to_string(),
+            None,
+            Category::Personal,
+            Frequency::Daily,
+            None,
+            None,
+        ).unwrap();
+        storage.create_habit(&habit).unwrap();
+        habit
+    }
+
+    fn active_count(storage: &SqliteStorage) -> usize {
+        storage.list_habits(None, true).unwrap().len()
+    }
+
+    #[test]
+    fn test_pause_all_flips_every_active_habit() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        create_habit(&storage, "Alpha");
+        create_habit(&storage, "Beta");
+        let paused = create_habit(&storage, "Gamma");
+        storage.delete_habit(&paused.id).unwrap();
+
+        let response = pause_all_habits(&storage).unwrap();
+
+        assert_eq!(response.changed, 2);
+        assert_eq!(active_count(&storage), 0);
+        assert!(response.message.contains("Paused 2 habits"));
+    }
+
+    #[test]
+    fn test_resume_all_restores_paused_habits() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        create_habit(&storage, "Alpha");
+        create_habit(&storage, "Beta");
+        create_habit(&storage, "Gamma");
+        pause_all_habits(&storage).unwrap();
+
+        let response = resume_all_habits(&storage).unwrap();
+
+        assert_eq!(response.changed, 3);
+        assert_eq!(active_count(&storage), 3);
+    }
+
+    #[test]
+    fn test_toggle_reports_nothing_to_change() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        create_habit(&storage, "Alpha");
+
+        let response = resume_all_habits(&storage).unwrap();
+
+        assert_eq!(response.changed, 0);
+        assert_eq!(response.message, "▶️ No paused habits to resume");
+    }
+}
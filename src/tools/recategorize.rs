@@ -0,0 +1,166 @@
+/// Tool for moving habits between categories in bulk
+/// 
+/// This module implements the habit_recategorize MCP tool.
+
+use serde::{Deserialize, Serialize};
+use crate::domain::{CategoryRegistry, HabitId};
+use crate::storage::{StorageError, HabitStorage};
+use crate::tools::create::parse_category;
+
+/// Parameters for recategorizing habits
+#[derive(Debug, Deserialize)]
+pub struct RecategorizeParams {
+    pub habit_ids: Vec<String>,
+    pub category: String, // Parsed like habit_create, including "custom:name"
+}
+
+/// Response from recategorizing habits
+#[derive(Debug, Serialize)]
+pub struct RecategorizeResponse {
+    pub success: bool,
+    pub moved: u32,
+    pub errors: Vec<String>, // One message per habit id that could not be moved
+    pub message: String,
+}
+
+/// Move a set of habits into a new category using the provided storage
+pub fn recategorize_habits<S: HabitStorage>(
+    storage: &S,
+    params: RecategorizeParams,
+    categories: &CategoryRegistry,
+) -> Result<RecategorizeResponse, StorageError> {
+    if params.habit_ids.is_empty() {
+        return Err(StorageError::Validation("At least one habit ID is required".to_string()));
+    }
+    
+    let category = parse_category(&params.category, categories)?;
+    
+    // Resolve ids up front so invalid ones are reported instead of aborting the batch
+    let mut valid_ids = Vec::new();
+    let mut errors = Vec::new();
+    
+    for id_str in &params.habit_ids {
+        match HabitId::from_string(id_str) {
+            Ok(habit_id) => match storage.get_habit(&habit_id) {
+                Ok(_) => valid_ids.push(habit_id),
+                Err(StorageError::HabitNotFound { .. }) => {
+                    errors.push(format!("{}: habit not found", id_str));
+                }
+                Err(e) => return Err(e),
+            },
+            Err(_) => errors.push(format!("{}: invalid habit ID format", id_str)),
+        }
+    }
+    
+    let moved = if valid_ids.is_empty() {
+        0
+    } else {
+        storage.set_category_for_habits(&valid_ids, &category)?
+    };
+    
+    let mut message = format!("📂 Moved {} habit{} to '{}'",
+                              moved,
+                              if moved == 1 { "" } else { "s" },
+                              category.display_name_in(categories));
+    if !errors.is_empty() {
+        message.push_str(&format!("\n⚠️ Skipped {}:\n{}", errors.len(), errors.join("\n")));
+    }
+    
+    Ok(RecategorizeResponse {
+        success: errors.is_empty(),
+        moved,
+        errors,
+        message,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Habit, Category, Frequency};
+    use crate::storage::sqlite::SqliteStorage;
+
+    fn create_habit(storage: &SqliteStorage, name: &str) -> Habit {
+        let habit = Habit::new(
+            name.to_string(),
+            None,
+            Category::Personal,
+            Frequency::Daily,
+            None,
+            None,
+        ).unwrap();
+        storage.create_habit(&habit).unwrap();
+        habit
+    }
+
+    #[test]
+    fn test_recategorize_into_custom_category() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let first = create_habit(&storage, "Write code");
+        let second = create_habit(&storage, "Read papers");
+        let untouched = create_habit(&storage, "Journal");
+
+        let params = RecategorizeParams {
+            habit_ids: vec![first.id.to_string(), second.id.to_string()],
+            category: "custom:startup".to_string(),
+        };
+
+        let response = recategorize_habits(&storage, params, &CategoryRegistry::default()).unwrap();
+        assert!(response.success);
+        assert_eq!(response.moved, 2);
+
+        let expected = Category::Custom("startup".to_string());
+        assert_eq!(storage.get_habit(&first.id).unwrap().category, expected);
+        assert_eq!(storage.get_habit(&second.id).unwrap().category, expected);
+        assert_eq!(storage.get_habit(&untouched.id).unwrap().category, Category::Personal);
+    }
+
+    #[test]
+    fn test_recategorize_rejects_custom_builtin_name() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let habit = create_habit(&storage, "Write code");
+
+        let params = RecategorizeParams {
+            habit_ids: vec![habit.id.to_string()],
+            category: "custom:Health".to_string(),
+        };
+
+        let err = recategorize_habits(&storage, params, &CategoryRegistry::default()).unwrap_err();
+        assert!(err.to_string().contains("use 'health' instead of 'custom:health'"));
+        assert_eq!(storage.get_habit(&habit.id).unwrap().category, Category::Personal);
+    }
+
+    #[test]
+    fn test_recategorize_reports_invalid_ids() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let habit = create_habit(&storage, "Write code");
+
+        let params = RecategorizeParams {
+            habit_ids: vec![
+                habit.id.to_string(),
+                "not-a-uuid".to_string(),
+                HabitId::new().to_string(),
+            ],
+            category: "productivity".to_string(),
+        };
+
+        let response = recategorize_habits(&storage, params, &CategoryRegistry::default()).unwrap();
+        assert!(!response.success);
+        assert_eq!(response.moved, 1);
+        assert_eq!(response.errors.len(), 2);
+        assert_eq!(storage.get_habit(&habit.id).unwrap().category, Category::Productivity);
+    }
+
+    #[test]
+    fn test_recategorize_rejects_invalid_category() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let habit = create_habit(&storage, "Write code");
+
+        let params = RecategorizeParams {
+            habit_ids: vec![habit.id.to_string()],
+            category: "nonsense".to_string(),
+        };
+
+        assert!(recategorize_habits(&storage, params, &CategoryRegistry::default()).is_err());
+    }
+}
@@ -0,0 +1,131 @@
+/// Tool for renaming a custom category across all habits
+/// 
+/// This module implements the habit_rename_category MCP tool.
+
+use serde::{Deserialize, Serialize};
+use crate::domain::{Category, CategoryRegistry, MAX_CATEGORY_NAME_LENGTH};
+use crate::storage::{StorageError, HabitStorage};
+
+/// Parameters for renaming a custom category
+#[derive(Debug, Deserialize)]
+pub struct RenameCategoryParams {
+    pub from: String, // Existing custom category name, without the "custom:" prefix
+    pub to: String,
+}
+
+/// Response from renaming a custom category
+#[derive(Debug, Serialize)]
+pub struct RenameCategoryResponse {
+    pub success: bool,
+    pub renamed: u32,
+    pub message: String,
+}
+
+/// Rename a custom category on every habit using it
+pub fn rename_custom_category<S: HabitStorage>(
+    storage: &S,
+    params: RenameCategoryParams,
+    categories: &CategoryRegistry,
+) -> Result<RenameCategoryResponse, StorageError> {
+    // Custom names are stored lowercased by habit_create, so match that here
+    let from = normalize_name(&params.from, "Current")?;
+    let to = normalize_name(&params.to, "New")?;
+    
+    // The old name may predate the built-in check, so only the new one is restricted
+    if let Some(builtin) = Category::first_class(&to, categories) {
+        return Err(StorageError::Validation(format!("'{}' is a built-in category; use habit_recategorize to move habits into it", builtin.display_name_in(categories))));
+    }
+    
+    let renamed = storage.rename_custom_category(&from, &to)?;
+    
+    let message = if renamed == 0 {
+        format!("🔍 No habits use the custom category '{}'", from)
+    } else {
+        format!("✏️ Renamed category '{}' to '{}' on {} habit{}",
+                from, to, renamed, if renamed == 1 { "" } else { "s" })
+    };
+    
+    Ok(RenameCategoryResponse {
+        success: true,
+        renamed,
+        message,
+    })
+}
+
+/// Validate and normalize a custom category name
+fn normalize_name(name: &str, label: &str) -> Result<String, StorageError> {
+    let name = name.trim();
+    let name = name.strip_prefix("custom:").unwrap_or(name).trim().to_lowercase();
+    
+    if name.is_empty() {
+        return Err(StorageError::Validation(format!("{} category name cannot be empty", label)));
+    }
+    
+    if name.chars().count() > MAX_CATEGORY_NAME_LENGTH {
+        return Err(StorageError::Validation(format!("{} category name cannot exceed {} characters", label, MAX_CATEGORY_NAME_LENGTH)));
+    }
+    
+    Ok(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Habit, Category, Frequency};
+    use crate::storage::sqlite::SqliteStorage;
+
+    fn create_habit(storage: &SqliteStorage, name: &str, category: Category) -> Habit {
+        let habit = Habit::new(
+            name.to_string(),
+            None,
+            category,
+            Frequency::Daily,
+            None,
+            None,
+        ).unwrap();
+        storage.create_habit(&habit).unwrap();
+        habit
+    }
+
+    fn params(from: &str, to: &str) -> RenameCategoryParams {
+        RenameCategoryParams { from: from.to_string(), to: to.to_string() }
+    }
+
+    #[test]
+    fn test_rename_only_matching_custom_category() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let side = Category::Custom("side project".to_string());
+        let first = create_habit(&storage, "Write code", side.clone());
+        let second = create_habit(&storage, "Ship features", side);
+        let other = create_habit(&storage, "Practice guitar", Category::Custom("music".to_string()));
+
+        let response = rename_custom_category(&storage, params("Side Project", "Startup"), &CategoryRegistry::default()).unwrap();
+        assert_eq!(response.renamed, 2);
+
+        let startup = Category::Custom("startup".to_string());
+        assert_eq!(storage.get_habit(&first.id).unwrap().category, startup);
+        assert_eq!(storage.get_habit(&second.id).unwrap().category, startup);
+        assert_eq!(storage.get_habit(&other.id).unwrap().category, Category::Custom("music".to_string()));
+    }
+
+    #[test]
+    fn test_rename_validates_new_name() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+
+        assert!(rename_custom_category(&storage, params("music", "  "), &CategoryRegistry::default()).is_err());
+        assert!(rename_custom_category(&storage, params("music", &"x".repeat(MAX_CATEGORY_NAME_LENGTH + 1)), &CategoryRegistry::default()).is_err());
+        assert!(rename_custom_category(&storage, params("", "music"), &CategoryRegistry::default()).is_err());
+    }
+
+    #[test]
+    fn test_rename_cannot_target_builtin_but_can_leave_one() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        // Created before built-in names were reserved
+        let legacy = create_habit(&storage, "Stretch", Category::Custom("health".to_string()));
+
+        assert!(rename_custom_category(&storage, params("music", "Health"), &CategoryRegistry::default()).is_err());
+
+        rename_custom_category(&storage, params("health", "mobility"), &CategoryRegistry::default()).unwrap();
+        assert_eq!(storage.get_habit(&legacy.id).unwrap().category, Category::Custom("mobility".to_string()));
+    }
+}
@@ -0,0 +1,143 @@
+/// Tool for arranging habits in a user-defined order
+/// 
+/// This module implements the habit_reorder MCP tool.
+
+use serde::{Deserialize, Serialize};
+use crate::domain::HabitId;
+use crate::storage::{StorageError, HabitStorage};
+
+/// Parameters for reordering habits
+#[derive(Debug, Deserialize)]
+pub struct ReorderParams {
+    pub habit_ids: Vec<String>, // Most important first
+}
+
+/// Response from reordering habits
+#[derive(Debug, Serialize)]
+pub struct ReorderResponse {
+    pub success: bool,
+    pub reordered: u32,
+    pub message: String,
+}
+
+/// Assign sequential positions to habits in the order given
+pub fn reorder_habits<S: HabitStorage>(
+    storage: &S,
+    params: ReorderParams,
+) -> Result<ReorderResponse, StorageError> {
+    if params.habit_ids.is_empty() {
+        return Err(StorageError::Validation("At least one habit ID is required".to_string()));
+    }
+    
+    let mut habit_ids = Vec::with_capacity(params.habit_ids.len());
+    for id_str in &params.habit_ids {
+        let habit_id = HabitId::from_string(id_str)
+            .map_err(|_| StorageError::HabitNotFound { habit_id: id_str.clone() })?;
+        
+        if habit_ids.contains(&habit_id) {
+            return Err(StorageError::Validation(format!("Habit {} is listed more than once", id_str)));
+        }
+        habit_ids.push(habit_id);
+    }
+    
+    let reordered = storage.set_habit_positions(&habit_ids)?;
+    
+    Ok(ReorderResponse {
+        success: true,
+        reordered,
+        message: format!("🔢 Reordered {} habit{}. Use sort_by 'position' to list them in this order.",
+                         reordered, if reordered == 1 { "" } else { "s" }),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Habit, Category, Frequency};
+    use crate::storage::sqlite::SqliteStorage;
+    use crate::tools::{list_habits, ListHabitsParams};
+
+    fn create_habit(storage: &SqliteStorage, name: &str) -> Habit {
+        let habit = Habit::new(
+            name.to_string(),
+            None,
+            Category::Personal,
+            Frequency::Daily,
+            None,
+            None,
+        ).unwrap();
+        storage.create_habit(&habit).unwrap();
+        habit
+    }
+
+    fn names_by_position(storage: &SqliteStorage) -> Vec<String> {
+        let params = ListHabitsParams {
+            category: None,
+            active_only: None,
+            sort_by: Some("position".to_string()),
+            recent_days: None,
+            abbreviate_days: None,
+            min_streak: None,
+            max_streak: None,
+            min_completion_rate: None,
+            max_completion_rate: None,
+            group_by: None,
+        };
+        list_habits(storage, params, &crate::domain::StreakOptions::default(), &crate::domain::SystemClock, &crate::domain::CategoryRegistry::default()).unwrap()
+            .habits
+            .into_iter()
+            .map(|h| h.name)
+            .collect()
+    }
+
+    #[test]
+    fn test_reorder_persists_and_list_respects_positions() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let alpha = create_habit(&storage, "Alpha");
+        let beta = create_habit(&storage, "Beta");
+        let gamma = create_habit(&storage, "Gamma");
+        create_habit(&storage, "Delta");
+
+        let params = ReorderParams {
+            habit_ids: vec![gamma.id.to_string(), alpha.id.to_string(), beta.id.to_string()],
+        };
+        let response = reorder_habits(&storage, params).unwrap();
+        assert_eq!(response.reordered, 3);
+
+        assert_eq!(storage.get_habit(&gamma.id).unwrap().position, Some(1));
+        assert_eq!(storage.get_habit(&beta.id).unwrap().position, Some(3));
+        // Unplaced habits come last
+        assert_eq!(names_by_position(&storage), vec!["Gamma", "Alpha", "Beta", "Delta"]);
+    }
+
+    #[test]
+    fn test_reorder_rejects_duplicates_and_unknown_ids() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let habit = create_habit(&storage, "Alpha");
+
+        let duplicate = ReorderParams { habit_ids: vec![habit.id.to_string(), habit.id.to_string()] };
+        assert!(reorder_habits(&storage, duplicate).is_err());
+
+        let unknown = ReorderParams { habit_ids: vec![habit.id.to_string(), HabitId::new().to_string()] };
+        assert!(matches!(reorder_habits(&storage, unknown), Err(StorageError::HabitNotFound { .. })));
+        assert_eq!(storage.get_habit(&habit.id).unwrap().position, None);
+    }
+
+    #[test]
+    fn test_reorder_clears_positions_of_unlisted_habits() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let alpha = create_habit(&storage, "Alpha");
+        let beta = create_habit(&storage, "Beta");
+        let gamma = create_habit(&storage, "Gamma");
+
+        reorder_habits(&storage, ReorderParams {
+            habit_ids: vec![alpha.id.to_string(), beta.id.to_string(), gamma.id.to_string()],
+        }).unwrap();
+        reorder_habits(&storage, ReorderParams { habit_ids: vec![gamma.id.to_string()] }).unwrap();
+
+        // Alpha no longer shares position 1 with Gamma
+        assert_eq!(storage.get_habit(&gamma.id).unwrap().position, Some(1));
+        assert_eq!(storage.get_habit(&alpha.id).unwrap().position, None);
+        assert_eq!(names_by_position(&storage)[0], "Gamma");
+    }
+}
@@ -0,0 +1,164 @@
+/// Administrative tool for overriding a habit's cached streak
+///
+/// This module implements the habit_reset_streak MCP tool. It writes the
+/// streak row directly and bypasses the calculation from entries, so it is
+/// meant for repairing data after a correction. Entries are left alone, and
+/// the next log or recalculation derives the streak from them again.
+
+use serde::{Deserialize, Serialize};
+use crate::domain::{Clock, HabitId};
+use crate::storage::{StorageError, HabitStorage};
+
+/// Parameters for overriding a habit's streak
+#[derive(Debug, Deserialize)]
+pub struct ResetStreakParams {
+    pub habit_id: String,
+    pub current_streak: Option<u32>, // Defaults to 0
+    pub longest_streak: Option<u32>, // Defaults to the stored longest streak
+    /// Must be true; guards against resetting a streak by accident
+    pub confirm: bool,
+}
+
+/// Response from overriding a habit's streak
+#[derive(Debug, Serialize)]
+pub struct ResetStreakResponse {
+    pub success: bool,
+    pub current_streak: u32,
+    pub longest_streak: u32,
+    pub previous_current_streak: u32,
+    pub previous_longest_streak: u32,
+    pub message: String,
+}
+
+/// Overwrite a habit's stored current and longest streak
+///
+/// The longest streak keeps its stored value unless overridden, and is
+/// raised to the current streak if it would otherwise be shorter. The
+/// last completion date, total completions and completion rate are kept.
+/// Neither may be longer than the days from the habit's creation to the
+/// clock's today.
+pub fn reset_habit_streak<S: HabitStorage>(
+    storage: &S,
+    params: ResetStreakParams,
+    clock: &dyn Clock,
+) -> Result<ResetStreakResponse, StorageError> {
+    if !params.confirm {
+        return Err(StorageError::Validation(
+            "Resetting a streak overrides the value calculated from entries. Pass confirm: true to proceed".to_string()
+        ));
+    }
+
+    let habit_id = HabitId::from_string(&params.habit_id)
+        .map_err(|_| StorageError::HabitNotFound { habit_id: params.habit_id.clone() })?;
+    let habit = storage.get_habit(&habit_id)?;
+    let mut streak = storage.get_streak(&habit_id)?;
+
+    let habit_age_days = (clock.today() - habit.created_at.date_naive()).num_days().max(0) + 1;
+    for (field, value) in [("current_streak", params.current_streak), ("longest_streak", params.longest_streak)] {
+        if let Some(value) = value.filter(|&v| i64::from(v) > habit_age_days) {
+            return Err(StorageError::Validation(format!(
+                "{} {} is longer than the {} days since '{}' was created", field, value, habit_age_days, habit.name
+            )));
+        }
+    }
+
+    let current = params.current_streak.unwrap_or(0);
+    let longest = match params.longest_streak {
+        Some(longest) if longest < current => {
+            return Err(StorageError::Validation(format!(
+                "longest_streak {} cannot be shorter than current_streak {}", longest, current
+            )));
+        }
+        Some(longest) => longest,
+        None => streak.longest_streak.max(current),
+    };
+
+    let previous_current_streak = streak.current_streak;
+    let previous_longest_streak = streak.longest_streak;
+    streak.current_streak = current;
+    streak.longest_streak = longest;
+    storage.update_streak(&streak)?;
+
+    Ok(ResetStreakResponse {
+        success: true,
+        current_streak: current,
+        longest_streak: longest,
+        previous_current_streak,
+        previous_longest_streak,
+        message: format!(
+            "🛠️ Set the streak for '{}' to current {}, longest {} (was {} and {}). \
+             Entries are unchanged; the next log recalculates the streak from them.",
+            habit.name, current, longest, previous_current_streak, previous_longest_streak
+        ),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use crate::domain::{Category, Frequency, Habit, Streak, SystemClock};
+    use crate::storage::sqlite::SqliteStorage;
+
+    fn setup() -> (SqliteStorage, Habit) {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let mut habit = Habit::new("Journal".to_string(), None, Category::Personal, Frequency::Daily, None, None).unwrap();
+        habit.created_at = Utc::now() - chrono::Duration::days(59);
+        storage.create_habit(&habit).unwrap();
+        let today = Utc::now().naive_utc().date();
+        storage.update_streak(&Streak::from_existing(habit.id.clone(), 12, 20, Some(today), 40, 0.8)).unwrap();
+        (storage, habit)
+    }
+
+    fn params(habit: &Habit, current: Option<u32>, longest: Option<u32>, confirm: bool) -> ResetStreakParams {
+        ResetStreakParams { habit_id: habit.id.to_string(), current_streak: current, longest_streak: longest, confirm }
+    }
+
+    #[test]
+    fn test_reset_writes_override_to_streak_row() {
+        let (storage, habit) = setup();
+
+        let response = reset_habit_streak(&storage, params(&habit, None, None, true), &SystemClock).unwrap();
+        assert_eq!((response.previous_current_streak, response.previous_longest_streak), (12, 20));
+
+        let stored = storage.get_streak(&habit.id).unwrap();
+        assert_eq!((stored.current_streak, stored.longest_streak), (0, 20));
+        assert_eq!(stored.total_completions, 40);
+        assert_eq!(stored.completion_rate, 0.8);
+
+        reset_habit_streak(&storage, params(&habit, Some(5), Some(9), true), &SystemClock).unwrap();
+        let stored = storage.get_streak(&habit.id).unwrap();
+        assert_eq!((stored.current_streak, stored.longest_streak), (5, 9));
+
+        // Without an override the longest streak grows to cover the current one
+        reset_habit_streak(&storage, params(&habit, Some(15), None, true), &SystemClock).unwrap();
+        let stored = storage.get_streak(&habit.id).unwrap();
+        assert_eq!((stored.current_streak, stored.longest_streak), (15, 15));
+    }
+
+    #[test]
+    fn test_reset_requires_confirmation_and_consistent_values() {
+        let (storage, habit) = setup();
+
+        let unconfirmed = reset_habit_streak(&storage, params(&habit, None, None, false), &SystemClock);
+        assert!(matches!(unconfirmed, Err(StorageError::Validation(ref msg)) if msg.contains("confirm: true")));
+
+        let inverted = reset_habit_streak(&storage, params(&habit, Some(10), Some(3), true), &SystemClock);
+        assert!(matches!(inverted, Err(StorageError::Validation(_))));
+
+        assert_eq!(storage.get_streak(&habit.id).unwrap().current_streak, 12);
+    }
+
+    #[test]
+    fn test_reset_rejects_streaks_longer_than_the_habit_has_existed() {
+        let (storage, habit) = setup();
+
+        // Created 59 days ago, so today is day 60
+        reset_habit_streak(&storage, params(&habit, Some(60), None, true), &SystemClock).unwrap();
+        for (current, longest) in [(Some(61), None), (Some(5), Some(61)), (Some(u32::MAX), None)] {
+            let result = reset_habit_streak(&storage, params(&habit, current, longest, true), &SystemClock);
+            assert!(matches!(result, Err(StorageError::Validation(ref msg)) if msg.contains("60 days")), "{:?}", result);
+        }
+        assert_eq!(storage.get_streak(&habit.id).unwrap().current_streak, 60);
+    }
+}
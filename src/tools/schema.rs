@@ -0,0 +1,179 @@
+/// Tool for describing the strings and limits the other tools accept
+///
+/// This module implements the habit_schema MCP tool. Everything it reports
+/// comes from the validation constants and parsers themselves, so clients
+/// building create/update calls can rely on it instead of tool descriptions.
+
+use serde::Serialize;
+use crate::domain::{
+    CategoryRegistry, BUILTIN_CATEGORY_NAMES, MAX_CATEGORY_NAME_LENGTH, MAX_DESCRIPTION_LENGTH, MAX_ENTRY_VALUE, MAX_INTENSITY, MAX_INTERVAL_DAYS,
+    MAX_MOOD_LENGTH, MAX_MOTIVATION_LENGTH, MAX_NAME_LENGTH, MAX_NOTES_LENGTH, MAX_TARGET_VALUE, MIN_INTENSITY,
+};
+
+/// Accepted category strings
+#[derive(Debug, Serialize)]
+pub struct CategoryGrammar {
+    pub builtin: Vec<String>,
+    /// Categories registered by this deployment with --categories-file
+    pub registered: Vec<String>,
+    /// Form of an ad-hoc category, e.g. "custom:startup"
+    pub custom: &'static str,
+}
+
+/// One accepted form of a frequency string
+#[derive(Debug, Serialize)]
+pub struct FrequencyForm {
+    /// Pattern with placeholders, e.g. "weekly:<n>"
+    pub form: &'static str,
+    /// Other spellings accepted for the same form
+    pub aliases: Vec<&'static str>,
+    pub example: String,
+    pub description: String,
+}
+
+/// Limits enforced on habit and entry fields
+#[derive(Debug, Serialize)]
+pub struct FieldLimits {
+    pub name_max_length: usize,
+    /// Longest name accepted after "custom:"
+    pub category_name_max_length: usize,
+    pub description_max_length: usize,
+    pub motivation_max_length: usize,
+    pub notes_max_length: usize,
+    pub mood_max_length: usize,
+    pub intensity_min: u8,
+    pub intensity_max: u8,
+    pub value_max: u32,
+    pub target_value_max: u32,
+    /// Days in the past a completion may be logged for, as configured on this server
+    pub max_backfill_days: u32,
+}
+
+/// Response describing the accepted strings and field limits
+#[derive(Debug, Serialize)]
+pub struct SchemaResponse {
+    pub categories: CategoryGrammar,
+    pub frequencies: Vec<FrequencyForm>,
+    pub fields: FieldLimits,
+    pub message: String,
+}
+
+/// Describe the category and frequency strings and the field limits in force
+pub fn describe_schema(max_backfill_days: u32, categories: &CategoryRegistry) -> SchemaResponse {
+    let categories = CategoryGrammar {
+        builtin: BUILTIN_CATEGORY_NAMES.iter().map(|name| name.to_string()).collect(),
+        registered: categories.names().map(str::to_string).collect(),
+        custom: "custom:<name>",
+    };
+
+    let frequencies = vec![
+        FrequencyForm {
+            form: "daily",
+            aliases: vec![],
+            example: "daily".to_string(),
+            description: "Every day".to_string(),
+        },
+        FrequencyForm {
+            form: "weekdays",
+            aliases: vec![],
+            example: "weekdays".to_string(),
+            description: "Monday to Friday".to_string(),
+        },
+        FrequencyForm {
+            form: "weekends",
+            aliases: vec![],
+            example: "weekends".to_string(),
+            description: "Saturday and Sunday".to_string(),
+        },
+        FrequencyForm {
+            form: "weekly:<n>",
+            aliases: vec!["weekly"],
+            example: "weekly:3".to_string(),
+            description: "Any n days per week, n from 1 to 7 (bare 'weekly' means 3)".to_string(),
+        },
+        FrequencyForm {
+            form: "custom:<days>",
+            aliases: vec!["custom"],
+            example: "custom:mon,wed,fri".to_string(),
+            description: "Specific weekdays, comma-separated names or abbreviations (bare 'custom' means Monday)".to_string(),
+        },
+        FrequencyForm {
+            form: "every:<n>",
+            aliases: vec!["interval:<n>"],
+            example: "every:3".to_string(),
+            description: format!("Every n days from the habit's creation, n from 1 to {}", MAX_INTERVAL_DAYS),
+        },
+    ];
+
+    let fields = FieldLimits {
+        name_max_length: MAX_NAME_LENGTH,
+        category_name_max_length: MAX_CATEGORY_NAME_LENGTH,
+        description_max_length: MAX_DESCRIPTION_LENGTH,
+        motivation_max_length: MAX_MOTIVATION_LENGTH,
+        notes_max_length: MAX_NOTES_LENGTH,
+        mood_max_length: MAX_MOOD_LENGTH,
+        intensity_min: MIN_INTENSITY,
+        intensity_max: MAX_INTENSITY,
+        value_max: MAX_ENTRY_VALUE,
+        target_value_max: MAX_TARGET_VALUE,
+        max_backfill_days,
+    };
+
+    let category_names: Vec<&str> = categories.builtin.iter()
+        .chain(&categories.registered)
+        .map(String::as_str)
+        .chain([categories.custom])
+        .collect();
+    let message = format!(
+        "📐 Accepted values\n   Categories: {}\n   Frequencies: {}\n   Limits: name ≤ {} chars, notes ≤ {} chars, intensity {}-{}, value ≤ {}, backfill ≤ {} days",
+        category_names.join(", "),
+        frequencies.iter().map(|f| f.form).collect::<Vec<_>>().join(", "),
+        fields.name_max_length,
+        fields.notes_max_length,
+        fields.intensity_min,
+        fields.intensity_max,
+        fields.value_max,
+        fields.max_backfill_days,
+    );
+
+    SchemaResponse {
+        categories,
+        frequencies,
+        fields,
+        message,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Category, Frequency, DEFAULT_MAX_BACKFILL_DAYS};
+
+    #[test]
+    fn test_schema_covers_every_frequency_form_and_real_limits() {
+        let mut categories = CategoryRegistry::default();
+        categories.register("cardio", "Cardio").unwrap();
+        let schema = describe_schema(DEFAULT_MAX_BACKFILL_DAYS, &categories);
+
+        let forms: Vec<&str> = schema.frequencies.iter().map(|f| f.form).collect();
+        assert_eq!(forms, vec!["daily", "weekdays", "weekends", "weekly:<n>", "custom:<days>", "every:<n>"]);
+        assert!(schema.frequencies.iter().any(|f| f.aliases.contains(&"interval:<n>")));
+        for form in &schema.frequencies {
+            assert!(form.example.parse::<Frequency>().is_ok(), "example '{}' should parse", form.example);
+        }
+        assert_eq!("interval:3".parse::<Frequency>().unwrap(), Frequency::Interval(3));
+
+        for name in &schema.categories.builtin {
+            assert!(!matches!(Category::parse_user_input(name, &categories).unwrap(), Category::Custom(_) | Category::Registered(_)));
+        }
+        assert_eq!(schema.categories.builtin.len(), 8);
+        assert_eq!(schema.categories.registered, vec!["cardio"]);
+
+        assert_eq!(schema.fields.name_max_length, 100);
+        assert_eq!(schema.fields.category_name_max_length, 50);
+        assert_eq!(schema.fields.notes_max_length, 500);
+        assert_eq!((schema.fields.intensity_min, schema.fields.intensity_max), (1, 10));
+        assert_eq!(schema.fields.value_max, 100_000);
+        assert_eq!(schema.fields.max_backfill_days, DEFAULT_MAX_BACKFILL_DAYS);
+    }
+}
@@ -0,0 +1,121 @@
+/// Tool for changing how often a habit is scheduled
+///
+/// This module implements the habit_set_frequency MCP tool. Streaks mean
+/// different things under different frequencies (days for a daily habit,
+/// weeks for a weekly one), so the cached streak is recalculated from the
+/// habit's entries as soon as the frequency changes.
+
+use serde::{Deserialize, Serialize};
+use crate::domain::{Frequency, HabitId};
+use crate::analytics::AnalyticsEngine;
+use crate::storage::{StorageError, HabitStorage};
+use crate::tools::log::recompute_and_store_streak;
+
+/// Parameters for changing a habit's frequency
+#[derive(Debug, Deserialize)]
+pub struct SetFrequencyParams {
+    pub habit_id: String,
+    /// New frequency, e.g. "daily", "weekly:4", "custom:tue,thu" or "every:3"
+    pub frequency: String,
+}
+
+/// Response from changing a habit's frequency
+#[derive(Debug, Serialize)]
+pub struct SetFrequencyResponse {
+    pub success: bool,
+    /// Human-readable new frequency, e.g. "4 times per week"
+    pub frequency: String,
+    pub previous_frequency: String,
+    pub current_streak: u32,
+    pub longest_streak: u32,
+    pub message: String,
+}
+
+/// Change a habit's frequency and recalculate its streak under it
+pub fn set_habit_frequency<S: HabitStorage>(
+    storage: &S,
+    analytics: &AnalyticsEngine,
+    params: SetFrequencyParams,
+) -> Result<SetFrequencyResponse, StorageError> {
+    let habit_id = HabitId::from_string(&params.habit_id)
+        .map_err(|_| StorageError::HabitNotFound { habit_id: params.habit_id.clone() })?;
+    let mut habit = storage.get_habit(&habit_id)?;
+    let frequency: Frequency = params.frequency.parse()?;
+
+    let previous_frequency = habit.frequency.display_name();
+    habit.update(None, None, Some(frequency), None, None, None)?;
+    storage.update_habit(&habit)?;
+
+    let streak = recompute_and_store_streak(storage, analytics, &habit_id)?;
+    let frequency = habit.frequency.display_name();
+
+    Ok(SetFrequencyResponse {
+        success: true,
+        message: format!(
+            "🔁 '{}' is now {} (was {}). Streak recalculated: current {}, longest {}",
+            habit.name,
+            frequency.to_lowercase(),
+            previous_frequency.to_lowercase(),
+            streak.current_streak,
+            streak.longest_streak,
+        ),
+        frequency,
+        previous_frequency,
+        current_streak: streak.current_streak,
+        longest_streak: streak.longest_streak,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use crate::domain::{Category, Habit, HabitEntry, Streak};
+    use crate::storage::sqlite::SqliteStorage;
+
+    #[test]
+    fn test_daily_to_weekly_recomputes_streak() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let mut habit = Habit::new("Run".to_string(), None, Category::Health, Frequency::Daily, None, None).unwrap();
+        habit.created_at = Utc::now() - chrono::Duration::days(30);
+        storage.create_habit(&habit).unwrap();
+        let today = Utc::now().naive_utc().date();
+        let entries: Vec<HabitEntry> = (0..10)
+            .map(|d| HabitEntry::new(habit.id.clone(), today - chrono::Duration::days(d), None, None, None).unwrap())
+            .collect();
+        storage.create_entries(&entries).unwrap();
+        assert_eq!(recompute_and_store_streak(&storage, &AnalyticsEngine::new(), &habit.id).unwrap().current_streak, 10);
+
+        let response = set_habit_frequency(&storage, &AnalyticsEngine::new(), SetFrequencyParams {
+            habit_id: habit.id.to_string(),
+            frequency: "weekly:3".to_string(),
+        }).unwrap();
+
+        assert_eq!(response.frequency, "3 times per week");
+        assert_eq!(response.previous_frequency, "Daily");
+        assert_eq!(storage.get_habit(&habit.id).unwrap().frequency, Frequency::Weekly(3));
+
+        // Ten days span at most two full weeks, and the stored streak now counts weeks
+        let expected = Streak::calculate_from_entries(habit.id.clone(), &entries, &Frequency::Weekly(3), habit.created_at.date_naive());
+        let stored = storage.get_streak(&habit.id).unwrap();
+        assert_eq!(stored.current_streak, expected.current_streak);
+        assert_eq!(stored.longest_streak, expected.longest_streak);
+        assert!(stored.longest_streak <= 2);
+        assert_eq!(response.current_streak, stored.current_streak);
+    }
+
+    #[test]
+    fn test_invalid_frequency_leaves_habit_unchanged() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let habit = Habit::new("Read".to_string(), None, Category::Personal, Frequency::Daily, None, None).unwrap();
+        storage.create_habit(&habit).unwrap();
+
+        let result = set_habit_frequency(&storage, &AnalyticsEngine::new(), SetFrequencyParams {
+            habit_id: habit.id.to_string(),
+            frequency: "custom:funday".to_string(),
+        });
+
+        assert!(matches!(result, Err(StorageError::Validation(_))));
+        assert_eq!(storage.get_habit(&habit.id).unwrap().frequency, Frequency::Daily);
+    }
+}
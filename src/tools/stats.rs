@@ -0,0 +1,99 @@
+/// Tool for reporting database size and row counts
+///
+/// This module implements the habit_stats MCP tool, which shows how much
+/// data has accumulated in the habit database over time.
+
+use serde::Serialize;
+use crate::storage::{StorageError, HabitStorage};
+
+/// Response with database usage statistics
+#[derive(Debug, Serialize)]
+pub struct StatsResponse {
+    pub success: bool,
+    pub habit_count: u32,
+    pub entry_count: u32,
+    pub streak_count: u32,
+    pub earliest_entry: Option<String>,
+    pub latest_entry: Option<String>,
+    pub database_bytes: u64,
+    pub message: String,
+}
+
+/// Format a byte count with a binary unit, e.g. "1.5 MiB"
+pub(crate) fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+/// Report row counts, the span of logged entries and the database size
+pub fn storage_stats<S: HabitStorage>(storage: &S) -> Result<StatsResponse, StorageError> {
+    let stats = storage.storage_stats()?;
+
+    let date_range = match (stats.earliest_entry, stats.latest_entry) {
+        (Some(earliest), Some(latest)) => format!("{} to {}", earliest, latest),
+        _ => "no entries yet".to_string(),
+    };
+    let message = format!(
+        "🗄️ Database size: {}\n   Habits: {} | Entries: {} | Streaks: {}\n   Entries logged: {}",
+        format_bytes(stats.database_bytes),
+        stats.habit_count,
+        stats.entry_count,
+        stats.streak_count,
+        date_range,
+    );
+
+    Ok(StatsResponse {
+        success: true,
+        habit_count: stats.habit_count,
+        entry_count: stats.entry_count,
+        streak_count: stats.streak_count,
+        earliest_entry: stats.earliest_entry.map(|d| d.to_string()),
+        latest_entry: stats.latest_entry.map(|d| d.to_string()),
+        database_bytes: stats.database_bytes,
+        message,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Habit, HabitEntry, Category, Frequency};
+    use crate::storage::sqlite::SqliteStorage;
+    use chrono::Utc;
+
+    #[test]
+    fn test_stats_report_seeded_database() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let today = Utc::now().naive_utc().date();
+        for (name, days_ago) in [("Read", 30), ("Run", 2)] {
+            let habit = Habit::new(name.to_string(), None, Category::Personal, Frequency::Daily, None, None).unwrap();
+            storage.create_habit(&habit).unwrap();
+            let date = today - chrono::Duration::days(days_ago);
+            storage.create_entry(&HabitEntry::new(habit.id.clone(), date, None, None, None).unwrap()).unwrap();
+        }
+
+        let response = storage_stats(&storage).unwrap();
+
+        assert_eq!(response.habit_count, 2);
+        assert_eq!(response.entry_count, 2);
+        assert_eq!(response.earliest_entry, Some((today - chrono::Duration::days(30)).to_string()));
+        assert_eq!(response.latest_entry, Some((today - chrono::Duration::days(2)).to_string()));
+        assert!(response.message.contains(&format_bytes(response.database_bytes)));
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(4096), "4.0 KiB");
+        assert_eq!(format_bytes(1536 * 1024), "1.5 MiB");
+    }
+}
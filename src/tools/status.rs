@@ -3,8 +3,14 @@
 /// This module implements the habit_status MCP tool.
 
 use serde::{Deserialize, Serialize};
-use crate::domain::{HabitId};
+use crate::analytics::AnalyticsEngine;
+use crate::domain::{Frequency, Habit, HabitEntry, HabitId, StreakOptions, WeeklyStreak, format_rate, format_rate_vs_target, format_streak_length, format_streak_totals, streak_unit_label, vacation_days, week_start_for};
 use crate::storage::{StorageError, HabitStorage};
+use crate::tools::list::{category_label, frequency_to_display_string};
+use chrono::{NaiveDate, Weekday};
+
+/// Days ahead of today that single-habit status projects the streak to
+const PROJECTION_HORIZONS: [u32; 3] = [7, 30, 90];
 
 /// Parameters for checking habit status
 #[derive(Debug, Deserialize)]
@@ -17,11 +23,78 @@ pub struct StatusParams {
 pub struct HabitStatus {
     pub habit_id: String,
     pub name: String,
+    /// Human-readable schedule, e.g. "Daily" or "3 times per week"
+    pub frequency: String,
+    pub category: String,
     pub current_streak: u32,
     pub longest_streak: u32,
+    /// What the streaks count: "days", "weeks" or "periods"
+    pub streak_unit: &'static str,
     pub completion_rate: f64,
+    /// 0-100 blend of streak, completion rate and recency (see `Streak::consistency_score`)
+    pub consistency_score: u8,
     pub last_completed: Option<String>,
     pub status: String, // "on_track", "missed", "new", etc.
+    /// Sum of logged values, present for measurable habits or valued entries
+    pub total_value: Option<u64>,
+    /// Mean of logged values, ignoring entries without a value
+    pub average_value: Option<f64>,
+    /// Values left out of the total and average as a possible unit mismatch
+    pub excluded_values: Vec<ExcludedValue>,
+    /// Unit the values are measured in, if the habit defines one
+    pub unit: Option<String>,
+    /// Date of the first logged entry (only filled in for single-habit status)
+    pub tracking_since: Option<String>,
+    /// Days since the first logged entry, counting both ends
+    pub tracking_days: Option<i64>,
+    /// Completions so far this week, for Weekly(n) habits
+    pub week_completions: Option<u32>,
+    /// Weekly completion target, for Weekly(n) habits
+    pub week_target: Option<u32>,
+    /// Whether this week's target has been reached, for Weekly(n) habits
+    pub week_goal_met: Option<bool>,
+    /// Run of fully elapsed weeks that met the target, for Weekly(n) habits
+    pub completed_week_streak: Option<u32>,
+    /// Whether the current streak provisionally counts this week, for Weekly(n) habits
+    pub includes_current_week: Option<bool>,
+    /// Streak is still alive but today's completion hasn't been logged yet
+    pub at_risk: bool,
+    /// Entries logged for today, which can exceed one for habits that allow it
+    pub completions_today: u32,
+    /// Where the current streak gets to if kept up (only filled in for single-habit status)
+    pub projections: Vec<StreakProjection>,
+}
+
+/// A logged value far above the habit's usual ones, left out of its aggregates
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExcludedValue {
+    pub date: String,
+    pub value: u32,
+}
+
+/// Streak length reached by a future date if every scheduled completion is done
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StreakProjection {
+    /// Calendar days from today
+    pub days_ahead: u32,
+    pub date: String,
+    /// Projected streak, in the habit's streak unit
+    pub streak: u32,
+}
+
+/// Aggregate streak figures across every active habit
+#[derive(Debug, Serialize)]
+pub struct PortfolioStats {
+    /// The single longest current streak among all habits
+    pub longest_current_streak: u32,
+    /// Name of the habit holding the longest current streak, if any is running
+    pub longest_current_streak_habit: Option<String>,
+    /// Unit of the longest current streak ("days", "weeks" or "periods")
+    pub longest_current_streak_unit: &'static str,
+    /// Mean of the habits' completion rates (0.0 - 1.0)
+    pub mean_completion_rate: f64,
+    /// Number of habits whose streak breaks unless they are done today
+    pub at_risk_count: u32,
 }
 
 /// Response from checking habit status
@@ -30,47 +103,58 @@ pub struct StatusResponse {
     pub habits: Vec<HabitStatus>,
     pub summary: String,
     pub message: String,
+    /// Portfolio-wide aggregates, only filled in when no habit_id was given
+    pub portfolio: Option<PortfolioStats>,
 }
 
 /// Get status for habits using the provided storage
+/// 
+/// The engine's streak options decide which day weekly progress counts
+/// from, and its outlier multiplier which values are left out of totals.
 pub fn get_habit_status<S: HabitStorage>(
     storage: &S,
+    analytics: &AnalyticsEngine,
     params: StatusParams,
 ) -> Result<StatusResponse, StorageError> {
+    let options = &analytics.config().streak_options();
+    let mut portfolio = None;
     let habits = if let Some(habit_id_str) = params.habit_id {
         // Get status for specific habit
         let habit_id = HabitId::from_string(&habit_id_str)
             .map_err(|_| StorageError::HabitNotFound { habit_id: habit_id_str.clone() })?;
         
-        // Try to get the habit - for now we'll create a simple status
-        // In the future, we can implement proper get_habit
-        let streak = storage.get_streak(&habit_id)?;
+        let habit = storage.get_habit(&habit_id)?;
+        let (habit_frequency, habit_created) = (habit.frequency.clone(), habit.created_at.naive_utc().date());
+        let mut status = build_status(storage, analytics, habit, options)?;
         
-        vec![HabitStatus {
-            habit_id: habit_id_str,
-            name: "Habit".to_string(), // We'll need to get this from storage later
-            current_streak: streak.current_streak,
-            longest_streak: streak.longest_streak,
-            completion_rate: streak.completion_rate,
-            last_completed: streak.last_completed.map(|d| d.to_string()),
-            status: if streak.current_streak > 0 { "active" } else { "inactive" }.to_string(),
-        }]
+        if let Some(first) = storage.get_first_entry_date(&habit_id)? {
+            let today = analytics.clock().today();
+            status.tracking_since = Some(first.to_string());
+            status.tracking_days = Some((today - first).num_days() + 1);
+        }
+        
+        if status.current_streak > 0 {
+            status.projections = project_streak(
+                &habit_frequency,
+                status.current_streak,
+                status.last_completed.as_deref().and_then(|d| d.parse().ok()),
+                habit_created,
+                analytics.clock().today(),
+                options.week_start,
+            );
+        }
+        
+        vec![status]
     } else {
-        // Get status for all habits - simplified implementation
         let all_habits = storage.list_habits(None, true)?;
         let mut habit_statuses = Vec::new();
         
         for habit in all_habits {
-            let streak = storage.get_streak(&habit.id)?;
-            habit_statuses.push(HabitStatus {
-                habit_id: habit.id.to_string(),
-                name: habit.name,
-                current_streak: streak.current_streak,
-                longest_streak: streak.longest_streak,
-                completion_rate: streak.completion_rate,
-                last_completed: streak.last_completed.map(|d| d.to_string()),
-                status: if streak.current_streak > 0 { "active" } else { "inactive" }.to_string(),
-            });
+            habit_statuses.push(build_status(storage, analytics, habit, options)?);
+        }
+        
+        if !habit_statuses.is_empty() {
+            portfolio = Some(portfolio_stats(&habit_statuses));
         }
         
         habit_statuses
@@ -81,22 +165,38 @@ pub fn get_habit_status<S: HabitStorage>(
     } else {
         let active_count = habits.iter().filter(|h| h.current_streak > 0).count();
         let total_count = habits.len();
-        format!("📊 Status: {} of {} habits active. Total streaks: {} days", 
+        format!("📊 Status: {} of {} habits active. Total streaks: {}{}", 
                active_count, total_count, 
-               habits.iter().map(|h| h.current_streak).sum::<u32>())
+               format_streak_totals(habits.iter().map(|h| (h.current_streak, h.streak_unit))),
+               portfolio.as_ref().map(format_portfolio_line).unwrap_or_default())
     };
     
     let message = format!("{}\n\n{}", summary, 
         habits.iter()
-            .map(|h| format!("🎯 {} ({})\n   Current streak: {} days | Best: {} days | Rate: {:.1}%{}", 
-                            h.name, h.habit_id[..8].to_string() + "...", 
-                            h.current_streak, h.longest_streak, 
-                            h.completion_rate * 100.0,
+            .map(|h| format!("🎯 {} ({}) · {}\n   Current streak: {} | Best: {} | Rate: {} | Consistency: {}/100{}{}{}{}{}{}", 
+                            h.name, h.habit_id[..8].to_string() + "...", h.frequency,
+                            format_streak_length(h.current_streak, h.streak_unit),
+                            format_streak_length(h.longest_streak, h.streak_unit), 
+                            format_rate_vs_target(h.completion_rate),
+                            h.consistency_score,
+                            format_value_line(h),
+                            if h.completions_today > 1 {
+                                format!("\n   Completions today: {}", h.completions_today)
+                            } else {
+                                String::new()
+                            },
+                            format_week_line(h),
                             if let Some(last) = &h.last_completed { 
                                 format!("\n   Last completed: {}", last) 
                             } else { 
                                 "".to_string() 
-                            }))
+                            },
+                            match (&h.tracking_since, h.tracking_days) {
+                                (Some(since), Some(days)) => format!("\n   Tracking since {} ({} day{})",
+                                                                     since, days, if days == 1 { "" } else { "s" }),
+                                _ => "".to_string(),
+                            },
+                            format_projection_line(h)))
             .collect::<Vec<_>>()
             .join("\n\n"));
     
@@ -104,5 +204,650 @@ pub fn get_habit_status<S: HabitStorage>(
         habits,
         summary,
         message,
+        portfolio,
+    })
+}
+
+/// Compute the longest current streak, mean completion rate and at-risk count
+fn portfolio_stats(habits: &[HabitStatus]) -> PortfolioStats {
+    let leader = habits.iter()
+        .filter(|h| h.current_streak > 0)
+        .max_by_key(|h| h.current_streak);
+    
+    PortfolioStats {
+        longest_current_streak: leader.map(|h| h.current_streak).unwrap_or(0),
+        longest_current_streak_habit: leader.map(|h| h.name.clone()),
+        longest_current_streak_unit: leader.map(|h| h.streak_unit).unwrap_or("days"),
+        mean_completion_rate: habits.iter().map(|h| h.completion_rate).sum::<f64>() / habits.len() as f64,
+        at_risk_count: habits.iter().filter(|h| h.at_risk).count() as u32,
+    }
+}
+
+/// Format the portfolio aggregates appended to the all-habits summary
+fn format_portfolio_line(stats: &PortfolioStats) -> String {
+    let longest = match &stats.longest_current_streak_habit {
+        Some(name) => format!("{} ({})", format_streak_length(stats.longest_current_streak, stats.longest_current_streak_unit), name),
+        None => "none".to_string(),
+    };
+    format!("\n🏆 Longest current streak: {} | Avg rate: {} | ⚠️ At risk today: {}",
+            longest, format_rate(stats.mean_completion_rate), stats.at_risk_count)
+}
+
+/// Build the status for a single habit from its streak and entries
+fn build_status<S: HabitStorage>(
+    storage: &S,
+    analytics: &AnalyticsEngine,
+    habit: Habit,
+    options: &StreakOptions,
+) -> Result<HabitStatus, StorageError> {
+    let streak = storage.get_streak(&habit.id)?;
+    let entries = storage.get_entries_for_habit(&habit.id, None)?;
+    let measurable = habit.target_value.is_some() || habit.unit.is_some();
+    let outliers = analytics.value_outliers(&entries);
+    let (typical, excluded): (Vec<&HabitEntry>, Vec<&HabitEntry>) = entries.iter()
+        .partition(|e| !outliers.iter().any(|o| o.id == e.id));
+    let (total_value, average_value) = aggregate_values(&typical, measurable);
+    let mut excluded_values: Vec<ExcludedValue> = excluded.iter()
+        .map(|e| ExcludedValue { date: e.completed_at.to_string(), value: e.value.unwrap_or(0) })
+        .collect();
+    excluded_values.sort_by(|a, b| a.date.cmp(&b.date));
+    
+    let today = analytics.clock().today();
+    let completions_today = entries.iter().filter(|e| e.completed_at == today).count() as u32;
+    let at_risk = streak.current_streak > 0
+        && streak.last_completed != Some(today)
+        && streak.is_on_track_with_clock(&habit.frequency, analytics.clock());
+    
+    let (week_completions, week_target, weekly_streak) = match habit.frequency {
+        Frequency::Weekly(target) => {
+            let satisfied = entries.iter()
+                .map(|e| e.completed_at)
+                .chain(storage.get_streak_freezes(&habit.id)?)
+                .collect();
+            let vacation_days = vacation_days(&storage.get_vacations(&habit.id)?, today);
+            let weekly_streak = WeeklyStreak::calculate(&satisfied, &vacation_days, target, options.week_start, today);
+            (Some(count_week_completions(storage, &habit.id, today, options)?), Some(u32::from(target)), Some(weekly_streak))
+        }
+        _ => (None, None, None),
+    };
+    
+    Ok(HabitStatus {
+        habit_id: habit.id.to_string(),
+        name: habit.name,
+        frequency: frequency_to_display_string(&habit.frequency, options.week_start, false),
+        category: category_label(&habit.category),
+        current_streak: streak.current_streak,
+        longest_streak: streak.longest_streak,
+        streak_unit: streak_unit_label(&habit.frequency),
+        completion_rate: streak.completion_rate,
+        consistency_score: streak.consistency_score_with_clock(&habit.frequency, analytics.clock()),
+        last_completed: streak.last_completed.map(|d| d.to_string()),
+        status: if streak.current_streak > 0 { "active" } else { "inactive" }.to_string(),
+        total_value,
+        average_value,
+        excluded_values,
+        unit: habit.unit,
+        tracking_since: None,
+        tracking_days: None,
+        week_completions,
+        week_target,
+        week_goal_met: week_completions.zip(week_target).map(|(done, target)| done >= target),
+        completed_week_streak: weekly_streak.map(|w| w.completed_weeks),
+        includes_current_week: weekly_streak.map(|w| w.includes_current_week),
+        at_risk,
+        completions_today,
+        projections: Vec::new(),
     })
-}
\ No newline at end of file
+}
+
+/// Project a running streak `PROJECTION_HORIZONS` days ahead of `today`
+///
+/// Each scheduled day from tomorrow to the horizon adds one, and so does
+/// today if it is scheduled and not yet completed. Weekly habits gain one
+/// per week that starts within the horizon.
+pub(crate) fn project_streak(
+    frequency: &Frequency,
+    current_streak: u32,
+    last_completed: Option<NaiveDate>,
+    created_at: NaiveDate,
+    today: NaiveDate,
+    week_start: Weekday,
+) -> Vec<StreakProjection> {
+    let today_pending = !matches!(frequency, Frequency::Weekly(_))
+        && last_completed != Some(today)
+        && frequency.is_scheduled_for_date_since(today, created_at);
+    
+    PROJECTION_HORIZONS.iter()
+        .map(|&days_ahead| {
+            let date = today + chrono::Duration::days(i64::from(days_ahead));
+            let added = (1..=i64::from(days_ahead))
+                .map(|offset| today + chrono::Duration::days(offset))
+                .filter(|day| match frequency {
+                    Frequency::Weekly(_) => week_start_for(*day, week_start) == *day,
+                    _ => frequency.is_scheduled_for_date_since(*day, created_at),
+                })
+                .count() as u32;
+            StreakProjection {
+                days_ahead,
+                date: date.to_string(),
+                streak: current_streak.saturating_add(added).saturating_add(u32::from(today_pending)),
+            }
+        })
+        .collect()
+}
+
+/// Format the "Keep it up and you'll hit 19 days by ..." projection line
+fn format_projection_line(status: &HabitStatus) -> String {
+    if status.projections.is_empty() {
+        return String::new();
+    }
+    
+    let targets = status.projections.iter()
+        .map(|p| format!("{} by {}", format_streak_length(p.streak, status.streak_unit), p.date))
+        .collect::<Vec<_>>();
+    format!("\n   🔮 Keep it up and you'll hit {}", targets.join(", "))
+}
+
+/// Count the distinct days a habit was completed in the week containing `date`
+fn count_week_completions<S: HabitStorage>(
+    storage: &S,
+    habit_id: &HabitId,
+    date: chrono::NaiveDate,
+    options: &StreakOptions,
+) -> Result<u32, StorageError> {
+    let week_start = week_start_for(date, options.week_start);
+    let week_end = week_start + chrono::Duration::days(6);
+    
+    let days = storage.get_entries_by_date_range(week_start, week_end)?
+        .into_iter()
+        .filter(|e| &e.habit_id == habit_id)
+        .map(|e| e.completed_at)
+        .collect::<std::collections::HashSet<_>>();
+    
+    Ok(days.len() as u32)
+}
+
+/// Format the "This week: 2 of 3 completed" line for weekly habits
+fn format_week_line(status: &HabitStatus) -> String {
+    match (status.week_completions, status.week_target) {
+        (Some(done), Some(target)) => format!("\n   This week: {} of {} completed{}",
+                                              done, target,
+                                              if done >= target { " ✅ goal met" } else { "" }),
+        _ => String::new(),
+    }
+}
+
+/// Sum and average the values logged on entries, ignoring entries without one
+///
+/// Aggregates are only reported when the habit is measurable or at least
+/// one entry carries a value. Callers pass the entries without outliers.
+fn aggregate_values(entries: &[&HabitEntry], measurable: bool) -> (Option<u64>, Option<f64>) {
+    let values: Vec<u64> = entries.iter().filter_map(|e| e.value).map(u64::from).collect();
+    
+    if values.is_empty() {
+        return if measurable { (Some(0), None) } else { (None, None) };
+    }
+    
+    let total: u64 = values.iter().sum();
+    (Some(total), Some(total as f64 / values.len() as f64))
+}
+
+/// Format the "Total: 450 minutes (avg 30)" line for habits with values
+fn format_value_line(status: &HabitStatus) -> String {
+    let Some(total) = status.total_value else {
+        return String::new();
+    };
+    
+    let unit = status.unit.as_deref().map(|u| format!(" {}", u)).unwrap_or_default();
+    let line = match status.average_value {
+        Some(avg) if avg.fract() == 0.0 => format!("\n   Total: {}{} (avg {:.0})", total, unit, avg),
+        Some(avg) => format!("\n   Total: {}{} (avg {:.1})", total, unit, avg),
+        None => format!("\n   Total: {}{}", total, unit),
+    };
+    if status.excluded_values.is_empty() {
+        return line;
+    }
+    
+    let excluded = status.excluded_values.iter()
+        .map(|v| format!("{}{} on {}", v.value, unit, v.date))
+        .collect::<Vec<_>>();
+    format!("{}\n   ⚠️ Excludes {} (possible unit mismatch)", line, excluded.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Category, Frequency};
+    use crate::storage::SqliteStorage;
+    use chrono::{Duration, Utc};
+
+    fn create_habit(storage: &SqliteStorage, target_value: Option<u32>, unit: Option<&str>) -> Habit {
+        create_habit_with_frequency(storage, Frequency::Daily, target_value, unit)
+    }
+
+    fn create_habit_with_frequency(
+        storage: &SqliteStorage,
+        frequency: Frequency,
+        target_value: Option<u32>,
+        unit: Option<&str>,
+    ) -> Habit {
+        let habit = Habit::new(
+            "Running".to_string(),
+            None,
+            Category::Health,
+            frequency,
+            target_value,
+            unit.map(|u| u.to_string()),
+        ).unwrap();
+        storage.create_habit(&habit).unwrap();
+        habit
+    }
+
+    fn log(storage: &SqliteStorage, habit: &Habit, days_ago: i64, value: Option<u32>) {
+        let date = Utc::now().naive_utc().date() - Duration::days(days_ago);
+        let entry = HabitEntry::new(habit.id.clone(), date, value, None, None).unwrap();
+        storage.create_entry(&entry).unwrap();
+    }
+
+    fn status_for(storage: &SqliteStorage, habit: &Habit) -> StatusResponse {
+        get_habit_status(storage, &AnalyticsEngine::new(), StatusParams { habit_id: Some(habit.id.to_string()) }).unwrap()
+    }
+
+    #[test]
+    fn test_status_aggregates_values_ignoring_unvalued_entries() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let habit = create_habit(&storage, Some(30), Some("minutes"));
+        log(&storage, &habit, 0, Some(20));
+        log(&storage, &habit, 1, Some(40));
+        log(&storage, &habit, 2, None);
+
+        let response = status_for(&storage, &habit);
+        let status = &response.habits[0];
+
+        assert_eq!(status.name, "Running");
+        assert_eq!(status.total_value, Some(60));
+        assert_eq!(status.average_value, Some(30.0));
+        assert!(response.message.contains("Total: 60 minutes (avg 30)"));
+    }
+
+    #[test]
+    fn test_status_excludes_unit_mismatch_outliers_from_aggregates() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let habit = create_habit(&storage, Some(2), Some("hours"));
+        for (days_ago, value) in [(0, 1), (1, 2), (2, 90), (3, 1), (4, 2)] {
+            log(&storage, &habit, days_ago, Some(value));
+        }
+        let outlier_date = (Utc::now().naive_utc().date() - Duration::days(2)).to_string();
+
+        let response = status_for(&storage, &habit);
+        let status = &response.habits[0];
+
+        assert_eq!(status.total_value, Some(6));
+        assert_eq!(status.average_value, Some(1.5));
+        assert_eq!(status.excluded_values, vec![ExcludedValue { date: outlier_date.clone(), value: 90 }]);
+        assert!(response.message.contains(&format!(
+            "Total: 6 hours (avg 1.5)\n   ⚠️ Excludes 90 hours on {} (possible unit mismatch)", outlier_date
+        )), "{}", response.message);
+
+        // A looser multiplier keeps the value in the totals
+        let lenient = AnalyticsEngine::with_config(crate::analytics::AnalyticsConfig {
+            value_outlier_multiplier: 100.0,
+            ..crate::analytics::AnalyticsConfig::default()
+        });
+        let response = get_habit_status(&storage, &lenient, StatusParams { habit_id: Some(habit.id.to_string()) }).unwrap();
+        assert_eq!(response.habits[0].total_value, Some(96));
+        assert!(response.habits[0].excluded_values.is_empty());
+    }
+
+    #[test]
+    fn test_status_omits_values_for_unmeasured_habit() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let habit = create_habit(&storage, None, None);
+        log(&storage, &habit, 0, None);
+
+        let response = status_for(&storage, &habit);
+
+        assert_eq!(response.habits[0].total_value, None);
+        assert_eq!(response.habits[0].average_value, None);
+        assert!(!response.message.contains("Total:"));
+    }
+
+    #[test]
+    fn test_status_reports_values_when_entries_carry_them() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let habit = create_habit(&storage, None, None);
+        log(&storage, &habit, 0, Some(5));
+
+        let response = status_for(&storage, &habit);
+
+        assert_eq!(response.habits[0].total_value, Some(5));
+        assert!(response.message.contains("Total: 5 (avg 5)"));
+    }
+
+    #[test]
+    fn test_status_shows_tracking_since_first_entry() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let habit = create_habit(&storage, None, None);
+        log(&storage, &habit, 2, None);
+        log(&storage, &habit, 9, None);
+
+        let response = status_for(&storage, &habit);
+        let first = Utc::now().naive_utc().date() - Duration::days(9);
+
+        assert_eq!(storage.get_first_entry_date(&habit.id).unwrap(), Some(first));
+        assert_eq!(response.habits[0].tracking_days, Some(10));
+        assert!(response.message.contains(&format!("Tracking since {} (10 days)", first)));
+    }
+
+    #[test]
+    fn test_status_without_entries_has_no_tracking_line() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let habit = create_habit(&storage, None, None);
+
+        let response = status_for(&storage, &habit);
+
+        assert_eq!(storage.get_first_entry_date(&habit.id).unwrap(), None);
+        assert_eq!(response.habits[0].tracking_since, None);
+        assert!(!response.message.contains("Tracking since"));
+    }
+
+    fn projected(frequency: Frequency, streak: u32, completed_today: bool) -> Vec<(u32, String)> {
+        let today = NaiveDate::from_ymd_opt(2024, 6, 1).unwrap();
+        let created = NaiveDate::from_ymd_opt(2024, 5, 1).unwrap();
+        let last = if completed_today { today } else { today - Duration::days(1) };
+        project_streak(&frequency, streak, Some(last), created, today, Weekday::Mon)
+            .into_iter()
+            .map(|p| (p.streak, p.date))
+            .collect()
+    }
+
+    #[test]
+    fn test_daily_streak_projection() {
+        let expected = |a, b, c| vec![(a, "2024-06-08".to_string()), (b, "2024-07-01".to_string()), (c, "2024-08-30".to_string())];
+        assert_eq!(projected(Frequency::Daily, 12, true), expected(19, 42, 102));
+        // Today still counts when it hasn't been logged yet
+        assert_eq!(projected(Frequency::Daily, 12, false), expected(20, 43, 103));
+        // A streak at the limit stays there instead of overflowing
+        assert!(projected(Frequency::Daily, u32::MAX, false).iter().all(|(streak, _)| *streak == u32::MAX));
+    }
+
+    #[test]
+    fn test_weekly_streak_projection_counts_new_weeks() {
+        // Mondays after Saturday 2024-06-01: 1 within a week, 5 by July 1, 13 by August 30
+        let projection = projected(Frequency::Weekly(3), 4, false);
+        assert_eq!(projection, vec![
+            (5, "2024-06-08".to_string()),
+            (9, "2024-07-01".to_string()),
+            (17, "2024-08-30".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn test_status_projection_only_for_running_streaks() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let habit = create_habit(&storage, None, None);
+        assert!(status_for(&storage, &habit).habits[0].projections.is_empty());
+
+        log(&storage, &habit, 0, None);
+        crate::tools::log::recompute_and_store_streak(&storage, &crate::analytics::AnalyticsEngine::new(), &habit.id).unwrap();
+        let response = status_for(&storage, &habit);
+        let in_week = (Utc::now().naive_utc().date() + Duration::days(7)).to_string();
+        assert_eq!(response.habits[0].projections[0].streak, 8);
+        assert!(response.message.contains(&format!("Keep it up and you'll hit 8 days by {}", in_week)), "{}", response.message);
+    }
+
+    #[test]
+    fn test_status_reads_today_from_the_rollover_clock() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let habit = create_habit(&storage, None, None);
+        log(&storage, &habit, 1, None);
+        log(&storage, &habit, 2, None);
+        // 1am, before a 3am rollover, still counts as yesterday
+        let today = Utc::now().naive_utc().date();
+        let analytics = AnalyticsEngine::new().with_clock(crate::domain::DayRolloverClock {
+            clock: crate::domain::FixedTime(today.and_hms_opt(1, 0, 0).unwrap()),
+            rollover_hour: 3,
+        });
+        crate::tools::log::recompute_and_store_streak(&storage, &analytics, &habit.id).unwrap();
+
+        let response = get_habit_status(&storage, &analytics, StatusParams { habit_id: Some(habit.id.to_string()) }).unwrap();
+        let status = &response.habits[0];
+
+        assert_eq!(status.completions_today, 1);
+        assert!(!status.at_risk);
+        assert_eq!(status.tracking_days, Some(2));
+        let yesterday = today - Duration::days(1);
+        assert_eq!(status.projections[0].date, (yesterday + Duration::days(7)).to_string());
+    }
+
+    /// Monday of a fully elapsed week, so every day in it can be logged
+    fn past_week_start() -> chrono::NaiveDate {
+        week_start_for(Utc::now().naive_utc().date() - Duration::days(14), chrono::Weekday::Mon)
+    }
+
+    /// Log the habit on the first `count` days of that past week
+    fn log_past_week(storage: &SqliteStorage, habit: &Habit, count: i64) {
+        for offset in 0..count {
+            let entry = HabitEntry::new(habit.id.clone(), past_week_start() + Duration::days(offset), None, None, None).unwrap();
+            storage.create_entry(&entry).unwrap();
+        }
+    }
+
+    /// Completions counted for the past week, as seen from its Wednesday
+    fn week_progress(storage: &SqliteStorage, habit: &Habit) -> u32 {
+        count_week_completions(storage, &habit.id, past_week_start() + Duration::days(2), &StreakOptions::default()).unwrap()
+    }
+
+    #[test]
+    fn test_weekly_progress_under_target() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let habit = create_habit_with_frequency(&storage, Frequency::Weekly(3), None, None);
+        log_past_week(&storage, &habit, 2);
+
+        assert_eq!(week_progress(&storage, &habit), 2);
+    }
+
+    #[test]
+    fn test_weekly_progress_met_and_over_target() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let met = create_habit_with_frequency(&storage, Frequency::Weekly(3), None, None);
+        let over = create_habit_with_frequency(&storage, Frequency::Weekly(2), None, None);
+        log_past_week(&storage, &met, 3);
+        log_past_week(&storage, &over, 4);
+
+        assert_eq!(week_progress(&storage, &met), 3);
+        assert_eq!(week_progress(&storage, &over), 4);
+    }
+
+    #[test]
+    fn test_weekly_status_reports_goal() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let habit = create_habit_with_frequency(&storage, Frequency::Weekly(1), None, None);
+        log(&storage, &habit, 0, None);
+
+        let response = status_for(&storage, &habit);
+        let status = &response.habits[0];
+
+        assert_eq!(status.week_completions, Some(1));
+        assert_eq!(status.week_target, Some(1));
+        assert_eq!(status.week_goal_met, Some(true));
+        assert!(response.message.contains("This week: 1 of 1 completed ✅ goal met"));
+        assert_eq!(status.includes_current_week, Some(true));
+        assert_eq!(status.completed_week_streak, Some(0));
+    }
+
+    #[test]
+    fn test_weekly_status_before_target_keeps_finished_weeks() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let habit = create_habit_with_frequency(&storage, Frequency::Weekly(2), None, None);
+        log_past_week(&storage, &habit, 2);
+        let last_week = past_week_start() + Duration::days(7);
+        for offset in [0, 1] {
+            storage.create_entry(&HabitEntry::new(habit.id.clone(), last_week + Duration::days(offset), None, None, None).unwrap()).unwrap();
+        }
+
+        let status = &status_for(&storage, &habit).habits[0];
+
+        assert_eq!(status.completed_week_streak, Some(2));
+        assert_eq!(status.includes_current_week, Some(false));
+        assert_eq!(status_for(&storage, &create_habit(&storage, None, None)).habits[0].includes_current_week, None);
+    }
+
+    #[test]
+    fn test_week_start_changes_counted_week() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let habit = create_habit_with_frequency(&storage, Frequency::Weekly(3), None, None);
+        let monday = past_week_start();
+        let sunday_before = monday - Duration::days(1);
+        let entry = HabitEntry::new(habit.id.clone(), sunday_before, None, None, None).unwrap();
+        storage.create_entry(&entry).unwrap();
+
+        let monday_weeks = StreakOptions::default();
+        let sunday_weeks = StreakOptions { week_start: chrono::Weekday::Sun, ..StreakOptions::default() };
+
+        assert_eq!(count_week_completions(&storage, &habit.id, monday, &monday_weeks).unwrap(), 0);
+        assert_eq!(count_week_completions(&storage, &habit.id, monday, &sunday_weeks).unwrap(), 1);
+    }
+
+    /// Store a habit with a precomputed streak, as the log tool would have left it
+    fn habit_with_streak(storage: &SqliteStorage, name: &str, current: u32, days_since_last: Option<i64>, rate: f64) -> Habit {
+        let habit = Habit::new(name.to_string(), None, Category::Health, Frequency::Daily, None, None).unwrap();
+        storage.create_habit(&habit).unwrap();
+        storage.update_streak(&crate::domain::Streak {
+            habit_id: habit.id.clone(),
+            current_streak: current,
+            longest_streak: current,
+            last_completed: days_since_last.map(|days| Utc::now().naive_utc().date() - Duration::days(days)),
+            total_completions: current,
+            completion_rate: rate,
+        }).unwrap();
+        habit
+    }
+
+    fn status_for_all(storage: &SqliteStorage) -> StatusResponse {
+        get_habit_status(storage, &AnalyticsEngine::new(), StatusParams { habit_id: None }).unwrap()
+    }
+
+    #[test]
+    fn test_all_status_reports_portfolio_aggregates() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        habit_with_streak(&storage, "Reading", 12, Some(0), 0.9);
+        habit_with_streak(&storage, "Running", 5, Some(1), 0.6);
+        habit_with_streak(&storage, "Stretching", 2, Some(1), 0.3);
+        habit_with_streak(&storage, "Journaling", 0, Some(6), 0.2);
+
+        let response = status_for_all(&storage);
+        let stats = response.portfolio.expect("all-habits status has aggregates");
+
+        assert_eq!(stats.longest_current_streak, 12);
+        assert_eq!(stats.longest_current_streak_habit.as_deref(), Some("Reading"));
+        assert!((stats.mean_completion_rate - 0.5).abs() < 1e-9);
+        assert_eq!(stats.at_risk_count, 2);
+        assert!(response.summary.contains("Longest current streak: 12 days (Reading) | Avg rate: 50% | ⚠️ At risk today: 2"));
+    }
+
+    #[test]
+    fn test_at_risk_excludes_done_today_and_broken_streaks() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        habit_with_streak(&storage, "Done", 3, Some(0), 1.0);
+        habit_with_streak(&storage, "Pending", 3, Some(1), 1.0);
+        habit_with_streak(&storage, "Lapsed", 0, Some(4), 0.5);
+        habit_with_streak(&storage, "Never", 0, None, 0.0);
+
+        let response = status_for_all(&storage);
+        let at_risk: Vec<&str> = response.habits.iter()
+            .filter(|h| h.at_risk)
+            .map(|h| h.name.as_str())
+            .collect();
+
+        assert_eq!(at_risk, vec!["Pending"]);
+        assert_eq!(response.portfolio.unwrap().at_risk_count, 1);
+    }
+
+    #[test]
+    fn test_all_status_without_running_streaks() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        habit_with_streak(&storage, "Lapsed", 0, Some(10), 0.25);
+
+        let response = status_for_all(&storage);
+        let stats = response.portfolio.unwrap();
+
+        assert_eq!(stats.longest_current_streak, 0);
+        assert_eq!(stats.longest_current_streak_habit, None);
+        assert!(response.summary.contains("Longest current streak: none"));
+    }
+
+    #[test]
+    fn test_single_habit_status_has_no_portfolio() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let habit = habit_with_streak(&storage, "Reading", 1, Some(0), 1.0);
+
+        assert!(status_for(&storage, &habit).portfolio.is_none());
+        assert!(status_for_all(&SqliteStorage::new(":memory:").unwrap()).portfolio.is_none());
+    }
+
+    #[test]
+    fn test_status_shows_frequency_for_each_schedule() {
+        let cases = [
+            (Frequency::Daily, "Daily"),
+            (Frequency::Weekly(3), "3 times per week"),
+            (Frequency::Weekdays, "Weekdays"),
+            (Frequency::Weekends, "Weekends"),
+            (Frequency::Custom(vec![chrono::Weekday::Fri, chrono::Weekday::Mon]), "Monday, Friday"),
+            (Frequency::Interval(3), "Every 3 days"),
+        ];
+
+        for (frequency, expected) in cases {
+            let storage = SqliteStorage::new(":memory:").unwrap();
+            let habit = create_habit_with_frequency(&storage, frequency, None, None);
+
+            let response = status_for(&storage, &habit);
+
+            assert_eq!(response.habits[0].frequency, expected);
+            assert_eq!(response.habits[0].category, "health");
+            assert!(response.message.contains(&format!(") · {}\n", expected)), "{}", response.message);
+
+            let all = get_habit_status(&storage, &AnalyticsEngine::new(), StatusParams { habit_id: None }).unwrap();
+            assert_eq!(all.habits[0].frequency, expected);
+        }
+    }
+
+    #[test]
+    fn test_streak_label_matches_frequency() {
+        let cases = [
+            (Frequency::Daily, "days", "4 days"),
+            (Frequency::Weekdays, "days", "4 days"),
+            (Frequency::Custom(vec![chrono::Weekday::Mon]), "days", "4 days"),
+            (Frequency::Weekly(3), "weeks", "4 weeks"),
+            (Frequency::Interval(3), "periods", "4 periods"),
+        ];
+
+        for (frequency, unit, rendered) in cases {
+            let storage = SqliteStorage::new(":memory:").unwrap();
+            let habit = create_habit_with_frequency(&storage, frequency, None, None);
+            storage.update_streak(&crate::domain::Streak::from_existing(
+                habit.id.clone(), 4, 4, Some(Utc::now().naive_utc().date()), 4, 1.0)).unwrap();
+
+            let response = status_for(&storage, &habit);
+            assert_eq!(response.habits[0].streak_unit, unit);
+            assert!(response.message.contains(&format!("Current streak: {} | Best: {} |", rendered, rendered)), "{}", response.message);
+
+            let all = status_for_all(&storage);
+            assert!(all.summary.contains(&format!("Longest current streak: {} (Running)", rendered)), "{}", all.summary);
+        }
+    }
+
+    #[test]
+    fn test_summary_totals_streaks_per_unit() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let today = Utc::now().naive_utc().date();
+        for (frequency, length) in [(Frequency::Daily, 4), (Frequency::Weekly(3), 3), (Frequency::Daily, 2)] {
+            let habit = create_habit_with_frequency(&storage, frequency, None, None);
+            storage.update_streak(&crate::domain::Streak::from_existing(
+                habit.id.clone(), length, length, Some(today), length, 1.0)).unwrap();
+        }
+
+        let all = status_for_all(&storage);
+        assert!(all.summary.contains("Total streaks: 6 days, 3 weeks"), "{}", all.summary);
+    }
+}
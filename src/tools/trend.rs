@@ -0,0 +1,133 @@
+/// Tool for charting a habit's completion rate week by week
+/// 
+/// This module implements the habit_trend MCP tool.
+
+use serde::{Deserialize, Serialize};
+use crate::analytics::AnalyticsEngine;
+use crate::domain::{HabitId, format_rate};
+use crate::storage::{StorageError, HabitStorage};
+
+/// Number of weeks shown when the caller doesn't ask for a specific count
+pub const DEFAULT_TREND_WEEKS: u32 = 8;
+
+/// Longest history the trend tool will return
+pub const MAX_TREND_WEEKS: u32 = 52;
+
+/// Characters used for the sparkline, from a 0% week to a 100% week
+const SPARK_LEVELS: &[char] = &['_', '.', ',', '-', '=', '+', '*', '#'];
+
+/// Parameters for fetching a habit's weekly trend
+#[derive(Debug, Deserialize)]
+pub struct TrendParams {
+    pub habit_id: String,
+    pub weeks: Option<u32>, // Defaults to 8
+}
+
+/// Completion rate for a single week
+#[derive(Debug, Serialize)]
+pub struct WeekRate {
+    pub week_start: String,
+    pub completion_rate: f64,
+}
+
+/// Response from fetching a habit's weekly trend
+#[derive(Debug, Serialize)]
+pub struct TrendResponse {
+    pub habit_id: String,
+    pub name: String,
+    pub weeks: Vec<WeekRate>, // Oldest first
+    pub sparkline: String,
+    pub message: String,
+}
+
+/// Compute a habit's completion rate for each of the last few weeks
+pub fn get_habit_trend<S: HabitStorage>(
+    storage: &S,
+    analytics: &AnalyticsEngine,
+    params: TrendParams,
+) -> Result<TrendResponse, StorageError> {
+    let weeks = params.weeks.unwrap_or(DEFAULT_TREND_WEEKS);
+    if weeks == 0 || weeks > MAX_TREND_WEEKS {
+        return Err(StorageError::Validation(format!("Weeks must be between 1 and {}", MAX_TREND_WEEKS)));
+    }
+    
+    let habit_id = HabitId::from_string(&params.habit_id)
+        .map_err(|_| StorageError::HabitNotFound { habit_id: params.habit_id.clone() })?;
+    let habit = storage.get_habit(&habit_id)?;
+    let entries = storage.get_entries_for_habit(&habit_id, None)?;
+    
+    let series = analytics.weekly_completion_series(&habit, &entries, weeks);
+    let rates: Vec<f64> = series.iter().map(|(_, rate)| *rate).collect();
+    let sparkline = sparkline(&rates);
+    
+    let (first, last) = (rates[0], rates[rates.len() - 1]);
+    let message = format!("📈 {} over the last {} week{}: {}\n   {} → {} (this week)",
+                          habit.name, weeks, if weeks == 1 { "" } else { "s" },
+                          sparkline, format_rate(first), format_rate(last));
+    
+    Ok(TrendResponse {
+        habit_id: habit.id.to_string(),
+        name: habit.name,
+        weeks: series.into_iter()
+            .map(|(week_start, completion_rate)| WeekRate {
+                week_start: week_start.to_string(),
+                completion_rate,
+            })
+            .collect(),
+        sparkline,
+        message,
+    })
+}
+
+/// Render rates between 0.0 and 1.0 as one ASCII character each
+fn sparkline(rates: &[f64]) -> String {
+    let top = (SPARK_LEVELS.len() - 1) as f64;
+    rates.iter()
+        .map(|rate| SPARK_LEVELS[(rate.clamp(0.0, 1.0) * top).round() as usize])
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Habit, HabitEntry, Category, Frequency};
+    use crate::storage::sqlite::SqliteStorage;
+    use chrono::Utc;
+
+    #[test]
+    fn test_sparkline_maps_rates_to_levels() {
+        assert_eq!(sparkline(&[0.0, 0.5, 1.0, 1.4]), "_=##");
+        assert_eq!(sparkline(&[]), "");
+    }
+
+    #[test]
+    fn test_trend_returns_requested_weeks() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let habit = Habit::new("Read".to_string(), None, Category::Personal, Frequency::Daily, None, None).unwrap();
+        storage.create_habit(&habit).unwrap();
+        let today = Utc::now().naive_utc().date();
+        storage.create_entry(&HabitEntry::new(habit.id.clone(), today, None, None, None).unwrap()).unwrap();
+
+        let params = TrendParams { habit_id: habit.id.to_string(), weeks: Some(3) };
+        let response = get_habit_trend(&storage, &AnalyticsEngine::new(), params).unwrap();
+
+        assert_eq!(response.weeks.len(), 3);
+        assert_eq!(response.sparkline.chars().count(), 3);
+        // Created today, so earlier weeks are empty and this week is complete so far
+        assert_eq!(response.weeks[0].completion_rate, 0.0);
+        assert_eq!(response.weeks[2].completion_rate, 1.0);
+        assert!(response.message.contains("__#"));
+    }
+
+    #[test]
+    fn test_trend_rejects_out_of_range_weeks() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let habit = Habit::new("Read".to_string(), None, Category::Personal, Frequency::Daily, None, None).unwrap();
+        storage.create_habit(&habit).unwrap();
+
+        for weeks in [0, MAX_TREND_WEEKS + 1] {
+            let params = TrendParams { habit_id: habit.id.to_string(), weeks: Some(weeks) };
+            assert!(get_habit_trend(&storage, &AnalyticsEngine::new(), params).is_err());
+        }
+    }
+}
@@ -17,6 +17,12 @@ pub struct UpdateHabitParams {
     pub target_value: Option<u32>,
     pub unit: Option<String>,
     pub is_active: Option<bool>,
+    /// New motivation for the habit; an empty string clears it
+    #[serde(default)]
+    pub motivation: Option<String>,
+    /// Whether several completions may be logged on the same day
+    #[serde(default)]
+    pub allow_multiple_per_day: Option<bool>,
 }
 
 /// Response from updating a habit
@@ -39,10 +45,9 @@ pub fn update_habit<S: HabitStorage>(
     let mut habit = storage.get_habit(&habit_id)?;
 
     // Parse frequency if provided
-    let frequency = if let Some(freq_str) = params.frequency {
-        Some(parse_frequency(&freq_str)?)
-    } else {
-        None
+    let frequency = match params.frequency {
+        Some(freq_str) => Some(freq_str.parse::<Frequency>()?),
+        None => None,
     };
 
     // Validate and apply updates
@@ -53,9 +58,13 @@ pub fn update_habit<S: HabitStorage>(
         params.target_value.map(Some), // Wrap in Option for the method signature
         params.unit.map(Some), // Wrap in Option for the method signature
         params.is_active,
-    ).map_err(|e| StorageError::Query(
-        rusqlite::Error::InvalidColumnType(0, e.to_string(), rusqlite::types::Type::Text)
-    ))?;
+    )?;
+    if params.motivation.is_some() {
+        habit.set_motivation(params.motivation)?;
+    }
+    if let Some(allow_multiple_per_day) = params.allow_multiple_per_day {
+        habit.allow_multiple_per_day = allow_multiple_per_day;
+    }
 
     // Save the updated habit
     storage.update_habit(&habit)?;
@@ -75,23 +84,6 @@ pub fn update_habit<S: HabitStorage>(
     })
 }
 
-/// Parse frequency string into Frequency enum
-fn parse_frequency(freq_str: &str) -> Result<Frequency, StorageError> {
-    match freq_str.trim().to_lowercase().as_str() {
-        "daily" => Ok(Frequency::Daily),
-        "weekdays" => Ok(Frequency::Weekdays),
-        "weekends" => Ok(Frequency::Weekends),
-        "weekly" => Ok(Frequency::Weekly(3)), // Default to 3 times per week
-        "custom" => Ok(Frequency::Custom(vec![chrono::Weekday::Mon])), // Default to Monday
-        _ => Err(StorageError::Query(
-            rusqlite::Error::InvalidColumnType(0,
-                format!("Invalid frequency '{}'. Valid options: daily, weekdays, weekends, weekly, custom", freq_str),
-                rusqlite::types::Type::Text
-            )
-        )),
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -127,6 +119,8 @@ mod tests {
             target_value: None,
             unit: None,
             is_active: None,
+            motivation: None,
+            allow_multiple_per_day: None,
         };
 
         let result = update_habit(&storage, params);
@@ -165,6 +159,8 @@ mod tests {
             target_value: None,
             unit: None,
             is_active: Some(false),
+            motivation: None,
+            allow_multiple_per_day: None,
         };
 
         let result = update_habit(&storage, params);
@@ -190,9 +186,48 @@ mod tests {
             target_value: None,
             unit: None,
             is_active: None,
+            motivation: None,
+            allow_multiple_per_day: None,
         };
 
         let result = update_habit(&storage, params);
         assert!(result.is_err());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_update_habit_motivation_set_and_clear() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let habit = Habit::new(
+            "Test Habit".to_string(),
+            None,
+            Category::Health,
+            Frequency::Daily,
+            None,
+            None,
+        ).unwrap();
+        let habit_id = habit.id.to_string();
+        storage.create_habit(&habit).unwrap();
+
+        let params = |motivation: &str| UpdateHabitParams {
+            habit_id: habit_id.clone(),
+            name: None,
+            description: None,
+            frequency: None,
+            target_value: None,
+            unit: None,
+            is_active: None,
+            motivation: Some(motivation.to_string()),
+            allow_multiple_per_day: None,
+        };
+
+        update_habit(&storage, params("Sleep better")).unwrap();
+        let stored = storage.get_habit(&habit.id).unwrap();
+        assert_eq!(stored.motivation.as_deref(), Some("Sleep better"));
+
+        let too_long = "x".repeat(crate::domain::MAX_MOTIVATION_LENGTH + 1);
+        assert!(matches!(update_habit(&storage, params(&too_long)), Err(StorageError::Validation(_))));
+
+        update_habit(&storage, params("")).unwrap();
+        assert_eq!(storage.get_habit(&habit.id).unwrap().motivation, None);
+    }
+}
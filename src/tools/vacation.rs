@@ -0,0 +1,207 @@
+/// Tools for putting a habit's streak on hold during a vacation
+///
+/// This module implements the habit_vacation_start and habit_vacation_end
+/// MCP tools. Unlike pausing, the habit stays active; scheduled days in a
+/// vacation window are skipped by the streak, so they neither break nor
+/// extend it. Vacations may overlap, and the streak is recalculated
+/// whenever one starts or ends.
+
+use serde::{Deserialize, Serialize};
+use crate::domain::{parse_date_input, HabitId, Vacation};
+use crate::analytics::AnalyticsEngine;
+use crate::storage::{StorageError, HabitStorage};
+use crate::tools::log::recompute_and_store_streak;
+
+/// Parameters for starting a vacation
+#[derive(Debug, Deserialize)]
+pub struct VacationStartParams {
+    pub habit_id: String,
+    pub start_date: Option<String>, // Defaults to today
+    /// Last day of the vacation; leave out to end it later with habit_vacation_end
+    pub end_date: Option<String>,
+}
+
+/// Response from starting a vacation
+#[derive(Debug, Serialize)]
+pub struct VacationStartResponse {
+    pub success: bool,
+    pub start_date: String,
+    pub end_date: Option<String>,
+    pub current_streak: u32,
+    pub message: String,
+}
+
+/// Parameters for ending a habit's open vacations
+#[derive(Debug, Deserialize)]
+pub struct VacationEndParams {
+    pub habit_id: String,
+    pub end_date: Option<String>, // Defaults to today
+}
+
+/// Response from ending a habit's open vacations
+#[derive(Debug, Serialize)]
+pub struct VacationEndResponse {
+    pub success: bool,
+    pub end_date: String,
+    pub vacations_ended: u32,
+    pub current_streak: u32,
+    pub message: String,
+}
+
+/// Mark a vacation window for a habit so its streak is kept on hold
+pub fn start_habit_vacation<S: HabitStorage>(
+    storage: &S,
+    analytics: &AnalyticsEngine,
+    params: VacationStartParams,
+) -> Result<VacationStartResponse, StorageError> {
+    let habit_id = HabitId::from_string(&params.habit_id)
+        .map_err(|_| StorageError::HabitNotFound { habit_id: params.habit_id.clone() })?;
+    let habit = storage.get_habit(&habit_id)?;
+
+    let start_date = match params.start_date {
+        Some(date_str) => parse_date_input(&date_str, analytics.clock())?,
+        None => analytics.clock().today(),
+    };
+    let end_date = params.end_date
+        .map(|date_str| parse_date_input(&date_str, analytics.clock()))
+        .transpose()?;
+    if start_date < habit.created_at.date_naive() {
+        return Err(StorageError::Validation(format!("Cannot start a vacation on {}, before '{}' was created", start_date, habit.name)));
+    }
+
+    let vacation = Vacation::new(habit_id.clone(), start_date, end_date)?;
+    storage.create_vacation(&vacation)?;
+    let streak = recompute_and_store_streak(storage, analytics, &habit_id)?;
+
+    let until = match end_date {
+        Some(end) => format!("through {}", end),
+        None => "until you end it with habit_vacation_end".to_string(),
+    };
+    Ok(VacationStartResponse {
+        success: true,
+        start_date: start_date.to_string(),
+        end_date: end_date.map(|d| d.to_string()),
+        current_streak: streak.current_streak,
+        message: format!(
+            "🏖️ '{}' is on vacation from {} {}. Your streak of {} is on hold.",
+            habit.name, start_date, until, streak.current_streak
+        ),
+    })
+}
+
+/// Close every open vacation of a habit
+pub fn end_habit_vacation<S: HabitStorage>(
+    storage: &S,
+    analytics: &AnalyticsEngine,
+    params: VacationEndParams,
+) -> Result<VacationEndResponse, StorageError> {
+    let habit_id = HabitId::from_string(&params.habit_id)
+        .map_err(|_| StorageError::HabitNotFound { habit_id: params.habit_id.clone() })?;
+    let habit = storage.get_habit(&habit_id)?;
+
+    let end_date = match params.end_date {
+        Some(date_str) => parse_date_input(&date_str, analytics.clock())?,
+        None => analytics.clock().today(),
+    };
+
+    let open: Vec<Vacation> = storage.get_vacations(&habit_id)?
+        .into_iter()
+        .filter(Vacation::is_open)
+        .collect();
+    if open.is_empty() {
+        return Err(StorageError::Validation(format!("'{}' has no open vacation to end", habit.name)));
+    }
+    if let Some(latest) = open.iter().map(|v| v.start_date).max() {
+        if end_date < latest {
+            return Err(StorageError::Validation(format!("Cannot end the vacation on {}, before it started on {}", end_date, latest)));
+        }
+    }
+
+    let vacations_ended = storage.end_vacations(&habit_id, end_date)?;
+    let streak = recompute_and_store_streak(storage, analytics, &habit_id)?;
+
+    Ok(VacationEndResponse {
+        success: true,
+        end_date: end_date.to_string(),
+        vacations_ended,
+        current_streak: streak.current_streak,
+        message: format!(
+            "👋 Welcome back! '{}' vacation ended on {}. Current streak: {}",
+            habit.name, end_date, streak.current_streak
+        ),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDate, Utc};
+    use crate::domain::{Category, Frequency, Habit, HabitEntry};
+    use crate::storage::sqlite::SqliteStorage;
+
+    /// Daily habit done on days 20-11 and 5-1 ago, missing days 10-6 in between
+    fn habit_with_gap() -> (SqliteStorage, Habit, NaiveDate) {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let mut habit = Habit::new("Run".to_string(), None, Category::Health, Frequency::Daily, None, None).unwrap();
+        habit.created_at = Utc::now() - chrono::Duration::days(30);
+        storage.create_habit(&habit).unwrap();
+        let today = Utc::now().naive_utc().date();
+        let entries: Vec<HabitEntry> = (1..=5).chain(11..=20)
+            .map(|d| HabitEntry::new(habit.id.clone(), today - chrono::Duration::days(d), None, None, None).unwrap())
+            .collect();
+        storage.create_entries(&entries).unwrap();
+        (storage, habit, today)
+    }
+
+    #[test]
+    fn test_streak_survives_marked_vacation_but_not_unmarked_gap() {
+        let (storage, habit, today) = habit_with_gap();
+        let days_ago = |d: i64| (today - chrono::Duration::days(d)).to_string();
+
+        // Unmarked, the five missed days break the streak
+        assert_eq!(recompute_and_store_streak(&storage, &AnalyticsEngine::new(), &habit.id).unwrap().current_streak, 5);
+
+        let response = start_habit_vacation(&storage, &AnalyticsEngine::new(), VacationStartParams {
+            habit_id: habit.id.to_string(),
+            start_date: Some(days_ago(10)),
+            end_date: Some(days_ago(6)),
+        }).unwrap();
+
+        // The vacation days are skipped, neither breaking nor adding to the streak
+        assert_eq!(response.current_streak, 15);
+        assert_eq!(storage.get_streak(&habit.id).unwrap().current_streak, 15);
+    }
+
+    #[test]
+    fn test_open_and_overlapping_vacations() {
+        let (storage, habit, today) = habit_with_gap();
+        let days_ago = |d: i64| (today - chrono::Duration::days(d)).to_string();
+        let start = |start: i64, end: Option<i64>| start_habit_vacation(&storage, &AnalyticsEngine::new(), VacationStartParams {
+            habit_id: habit.id.to_string(),
+            start_date: Some(days_ago(start)),
+            end_date: end.map(days_ago),
+        });
+
+        // Two overlapping windows that together cover the gap; while the
+        // second is open, the days logged since don't extend the streak
+        start(10, Some(8)).unwrap();
+        let response = start(9, None).unwrap();
+        assert_eq!(response.current_streak, 10);
+
+        let early = end_habit_vacation(&storage, &AnalyticsEngine::new(), VacationEndParams {
+            habit_id: habit.id.to_string(),
+            end_date: Some(days_ago(12)),
+        });
+        assert!(matches!(early, Err(StorageError::Validation(_))));
+
+        let ended = end_habit_vacation(&storage, &AnalyticsEngine::new(), VacationEndParams {
+            habit_id: habit.id.to_string(),
+            end_date: Some(days_ago(6)),
+        }).unwrap();
+        assert_eq!(ended.vacations_ended, 1);
+        assert_eq!(ended.current_streak, 15);
+
+        let again = end_habit_vacation(&storage, &AnalyticsEngine::new(), VacationEndParams { habit_id: habit.id.to_string(), end_date: None });
+        assert!(matches!(again, Err(StorageError::Validation(ref msg)) if msg.contains("no open vacation")));
+    }
+}
@@ -3,6 +3,7 @@ use habit_tracker_mcp::*;
 use tempfile::NamedTempFile;
 
 #[cfg(test)]
+#[allow(clippy::assertions_on_constants)]
 mod basic_integration_tests {
     use super::*;
 